@@ -0,0 +1,49 @@
+use actix_web::{dev::Payload, http::header, web, FromRequest, HttpMessage, HttpRequest};
+use futures_util::future::LocalBoxFuture;
+use delpopolo_core::CoreError;
+use delpopolo_domain::User;
+use crate::error::ApiError;
+use super::service::AuthService;
+
+/// Usuário autenticado pelo access token do header `Authorization: Bearer`
+#[derive(Debug, Clone)]
+pub struct AuthenticatedUser(pub User);
+
+impl std::ops::Deref for AuthenticatedUser {
+    type Target = User;
+    
+    fn deref(&self) -> &User {
+        &self.0
+    }
+}
+
+pub fn bearer_token(req: &HttpRequest) -> Option<String> {
+    req.headers()
+        .get(header::AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+        .map(|token| token.trim().to_string())
+}
+
+impl FromRequest for AuthenticatedUser {
+    type Error = ApiError;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+    
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        // Já validado por `RequirePermission`
+        if let Some(user) = req.extensions().get::<User>().cloned() {
+            return Box::pin(async move { Ok(AuthenticatedUser(user)) });
+        }
+        
+        let auth = req.app_data::<web::Data<AuthService>>().cloned();
+        let token = bearer_token(req);
+        
+        Box::pin(async move {
+            let auth = auth.ok_or_else(|| CoreError::internal("AuthService não configurado"))?;
+            let token = token.ok_or_else(|| CoreError::unauthorized("Token de acesso ausente"))?;
+            
+            Ok(AuthenticatedUser(auth.authenticate(&token).await?))
+        })
+    }
+}
@@ -0,0 +1,177 @@
+use std::future::{ready, Ready};
+use std::rc::Rc;
+use actix_web::{
+    body::EitherBody,
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    web, Error, HttpMessage,
+};
+use futures_util::future::LocalBoxFuture;
+use delpopolo_core::CoreError;
+use delpopolo_domain::{Permission, User};
+use crate::error::ApiError;
+use super::extractor::bearer_token;
+use super::service::AuthService;
+
+/// Exige usuário autenticado com a permissão informada; aplicável em `scope` ou `resource`
+///
+/// O usuário validado fica nas extensions da requisição e é reaproveitado por `AuthenticatedUser`.
+#[derive(Clone, Copy)]
+pub struct RequirePermission(pub Permission);
+
+impl<S, B> Transform<S, ServiceRequest> for RequirePermission
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = RequirePermissionMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+    
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequirePermissionMiddleware {
+            service: Rc::new(service),
+            permission: self.0,
+        }))
+    }
+}
+
+pub struct RequirePermissionMiddleware<S> {
+    service: Rc<S>,
+    permission: Permission,
+}
+
+impl<S, B> Service<ServiceRequest> for RequirePermissionMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+    
+    forward_ready!(service);
+    
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+        let permission = self.permission;
+        let cached = req.extensions().get::<User>().cloned();
+        let auth = req.app_data::<web::Data<AuthService>>().cloned();
+        let token = bearer_token(req.request());
+        
+        Box::pin(async move {
+            let result = match cached {
+                Some(user) => Ok(user),
+                None => authenticate(auth, token).await,
+            }
+            .and_then(|user| authorize(&user, permission).map(|_| user));
+            
+            match result {
+                Ok(user) => {
+                    req.extensions_mut().insert(user);
+                    service.call(req).await.map(ServiceResponse::map_into_left_body)
+                }
+                Err(error) => Ok(req.error_response(ApiError::from(error)).map_into_right_body()),
+            }
+        })
+    }
+}
+
+async fn authenticate(auth: Option<web::Data<AuthService>>, token: Option<String>) -> Result<User, CoreError> {
+    let auth = auth.ok_or_else(|| CoreError::internal("AuthService não configurado"))?;
+    let token = token.ok_or_else(|| CoreError::unauthorized("Token de acesso ausente"))?;
+    auth.authenticate(&token).await
+}
+
+/// Verificação pontual dentro de handlers (ex.: regra que depende do corpo da requisição)
+pub fn authorize(user: &User, permission: Permission) -> Result<(), CoreError> {
+    if user.has_permission(permission) {
+        Ok(())
+    } else {
+        tracing::warn!("User {} ({:?}) denied {:?}", user.id, user.role, permission);
+        Err(CoreError::forbidden("Permissão insuficiente para esta operação"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use actix_web::{test, App, HttpResponse};
+    use delpopolo_domain::UserRole;
+    use crate::auth::service::tests::{config, user};
+    use crate::auth::store::memory::InMemoryAuthStore;
+    use crate::auth::AuthenticatedUser;
+    use super::*;
+    
+    async fn whoami(user: AuthenticatedUser) -> HttpResponse {
+        HttpResponse::Ok().body(user.email.clone())
+    }
+    
+    async fn status_for(role: UserRole, method: &str, uri: &str) -> u16 {
+        let user = user(role);
+        let store = Arc::new(InMemoryAuthStore::default().with_user(user.clone()));
+        let auth = AuthService::new(store, &config());
+        let token = auth.login(&user.email, "fermento123").await.unwrap().access_token;
+        
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(auth))
+                .service(
+                    web::scope("/inventory")
+                        .wrap(RequirePermission(Permission::InventoryRead))
+                        .route("", web::get().to(whoami))
+                        .service(
+                            web::resource("/adjust")
+                                .wrap(RequirePermission(Permission::InventoryWrite))
+                                .route(web::post().to(whoami)),
+                        ),
+                ),
+        )
+        .await;
+        
+        let req = test::TestRequest::default()
+            .method(method.parse().unwrap())
+            .uri(uri)
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+        test::call_service(&app, req).await.status().as_u16()
+    }
+    
+    #[actix_web::test]
+    async fn test_scope_and_route_guards_per_role() {
+        let cases = [
+            (UserRole::Admin, 200, 200),
+            (UserRole::Manager, 200, 200),
+            (UserRole::InventoryManager, 200, 200),
+            (UserRole::Kitchen, 200, 403),
+            (UserRole::Cashier, 403, 403),
+            (UserRole::Delivery, 403, 403),
+        ];
+        
+        for (role, read, write) in cases {
+            assert_eq!(status_for(role, "GET", "/inventory").await, read, "{:?} GET", role);
+            assert_eq!(status_for(role, "POST", "/inventory/adjust").await, write, "{:?} POST", role);
+        }
+    }
+    
+    #[actix_web::test]
+    async fn test_missing_token_is_unauthorized() {
+        let store = Arc::new(InMemoryAuthStore::default());
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(AuthService::new(store, &config())))
+                .service(
+                    web::scope("/inventory")
+                        .wrap(RequirePermission(Permission::InventoryRead))
+                        .route("", web::get().to(whoami)),
+                ),
+        )
+        .await;
+        
+        let req = test::TestRequest::get().uri("/inventory").to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 401);
+    }
+}
@@ -0,0 +1,12 @@
+pub mod password;
+pub mod tokens;
+pub mod store;
+pub mod service;
+pub mod extractor;
+pub mod guard;
+pub mod query_token;
+
+pub use extractor::AuthenticatedUser;
+pub use guard::RequirePermission;
+pub use query_token::QueryAccessToken;
+pub use service::{AuthService, TokenPair};
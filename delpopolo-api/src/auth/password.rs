@@ -0,0 +1,65 @@
+use argon2::password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use std::sync::OnceLock;
+use delpopolo_core::CoreError;
+
+pub const MIN_PASSWORD_LENGTH: usize = 8;
+
+/// Gera o hash Argon2id (formato PHC) da senha. O Argon2 ocupa a CPU por dezenas de
+/// milissegundos, então roda no pool de bloqueio e não nas threads do runtime.
+pub async fn hash_password(password: &str) -> Result<String, CoreError> {
+    let password = password.to_string();
+    tokio::task::spawn_blocking(move || hash_password_blocking(&password))
+        .await
+        .map_err(CoreError::internal)?
+}
+
+pub fn hash_password_blocking(password: &str) -> Result<String, CoreError> {
+    let salt = SaltString::generate(&mut OsRng);
+    
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| CoreError::internal(format!("Falha ao gerar hash da senha: {}", e)))
+}
+
+pub async fn verify_password(password: &str, password_hash: &str) -> bool {
+    let (password, password_hash) = (password.to_string(), password_hash.to_string());
+    tokio::task::spawn_blocking(move || verify_password_blocking(&password, &password_hash))
+        .await
+        .unwrap_or(false)
+}
+
+fn verify_password_blocking(password: &str, password_hash: &str) -> bool {
+    PasswordHash::new(password_hash)
+        .map(|hash| Argon2::default().verify_password(password.as_bytes(), &hash).is_ok())
+        .unwrap_or(false)
+}
+
+/// Verificação contra um hash descartável, com o mesmo custo de uma verificação real.
+/// Usada quando o e-mail não existe, para o tempo de resposta não revelar quais existem.
+pub async fn verify_dummy_password(password: &str) {
+    static DUMMY_HASH: OnceLock<String> = OnceLock::new();
+    let password = password.to_string();
+    let _ = tokio::task::spawn_blocking(move || {
+        let hash = DUMMY_HASH.get_or_init(|| hash_password_blocking("senha-inexistente-0").unwrap_or_default());
+        verify_password_blocking(&password, hash)
+    })
+    .await;
+}
+
+/// Regras mínimas para novas senhas
+pub fn validate_new_password(password: &str) -> Result<(), CoreError> {
+    if password.chars().count() < MIN_PASSWORD_LENGTH {
+        return Err(CoreError::validation(format!(
+            "A senha deve ter pelo menos {} caracteres",
+            MIN_PASSWORD_LENGTH
+        )));
+    }
+    
+    if !password.chars().any(|c| c.is_alphabetic()) || !password.chars().any(|c| c.is_ascii_digit()) {
+        return Err(CoreError::validation("A senha deve conter letras e números"));
+    }
+    
+    Ok(())
+}
@@ -0,0 +1,133 @@
+use std::future::{ready, Ready};
+use std::rc::Rc;
+use actix_web::{
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    http::{header::{HeaderValue, AUTHORIZATION}, Uri},
+    web, Error,
+};
+use futures_util::future::LocalBoxFuture;
+
+const PARAM: &str = "access_token";
+
+/// Tira o `access_token` da query antes que a URI chegue aos middlewares de log e, só nos
+/// caminhos sob o prefixo (o handshake de SSE/WebSocket), o usa como header `Authorization`.
+/// Deve ser registrado por fora do `TracingLogger` e do `Logger`.
+pub struct QueryAccessToken(pub &'static str);
+
+impl<S, B> Transform<S, ServiceRequest> for QueryAccessToken
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = QueryAccessTokenMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+    
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(QueryAccessTokenMiddleware { service: Rc::new(service), prefix: self.0 }))
+    }
+}
+
+pub struct QueryAccessTokenMiddleware<S> {
+    service: Rc<S>,
+    prefix: &'static str,
+}
+
+impl<S, B> Service<ServiceRequest> for QueryAccessTokenMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+    
+    forward_ready!(service);
+    
+    fn call(&self, mut req: ServiceRequest) -> Self::Future {
+        let token = strip_token(&mut req);
+        if let Some(token) = token.filter(|_| req.path().starts_with(self.prefix)) {
+            if !req.headers().contains_key(AUTHORIZATION) {
+                if let Ok(value) = HeaderValue::from_str(&format!("Bearer {token}")) {
+                    req.headers_mut().insert(AUTHORIZATION, value);
+                }
+            }
+        }
+        let service = Rc::clone(&self.service);
+        Box::pin(async move { service.call(req).await })
+    }
+}
+
+/// Remove o parâmetro da URI e devolve o token que ele trazia
+fn strip_token(req: &mut ServiceRequest) -> Option<String> {
+    let query = req.query_string();
+    if !query.split('&').any(|pair| pair == PARAM || pair.starts_with("access_token=")) {
+        return None;
+    }
+    
+    let token = web::Query::<Vec<(String, String)>>::from_query(query)
+        .ok()
+        .and_then(|pairs| pairs.into_inner().into_iter().find(|(key, _)| key == PARAM))
+        .map(|(_, value)| value)
+        .filter(|value| !value.is_empty());
+    let rest = query
+        .split('&')
+        .filter(|pair| *pair != PARAM && !pair.starts_with("access_token="))
+        .collect::<Vec<_>>()
+        .join("&");
+    
+    let path_and_query = if rest.is_empty() {
+        req.path().to_string()
+    } else {
+        format!("{}?{}", req.path(), rest)
+    };
+    let mut parts = req.uri().clone().into_parts();
+    parts.path_and_query = path_and_query.parse().ok();
+    let uri = Uri::from_parts(parts).ok()?;
+    req.match_info_mut().get_mut().update(&uri);
+    req.head_mut().uri = uri;
+    token
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{test, App, HttpRequest, HttpResponse};
+    use super::*;
+    
+    async fn echo(req: HttpRequest) -> HttpResponse {
+        let auth = req.headers().get(AUTHORIZATION).and_then(|v| v.to_str().ok()).unwrap_or("-");
+        HttpResponse::Ok().body(format!("{} {}", req.uri(), auth))
+    }
+    
+    #[actix_web::test]
+    async fn test_token_leaves_the_uri_and_only_authenticates_events() {
+        let app = test::init_service(
+            App::new()
+                .wrap(QueryAccessToken("/events"))
+                .route("/events/stream", web::get().to(echo))
+                .route("/orders", web::post().to(echo)),
+        ).await;
+        
+        let req = test::TestRequest::get()
+            .uri("/events/stream?topics=orders&access_token=abc.def-ghi")
+            .to_request();
+        let body = test::call_and_read_body(&app, req).await;
+        assert_eq!(body, "/events/stream?topics=orders Bearer abc.def-ghi");
+        
+        let req = test::TestRequest::get()
+            .uri("/events/stream?access_token=abc")
+            .insert_header((AUTHORIZATION, "Bearer header"))
+            .to_request();
+        let body = test::call_and_read_body(&app, req).await;
+        assert_eq!(body, "/events/stream Bearer header");
+        
+        // Fora do prefixo o token some da URI, mas não autentica
+        let req = test::TestRequest::post().uri("/orders?access_token=abc").to_request();
+        let body = test::call_and_read_body(&app, req).await;
+        assert_eq!(body, "/orders -");
+    }
+}
@@ -0,0 +1,321 @@
+use std::sync::Arc;
+use chrono::Duration;
+use uuid::Uuid;
+use tracing::{info, warn};
+use delpopolo_core::CoreError;
+use delpopolo_domain::{RefreshToken, User, UserRole};
+use delpopolo_infrastructure::config::AuthConfig;
+use super::password::{hash_password, validate_new_password, verify_dummy_password, verify_password};
+use super::store::AuthStore;
+use super::tokens::{Claims, TokenKind, TokenService};
+
+/// Par de tokens devolvido no login e no refresh
+#[derive(Debug)]
+pub struct TokenPair {
+    pub access_token: String,
+    pub refresh_token: String,
+    pub expires_in: i64, // segundos até expirar o access token
+    pub user: User,
+}
+
+/// Política de bloqueio após senhas incorretas
+#[derive(Debug, Clone, Copy)]
+pub struct LockoutPolicy {
+    pub max_attempts: i32,
+    pub duration: Duration,
+}
+
+#[derive(Clone)]
+pub struct AuthService {
+    store: Arc<dyn AuthStore>,
+    tokens: TokenService,
+    lockout: LockoutPolicy,
+}
+
+impl AuthService {
+    pub fn new(store: Arc<dyn AuthStore>, config: &AuthConfig) -> Self {
+        Self {
+            store,
+            tokens: TokenService::new(config),
+            lockout: LockoutPolicy {
+                max_attempts: config.max_failed_logins,
+                duration: Duration::minutes(config.lockout_minutes),
+            },
+        }
+    }
+    
+    pub async fn login(&self, email: &str, password: &str) -> Result<TokenPair, CoreError> {
+        let invalid = || CoreError::unauthorized("E-mail ou senha inválidos");
+        
+        let Some(mut user) = self.store.find_user_by_email(email.trim()).await? else {
+            verify_dummy_password(password).await;
+            return Err(invalid());
+        };
+        
+        // Nada sobre a conta (bloqueio, inatividade) é revelado antes de a senha ser comprovada
+        if !verify_password(password, &user.password_hash).await {
+            if !user.is_locked() {
+                user.register_failed_login(self.lockout.max_attempts, self.lockout.duration);
+                self.store.save_user(&user).await?;
+                
+                if user.is_locked() {
+                    warn!("Account {} locked after repeated login failures", user.id);
+                }
+            }
+            return Err(invalid());
+        }
+        
+        if user.is_locked() {
+            warn!("Login attempt on locked account {}", user.id);
+            return Err(CoreError::unauthorized(
+                "Conta bloqueada temporariamente por excesso de tentativas",
+            ));
+        }
+        
+        if !user.is_active {
+            return Err(invalid());
+        }
+        
+        user.update_last_login();
+        self.store.save_user(&user).await?;
+        
+        info!("User {} logged in", user.id);
+        let (pair, _) = self.issue_pair(user, Uuid::new_v4()).await?;
+        Ok(pair)
+    }
+    
+    /// Troca o refresh token por um novo par (rotação). Reapresentar um token
+    /// já rotacionado indica vazamento: a família inteira é revogada.
+    pub async fn refresh(&self, refresh_token: &str) -> Result<TokenPair, CoreError> {
+        let claims = self.tokens.verify(refresh_token, TokenKind::Refresh)?;
+        
+        let stored = self.store
+            .find_refresh_token(claims.jti)
+            .await?
+            .ok_or_else(|| CoreError::unauthorized("Sessão inválida"))?;
+        
+        if stored.revoked_at.is_some() {
+            return Err(self.reject_reuse(&stored).await);
+        }
+        
+        if !stored.is_active() {
+            return Err(CoreError::unauthorized("Sessão expirada"));
+        }
+        
+        let user = self.active_user(stored.user_id).await?;
+        let (pair, new_token_id) = self.issue_pair(user, stored.family_id).await?;
+        
+        // Duas requisições com o mesmo token: só uma consegue revogá-lo; a outra é reuso
+        // e a revogação da família também derruba o token que ela acabou de emitir
+        if !self.store.rotate_refresh_token(stored.id, new_token_id).await? {
+            return Err(self.reject_reuse(&stored).await);
+        }
+        
+        Ok(pair)
+    }
+    
+    /// Encerra a sessão do refresh token informado
+    pub async fn logout(&self, refresh_token: &str) -> Result<(), CoreError> {
+        let claims = self.tokens.verify(refresh_token, TokenKind::Refresh)?;
+        
+        if let Some(family_id) = claims.fam {
+            self.store.revoke_token_family(family_id).await?;
+        }
+        
+        Ok(())
+    }
+    
+    /// Troca a senha e revoga todas as sessões do usuário
+    pub async fn change_password(&self, user: &User, current: &str, new_password: &str) -> Result<(), CoreError> {
+        if !verify_password(current, &user.password_hash).await {
+            return Err(CoreError::unauthorized("Senha atual incorreta"));
+        }
+        
+        validate_new_password(new_password)?;
+        
+        let mut user = user.clone();
+        user.change_password(hash_password(new_password).await?);
+        self.store.save_user(&user).await?;
+        self.store.revoke_user_tokens(user.id).await?;
+        
+        info!("User {} changed password", user.id);
+        Ok(())
+    }
+    
+    /// Cria o primeiro administrador na inicialização. Não altera um usuário que já
+    /// exista com o e-mail; devolve `true` quando o criou.
+    pub async fn bootstrap_admin(&self, name: &str, email: &str, password: &str) -> Result<bool, CoreError> {
+        let email = email.trim();
+        if self.store.find_user_by_email(email).await?.is_some() {
+            return Ok(false);
+        }
+        
+        validate_new_password(password)?;
+        let admin = User::new(name.to_string(), email.to_string(), hash_password(password).await?, UserRole::Admin);
+        self.store.save_user(&admin).await?;
+        
+        info!("Bootstrap admin {} created", admin.id);
+        Ok(true)
+    }
+    
+    /// Resolve o usuário de um access token (usado pelo extractor)
+    pub async fn authenticate(&self, access_token: &str) -> Result<User, CoreError> {
+        let claims = self.tokens.verify(access_token, TokenKind::Access)?;
+        self.active_user(claims.sub).await
+    }
+    
+    /// Confere só a assinatura e a validade do access token, sem consultar o banco
+    pub fn verify_access(&self, access_token: &str) -> Result<Claims, CoreError> {
+        self.tokens.verify(access_token, TokenKind::Access)
+    }
+    
+    /// Reapresentação de um token já revogado: revoga a família inteira
+    async fn reject_reuse(&self, stored: &RefreshToken) -> CoreError {
+        warn!("Refresh token reuse detected for user {}; revoking session family", stored.user_id);
+        match self.store.revoke_token_family(stored.family_id).await {
+            Ok(()) => CoreError::unauthorized("Sessão revogada"),
+            Err(e) => e,
+        }
+    }
+    
+    async fn active_user(&self, user_id: Uuid) -> Result<User, CoreError> {
+        let user = self.store
+            .find_user_by_id(user_id)
+            .await?
+            .ok_or_else(|| CoreError::unauthorized("Usuário não encontrado"))?;
+        
+        if !user.is_active || user.is_locked() {
+            return Err(CoreError::unauthorized("Usuário inativo ou bloqueado"));
+        }
+        
+        Ok(user)
+    }
+    
+    /// Emite access + refresh e persiste o refresh; devolve também o id (jti) do refresh
+    async fn issue_pair(&self, user: User, family_id: Uuid) -> Result<(TokenPair, Uuid), CoreError> {
+        let access = self.tokens.issue_access(&user)?;
+        let refresh = self.tokens.issue_refresh(&user, family_id)?;
+        
+        self.store
+            .save_refresh_token(&RefreshToken::new(
+                refresh.claims.jti,
+                user.id,
+                family_id,
+                refresh.expires_at(),
+            ))
+            .await?;
+        
+        let pair = TokenPair {
+            access_token: access.token,
+            refresh_token: refresh.token,
+            expires_in: self.tokens.access_ttl().num_seconds(),
+            user,
+        };
+        
+        Ok((pair, refresh.claims.jti))
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::auth::password::hash_password_blocking;
+    use crate::auth::store::memory::InMemoryAuthStore;
+    
+    pub(crate) fn config() -> AuthConfig {
+        AuthConfig {
+            jwt_secret: "segredo-de-teste".into(),
+            issuer: "delpopolo-api".to_string(),
+            access_token_minutes: 15,
+            refresh_token_days: 30,
+            max_failed_logins: 3,
+            lockout_minutes: 15,
+            bootstrap_admin: None,
+        }
+    }
+    
+    pub(crate) fn user(role: UserRole) -> User {
+        User::new(
+            "Maria".to_string(),
+            "maria@delpopolo.com.br".to_string(),
+            hash_password_blocking("fermento123").unwrap(),
+            role,
+        )
+    }
+    
+    fn service(user: User) -> (AuthService, Arc<InMemoryAuthStore>) {
+        let store = Arc::new(InMemoryAuthStore::default().with_user(user));
+        (AuthService::new(store.clone(), &config()), store)
+    }
+    
+    #[actix_web::test]
+    async fn test_login_and_lockout() {
+        let maria = user(UserRole::Cashier);
+        let (auth, store) = service(maria.clone());
+        
+        let pair = auth.login("MARIA@delpopolo.com.br", "fermento123").await.unwrap();
+        assert_eq!(auth.authenticate(&pair.access_token).await.unwrap().id, maria.id);
+        assert!(auth.authenticate(&pair.refresh_token).await.is_err());
+        
+        for _ in 0..3 {
+            assert!(auth.login("maria@delpopolo.com.br", "errada").await.is_err());
+        }
+        assert!(store.user(maria.id).is_locked());
+        
+        // Senha errada numa conta bloqueada não revela o bloqueio
+        let err = auth.login("maria@delpopolo.com.br", "errada").await.unwrap_err();
+        assert!(!err.to_string().contains("bloqueada"));
+        
+        // Senha correta não entra enquanto a conta estiver bloqueada
+        let err = auth.login("maria@delpopolo.com.br", "fermento123").await.unwrap_err();
+        assert!(err.to_string().contains("bloqueada"));
+        
+        // E-mail inexistente recebe a mesma resposta de senha errada
+        let unknown = auth.login("joao@delpopolo.com.br", "fermento123").await.unwrap_err();
+        let wrong = auth.login("maria@delpopolo.com.br", "errada").await.unwrap_err();
+        assert_eq!(unknown.to_string(), wrong.to_string());
+    }
+    
+    #[actix_web::test]
+    async fn test_refresh_rotation_detects_reuse() {
+        let (auth, _) = service(user(UserRole::Manager));
+        
+        let first = auth.login("maria@delpopolo.com.br", "fermento123").await.unwrap();
+        let second = auth.refresh(&first.refresh_token).await.unwrap();
+        assert_ne!(first.refresh_token, second.refresh_token);
+        
+        // Reuso do token antigo revoga também o token novo
+        assert!(auth.refresh(&first.refresh_token).await.is_err());
+        assert!(auth.refresh(&second.refresh_token).await.is_err());
+    }
+    
+    #[actix_web::test]
+    async fn test_bootstrap_admin_only_once() {
+        let (auth, _) = service(user(UserRole::Cashier));
+        
+        assert!(auth.bootstrap_admin("Dono", "dono@delpopolo.com.br", "curta").await.is_err());
+        assert!(auth.bootstrap_admin("Dono", "dono@delpopolo.com.br", "padaria2025").await.unwrap());
+        let pair = auth.login("dono@delpopolo.com.br", "padaria2025").await.unwrap();
+        assert_eq!(pair.user.role, UserRole::Admin);
+        
+        // Reiniciar com a mesma configuração não recria nem troca a senha
+        assert!(!auth.bootstrap_admin("Dono", "dono@delpopolo.com.br", "outraSenha9").await.unwrap());
+        assert!(!auth.bootstrap_admin("Maria", "maria@delpopolo.com.br", "outraSenha9").await.unwrap());
+        assert!(auth.login("maria@delpopolo.com.br", "fermento123").await.is_ok());
+    }
+    
+    #[actix_web::test]
+    async fn test_change_password_revokes_sessions() {
+        let maria = user(UserRole::Admin);
+        let (auth, store) = service(maria.clone());
+        let pair = auth.login("maria@delpopolo.com.br", "fermento123").await.unwrap();
+        
+        assert!(matches!(auth.change_password(&maria, "errada", "novaSenha1").await, Err(CoreError::Unauthorized(_))));
+        assert!(auth.change_password(&maria, "fermento123", "curta").await.is_err());
+        auth.change_password(&store.user(maria.id), "fermento123", "novaSenha1").await.unwrap();
+        
+        assert!(auth.refresh(&pair.refresh_token).await.is_err());
+        assert!(auth.login("maria@delpopolo.com.br", "fermento123").await.is_err());
+        assert!(auth.login("maria@delpopolo.com.br", "novaSenha1").await.is_ok());
+    }
+}
@@ -0,0 +1,141 @@
+use async_trait::async_trait;
+use uuid::Uuid;
+use delpopolo_core::CoreError;
+use delpopolo_domain::{RefreshToken, User};
+use delpopolo_infrastructure::repositories::UserRepository;
+
+/// Persistência usada pela autenticação (usuários e refresh tokens)
+#[async_trait]
+pub trait AuthStore: Send + Sync {
+    async fn find_user_by_id(&self, id: Uuid) -> Result<Option<User>, CoreError>;
+    async fn find_user_by_email(&self, email: &str) -> Result<Option<User>, CoreError>;
+    async fn save_user(&self, user: &User) -> Result<(), CoreError>;
+    async fn save_refresh_token(&self, token: &RefreshToken) -> Result<(), CoreError>;
+    async fn find_refresh_token(&self, id: Uuid) -> Result<Option<RefreshToken>, CoreError>;
+    /// Revoga o token se ainda estiver ativo, numa operação só; `false` se já estava revogado
+    async fn rotate_refresh_token(&self, id: Uuid, replaced_by: Uuid) -> Result<bool, CoreError>;
+    async fn revoke_token_family(&self, family_id: Uuid) -> Result<(), CoreError>;
+    async fn revoke_user_tokens(&self, user_id: Uuid) -> Result<(), CoreError>;
+}
+
+#[async_trait]
+impl AuthStore for UserRepository {
+    async fn find_user_by_id(&self, id: Uuid) -> Result<Option<User>, CoreError> {
+        self.find_by_id(id).await.map_err(CoreError::database)
+    }
+    
+    async fn find_user_by_email(&self, email: &str) -> Result<Option<User>, CoreError> {
+        self.find_by_email(email).await.map_err(CoreError::database)
+    }
+    
+    async fn save_user(&self, user: &User) -> Result<(), CoreError> {
+        self.save(user).await.map_err(CoreError::database)
+    }
+    
+    async fn save_refresh_token(&self, token: &RefreshToken) -> Result<(), CoreError> {
+        UserRepository::save_refresh_token(self, token).await.map_err(CoreError::database)
+    }
+    
+    async fn find_refresh_token(&self, id: Uuid) -> Result<Option<RefreshToken>, CoreError> {
+        UserRepository::find_refresh_token(self, id).await.map_err(CoreError::database)
+    }
+    
+    async fn rotate_refresh_token(&self, id: Uuid, replaced_by: Uuid) -> Result<bool, CoreError> {
+        UserRepository::rotate_refresh_token(self, id, replaced_by).await.map_err(CoreError::database)
+    }
+    
+    async fn revoke_token_family(&self, family_id: Uuid) -> Result<(), CoreError> {
+        UserRepository::revoke_token_family(self, family_id)
+            .await
+            .map(|_| ())
+            .map_err(CoreError::database)
+    }
+    
+    async fn revoke_user_tokens(&self, user_id: Uuid) -> Result<(), CoreError> {
+        UserRepository::revoke_user_tokens(self, user_id)
+            .await
+            .map(|_| ())
+            .map_err(CoreError::database)
+    }
+}
+
+#[cfg(test)]
+pub mod memory {
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+    use chrono::Utc;
+    use super::*;
+    
+    /// Armazenamento em memória para os testes
+    #[derive(Default)]
+    pub struct InMemoryAuthStore {
+        users: Mutex<HashMap<Uuid, User>>,
+        tokens: Mutex<HashMap<Uuid, RefreshToken>>,
+    }
+    
+    impl InMemoryAuthStore {
+        pub fn with_user(self, user: User) -> Self {
+            self.users.lock().unwrap().insert(user.id, user);
+            self
+        }
+        
+        pub fn user(&self, id: Uuid) -> User {
+            self.users.lock().unwrap()[&id].clone()
+        }
+    }
+    
+    #[async_trait]
+    impl AuthStore for InMemoryAuthStore {
+        async fn find_user_by_id(&self, id: Uuid) -> Result<Option<User>, CoreError> {
+            Ok(self.users.lock().unwrap().get(&id).cloned())
+        }
+        
+        async fn find_user_by_email(&self, email: &str) -> Result<Option<User>, CoreError> {
+            Ok(self.users.lock().unwrap().values().find(|u| u.email.eq_ignore_ascii_case(email)).cloned())
+        }
+        
+        async fn save_user(&self, user: &User) -> Result<(), CoreError> {
+            self.users.lock().unwrap().insert(user.id, user.clone());
+            Ok(())
+        }
+        
+        async fn save_refresh_token(&self, token: &RefreshToken) -> Result<(), CoreError> {
+            self.tokens.lock().unwrap().insert(token.id, token.clone());
+            Ok(())
+        }
+        
+        async fn find_refresh_token(&self, id: Uuid) -> Result<Option<RefreshToken>, CoreError> {
+            Ok(self.tokens.lock().unwrap().get(&id).cloned())
+        }
+        
+        async fn rotate_refresh_token(&self, id: Uuid, replaced_by: Uuid) -> Result<bool, CoreError> {
+            let mut tokens = self.tokens.lock().unwrap();
+            match tokens.get_mut(&id) {
+                Some(token) if token.revoked_at.is_none() => {
+                    token.revoked_at = Some(Utc::now());
+                    token.replaced_by = Some(replaced_by);
+                    Ok(true)
+                }
+                _ => Ok(false),
+            }
+        }
+        
+        async fn revoke_token_family(&self, family_id: Uuid) -> Result<(), CoreError> {
+            for token in self.tokens.lock().unwrap().values_mut() {
+                if token.family_id == family_id && token.revoked_at.is_none() {
+                    token.revoked_at = Some(Utc::now());
+                }
+            }
+            Ok(())
+        }
+        
+        async fn revoke_user_tokens(&self, user_id: Uuid) -> Result<(), CoreError> {
+            for token in self.tokens.lock().unwrap().values_mut() {
+                if token.user_id == user_id && token.revoked_at.is_none() {
+                    token.revoked_at = Some(Utc::now());
+                }
+            }
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use chrono::{DateTime, Duration, Utc};
+use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use delpopolo_core::CoreError;
+use delpopolo_domain::{User, UserRole};
+use delpopolo_infrastructure::config::AuthConfig;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenKind {
+    Access,
+    Refresh,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Claims {
+    pub sub: Uuid,
+    pub role: UserRole,
+    pub typ: TokenKind,
+    pub jti: Uuid,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fam: Option<Uuid>, // família do refresh token
+    pub iss: String,
+    pub iat: i64,
+    pub exp: i64,
+}
+
+/// Emissão e validação dos JWT (HS256) de acesso e de refresh
+#[derive(Clone)]
+pub struct TokenService {
+    encoding: EncodingKey,
+    decoding: DecodingKey,
+    issuer: String,
+    access_ttl: Duration,
+    refresh_ttl: Duration,
+}
+
+pub struct IssuedToken {
+    pub token: String,
+    pub claims: Claims,
+}
+
+impl IssuedToken {
+    pub fn expires_at(&self) -> DateTime<Utc> {
+        DateTime::from_timestamp(self.claims.exp, 0).unwrap_or_else(Utc::now)
+    }
+}
+
+impl TokenService {
+    pub fn new(config: &AuthConfig) -> Self {
+        Self {
+            encoding: EncodingKey::from_secret(config.jwt_secret.expose().as_bytes()),
+            decoding: DecodingKey::from_secret(config.jwt_secret.expose().as_bytes()),
+            issuer: config.issuer.clone(),
+            access_ttl: Duration::minutes(config.access_token_minutes),
+            refresh_ttl: Duration::days(config.refresh_token_days),
+        }
+    }
+    
+    pub fn access_ttl(&self) -> Duration {
+        self.access_ttl
+    }
+    
+    pub fn issue_access(&self, user: &User) -> Result<IssuedToken, CoreError> {
+        self.issue(user, TokenKind::Access, None, self.access_ttl)
+    }
+    
+    pub fn issue_refresh(&self, user: &User, family_id: Uuid) -> Result<IssuedToken, CoreError> {
+        self.issue(user, TokenKind::Refresh, Some(family_id), self.refresh_ttl)
+    }
+    
+    fn issue(&self, user: &User, typ: TokenKind, fam: Option<Uuid>, ttl: Duration) -> Result<IssuedToken, CoreError> {
+        let now = Utc::now();
+        let claims = Claims {
+            sub: user.id,
+            role: user.role,
+            typ,
+            jti: Uuid::new_v4(),
+            fam,
+            iss: self.issuer.clone(),
+            iat: now.timestamp(),
+            exp: (now + ttl).timestamp(),
+        };
+        
+        let token = encode(&Header::new(Algorithm::HS256), &claims, &self.encoding)
+            .map_err(|e| CoreError::internal(format!("Falha ao assinar token: {}", e)))?;
+        
+        Ok(IssuedToken { token, claims })
+    }
+    
+    /// Valida assinatura, emissor, expiração e o tipo esperado do token
+    pub fn verify(&self, token: &str, expected: TokenKind) -> Result<Claims, CoreError> {
+        let mut validation = Validation::new(Algorithm::HS256);
+        validation.set_issuer(&[&self.issuer]);
+        validation.leeway = 0;
+        
+        let claims = decode::<Claims>(token, &self.decoding, &validation)
+            .map_err(|_| CoreError::unauthorized("Token inválido ou expirado"))?
+            .claims;
+        
+        if claims.typ != expected {
+            return Err(CoreError::unauthorized("Tipo de token inválido"));
+        }
+        
+        Ok(claims)
+    }
+}
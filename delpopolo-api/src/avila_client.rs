@@ -0,0 +1,190 @@
+use std::time::{Duration, Instant};
+use anyhow::{bail, Context, Result};
+use reqwest::{Client, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tokio::sync::Mutex;
+use delpopolo_infrastructure::config::AvilaConfig;
+use delpopolo_infrastructure::Secret;
+
+/// Validade assumida quando o Avila não informa `expires_in`
+const DEFAULT_TOKEN_TTL: Duration = Duration::from_secs(3600);
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: Option<u64>,
+}
+
+struct AccessToken {
+    value: String,
+    refresh_at: Instant,
+}
+
+/// Cliente do Avila Framework com token `client_credentials` renovado antes de expirar.
+/// Só existe no modo `avila-connected`; em `standalone` ninguém o constrói.
+pub struct AvilaClient {
+    http: Client,
+    api_url: String,
+    client_id: String,
+    client_secret: Secret,
+    refresh_margin: Duration,
+    token: Mutex<Option<AccessToken>>,
+}
+
+impl AvilaClient {
+    pub fn new(config: &AvilaConfig) -> Result<Self> {
+        if config.client_secret.is_empty() {
+            bail!("AVILA__CLIENT_SECRET is required in avila-connected mode");
+        }
+        Ok(Self {
+            http: Client::builder().timeout(Duration::from_millis(config.timeout_ms)).build()?,
+            api_url: config.api_url.trim_end_matches('/').to_string(),
+            client_id: config.client_id.clone(),
+            client_secret: config.client_secret.clone(),
+            refresh_margin: Duration::from_secs(config.refresh_margin_secs),
+            token: Mutex::new(None),
+        })
+    }
+    
+    pub fn api_url(&self) -> &str {
+        &self.api_url
+    }
+    
+    /// Token em cache ou um novo, se faltar menos que a margem para expirar
+    pub async fn access_token(&self) -> Result<String> {
+        let mut token = self.token.lock().await;
+        if let Some(current) = token.as_ref().filter(|t| Instant::now() < t.refresh_at) {
+            return Ok(current.value.clone());
+        }
+        let fresh = self.request_token().await?;
+        let value = fresh.value.clone();
+        *token = Some(fresh);
+        Ok(value)
+    }
+    
+    async fn request_token(&self) -> Result<AccessToken> {
+        let response = self
+            .http
+            .post(format!("{}/auth/token", self.api_url))
+            .json(&json!({
+                "client_id": self.client_id,
+                "client_secret": self.client_secret.expose(),
+                "grant_type": "client_credentials"
+            }))
+            .send()
+            .await
+            .context("requesting Avila token")?;
+        if !response.status().is_success() {
+            bail!("Avila token request failed with {}", response.status());
+        }
+        
+        let body: TokenResponse = response.json().await.context("decoding Avila token")?;
+        let ttl = body.expires_in.map(Duration::from_secs).unwrap_or(DEFAULT_TOKEN_TTL);
+        tracing::debug!("Obtained Avila token valid for {:?}", ttl);
+        Ok(AccessToken {
+            value: body.access_token,
+            refresh_at: Instant::now() + ttl.saturating_sub(self.refresh_margin),
+        })
+    }
+    
+    /// POST autenticado em `{api_url}{path}`; um 401 descarta o token para a próxima chamada
+    pub async fn post_json<T: Serialize + ?Sized>(&self, path: &str, body: &T) -> Result<Response> {
+        let token = self.access_token().await?;
+        let response = self
+            .http
+            .post(format!("{}{}", self.api_url, path))
+            .bearer_auth(token)
+            .json(body)
+            .send()
+            .await?;
+        if response.status() == StatusCode::UNAUTHORIZED {
+            self.token.lock().await.take();
+        }
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+pub mod stub {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use actix_web::{web, App, HttpResponse, HttpServer};
+    use serde_json::Value;
+    use super::*;
+    
+    /// Avila local: emite tokens com a validade pedida e guarda os corpos recebidos por caminho
+    #[derive(Default)]
+    pub struct AvilaStub {
+        pub tokens_issued: AtomicUsize,
+        pub received: Mutex<Vec<(String, Value)>>,
+    }
+    
+    pub fn start(expires_in: u64) -> (AvilaConfig, Arc<AvilaStub>) {
+        let stub = Arc::new(AvilaStub::default());
+        let data = web::Data::from(stub.clone());
+        let server = HttpServer::new(move || {
+            App::new()
+                .app_data(data.clone())
+                .route("/auth/token", web::post().to(move |stub: web::Data<AvilaStub>| async move {
+                    let n = stub.tokens_issued.fetch_add(1, Ordering::SeqCst) + 1;
+                    HttpResponse::Ok().json(json!({"access_token": format!("token-{n}"), "expires_in": expires_in}))
+                }))
+                .route("/v1/{kind}", web::post().to(|stub: web::Data<AvilaStub>, kind: web::Path<String>, body: web::Json<Value>| async move {
+                    stub.received.lock().unwrap().push((kind.into_inner(), body.into_inner()));
+                    HttpResponse::Accepted().finish()
+                }))
+        })
+        .workers(1)
+        .bind(("127.0.0.1", 0))
+        .unwrap();
+        let config = config(format!("http://{}", server.addrs()[0]));
+        actix_web::rt::spawn(server.run());
+        (config, stub)
+    }
+    
+    /// Porta sem ninguém escutando
+    pub fn offline() -> AvilaConfig {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        config(format!("http://{}", listener.local_addr().unwrap()))
+    }
+    
+    fn config(api_url: String) -> AvilaConfig {
+        AvilaConfig {
+            api_url,
+            client_secret: "segredo".into(),
+            refresh_margin_secs: 60,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::Ordering;
+    use delpopolo_infrastructure::config::AvilaMode;
+    use super::*;
+    
+    #[test]
+    fn test_connected_mode_requires_secret() {
+        let config = AvilaConfig { mode: AvilaMode::AvilaConnected, ..Default::default() };
+        assert!(AvilaClient::new(&config).is_err());
+    }
+    
+    #[actix_web::test]
+    async fn test_token_is_reused_until_refresh_margin() {
+        // Validade longa: o mesmo token serve as chamadas seguintes
+        let (config, stub) = stub::start(3600);
+        let client = AvilaClient::new(&config).unwrap();
+        assert_eq!(client.access_token().await.unwrap(), "token-1");
+        assert_eq!(client.access_token().await.unwrap(), "token-1");
+        assert_eq!(stub.tokens_issued.load(Ordering::SeqCst), 1);
+        
+        // Validade menor que a margem: renova antes de expirar, a cada uso
+        let (config, stub) = stub::start(30);
+        let client = AvilaClient::new(&config).unwrap();
+        client.access_token().await.unwrap();
+        assert_eq!(client.access_token().await.unwrap(), "token-2");
+        assert_eq!(stub.tokens_issued.load(Ordering::SeqCst), 2);
+    }
+}
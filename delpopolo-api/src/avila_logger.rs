@@ -1,63 +1,446 @@
-use reqwest::Client;
-use serde_json::json;
-use anyhow::Result;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use anyhow::{Context, Result};
+use reqwest::StatusCode;
+use serde::Serialize;
+use serde_json::{json, Value};
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+use delpopolo_infrastructure::config::AvilaLoggerConfig;
+use crate::avila_client::AvilaClient;
+use crate::metrics::METRICS;
 
+const INITIAL_BACKOFF: Duration = Duration::from_millis(200);
+const MAX_BACKOFF: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Kind {
+    Log,
+    Metric,
+}
+
+impl Kind {
+    fn path(self) -> &'static str {
+        match self {
+            Kind::Log => "logs",
+            Kind::Metric => "metrics",
+        }
+    }
+    
+    fn endpoint(self) -> &'static str {
+        match self {
+            Kind::Log => "/v1/logs",
+            Kind::Metric => "/v1/metrics",
+        }
+    }
+    
+    fn from_path(path: &str) -> Option<Self> {
+        match path {
+            "logs" => Some(Kind::Log),
+            "metrics" => Some(Kind::Metric),
+            _ => None,
+        }
+    }
+}
+
+struct Entry {
+    kind: Kind,
+    payload: Value,
+}
+
+/// Contadores do exportador, espelhados em `delpopolo_avila_logger_records_total`
+#[derive(Debug, Default)]
+pub struct LoggerStats {
+    pub sent: AtomicU64,
+    pub spooled: AtomicU64,
+    /// Recusados pelo Avila (4xx); reenviar não adiantaria
+    pub rejected: AtomicU64,
+    /// Fila cheia, logger encerrado ou spool sem espaço
+    pub dropped: AtomicU64,
+}
+
+/// Leitura dos contadores, exposta no `/health/ready`
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct LoggerSnapshot {
+    pub sent: u64,
+    pub spooled: u64,
+    pub rejected: u64,
+    pub dropped: u64,
+}
+
+impl LoggerStats {
+    pub fn snapshot(&self) -> LoggerSnapshot {
+        LoggerSnapshot {
+            sent: self.sent.load(Ordering::Relaxed),
+            spooled: self.spooled.load(Ordering::Relaxed),
+            rejected: self.rejected.load(Ordering::Relaxed),
+            dropped: self.dropped.load(Ordering::Relaxed),
+        }
+    }
+    
+    fn add(&self, counter: &AtomicU64, outcome: &str, count: usize) {
+        counter.fetch_add(count as u64, Ordering::Relaxed);
+        METRICS.avila_records(outcome, count as u64);
+    }
+}
+
+/// Logs e métricas para o Avila sem esperar a rede: os registros entram numa fila limitada e
+/// um worker envia em lotes, com novas tentativas e spool em disco quando o Avila está fora.
+/// No modo `standalone` o logger é desligado e descarta tudo sem contar como perda.
+#[derive(Clone)]
 pub struct AvilaLogger {
-    client: Client,
-    token: String,
+    sender: Option<mpsc::Sender<Entry>>,
+    stats: Arc<LoggerStats>,
+}
+
+/// Worker do `AvilaLogger`; `shutdown` esvazia a fila antes de o processo sair
+pub struct LoggerWorker {
+    stop: oneshot::Sender<()>,
+    handle: JoinHandle<()>,
 }
 
 impl AvilaLogger {
-    pub fn new(token: String) -> Self {
-        Self {
-            client: Client::new(),
-            token,
+    pub fn start(config: &AvilaLoggerConfig, client: Arc<AvilaClient>) -> (Self, LoggerWorker) {
+        let (sender, receiver) = mpsc::channel(config.capacity.max(1));
+        let (stop, stopped) = oneshot::channel();
+        let stats = Arc::new(LoggerStats::default());
+        
+        let exporter = Exporter {
+            client,
+            batch_size: config.batch_size.max(1),
+            max_retries: config.max_retries,
+            spool: Spool { dir: PathBuf::from(&config.spool_path), max_files: config.spool_max_files },
+            stats: stats.clone(),
+        };
+        let handle = tokio::spawn(exporter.run(receiver, stopped, Duration::from_millis(config.flush_interval_ms)));
+        
+        (Self { sender: Some(sender), stats }, LoggerWorker { stop, handle })
+    }
+    
+    pub fn disabled() -> Self {
+        Self { sender: None, stats: Arc::default() }
+    }
+    
+    pub fn stats(&self) -> &LoggerStats {
+        &self.stats
+    }
+    
+    fn enqueue(&self, kind: Kind, payload: Value) {
+        let Some(sender) = &self.sender else {
+            return;
+        };
+        if sender.try_send(Entry { kind, payload }).is_err() {
+            self.stats.add(&self.stats.dropped, "dropped", 1);
         }
     }
     
-    /// Log todas as operaes em api.avila.inc/logs
-    pub async fn log_operation(&self, service: &str, operation: &str, success: bool, details: serde_json::Value) -> Result<()> {
-        let payload = json!({
+    /// Log de operações em `/v1/logs`
+    pub fn log_operation(&self, service: &str, operation: &str, success: bool, details: Value) {
+        self.enqueue(Kind::Log, json!({
             "timestamp": chrono::Utc::now().to_rfc3339(),
             "service": service,
             "operation": operation,
             "success": success,
             "details": details,
             "source": "delpopolo-panificadora"
-        });
-        
-        let _response = self.client
-            .post("https://api.avila.inc/v1/logs")
-            .header("Authorization", format!("Bearer {}", self.token))
-            .json(&payload)
-            .send()
-            .await;
-        
-        Ok(())
+        }));
     }
     
-    /// Enviar mtricas para api.avila.inc/metrics
-    pub async fn send_metric(&self, metric_name: &str, value: f64, tags: serde_json::Value) -> Result<()> {
-        let payload = json!({
+    /// Métricas em `/v1/metrics`
+    pub fn send_metric(&self, metric_name: &str, value: f64, tags: Value) {
+        self.enqueue(Kind::Metric, json!({
             "metric": metric_name,
             "value": value,
             "tags": tags,
             "timestamp": chrono::Utc::now().to_rfc3339(),
             "source": "delpopolo-panificadora"
-        });
+        }));
+    }
+    
+    /// Log de eventos de negócio
+    pub fn log_business_event(&self, event_type: &str, data: Value) {
+        self.log_operation("business", event_type, true, data)
+    }
+}
+
+impl LoggerWorker {
+    /// Para de aceitar registros, envia o que restou numa única tentativa e grava o resto no spool
+    pub async fn shutdown(self) {
+        let _ = self.stop.send(());
+        if let Err(e) = self.handle.await {
+            tracing::error!("Avila logger worker panicked: {}", e);
+        }
+    }
+}
+
+enum Delivery {
+    Sent,
+    Rejected,
+    Failed,
+}
+
+struct Exporter {
+    client: Arc<AvilaClient>,
+    batch_size: usize,
+    max_retries: u32,
+    spool: Spool,
+    stats: Arc<LoggerStats>,
+}
+
+impl Exporter {
+    async fn run(self, mut receiver: mpsc::Receiver<Entry>, mut stopped: oneshot::Receiver<()>, interval: Duration) {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        let mut batch = Vec::with_capacity(self.batch_size);
         
-        let _response = self.client
-            .post("https://api.avila.inc/v1/metrics")
-            .header("Authorization", format!("Bearer {}", self.token))
-            .json(&payload)
-            .send()
-            .await;
+        loop {
+            tokio::select! {
+                entry = receiver.recv() => match entry {
+                    Some(entry) => {
+                        batch.push(entry);
+                        if batch.len() >= self.batch_size {
+                            self.flush(&mut batch, self.max_retries).await;
+                        }
+                    }
+                    None => break,
+                },
+                _ = ticker.tick() => {
+                    self.flush(&mut batch, self.max_retries).await;
+                    self.replay_spool().await;
+                }
+                _ = &mut stopped => break,
+            }
+        }
         
-        Ok(())
+        // Encerramento: o que já estava na fila vai no último lote, sem esperar backoff
+        receiver.close();
+        while let Some(entry) = receiver.recv().await {
+            batch.push(entry);
+        }
+        self.flush(&mut batch, 0).await;
+    }
+    
+    async fn flush(&self, batch: &mut Vec<Entry>, retries: u32) {
+        let mut groups: BTreeMap<Kind, Vec<Value>> = BTreeMap::new();
+        for entry in batch.drain(..) {
+            groups.entry(entry.kind).or_default().push(entry.payload);
+        }
+        
+        for (kind, payloads) in groups {
+            match self.deliver(kind, &payloads, retries).await {
+                Delivery::Sent => self.stats.add(&self.stats.sent, "sent", payloads.len()),
+                Delivery::Rejected => self.stats.add(&self.stats.rejected, "rejected", payloads.len()),
+                Delivery::Failed => match self.spool.write(kind, &payloads).await {
+                    Ok(evicted) => {
+                        self.stats.add(&self.stats.spooled, "spooled", payloads.len());
+                        if evicted > 0 {
+                            self.stats.add(&self.stats.dropped, "dropped", evicted);
+                        }
+                    }
+                    Err(e) => {
+                        tracing::warn!("Could not spool {} Avila {}: {:#}", payloads.len(), kind.path(), e);
+                        self.stats.add(&self.stats.dropped, "dropped", payloads.len());
+                    }
+                },
+            }
+        }
+    }
+    
+    /// Backoff exponencial entre as tentativas; 4xx não é repetido, exceto 401 (token
+    /// renovado na próxima tentativa), 408 e 429
+    async fn deliver(&self, kind: Kind, payloads: &[Value], retries: u32) -> Delivery {
+        let mut backoff = INITIAL_BACKOFF;
+        
+        for attempt in 0..=retries {
+            if attempt > 0 {
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+            }
+            match self.client.post_json(kind.endpoint(), payloads).await {
+                Ok(response) if response.status().is_success() => return Delivery::Sent,
+                Ok(response)
+                    if response.status().is_client_error()
+                        && response.status() != StatusCode::UNAUTHORIZED
+                        && response.status() != StatusCode::REQUEST_TIMEOUT
+                        && response.status() != StatusCode::TOO_MANY_REQUESTS =>
+                {
+                    tracing::warn!("Avila rejected {} {} with {}", payloads.len(), kind.path(), response.status());
+                    return Delivery::Rejected;
+                }
+                Ok(response) => {
+                    tracing::debug!("Avila answered {} to {} (attempt {})", response.status(), kind.endpoint(), attempt + 1)
+                }
+                Err(e) => tracing::debug!("Avila unreachable for {} (attempt {}): {:#}", kind.endpoint(), attempt + 1, e),
+            }
+        }
+        Delivery::Failed
+    }
+    
+    /// Reenvia os lotes do spool, do mais antigo ao mais novo, até a primeira falha
+    async fn replay_spool(&self) {
+        loop {
+            let Some((path, kind)) = self.spool.oldest().await else {
+                return;
+            };
+            let payloads = match Spool::read(&path).await {
+                Ok(payloads) => payloads,
+                Err(e) => {
+                    tracing::warn!("Discarding unreadable spool file {}: {:#}", path.display(), e);
+                    let _ = tokio::fs::remove_file(&path).await;
+                    continue;
+                }
+            };
+            match self.deliver(kind, &payloads, 0).await {
+                Delivery::Sent => self.stats.add(&self.stats.sent, "sent", payloads.len()),
+                Delivery::Rejected => self.stats.add(&self.stats.rejected, "rejected", payloads.len()),
+                Delivery::Failed => return,
+            }
+            let _ = tokio::fs::remove_file(&path).await;
+        }
+    }
+}
+
+/// Um arquivo JSON por lote, com nome ordenável pelo horário de gravação
+struct Spool {
+    dir: PathBuf,
+    max_files: usize,
+}
+
+/// IO pelo `tokio::fs`, para o worker não travar uma thread do runtime com o disco lento
+impl Spool {
+    /// Grava o lote; devolve quantos registros foram descartados para respeitar `max_files`
+    async fn write(&self, kind: Kind, payloads: &[Value]) -> Result<usize> {
+        tokio::fs::create_dir_all(&self.dir).await.with_context(|| format!("creating {}", self.dir.display()))?;
+        
+        let mut evicted = 0;
+        let mut files = self.files().await;
+        while self.max_files > 0 && files.len() >= self.max_files {
+            let oldest = files.remove(0);
+            evicted += Self::read(&oldest).await.map(|p| p.len()).unwrap_or(0);
+            tokio::fs::remove_file(&oldest).await.ok();
+        }
+        
+        let name = format!(
+            "{:020}-{}-{}.json",
+            chrono::Utc::now().timestamp_micros(),
+            kind.path(),
+            Uuid::new_v4().simple()
+        );
+        tokio::fs::write(self.dir.join(name), serde_json::to_vec(payloads)?).await?;
+        Ok(evicted)
+    }
+    
+    async fn files(&self) -> Vec<PathBuf> {
+        let mut files = Vec::new();
+        if let Ok(mut dir) = tokio::fs::read_dir(&self.dir).await {
+            while let Ok(Some(entry)) = dir.next_entry().await {
+                let path = entry.path();
+                if path.extension().is_some_and(|ext| ext == "json") {
+                    files.push(path);
+                }
+            }
+        }
+        files.sort();
+        files
     }
     
-    /// Log de eventos de negcio
-    pub async fn log_business_event(&self, event_type: &str, data: serde_json::Value) -> Result<()> {
-        self.log_operation("business", event_type, true, data).await
+    async fn oldest(&self) -> Option<(PathBuf, Kind)> {
+        self.files().await.into_iter().find_map(|path| {
+            let kind = path.file_stem()?.to_str()?.split('-').nth(1).and_then(Kind::from_path)?;
+            Some((path, kind))
+        })
+    }
+    
+    async fn read(path: &Path) -> Result<Vec<Value>> {
+        Ok(serde_json::from_slice(&tokio::fs::read(path).await?)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::avila_client::stub;
+    use delpopolo_infrastructure::config::AvilaConfig;
+    use super::*;
+    
+    fn start(avila: &AvilaConfig, config: AvilaLoggerConfig) -> (AvilaLogger, LoggerWorker) {
+        AvilaLogger::start(&config, Arc::new(AvilaClient::new(avila).unwrap()))
+    }
+    
+    fn config(spool: &Path) -> AvilaLoggerConfig {
+        AvilaLoggerConfig {
+            max_retries: 0,
+            flush_interval_ms: 50,
+            spool_path: spool.display().to_string(),
+            ..Default::default()
+        }
+    }
+    
+    fn spool_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("delpopolo-spool-{}", Uuid::new_v4()))
+    }
+    
+    #[actix_web::test]
+    async fn test_batches_by_kind_and_flushes_on_shutdown() {
+        let (avila, stub) = stub::start(3600);
+        let spool = spool_dir();
+        let (logger, worker) = start(&avila, AvilaLoggerConfig { flush_interval_ms: 60_000, ..config(&spool) });
+        
+        logger.log_business_event("product_created", json!({"sku": "PAO-01"}));
+        logger.log_business_event("product_created", json!({"sku": "PAO-02"}));
+        logger.send_metric("orders", 1.0, json!({}));
+        worker.shutdown().await;
+        
+        let received = stub.received.lock().unwrap();
+        let sizes: Vec<(&str, usize)> =
+            received.iter().map(|(kind, batch)| (kind.as_str(), batch.as_array().unwrap().len())).collect();
+        assert_eq!(sizes, [("logs", 2), ("metrics", 1)]);
+        assert_eq!(logger.stats().snapshot().sent, 3);
+        assert!(!spool.exists());
+    }
+    
+    #[actix_web::test]
+    async fn test_offline_batches_are_spooled_then_replayed() {
+        let spool = spool_dir();
+        let (logger, worker) = start(&stub::offline(), config(&spool));
+        logger.log_operation("products", "create", true, json!({}));
+        logger.log_operation("products", "update", false, json!({}));
+        worker.shutdown().await;
+        assert_eq!(logger.stats().spooled.load(Ordering::Relaxed), 2);
+        assert_eq!(std::fs::read_dir(&spool).unwrap().count(), 1);
+        
+        // Com o Avila de volta, o próximo ciclo reenvia e limpa o spool
+        let (avila, stub) = stub::start(3600);
+        let (logger, worker) = start(&avila, config(&spool));
+        for _ in 0..100 {
+            if !stub.received.lock().unwrap().is_empty() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        worker.shutdown().await;
+        assert_eq!(stub.received.lock().unwrap()[0].1.as_array().unwrap().len(), 2);
+        assert_eq!(logger.stats().sent.load(Ordering::Relaxed), 2);
+        assert_eq!(std::fs::read_dir(&spool).unwrap().count(), 0);
+        std::fs::remove_dir_all(&spool).ok();
+    }
+    
+    #[actix_web::test]
+    async fn test_full_queue_drops_instead_of_blocking() {
+        let spool = spool_dir();
+        let (logger, worker) = start(&stub::offline(), AvilaLoggerConfig { capacity: 2, ..config(&spool) });
+        
+        // Sem ceder a vez ao worker, só cabem dois registros na fila
+        for i in 0..5 {
+            logger.log_operation("orders", "create", true, json!({"n": i}));
+        }
+        assert_eq!(logger.stats().dropped.load(Ordering::Relaxed), 3);
+        
+        worker.shutdown().await;
+        assert_eq!(logger.stats().spooled.load(Ordering::Relaxed), 2);
+        std::fs::remove_dir_all(&spool).ok();
     }
 }
@@ -0,0 +1,102 @@
+use std::path::PathBuf;
+use image::{imageops::FilterType, ImageFormat};
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use delpopolo_core::CoreError;
+use delpopolo_infrastructure::config::StorageConfig;
+
+/// Lado máximo (px) de cada miniatura gerada
+pub const THUMBNAIL_SIZES: [u32; 2] = [480, 160];
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct StoredImage {
+    pub url: String,
+    pub thumbnails: Vec<String>,
+}
+
+/// Imagens de produtos em disco local, servidas em `public_url`
+#[derive(Debug, Clone)]
+pub struct ImageStorage {
+    root: PathBuf,
+    public_url: String,
+}
+
+impl ImageStorage {
+    pub fn new(root: impl Into<PathBuf>, public_url: impl Into<String>) -> Self {
+        Self {
+            root: root.into(),
+            public_url: public_url.into().trim_end_matches('/').to_string(),
+        }
+    }
+    
+    pub fn from_config(config: &StorageConfig) -> Self {
+        Self::new(PathBuf::from(&config.path).join("products"), format!("{}/products", config.public_url.trim_end_matches('/')))
+    }
+    
+    /// Valida o arquivo (PNG, JPEG ou WebP), grava o original e as miniaturas em JPEG
+    pub async fn store(&self, product_id: Uuid, bytes: Vec<u8>) -> Result<StoredImage, CoreError> {
+        let storage = self.clone();
+        tokio::task::spawn_blocking(move || storage.store_blocking(product_id, &bytes))
+            .await
+            .map_err(CoreError::internal)?
+    }
+    
+    fn store_blocking(&self, product_id: Uuid, bytes: &[u8]) -> Result<StoredImage, CoreError> {
+        let format = image::guess_format(bytes)
+            .ok()
+            .filter(|f| matches!(f, ImageFormat::Png | ImageFormat::Jpeg | ImageFormat::WebP))
+            .ok_or_else(|| CoreError::validation("Formato de imagem não suportado (use PNG, JPEG ou WebP)"))?;
+        let img = image::load_from_memory_with_format(bytes, format)
+            .map_err(|e| CoreError::validation(format!("Imagem inválida: {}", e)))?;
+        
+        std::fs::create_dir_all(&self.root).map_err(CoreError::internal)?;
+        
+        // Versão no nome evita cache desatualizado após nova imagem
+        let stem = format!("{}-{}", product_id.simple(), chrono::Utc::now().timestamp());
+        let original = format!("{}.{}", stem, format.extensions_str()[0]);
+        std::fs::write(self.root.join(&original), bytes).map_err(CoreError::internal)?;
+        
+        let mut thumbnails = Vec::with_capacity(THUMBNAIL_SIZES.len());
+        for size in THUMBNAIL_SIZES {
+            let name = format!("{}_{}.jpg", stem, size);
+            img.resize(size, size, FilterType::Lanczos3)
+                .to_rgb8()
+                .save_with_format(self.root.join(&name), ImageFormat::Jpeg)
+                .map_err(CoreError::internal)?;
+            thumbnails.push(format!("{}/{}", self.public_url, name));
+        }
+        
+        Ok(StoredImage {
+            url: format!("{}/{}", self.public_url, original),
+            thumbnails,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+    use image::{DynamicImage, GenericImageView, RgbImage};
+    use super::*;
+    
+    #[tokio::test]
+    async fn test_store_generates_thumbnails() {
+        let dir = std::env::temp_dir().join(format!("delpopolo-img-{}", Uuid::new_v4()));
+        let storage = ImageStorage::new(&dir, "/media/products/");
+        
+        let mut png = Vec::new();
+        DynamicImage::ImageRgb8(RgbImage::new(1000, 500))
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+            .unwrap();
+        
+        let stored = storage.store(Uuid::new_v4(), png).await.unwrap();
+        assert!(stored.url.starts_with("/media/products/") && stored.url.ends_with(".png"));
+        
+        let small = stored.thumbnails[1].rsplit('/').next().unwrap();
+        assert_eq!(image::open(dir.join(small)).unwrap().dimensions(), (160, 80));
+        
+        assert!(storage.store(Uuid::new_v4(), b"nao e imagem".to_vec()).await.is_err());
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
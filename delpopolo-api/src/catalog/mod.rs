@@ -0,0 +1,5 @@
+pub mod store;
+pub mod images;
+
+pub use images::{ImageStorage, StoredImage};
+pub use store::ProductStore;
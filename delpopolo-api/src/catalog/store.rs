@@ -0,0 +1,128 @@
+use async_trait::async_trait;
+use uuid::Uuid;
+use delpopolo_core::{traits::Repository, CoreError};
+use delpopolo_domain::Product;
+use delpopolo_infrastructure::repositories::product_repository::ProductQuery;
+use delpopolo_infrastructure::repositories::ProductRepository;
+
+/// Persistência do catálogo usada pelas rotas de produtos
+#[async_trait]
+pub trait ProductStore: Send + Sync {
+    async fn find(&self, id: Uuid) -> Result<Option<Product>, CoreError>;
+    async fn find_by_barcode(&self, barcode: &str) -> Result<Option<Product>, CoreError>;
+    async fn list(&self, query: &ProductQuery) -> Result<Vec<Product>, CoreError>;
+    async fn low_stock(&self) -> Result<Vec<Product>, CoreError>;
+    async fn insert(&self, product: &Product) -> Result<(), CoreError>;
+    async fn update(&self, product: &Product) -> Result<(), CoreError>;
+    async fn delete(&self, id: Uuid) -> Result<(), CoreError>;
+}
+
+#[async_trait]
+impl ProductStore for ProductRepository {
+    async fn find(&self, id: Uuid) -> Result<Option<Product>, CoreError> {
+        Ok(self.find_by_id(id).await)
+    }
+    
+    async fn find_by_barcode(&self, barcode: &str) -> Result<Option<Product>, CoreError> {
+        ProductRepository::find_by_barcode(self, barcode).await.map_err(CoreError::database)
+    }
+    
+    async fn list(&self, query: &ProductQuery) -> Result<Vec<Product>, CoreError> {
+        ProductRepository::list(self, query).await.map_err(CoreError::database)
+    }
+    
+    async fn low_stock(&self) -> Result<Vec<Product>, CoreError> {
+        self.find_low_stock_products().await.map_err(CoreError::database)
+    }
+    
+    async fn insert(&self, product: &Product) -> Result<(), CoreError> {
+        self.save(product).await.map(|_| ()).map_err(CoreError::database)
+    }
+    
+    async fn update(&self, product: &Product) -> Result<(), CoreError> {
+        Repository::update(self, product).await.map(|_| ()).map_err(CoreError::database)
+    }
+    
+    async fn delete(&self, id: Uuid) -> Result<(), CoreError> {
+        Repository::delete(self, id).await.map_err(CoreError::database)
+    }
+}
+
+#[cfg(test)]
+pub mod memory {
+    use std::cmp::Ordering;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+    use delpopolo_infrastructure::repositories::product_repository::ProductSort;
+    use super::*;
+    
+    /// Catálogo em memória para os testes
+    #[derive(Default)]
+    pub struct InMemoryProductStore {
+        products: Mutex<HashMap<Uuid, Product>>,
+    }
+    
+    impl InMemoryProductStore {
+        pub fn with_products(self, products: Vec<Product>) -> Self {
+            self.products.lock().unwrap().extend(products.into_iter().map(|p| (p.id, p)));
+            self
+        }
+    }
+    
+    fn compare(query: &ProductQuery, a: &Product, b: &Product) -> Ordering {
+        let by_key = match query.sort {
+            ProductSort::Name => a.name.cmp(&b.name),
+            ProductSort::Price => a.price.amount.total_cmp(&b.price.amount),
+            ProductSort::CreatedAt => a.created_at.cmp(&b.created_at),
+        };
+        let ordering = by_key.then(a.id.cmp(&b.id));
+        if query.descending { ordering.reverse() } else { ordering }
+    }
+    
+    #[async_trait]
+    impl ProductStore for InMemoryProductStore {
+        async fn find(&self, id: Uuid) -> Result<Option<Product>, CoreError> {
+            Ok(self.products.lock().unwrap().get(&id).cloned())
+        }
+        
+        async fn find_by_barcode(&self, barcode: &str) -> Result<Option<Product>, CoreError> {
+            Ok(self.products.lock().unwrap().values().find(|p| p.barcode.as_deref() == Some(barcode)).cloned())
+        }
+        
+        async fn list(&self, query: &ProductQuery) -> Result<Vec<Product>, CoreError> {
+            let mut items: Vec<Product> = self.products.lock().unwrap().values().filter(|p| query.matches(p)).cloned().collect();
+            items.sort_by(|a, b| compare(query, a, b));
+            
+            // Tudo que vem depois do item do cursor
+            if let Some(cursor) = &query.after {
+                if let Some(pos) = items.iter().position(|p| p.id == cursor.id) {
+                    items.drain(..=pos);
+                }
+            }
+            items.truncate(query.limit as usize);
+            Ok(items)
+        }
+        
+        async fn low_stock(&self) -> Result<Vec<Product>, CoreError> {
+            Ok(self.products.lock().unwrap().values().filter(|p| p.is_active && p.is_low_stock()).cloned().collect())
+        }
+        
+        async fn insert(&self, product: &Product) -> Result<(), CoreError> {
+            self.products.lock().unwrap().insert(product.id, product.clone());
+            Ok(())
+        }
+        
+        async fn update(&self, product: &Product) -> Result<(), CoreError> {
+            self.products.lock().unwrap().insert(product.id, product.clone());
+            Ok(())
+        }
+        
+        async fn delete(&self, id: Uuid) -> Result<(), CoreError> {
+            if let Some(product) = self.products.lock().unwrap().get_mut(&id) {
+                product.is_active = false;
+                product.updated_at = chrono::Utc::now();
+            }
+            Ok(())
+        }
+    }
+}
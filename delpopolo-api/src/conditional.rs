@@ -0,0 +1,33 @@
+use actix_web::http::header::{self, EntityTag, Header, IfNoneMatch};
+use actix_web::{HttpRequest, HttpResponse};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+/// ETag derivado da versão da entidade (id + `updated_at`)
+pub fn entity_tag(id: Uuid, updated_at: DateTime<Utc>) -> EntityTag {
+    EntityTag::new_strong(format!("{}-{}", id.simple(), updated_at.timestamp_micros()))
+}
+
+/// `true` se o cliente já tem a versão atual (`If-None-Match`)
+pub fn is_fresh(req: &HttpRequest, etag: &EntityTag) -> bool {
+    match IfNoneMatch::parse(req) {
+        Ok(IfNoneMatch::Any) => true,
+        Ok(IfNoneMatch::Items(tags)) => tags.iter().any(|tag| tag.weak_eq(etag)),
+        Err(_) => false,
+    }
+}
+
+/// Responde 304 quando o ETag confere; senão 200 com o corpo e o header `ETag`
+pub fn conditional_json<T: Serialize>(req: &HttpRequest, etag: EntityTag, body: &T) -> HttpResponse {
+    if is_fresh(req, &etag) {
+        return HttpResponse::NotModified()
+            .insert_header(header::ETag(etag))
+            .finish();
+    }
+    
+    HttpResponse::Ok()
+        .insert_header(header::ETag(etag))
+        .insert_header((header::CACHE_CONTROL, "private, no-cache"))
+        .json(body)
+}
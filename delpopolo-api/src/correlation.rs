@@ -0,0 +1,76 @@
+use std::future::{ready, Ready};
+use std::rc::Rc;
+use actix_web::{
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    http::header::{HeaderName, HeaderValue},
+    Error, HttpMessage,
+};
+use futures_util::future::LocalBoxFuture;
+use tracing_actix_web::RequestId;
+use uuid::Uuid;
+
+pub const HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+tokio::task_local! {
+    static CORRELATION_ID: String;
+}
+
+/// Id de correlação da requisição em andamento, se houver
+pub fn current() -> Option<String> {
+    CORRELATION_ID.try_with(|id| id.clone()).ok()
+}
+
+/// Disponibiliza o `request_id` do span do `TracingLogger` para as respostas de erro
+/// e o devolve no header `X-Request-Id`. Deve ser registrado dentro do `TracingLogger`.
+pub struct CorrelationId;
+
+impl<S, B> Transform<S, ServiceRequest> for CorrelationId
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = CorrelationIdMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+    
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(CorrelationIdMiddleware { service: Rc::new(service) }))
+    }
+}
+
+pub struct CorrelationIdMiddleware<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for CorrelationIdMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+    
+    forward_ready!(service);
+    
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+        let id = req
+            .extensions()
+            .get::<RequestId>()
+            .map(|id| id.to_string())
+            .unwrap_or_else(|| Uuid::new_v4().to_string());
+        
+        Box::pin(CORRELATION_ID.scope(id.clone(), async move {
+            let mut res = service.call(req).await?;
+            if let Ok(value) = HeaderValue::from_str(&id) {
+                res.headers_mut().insert(HEADER, value);
+            }
+            Ok(res)
+        }))
+    }
+}
@@ -0,0 +1,101 @@
+use async_trait::async_trait;
+use uuid::Uuid;
+use delpopolo_core::CoreError;
+use delpopolo_domain::{ConsentRecord, Customer};
+use delpopolo_infrastructure::repositories::customer_repository::CustomerQuery;
+use delpopolo_infrastructure::repositories::CustomerRepository;
+
+/// Persistência de clientes e do histórico de consentimentos
+#[async_trait]
+pub trait CustomerStore: Send + Sync {
+    async fn find(&self, id: Uuid) -> Result<Option<Customer>, CoreError>;
+    async fn find_by_cpf(&self, cpf: &str) -> Result<Option<Customer>, CoreError>;
+    async fn list(&self, query: &CustomerQuery) -> Result<Vec<Customer>, CoreError>;
+    /// Cliente e consentimentos são gravados juntos
+    async fn insert(&self, customer: &Customer, consents: &[ConsentRecord]) -> Result<(), CoreError>;
+    async fn update(&self, customer: &Customer, consents: &[ConsentRecord]) -> Result<(), CoreError>;
+    async fn consents(&self, customer_id: Uuid) -> Result<Vec<ConsentRecord>, CoreError>;
+}
+
+#[async_trait]
+impl CustomerStore for CustomerRepository {
+    async fn find(&self, id: Uuid) -> Result<Option<Customer>, CoreError> {
+        self.find_by_id(id).await.map_err(CoreError::database)
+    }
+    
+    async fn find_by_cpf(&self, cpf: &str) -> Result<Option<Customer>, CoreError> {
+        CustomerRepository::find_by_cpf(self, cpf).await.map_err(CoreError::database)
+    }
+    
+    async fn list(&self, query: &CustomerQuery) -> Result<Vec<Customer>, CoreError> {
+        CustomerRepository::list(self, query).await.map_err(CoreError::database)
+    }
+    
+    async fn insert(&self, customer: &Customer, consents: &[ConsentRecord]) -> Result<(), CoreError> {
+        CustomerRepository::insert(self, customer, consents).await.map_err(CoreError::database)
+    }
+    
+    async fn update(&self, customer: &Customer, consents: &[ConsentRecord]) -> Result<(), CoreError> {
+        CustomerRepository::update(self, customer, consents).await.map_err(CoreError::database)
+    }
+    
+    async fn consents(&self, customer_id: Uuid) -> Result<Vec<ConsentRecord>, CoreError> {
+        CustomerRepository::consents(self, customer_id).await.map_err(CoreError::database)
+    }
+}
+
+#[cfg(test)]
+pub mod memory {
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+    use super::*;
+    
+    /// Clientes e consentimentos em memória para os testes
+    #[derive(Default)]
+    pub struct InMemoryCustomerStore {
+        customers: Mutex<HashMap<Uuid, Customer>>,
+        consents: Mutex<Vec<ConsentRecord>>,
+    }
+    
+    #[async_trait]
+    impl CustomerStore for InMemoryCustomerStore {
+        async fn find(&self, id: Uuid) -> Result<Option<Customer>, CoreError> {
+            Ok(self.customers.lock().unwrap().get(&id).cloned())
+        }
+        
+        async fn find_by_cpf(&self, cpf: &str) -> Result<Option<Customer>, CoreError> {
+            Ok(self
+                .customers
+                .lock()
+                .unwrap()
+                .values()
+                .find(|c| c.cpf.as_ref().is_some_and(|v| v.value() == cpf))
+                .cloned())
+        }
+        
+        async fn list(&self, query: &CustomerQuery) -> Result<Vec<Customer>, CoreError> {
+            let mut items: Vec<Customer> = self.customers.lock().unwrap().values().filter(|c| query.matches(c)).cloned().collect();
+            items.sort_by(|a, b| (&a.name, a.id).cmp(&(&b.name, b.id)));
+            
+            if let Some(cursor) = &query.after {
+                items.retain(|c| (&c.name, c.id) > (&cursor.name, cursor.id));
+            }
+            items.truncate(query.limit as usize);
+            Ok(items)
+        }
+        
+        async fn insert(&self, customer: &Customer, consents: &[ConsentRecord]) -> Result<(), CoreError> {
+            self.customers.lock().unwrap().insert(customer.id, customer.clone());
+            self.consents.lock().unwrap().extend_from_slice(consents);
+            Ok(())
+        }
+        
+        async fn update(&self, customer: &Customer, consents: &[ConsentRecord]) -> Result<(), CoreError> {
+            self.insert(customer, consents).await
+        }
+        
+        async fn consents(&self, customer_id: Uuid) -> Result<Vec<ConsentRecord>, CoreError> {
+            Ok(self.consents.lock().unwrap().iter().filter(|c| c.customer_id == customer_id).cloned().collect())
+        }
+    }
+}
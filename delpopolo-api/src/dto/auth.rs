@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+use delpopolo_domain::{User, UserRole};
+use crate::auth::TokenPair;
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct LoginRequest {
+    #[validate(custom(function = "crate::validation::not_blank", message = "E-mail é obrigatório"))]
+    pub email: String,
+    #[validate(custom(function = "crate::validation::not_blank", message = "Senha é obrigatória"))]
+    pub password: String,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct RefreshRequest {
+    #[validate(custom(function = "crate::validation::not_blank", message = "Token de renovação é obrigatório"))]
+    pub refresh_token: String,
+}
+
+/// As regras da nova senha ficam no `AuthService`
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct ChangePasswordRequest {
+    #[validate(custom(function = "crate::validation::not_blank", message = "Senha atual é obrigatória"))]
+    pub current_password: String,
+    pub new_password: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct UserResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub email: String,
+    pub role: UserRole,
+}
+
+impl From<&User> for UserResponse {
+    fn from(user: &User) -> Self {
+        Self {
+            id: user.id,
+            name: user.name.clone(),
+            email: user.email.clone(),
+            role: user.role,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TokenResponse {
+    pub access_token: String,
+    pub refresh_token: String,
+    #[schema(example = "Bearer")]
+    pub token_type: &'static str,
+    /// Validade do token de acesso, em segundos
+    pub expires_in: i64,
+    pub user: UserResponse,
+}
+
+impl From<TokenPair> for TokenResponse {
+    fn from(pair: TokenPair) -> Self {
+        Self {
+            user: UserResponse::from(&pair.user),
+            access_token: pair.access_token,
+            refresh_token: pair.refresh_token,
+            token_type: "Bearer",
+            expires_in: pair.expires_in,
+        }
+    }
+}
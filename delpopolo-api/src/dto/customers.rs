@@ -0,0 +1,265 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+use delpopolo_core::{CoreError, FieldError};
+use delpopolo_domain::{Address, ConsentKind, ConsentRecord, Cpf, Customer, Email, Phone};
+use delpopolo_infrastructure::repositories::customer_repository::{CustomerCursor, CustomerQuery};
+use crate::dto::orders::OrderResponse;
+use crate::pagination::{clamp_limit, decode_cursor};
+use crate::validation::{error, validate};
+
+/// Consentimentos informados pelo titular; campos ausentes ficam como estão
+#[derive(Debug, Clone, Default, Deserialize, ToSchema)]
+pub struct ConsentRequest {
+    pub accepts_marketing: Option<bool>,
+    pub whatsapp_optin: Option<bool>,
+    pub channel: Option<String>,
+}
+
+impl ConsentRequest {
+    /// Aplica pelo domínio e devolve os registros do histórico
+    pub fn apply(&self, customer: &mut Customer, recorded_by: Option<Uuid>) -> Result<Vec<ConsentRecord>, CoreError> {
+        let mut records = Vec::new();
+        for (kind, granted) in [
+            (ConsentKind::Marketing, self.accepts_marketing),
+            (ConsentKind::Whatsapp, self.whatsapp_optin),
+        ] {
+            if let Some(granted) = granted {
+                records.extend(customer.set_consent(kind, granted, self.channel.clone(), recorded_by)?);
+            }
+        }
+        Ok(records)
+    }
+}
+
+/// Corpo de criação e de atualização; PUT substitui os dados cadastrais
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct CustomerRequest {
+    #[validate(custom(function = "crate::validation::not_blank", message = "Nome é obrigatório"))]
+    pub name: String,
+    #[validate(custom(function = "crate::validation::cpf"))]
+    pub cpf: String,
+    #[validate(custom(function = "crate::validation::email"))]
+    pub email: Option<String>,
+    #[validate(custom(function = "crate::validation::phone"))]
+    pub phone: Option<String>,
+    #[validate(custom(function = "crate::validation::phone"))]
+    pub whatsapp_number: Option<String>,
+    pub address: Option<Address>,
+    #[validate(custom(function = "in_the_past"))]
+    pub birth_date: Option<NaiveDate>,
+    #[serde(default)]
+    pub dietary_restrictions: Vec<String>,
+    #[serde(default)]
+    pub favorite_products: Vec<Uuid>,
+    pub consents: Option<ConsentRequest>,
+}
+
+/// Campos já convertidos nos value objects do domínio
+struct ParsedFields {
+    cpf: Cpf,
+    email: Option<Email>,
+    phone: Option<Phone>,
+    whatsapp: Option<Phone>,
+}
+
+fn in_the_past(date: &NaiveDate) -> Result<(), ValidationError> {
+    if *date >= Utc::now().date_naive() {
+        return Err(error("birth_date", "Data de nascimento deve estar no passado"));
+    }
+    Ok(())
+}
+
+impl CustomerRequest {
+    fn parse(&self) -> Result<ParsedFields, CoreError> {
+        validate(self)?;
+        
+        // Já validados acima, as conversões não falham
+        let phone = |value: &Option<String>| value.clone().map(Phone::new).transpose().map_err(CoreError::validation);
+        Ok(ParsedFields {
+            cpf: Cpf::new(self.cpf.clone()).map_err(CoreError::validation)?,
+            email: self.email.clone().map(Email::new).transpose().map_err(CoreError::validation)?,
+            phone: phone(&self.phone)?,
+            whatsapp: phone(&self.whatsapp_number)?,
+        })
+    }
+    
+    pub fn into_customer(self) -> Result<Customer, CoreError> {
+        let fields = self.parse()?;
+        let mut customer = Customer::new(self.name.trim().to_string(), fields.cpf.clone());
+        self.fill(&mut customer, fields);
+        Ok(customer)
+    }
+    
+    pub fn apply_to(self, customer: &mut Customer) -> Result<(), CoreError> {
+        let fields = self.parse()?;
+        self.fill(customer, fields);
+        Ok(())
+    }
+    
+    fn fill(self, customer: &mut Customer, fields: ParsedFields) {
+        customer.name = self.name.trim().to_string();
+        customer.cpf = Some(fields.cpf);
+        customer.email = fields.email;
+        customer.phone = fields.phone;
+        customer.whatsapp_number = fields.whatsapp.map(|p| p.whatsapp_format());
+        customer.address = self.address;
+        customer.birth_date = self.birth_date.and_then(|d| d.and_hms_opt(0, 0, 0)).map(|d| d.and_utc());
+        customer.dietary_restrictions = self.dietary_restrictions;
+        customer.favorite_products = self.favorite_products;
+        customer.updated_at = Utc::now();
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CustomerResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub cpf: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub whatsapp_number: Option<String>,
+    pub address: Option<Address>,
+    pub birth_date: Option<NaiveDate>,
+    pub is_active: bool,
+    pub accepts_marketing: bool,
+    pub whatsapp_optin: bool,
+    pub loyalty_points: i32,
+    pub total_orders: i32,
+    pub total_spent: f64,
+    pub is_vip: bool,
+    pub dietary_restrictions: Vec<String>,
+    pub favorite_products: Vec<Uuid>,
+    pub last_order_at: Option<DateTime<Utc>>,
+    pub anonymized_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<&Customer> for CustomerResponse {
+    fn from(customer: &Customer) -> Self {
+        Self {
+            id: customer.id,
+            name: customer.name.clone(),
+            cpf: customer.cpf.as_ref().map(Cpf::formatted),
+            email: customer.email.as_ref().map(|e| e.value().to_string()),
+            phone: customer.phone.as_ref().map(Phone::formatted),
+            whatsapp_number: customer.whatsapp_number.clone(),
+            address: customer.address.clone(),
+            birth_date: customer.birth_date.map(|d| d.date_naive()),
+            is_active: customer.is_active,
+            accepts_marketing: customer.accepts_marketing,
+            whatsapp_optin: customer.whatsapp_optin,
+            loyalty_points: customer.loyalty_points,
+            total_orders: customer.total_orders,
+            total_spent: customer.total_spent,
+            is_vip: customer.is_vip(),
+            dietary_restrictions: customer.dietary_restrictions.clone(),
+            favorite_products: customer.favorite_products.clone(),
+            last_order_at: customer.last_order_at,
+            anonymized_at: customer.anonymized_at,
+            created_at: customer.created_at,
+            updated_at: customer.updated_at,
+        }
+    }
+}
+
+/// Tudo que guardamos sobre o titular (LGPD art. 18, II)
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CustomerExport {
+    pub generated_at: DateTime<Utc>,
+    pub customer: Customer,
+    pub consents: Vec<ConsentRecord>,
+    pub orders: Vec<OrderResponse>,
+}
+
+/// Conteúdo do cursor opaco da listagem
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CustomerCursorToken {
+    pub n: String,
+    pub id: Uuid,
+}
+
+/// `GET /customers?cpf=&phone=&name=&active=&cursor=&limit=`
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CustomerListQuery {
+    /// Com ou sem máscara
+    pub cpf: Option<String>,
+    pub phone: Option<String>,
+    pub name: Option<String>,
+    pub active: Option<bool>,
+    /// `next_cursor` da página anterior
+    pub cursor: Option<String>,
+    pub limit: Option<i64>,
+}
+
+impl CustomerListQuery {
+    pub fn to_query(&self) -> Result<CustomerQuery, CoreError> {
+        let cpf = match &self.cpf {
+            Some(cpf) => Some(
+                Cpf::new(cpf.clone())
+                    .map_err(|_| CoreError::invalid_fields(vec![FieldError::new("cpf", "CPF inválido")]))?
+                    .value()
+                    .to_string(),
+            ),
+            None => None,
+        };
+        let phone = self
+            .phone
+            .as_deref()
+            .map(|p| p.chars().filter(|c| c.is_ascii_digit()).collect::<String>())
+            .filter(|p| !p.is_empty());
+        
+        let after = match &self.cursor {
+            Some(cursor) => {
+                let token: CustomerCursorToken = decode_cursor(cursor)?;
+                Some(CustomerCursor { name: token.n, id: token.id })
+            }
+            None => None,
+        };
+        
+        Ok(CustomerQuery {
+            cpf,
+            phone,
+            name: self.name.as_deref().map(str::trim).filter(|n| !n.is_empty()).map(String::from),
+            active: self.active,
+            after,
+            limit: clamp_limit(self.limit),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn request() -> CustomerRequest {
+        serde_json::from_value(serde_json::json!({
+            "name": "Maria Souza",
+            "cpf": "529.982.247-25",
+            "phone": "(11) 98765-4321",
+            "whatsapp_number": "11987654321",
+            "birth_date": "1990-05-17"
+        }))
+        .unwrap()
+    }
+    
+    #[test]
+    fn test_request_builds_value_objects() {
+        let customer = request().into_customer().unwrap();
+        assert_eq!(customer.cpf.as_ref().unwrap().value(), "52998224725");
+        assert_eq!(customer.phone.as_ref().unwrap().value(), "11987654321");
+        assert_eq!(customer.whatsapp_number.as_deref(), Some("+5511987654321"));
+        
+        let mut invalid = request();
+        invalid.cpf = "111.111.111-11".to_string();
+        invalid.email = Some("maria".to_string());
+        match invalid.into_customer() {
+            Err(CoreError::InvalidFields(errors)) => assert_eq!(errors.len(), 2),
+            other => panic!("esperava erro de validação: {:?}", other.map(|c| c.id)),
+        }
+    }
+}
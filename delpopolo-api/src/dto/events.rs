@@ -0,0 +1,32 @@
+use serde::Deserialize;
+use utoipa::IntoParams;
+use delpopolo_core::{CoreError, FieldError};
+use crate::events::Topic;
+
+/// `GET /events/stream?topics=orders,kitchen`
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct EventsQuery {
+    /// Tópicos separados por vírgula (`orders`, `kitchen`, `stock`); ausente assina todos os permitidos
+    pub topics: Option<String>,
+    /// Access token para clientes que não enviam `Authorization` (`EventSource`, WebSocket do navegador)
+    pub access_token: Option<String>,
+}
+
+impl EventsQuery {
+    pub fn topics(&self) -> Result<Option<Vec<Topic>>, CoreError> {
+        let Some(list) = &self.topics else {
+            return Ok(None);
+        };
+        let mut topics = Vec::new();
+        for name in list.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+            let topic = Topic::parse(name).ok_or_else(|| {
+                CoreError::invalid_fields(vec![FieldError::new("topics", format!("Tópico desconhecido: {}", name))])
+            })?;
+            if !topics.contains(&topic) {
+                topics.push(topic);
+            }
+        }
+        Ok(Some(topics))
+    }
+}
@@ -0,0 +1,70 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+use validator::Validate;
+use delpopolo_core::CoreError;
+use delpopolo_domain::FiscalEntry;
+use delpopolo_nfe::FiscalLedger;
+
+/// `GET /fiscal/summary?year=2025&month=1`; o mês segue o horário de Brasília
+#[derive(Debug, Deserialize, Validate, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct FiscalPeriodQuery {
+    #[validate(range(min = 2000, max = 2100, message = "Ano inválido"))]
+    pub year: i32,
+    #[validate(range(min = 1, max = 12, message = "Mês deve estar entre 1 e 12"))]
+    pub month: u32,
+}
+
+impl FiscalPeriodQuery {
+    pub fn range(&self) -> Result<(DateTime<Utc>, DateTime<Utc>), CoreError> {
+        FiscalLedger::month_range(self.year, self.month)
+            .ok_or_else(|| CoreError::validation("Período inválido"))
+    }
+    
+    /// Sufixo dos arquivos exportados, ex.: `2025-01`
+    pub fn label(&self) -> String {
+        format!("{}-{:02}", self.year, self.month)
+    }
+}
+
+/// Nota lançada no livro fiscal
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FiscalEntryResponse {
+    pub id: Uuid,
+    pub nfe_key: String,
+    pub number: String,
+    pub series: String,
+    pub model: String,
+    pub issued_at: DateTime<Utc>,
+    pub issuer_cnpj: String,
+    pub issuer_name: String,
+    pub total_value: f64,
+    pub icms_value: f64,
+    pub ipi_value: f64,
+    pub pis_value: f64,
+    pub cofins_value: f64,
+    pub items: usize,
+}
+
+impl From<&FiscalEntry> for FiscalEntryResponse {
+    fn from(entry: &FiscalEntry) -> Self {
+        Self {
+            id: entry.id,
+            nfe_key: entry.nfe_key.clone(),
+            number: entry.number.clone(),
+            series: entry.series.clone(),
+            model: entry.model.clone(),
+            issued_at: entry.issued_at,
+            issuer_cnpj: entry.issuer_cnpj.clone(),
+            issuer_name: entry.issuer_name.clone(),
+            total_value: entry.total_value,
+            icms_value: entry.icms_value,
+            ipi_value: entry.ipi_value,
+            pis_value: entry.pis_value,
+            cofins_value: entry.cofins_value,
+            items: entry.items.len(),
+        }
+    }
+}
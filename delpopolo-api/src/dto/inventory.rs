@@ -0,0 +1,194 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+use delpopolo_core::{CoreError, FieldError};
+use delpopolo_domain::{Inventory, MovementType};
+use delpopolo_infrastructure::repositories::inventory_repository::{MovementCursor, MovementQuery};
+use crate::pagination::{clamp_limit, decode_cursor, MAX_LIMIT};
+use crate::validation::error;
+
+// Toda operação leva um `request_id` gerado pelo cliente: repetir a requisição
+// com o mesmo id devolve o resultado da primeira execução.
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct EntryRequest {
+    pub request_id: Uuid,
+    #[validate(range(exclusive_min = 0.0, message = "Quantidade deve ser maior que zero"))]
+    pub quantity: f64,
+    #[validate(range(min = 0.0, message = "Custo não pode ser negativo"))]
+    pub unit_cost: Option<f64>,
+    #[validate(custom(function = "nfe_access_key"))]
+    pub nfe_key: Option<String>,
+    pub supplier_id: Option<Uuid>,
+}
+
+fn nfe_access_key(key: &str) -> Result<(), ValidationError> {
+    if key.len() != 44 || !key.chars().all(|c| c.is_ascii_digit()) {
+        return Err(error("nfe_key", "Chave de NF-e deve ter 44 dígitos"));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct WithdrawalRequest {
+    pub request_id: Uuid,
+    #[validate(range(exclusive_min = 0.0, message = "Quantidade deve ser maior que zero"))]
+    pub quantity: f64,
+    pub order_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct ReservationRequest {
+    pub request_id: Uuid,
+    #[validate(range(exclusive_min = 0.0, message = "Quantidade deve ser maior que zero"))]
+    pub quantity: f64,
+    pub order_id: Uuid,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct ReleaseRequest {
+    pub request_id: Uuid,
+    #[validate(range(exclusive_min = 0.0, message = "Quantidade deve ser maior que zero"))]
+    pub quantity: f64,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct AdjustmentRequest {
+    pub request_id: Uuid,
+    #[validate(range(min = 0.0, message = "Quantidade não pode ser negativa"))]
+    pub new_quantity: f64,
+    #[validate(custom(function = "crate::validation::not_blank", message = "Informe o motivo"))]
+    pub reason: String,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct LossRequest {
+    pub request_id: Uuid,
+    #[validate(range(exclusive_min = 0.0, message = "Quantidade deve ser maior que zero"))]
+    pub quantity: f64,
+    #[validate(custom(function = "crate::validation::not_blank", message = "Informe o motivo"))]
+    pub reason: String,
+}
+
+/// Saldo de um produto
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct StockLevelResponse {
+    pub product_id: Uuid,
+    pub quantity: f64,
+    pub reserved_quantity: f64,
+    pub available_quantity: f64,
+    pub last_movement_at: Option<DateTime<Utc>>,
+}
+
+impl From<&Inventory> for StockLevelResponse {
+    fn from(inventory: &Inventory) -> Self {
+        Self {
+            product_id: inventory.product_id,
+            quantity: inventory.quantity,
+            reserved_quantity: inventory.reserved_quantity,
+            available_quantity: inventory.available_quantity,
+            last_movement_at: inventory.last_movement_at,
+        }
+    }
+}
+
+/// `GET /inventory/availability?product_ids=<uuid>,<uuid>`
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AvailabilityQuery {
+    /// Ids separados por vírgula
+    pub product_ids: String,
+}
+
+impl AvailabilityQuery {
+    pub fn product_ids(&self) -> Result<Vec<Uuid>, CoreError> {
+        let invalid = |message: &str| CoreError::invalid_fields(vec![FieldError::new("product_ids", message)]);
+        
+        let mut ids = Vec::new();
+        for part in self.product_ids.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let id: Uuid = part.parse().map_err(|_| invalid("Lista de ids inválida"))?;
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
+        }
+        
+        if ids.is_empty() {
+            return Err(invalid("Informe ao menos um produto"));
+        }
+        if ids.len() as i64 > MAX_LIMIT {
+            return Err(invalid(&format!("No máximo {} produtos por consulta", MAX_LIMIT)));
+        }
+        Ok(ids)
+    }
+}
+
+/// Conteúdo do cursor opaco do razão
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MovementCursorToken {
+    pub t: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+/// `GET /inventory/{product_id}/movements?type=&from=&to=&nfe_key=&supplier_id=&cursor=&limit=`
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct MovementListQuery {
+    #[serde(rename = "type")]
+    #[param(rename = "type")]
+    pub movement_type: Option<MovementType>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub nfe_key: Option<String>,
+    pub supplier_id: Option<Uuid>,
+    /// `next_cursor` da página anterior
+    pub cursor: Option<String>,
+    pub limit: Option<i64>,
+}
+
+impl MovementListQuery {
+    pub fn to_query(&self, product_id: Uuid) -> Result<MovementQuery, CoreError> {
+        if let (Some(from), Some(to)) = (self.from, self.to) {
+            if from >= to {
+                return Err(CoreError::invalid_fields(vec![FieldError::new("to", "Fim do período deve ser depois do início")]));
+            }
+        }
+        
+        let after = match &self.cursor {
+            Some(cursor) => {
+                let token: MovementCursorToken = decode_cursor(cursor)?;
+                Some(MovementCursor { created_at: token.t, id: token.id })
+            }
+            None => None,
+        };
+        
+        Ok(MovementQuery {
+            product_id,
+            movement_type: self.movement_type,
+            from: self.from,
+            to: self.to,
+            nfe_key: self.nfe_key.clone(),
+            supplier_id: self.supplier_id,
+            after,
+            limit: clamp_limit(self.limit),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_availability_ids_are_deduplicated_and_validated() {
+        let id = Uuid::new_v4();
+        let query = AvailabilityQuery { product_ids: format!("{id}, {id},") };
+        assert_eq!(query.product_ids().unwrap(), vec![id]);
+        
+        for invalid in ["", "abc"] {
+            let query = AvailabilityQuery { product_ids: invalid.to_string() };
+            assert!(matches!(query.product_ids(), Err(CoreError::InvalidFields(_))));
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use delpopolo_domain::{KitchenStation, OrderItem, OrderSource, OrderStatus, TicketStage};
+use crate::kitchen::{KitchenSettings, StationTicket, Urgency};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TicketItemResponse {
+    pub id: Uuid,
+    pub product_name: String,
+    pub quantity: f64,
+    pub notes: Option<String>,
+}
+
+impl From<&OrderItem> for TicketItemResponse {
+    fn from(item: &OrderItem) -> Self {
+        Self {
+            id: item.id,
+            product_name: item.product_name.clone(),
+            quantity: item.quantity,
+            notes: item.notes.clone(),
+        }
+    }
+}
+
+/// Pedido na tela de uma estação, só com os itens que ela prepara
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TicketResponse {
+    pub order_id: Uuid,
+    pub order_number: String,
+    pub station: KitchenStation,
+    pub stage: TicketStage,
+    pub order_status: OrderStatus,
+    pub source: OrderSource,
+    pub customer_name: Option<String>,
+    pub table_number: Option<String>,
+    pub notes: Option<String>,
+    pub items: Vec<TicketItemResponse>,
+    pub promised_at: DateTime<Utc>,
+    /// Negativo quando o pedido está atrasado
+    pub minutes_remaining: i64,
+    pub urgency: Urgency,
+    pub started_at: Option<DateTime<Utc>>,
+    pub bumped_at: Option<DateTime<Utc>>,
+}
+
+impl TicketResponse {
+    pub fn new(ticket: &StationTicket, settings: &KitchenSettings, now: DateTime<Utc>) -> Self {
+        let order = &ticket.order;
+        let promised_at = settings.promised_at(order);
+        Self {
+            order_id: order.id,
+            order_number: order.order_number.clone(),
+            station: ticket.ticket.station,
+            stage: ticket.ticket.stage(),
+            order_status: order.status,
+            source: order.source,
+            customer_name: order.customer_name.clone(),
+            table_number: order.table_number.clone(),
+            notes: order.notes.clone(),
+            items: ticket.items.iter().map(TicketItemResponse::from).collect(),
+            promised_at,
+            minutes_remaining: (promised_at - now).num_minutes(),
+            urgency: settings.urgency(promised_at, now),
+            started_at: ticket.ticket.started_at,
+            bumped_at: ticket.ticket.bumped_at,
+        }
+    }
+}
+
+/// Tela da estação: a fila por horário prometido e os últimos finalizados, para o recall
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StationQueueResponse {
+    pub station: KitchenStation,
+    pub generated_at: DateTime<Utc>,
+    pub tickets: Vec<TicketResponse>,
+    pub done: Vec<TicketResponse>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StationSummaryResponse {
+    pub station: KitchenStation,
+    pub waiting: usize,
+    pub preparing: usize,
+    pub late: usize,
+}
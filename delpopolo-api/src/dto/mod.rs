@@ -1 +1,10 @@
 // DTOs (Data Transfer Objects) para API
+pub mod auth;
+pub mod products;
+pub mod orders;
+pub mod customers;
+pub mod inventory;
+pub mod suppliers;
+pub mod events;
+pub mod kitchen;
+pub mod fiscal;
@@ -0,0 +1,237 @@
+use chrono::{DateTime, FixedOffset, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+use delpopolo_core::{CoreError, FieldError};
+use delpopolo_domain::{Order, OrderItem, OrderSource, OrderStatus, PaymentMethod};
+use delpopolo_infrastructure::repositories::order_repository::{OrderCursor, OrderQuery};
+use crate::pagination::{clamp_limit, decode_cursor};
+use crate::validation::error;
+
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct OrderItemRequest {
+    pub product_id: Uuid,
+    #[validate(range(exclusive_min = 0.0, message = "Quantidade deve ser maior que zero"))]
+    pub quantity: f64,
+    pub notes: Option<String>,
+}
+
+/// Corpo de criação; preços vêm do catálogo, nunca do cliente
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+#[validate(schema(function = "check_order", skip_on_field_errors = false))]
+pub struct CreateOrderRequest {
+    pub source: OrderSource,
+    #[validate(nested)]
+    pub items: Vec<OrderItemRequest>,
+    pub customer_id: Option<Uuid>,
+    pub customer_name: Option<String>,
+    pub customer_cpf: Option<String>,
+    #[serde(default)]
+    #[validate(range(min = 0.0, message = "Taxa de entrega não pode ser negativa"))]
+    pub delivery_fee: f64,
+    pub delivery_address: Option<String>,
+    pub delivery_time: Option<DateTime<Utc>>,
+    pub table_number: Option<String>,
+    pub payment_method: Option<PaymentMethod>,
+    pub notes: Option<String>,
+}
+
+fn check_order(request: &CreateOrderRequest) -> Result<(), ValidationError> {
+    if request.items.is_empty() {
+        return Err(error("items", "Pedido precisa de ao menos um item"));
+    }
+    if request.delivery_fee > 0.0 && request.delivery_address.as_deref().is_none_or(|a| a.trim().is_empty()) {
+        return Err(error("delivery_address", "Endereço é obrigatório para entrega"));
+    }
+    Ok(())
+}
+
+/// O limite superior (subtotal do pedido) é conferido pelo domínio
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct DiscountRequest {
+    pub amount: f64,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CancelOrderRequest {
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct OrderItemResponse {
+    pub id: Uuid,
+    pub product_id: Uuid,
+    pub product_name: String,
+    pub quantity: f64,
+    pub unit_price: f64,
+    pub total_price: f64,
+    pub notes: Option<String>,
+}
+
+impl From<&OrderItem> for OrderItemResponse {
+    fn from(item: &OrderItem) -> Self {
+        Self {
+            id: item.id,
+            product_id: item.product_id,
+            product_name: item.product_name.clone(),
+            quantity: item.quantity,
+            unit_price: item.unit_price.amount,
+            total_price: item.total_price.amount,
+            notes: item.notes.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct OrderResponse {
+    pub id: Uuid,
+    pub order_number: String,
+    pub status: OrderStatus,
+    pub source: OrderSource,
+    pub customer_id: Option<Uuid>,
+    pub customer_name: Option<String>,
+    pub items: Vec<OrderItemResponse>,
+    pub subtotal: f64,
+    pub discount: f64,
+    pub delivery_fee: f64,
+    pub total: f64,
+    pub payment_method: Option<PaymentMethod>,
+    pub is_paid: bool,
+    pub delivery_address: Option<String>,
+    pub delivery_time: Option<DateTime<Utc>>,
+    pub table_number: Option<String>,
+    pub notes: Option<String>,
+    pub preparation_started_at: Option<DateTime<Utc>>,
+    pub ready_at: Option<DateTime<Utc>>,
+    pub delivered_at: Option<DateTime<Utc>>,
+    pub cancelled_at: Option<DateTime<Utc>>,
+    pub cancellation_reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<&Order> for OrderResponse {
+    fn from(order: &Order) -> Self {
+        Self {
+            id: order.id,
+            order_number: order.order_number.clone(),
+            status: order.status,
+            source: order.source,
+            customer_id: order.customer_id,
+            customer_name: order.customer_name.clone(),
+            items: order.items.iter().map(OrderItemResponse::from).collect(),
+            subtotal: order.subtotal.amount,
+            discount: order.discount.amount,
+            delivery_fee: order.delivery_fee.amount,
+            total: order.total.amount,
+            payment_method: order.payment_method,
+            is_paid: order.is_paid,
+            delivery_address: order.delivery_address.clone(),
+            delivery_time: order.delivery_time,
+            table_number: order.table_number.clone(),
+            notes: order.notes.clone(),
+            preparation_started_at: order.preparation_started_at,
+            ready_at: order.ready_at,
+            delivered_at: order.delivered_at,
+            cancelled_at: order.cancelled_at,
+            cancellation_reason: order.cancellation_reason.clone(),
+            created_at: order.created_at,
+            updated_at: order.updated_at,
+        }
+    }
+}
+
+/// Conteúdo do cursor opaco da listagem
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OrderCursorToken {
+    pub t: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+/// `GET /orders?status=&source=&customer_id=&date=&from=&to=&cursor=&limit=`
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct OrderListQuery {
+    pub status: Option<OrderStatus>,
+    pub source: Option<OrderSource>,
+    pub customer_id: Option<Uuid>,
+    /// Dia inteiro no horário de Brasília; exclui `from`/`to`
+    pub date: Option<NaiveDate>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    /// `next_cursor` da página anterior
+    pub cursor: Option<String>,
+    pub limit: Option<i64>,
+}
+
+impl OrderListQuery {
+    pub fn to_query(&self) -> Result<OrderQuery, CoreError> {
+        let (mut from, mut to) = (self.from, self.to);
+        
+        if let Some(date) = self.date {
+            if from.is_some() || to.is_some() {
+                return Err(CoreError::invalid_fields(vec![FieldError::new(
+                    "date",
+                    "Use 'date' ou o intervalo 'from'/'to', não ambos",
+                )]));
+            }
+            let brasilia = FixedOffset::west_opt(3 * 3600).expect("valid offset");
+            let start = date
+                .and_hms_opt(0, 0, 0)
+                .and_then(|d| d.and_local_timezone(brasilia).single())
+                .ok_or_else(|| CoreError::validation("Data inválida"))?
+                .with_timezone(&Utc);
+            from = Some(start);
+            to = Some(start + chrono::Duration::days(1));
+        }
+        
+        if let (Some(from), Some(to)) = (from, to) {
+            if from >= to {
+                return Err(CoreError::invalid_fields(vec![FieldError::new("to", "Fim do período deve ser depois do início")]));
+            }
+        }
+        
+        let after = match &self.cursor {
+            Some(cursor) => {
+                let token: OrderCursorToken = decode_cursor(cursor)?;
+                Some(OrderCursor { created_at: token.t, id: token.id })
+            }
+            None => None,
+        };
+        
+        Ok(OrderQuery {
+            status: self.status,
+            source: self.source,
+            customer_id: self.customer_id,
+            from,
+            to,
+            after,
+            limit: clamp_limit(self.limit),
+            oldest_first: false,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_date_filter_uses_brasilia_day() {
+        let query = OrderListQuery {
+            date: NaiveDate::from_ymd_opt(2025, 1, 15),
+            ..Default::default()
+        };
+        let query = query.to_query().unwrap();
+        assert_eq!(query.from.unwrap().to_rfc3339(), "2025-01-15T03:00:00+00:00");
+        assert_eq!(query.to.unwrap().to_rfc3339(), "2025-01-16T03:00:00+00:00");
+        
+        let query = OrderListQuery {
+            date: NaiveDate::from_ymd_opt(2025, 1, 15),
+            from: Some(Utc::now()),
+            ..Default::default()
+        };
+        assert!(query.to_query().is_err());
+    }
+}
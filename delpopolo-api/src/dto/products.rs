@@ -0,0 +1,263 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+use delpopolo_core::{CoreError, FieldError};
+use delpopolo_domain::{Money, Product, ProductCategory, UnitOfMeasure};
+use delpopolo_infrastructure::repositories::product_repository::{ProductCursor, ProductQuery, ProductSort};
+use crate::pagination::{clamp_limit, decode_cursor};
+use crate::validation::error;
+
+fn default_true() -> bool {
+    true
+}
+
+/// Corpo de criação e de atualização (PUT substitui os dados cadastrais; estoque é do inventário)
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+#[validate(schema(function = "check_stock_levels", skip_on_field_errors = false))]
+pub struct ProductRequest {
+    #[validate(custom(function = "crate::validation::not_blank", message = "Nome é obrigatório"))]
+    pub name: String,
+    pub description: Option<String>,
+    #[validate(custom(function = "crate::validation::not_blank", message = "SKU é obrigatório"))]
+    pub sku: String,
+    #[validate(custom(function = "gtin"))]
+    pub barcode: Option<String>,
+    pub category: ProductCategory,
+    pub unit_of_measure: UnitOfMeasure,
+    #[validate(range(exclusive_min = 0.0, message = "Preço deve ser maior que zero"))]
+    pub price: f64,
+    #[validate(range(min = 0.0, message = "Custo não pode ser negativo"))]
+    pub cost: f64,
+    #[serde(default)]
+    #[validate(range(min = 0.0, message = "Estoque mínimo não pode ser negativo"))]
+    pub min_stock_level: f64,
+    pub max_stock_level: Option<f64>,
+    #[serde(default = "default_true")]
+    pub is_active: bool,
+    #[serde(default = "default_true")]
+    pub is_available_online: bool,
+    pub weight: Option<f64>,
+    pub preparation_time_minutes: Option<i32>,
+    pub supplier_id: Option<Uuid>,
+    #[validate(length(equal = 8, message = "Deve ter 8 dígitos"), custom(function = "crate::validation::digits"))]
+    pub nfe_ncm: Option<String>,
+    #[validate(length(equal = 7, message = "Deve ter 7 dígitos"), custom(function = "crate::validation::digits"))]
+    pub nfe_cest: Option<String>,
+    #[validate(length(equal = 4, message = "Deve ter 4 dígitos"), custom(function = "crate::validation::digits"))]
+    pub nfe_cfop: Option<String>,
+}
+
+fn gtin(code: &str) -> Result<(), ValidationError> {
+    if !is_valid_gtin(code) {
+        return Err(error("gtin", "Código de barras (GTIN) inválido"));
+    }
+    Ok(())
+}
+
+fn check_stock_levels(request: &ProductRequest) -> Result<(), ValidationError> {
+    if request.max_stock_level.is_some_and(|max| max < request.min_stock_level) {
+        return Err(error("max_stock_level", "Estoque máximo menor que o mínimo"));
+    }
+    Ok(())
+}
+
+impl ProductRequest {
+    pub fn into_product(self) -> Product {
+        let mut product = Product::new(
+            self.name.clone(),
+            self.category,
+            self.unit_of_measure,
+            Money::brl(self.price),
+            Money::brl(self.cost),
+        );
+        self.apply_to(&mut product);
+        product
+    }
+    
+    pub fn apply_to(self, product: &mut Product) {
+        product.name = self.name.trim().to_string();
+        product.description = self.description;
+        product.sku = self.sku.trim().to_string();
+        product.barcode = self.barcode;
+        product.category = self.category;
+        product.unit_of_measure = self.unit_of_measure;
+        product.price = Money::brl(self.price);
+        product.cost = Money::brl(self.cost);
+        product.min_stock_level = self.min_stock_level;
+        product.max_stock_level = self.max_stock_level;
+        product.is_active = self.is_active;
+        product.is_available_online = self.is_available_online;
+        product.weight = self.weight;
+        product.preparation_time_minutes = self.preparation_time_minutes;
+        product.supplier_id = self.supplier_id;
+        product.nfe_ncm = self.nfe_ncm;
+        product.nfe_cest = self.nfe_cest;
+        product.nfe_cfop = self.nfe_cfop;
+        product.updated_at = Utc::now();
+    }
+}
+
+/// GTIN-8/12/13/14 com dígito verificador módulo 10
+pub fn is_valid_gtin(code: &str) -> bool {
+    if !matches!(code.len(), 8 | 12 | 13 | 14) || !code.chars().all(|c| c.is_ascii_digit()) {
+        return false;
+    }
+    
+    let digits: Vec<u32> = code.chars().filter_map(|c| c.to_digit(10)).collect();
+    let (body, check) = digits.split_at(digits.len() - 1);
+    let sum: u32 = body
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, d)| if i % 2 == 0 { d * 3 } else { *d })
+        .sum();
+    
+    (10 - sum % 10) % 10 == check[0]
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ProductResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub sku: String,
+    pub barcode: Option<String>,
+    pub category: ProductCategory,
+    pub unit_of_measure: UnitOfMeasure,
+    pub price: f64,
+    pub cost: f64,
+    pub stock_quantity: f64,
+    pub min_stock_level: f64,
+    pub max_stock_level: Option<f64>,
+    pub low_stock: bool,
+    pub is_active: bool,
+    pub is_available_online: bool,
+    pub image_url: Option<String>,
+    pub weight: Option<f64>,
+    pub preparation_time_minutes: Option<i32>,
+    pub supplier_id: Option<Uuid>,
+    pub nfe_ncm: Option<String>,
+    pub nfe_cest: Option<String>,
+    pub nfe_cfop: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<&Product> for ProductResponse {
+    fn from(product: &Product) -> Self {
+        Self {
+            id: product.id,
+            name: product.name.clone(),
+            description: product.description.clone(),
+            sku: product.sku.clone(),
+            barcode: product.barcode.clone(),
+            category: product.category,
+            unit_of_measure: product.unit_of_measure,
+            price: product.price.amount,
+            cost: product.cost.amount,
+            stock_quantity: product.stock_quantity,
+            min_stock_level: product.min_stock_level,
+            max_stock_level: product.max_stock_level,
+            low_stock: product.is_low_stock(),
+            is_active: product.is_active,
+            is_available_online: product.is_available_online,
+            image_url: product.image_url.clone(),
+            weight: product.weight,
+            preparation_time_minutes: product.preparation_time_minutes,
+            supplier_id: product.supplier_id,
+            nfe_ncm: product.nfe_ncm.clone(),
+            nfe_cest: product.nfe_cest.clone(),
+            nfe_cfop: product.nfe_cfop.clone(),
+            created_at: product.created_at,
+            updated_at: product.updated_at,
+        }
+    }
+}
+
+/// Conteúdo do cursor opaco da listagem
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProductCursorToken {
+    pub v: String,
+    pub id: Uuid,
+}
+
+/// `GET /products?q=&category=&active=&low_stock=&sort=-price&cursor=&limit=`
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ProductListQuery {
+    /// Busca por nome, SKU ou código de barras
+    pub q: Option<String>,
+    pub category: Option<ProductCategory>,
+    /// Padrão `true`: produtos removidos ficam inativos e saem da listagem
+    pub active: Option<bool>,
+    #[serde(default)]
+    pub low_stock: bool,
+    /// `name`, `price` ou `created_at`; prefixo `-` para decrescente
+    pub sort: Option<String>,
+    /// `next_cursor` da página anterior
+    pub cursor: Option<String>,
+    pub limit: Option<i64>,
+}
+
+impl ProductListQuery {
+    pub fn to_query(&self) -> Result<ProductQuery, CoreError> {
+        let sort = self.sort.as_deref().unwrap_or("name");
+        let (descending, field) = match sort.strip_prefix('-') {
+            Some(field) => (true, field),
+            None => (false, sort),
+        };
+        let sort = match field {
+            "name" => ProductSort::Name,
+            "price" => ProductSort::Price,
+            "created_at" => ProductSort::CreatedAt,
+            other => {
+                return Err(CoreError::invalid_fields(vec![FieldError::new(
+                    "sort",
+                    format!("Ordenação desconhecida: {}", other),
+                )]))
+            }
+        };
+        
+        let after = match &self.cursor {
+            Some(cursor) => {
+                let token: ProductCursorToken = decode_cursor(cursor)?;
+                let valid = match sort {
+                    ProductSort::Name => true,
+                    ProductSort::Price => token.v.parse::<f64>().is_ok(),
+                    ProductSort::CreatedAt => DateTime::parse_from_rfc3339(&token.v).is_ok(),
+                };
+                if !valid {
+                    return Err(CoreError::validation("Cursor não corresponde à ordenação informada"));
+                }
+                Some(ProductCursor { value: token.v, id: token.id })
+            }
+            None => None,
+        };
+        
+        Ok(ProductQuery {
+            search: self.q.as_deref().map(str::trim).filter(|q| !q.is_empty()).map(String::from),
+            category: self.category,
+            active: Some(self.active.unwrap_or(true)),
+            low_stock: self.low_stock,
+            sort,
+            descending,
+            after,
+            limit: clamp_limit(self.limit),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_gtin_check_digit() {
+        assert!(is_valid_gtin("7891000100103"));
+        assert!(is_valid_gtin("96385074"));
+        assert!(!is_valid_gtin("7891000100104"));
+        assert!(!is_valid_gtin("789100010010"));
+    }
+}
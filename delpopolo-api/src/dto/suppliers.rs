@@ -0,0 +1,261 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+use validator::Validate;
+use delpopolo_core::{CoreError, FieldError};
+use delpopolo_domain::{Address, Cnpj, Email, Phone, Supplier, SupplierContact, SupplierProduct};
+use delpopolo_infrastructure::repositories::supplier_repository::{SupplierCursor, SupplierQuery};
+use crate::pagination::{clamp_limit, decode_cursor};
+use crate::validation::validate;
+
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct ContactRequest {
+    #[validate(custom(function = "crate::validation::not_blank", message = "Nome do contato é obrigatório"))]
+    pub name: String,
+    pub role: Option<String>,
+    #[validate(custom(function = "crate::validation::email"))]
+    pub email: Option<String>,
+    #[validate(custom(function = "crate::validation::phone"))]
+    pub phone: Option<String>,
+}
+
+/// Corpo de criação e de atualização; PUT substitui os dados cadastrais, mas não o catálogo
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct SupplierRequest {
+    #[validate(custom(function = "crate::validation::not_blank", message = "Razão social é obrigatória"))]
+    pub name: String,
+    pub trade_name: Option<String>,
+    #[validate(custom(function = "crate::validation::cnpj"))]
+    pub cnpj: Option<String>,
+    #[validate(custom(function = "crate::validation::email"))]
+    pub email: Option<String>,
+    #[validate(custom(function = "crate::validation::phone"))]
+    pub phone: Option<String>,
+    #[validate(custom(function = "crate::validation::phone"))]
+    pub whatsapp: Option<String>,
+    pub contact_person: Option<String>,
+    #[serde(default)]
+    #[validate(nested)]
+    pub contacts: Vec<ContactRequest>,
+    pub address: Option<Address>,
+    #[validate(range(min = 0.0, max = 5.0, message = "Avaliação deve ficar entre 0 e 5"))]
+    pub rating: Option<f32>,
+    #[serde(default)]
+    pub is_preferred: bool,
+}
+
+/// Campos já convertidos nos value objects do domínio
+struct ParsedFields {
+    cnpj: Option<Cnpj>,
+    email: Option<Email>,
+    phone: Option<Phone>,
+    whatsapp: Option<Phone>,
+    contacts: Vec<SupplierContact>,
+}
+
+fn parse_email(value: &Option<String>) -> Result<Option<Email>, CoreError> {
+    value.clone().map(Email::new).transpose().map_err(CoreError::validation)
+}
+
+fn parse_phone(value: &Option<String>) -> Result<Option<Phone>, CoreError> {
+    value.clone().map(Phone::new).transpose().map_err(CoreError::validation)
+}
+
+impl SupplierRequest {
+    fn parse(&self) -> Result<ParsedFields, CoreError> {
+        validate(self)?;
+        
+        // Já validados acima, as conversões não falham
+        let mut contacts = Vec::with_capacity(self.contacts.len());
+        for contact in &self.contacts {
+            contacts.push(SupplierContact {
+                name: contact.name.trim().to_string(),
+                role: contact.role.clone(),
+                email: parse_email(&contact.email)?,
+                phone: parse_phone(&contact.phone)?,
+            });
+        }
+        Ok(ParsedFields {
+            cnpj: self.cnpj.clone().map(Cnpj::new).transpose().map_err(CoreError::validation)?,
+            email: parse_email(&self.email)?,
+            phone: parse_phone(&self.phone)?,
+            whatsapp: parse_phone(&self.whatsapp)?,
+            contacts,
+        })
+    }
+    
+    pub fn into_supplier(self) -> Result<Supplier, CoreError> {
+        let fields = self.parse()?;
+        let mut supplier = Supplier::new(self.name.trim().to_string());
+        self.fill(&mut supplier, fields);
+        Ok(supplier)
+    }
+    
+    pub fn apply_to(self, supplier: &mut Supplier) -> Result<(), CoreError> {
+        let fields = self.parse()?;
+        self.fill(supplier, fields);
+        Ok(())
+    }
+    
+    fn fill(self, supplier: &mut Supplier, fields: ParsedFields) {
+        supplier.name = self.name.trim().to_string();
+        supplier.trade_name = self.trade_name.map(|t| t.trim().to_string()).filter(|t| !t.is_empty());
+        supplier.cnpj = fields.cnpj;
+        supplier.email = fields.email;
+        supplier.phone = fields.phone;
+        supplier.whatsapp = fields.whatsapp.map(|p| p.whatsapp_format());
+        supplier.contact_person = self.contact_person;
+        supplier.contacts = fields.contacts;
+        supplier.address = self.address;
+        supplier.rating = self.rating;
+        supplier.is_preferred = self.is_preferred;
+        supplier.updated_at = Utc::now();
+    }
+}
+
+/// `PUT /suppliers/{id}/products/{product_id}`
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct SupplierProductRequest {
+    pub unit_price: f64,
+    pub min_order_quantity: Option<f64>,
+    pub lead_time_days: Option<i32>,
+    pub supplier_sku: Option<String>,
+    #[serde(default = "available")]
+    pub is_available: bool,
+}
+
+fn available() -> bool {
+    true
+}
+
+impl SupplierProductRequest {
+    /// As regras de preço, pedido mínimo e prazo ficam no `Supplier::upsert_product`
+    pub fn into_product(self, product_id: Uuid) -> SupplierProduct {
+        SupplierProduct {
+            product_id,
+            supplier_sku: self.supplier_sku,
+            unit_price: self.unit_price,
+            min_order_quantity: self.min_order_quantity,
+            lead_time_days: self.lead_time_days,
+            is_available: self.is_available,
+            updated_at: Utc::now(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SupplierResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub trade_name: Option<String>,
+    pub cnpj: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub whatsapp: Option<String>,
+    pub contact_person: Option<String>,
+    pub contacts: Vec<SupplierContact>,
+    pub address: Option<Address>,
+    pub products: Vec<SupplierProduct>,
+    pub rating: Option<f32>,
+    pub is_active: bool,
+    pub is_preferred: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<&Supplier> for SupplierResponse {
+    fn from(supplier: &Supplier) -> Self {
+        Self {
+            id: supplier.id,
+            name: supplier.name.clone(),
+            trade_name: supplier.trade_name.clone(),
+            cnpj: supplier.cnpj.as_ref().map(Cnpj::formatted),
+            email: supplier.email.as_ref().map(|e| e.value().to_string()),
+            phone: supplier.phone.as_ref().map(Phone::formatted),
+            whatsapp: supplier.whatsapp.clone(),
+            contact_person: supplier.contact_person.clone(),
+            contacts: supplier.contacts.clone(),
+            address: supplier.address.clone(),
+            products: supplier.products.clone(),
+            rating: supplier.rating,
+            is_active: supplier.is_active,
+            is_preferred: supplier.is_preferred,
+            created_at: supplier.created_at,
+            updated_at: supplier.updated_at,
+        }
+    }
+}
+
+/// Conteúdo do cursor opaco da listagem
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SupplierCursorToken {
+    pub n: String,
+    pub id: Uuid,
+}
+
+/// `GET /suppliers?cnpj=&name=&active=&preferred=&cursor=&limit=`
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SupplierListQuery {
+    /// Com ou sem máscara
+    pub cnpj: Option<String>,
+    pub name: Option<String>,
+    pub active: Option<bool>,
+    pub preferred: Option<bool>,
+    /// `next_cursor` da página anterior
+    pub cursor: Option<String>,
+    pub limit: Option<i64>,
+}
+
+/// CNPJ validado e sem máscara, para buscas
+pub fn parse_cnpj(cnpj: &str) -> Result<String, CoreError> {
+    Cnpj::new(cnpj.to_string())
+        .map(|c| c.value().to_string())
+        .map_err(|_| CoreError::invalid_fields(vec![FieldError::new("cnpj", "CNPJ inválido")]))
+}
+
+impl SupplierListQuery {
+    pub fn to_query(&self) -> Result<SupplierQuery, CoreError> {
+        let cnpj = self.cnpj.as_deref().map(parse_cnpj).transpose()?;
+        
+        let after = match &self.cursor {
+            Some(cursor) => {
+                let token: SupplierCursorToken = decode_cursor(cursor)?;
+                Some(SupplierCursor { name: token.n, id: token.id })
+            }
+            None => None,
+        };
+        
+        Ok(SupplierQuery {
+            cnpj,
+            name: self.name.as_deref().map(str::trim).filter(|n| !n.is_empty()).map(String::from),
+            active: self.active,
+            preferred: self.preferred,
+            after,
+            limit: clamp_limit(self.limit),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_request_reports_every_invalid_field() {
+        let request: SupplierRequest = serde_json::from_value(serde_json::json!({
+            "name": "Moinho Paulista",
+            "cnpj": "11.222.333/0001-00",
+            "rating": 7.5,
+            "contacts": [{"name": "Carla", "email": "carla"}]
+        }))
+        .unwrap();
+        
+        let Err(CoreError::InvalidFields(errors)) = request.into_supplier() else {
+            panic!("esperava erros de campo");
+        };
+        let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, ["cnpj", "contacts[0].email", "rating"]);
+    }
+}
@@ -0,0 +1,203 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use actix_web::{error::JsonPayloadError, http::StatusCode, web, HttpRequest, HttpResponse, ResponseError};
+use serde::Serialize;
+use utoipa::ToSchema;
+use delpopolo_core::{CoreError, FieldError};
+use crate::correlation;
+
+pub const PROBLEM_CONTENT_TYPE: &str = "application/problem+json";
+
+static HIDE_INTERNAL_DETAILS: AtomicBool = AtomicBool::new(false);
+
+/// Em produção, detalhes de erros internos/banco não são enviados ao cliente
+pub fn hide_internal_details(hide: bool) {
+    HIDE_INTERNAL_DETAILS.store(hide, Ordering::Relaxed);
+}
+
+/// Adapta `CoreError` para respostas HTTP
+#[derive(Debug)]
+pub struct ApiError(pub CoreError);
+
+impl From<CoreError> for ApiError {
+    fn from(error: CoreError) -> Self {
+        ApiError(error)
+    }
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Corpo RFC 7807 (`application/problem+json`)
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Problem {
+    #[serde(rename = "type")]
+    #[schema(rename = "type")]
+    pub problem_type: String,
+    pub title: &'static str,
+    pub status: u16,
+    pub detail: String,
+    pub code: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correlation_id: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<FieldError>,
+}
+
+impl Problem {
+    pub fn from_error(error: &CoreError, hide_internal: bool) -> Self {
+        let (code, title) = code_and_title(error);
+        let status = status_of(error);
+        
+        let detail = match error {
+            CoreError::Internal(_) | CoreError::Database(_) | CoreError::Serialization(_) if hide_internal => {
+                "Ocorreu um erro inesperado. Informe o código de correlação ao suporte.".to_string()
+            }
+            CoreError::InvalidFields(_) => "Um ou mais campos são inválidos".to_string(),
+            CoreError::Internal(msg)
+            | CoreError::NotFound(msg)
+            | CoreError::Validation(msg)
+            | CoreError::Unauthorized(msg)
+            | CoreError::Forbidden(msg)
+            | CoreError::Conflict(msg)
+            | CoreError::RateLimited(msg)
+            | CoreError::PayloadTooLarge(msg)
+            | CoreError::ExternalService(msg)
+            | CoreError::Database(msg)
+            | CoreError::Serialization(msg) => msg.clone(),
+        };
+        
+        let errors = match error {
+            CoreError::InvalidFields(errors) => errors.clone(),
+            _ => Vec::new(),
+        };
+        
+        Self {
+            problem_type: format!("/problems/{}", code.replace('_', "-")),
+            title,
+            status: status.as_u16(),
+            detail,
+            code,
+            correlation_id: correlation::current(),
+            errors,
+        }
+    }
+}
+
+fn code_and_title(error: &CoreError) -> (&'static str, &'static str) {
+    match error {
+        CoreError::Internal(_) => ("internal_error", "Erro interno"),
+        CoreError::NotFound(_) => ("not_found", "Recurso não encontrado"),
+        CoreError::Validation(_) | CoreError::InvalidFields(_) => ("validation_error", "Dados inválidos"),
+        CoreError::Unauthorized(_) => ("unauthorized", "Autenticação necessária"),
+        CoreError::Forbidden(_) => ("forbidden", "Acesso negado"),
+        CoreError::Conflict(_) => ("conflict", "Conflito com o estado atual do recurso"),
+        CoreError::RateLimited(_) => ("rate_limited", "Muitas requisições"),
+        CoreError::PayloadTooLarge(_) => ("payload_too_large", "Corpo da requisição grande demais"),
+        CoreError::ExternalService(_) => ("external_service_error", "Falha em serviço externo"),
+        CoreError::Database(_) => ("database_error", "Erro de banco de dados"),
+        CoreError::Serialization(_) => ("serialization_error", "Erro de serialização"),
+    }
+}
+
+fn status_of(error: &CoreError) -> StatusCode {
+    match error {
+        CoreError::NotFound(_) => StatusCode::NOT_FOUND,
+        CoreError::Validation(_) | CoreError::InvalidFields(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        CoreError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+        CoreError::Forbidden(_) => StatusCode::FORBIDDEN,
+        CoreError::Conflict(_) => StatusCode::CONFLICT,
+        CoreError::RateLimited(_) => StatusCode::TOO_MANY_REQUESTS,
+        CoreError::PayloadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+        CoreError::ExternalService(_) => StatusCode::BAD_GATEWAY,
+        CoreError::Internal(_) | CoreError::Database(_) | CoreError::Serialization(_) => {
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+impl ResponseError for ApiError {
+    fn status_code(&self) -> StatusCode {
+        status_of(&self.0)
+    }
+    
+    fn error_response(&self) -> HttpResponse {
+        let problem = Problem::from_error(&self.0, HIDE_INTERNAL_DETAILS.load(Ordering::Relaxed));
+        
+        if self.status_code().is_server_error() {
+            tracing::error!(
+                correlation_id = problem.correlation_id.as_deref().unwrap_or("-"),
+                "Request failed: {}",
+                self.0
+            );
+        }
+        
+        HttpResponse::build(self.status_code())
+            .content_type(PROBLEM_CONTENT_TYPE)
+            .json(problem)
+    }
+}
+
+/// Corpo JSON malformado também responde com problem+json
+pub fn json_config() -> web::JsonConfig {
+    web::JsonConfig::default().error_handler(|err: JsonPayloadError, _req: &HttpRequest| {
+        let error = match &err {
+            JsonPayloadError::Deserialize(e) => CoreError::validation(format!("JSON inválido: {}", e)),
+            JsonPayloadError::ContentType => CoreError::validation("Content-Type deve ser application/json"),
+            _ => CoreError::validation(format!("Corpo da requisição inválido: {}", err)),
+        };
+        ApiError(error).into()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{body::to_bytes, test::{call_service, init_service, TestRequest}, App};
+    use serde_json::Value;
+    use crate::correlation::CorrelationId;
+    use super::*;
+    
+    #[test]
+    fn test_internal_details_hidden_in_production() {
+        let error = CoreError::database("relation \"users\" does not exist");
+        
+        let dev = Problem::from_error(&error, false);
+        assert_eq!(dev.status, 500);
+        assert!(dev.detail.contains("users"));
+        
+        let prod = Problem::from_error(&error, true);
+        assert_eq!(prod.code, "database_error");
+        assert!(!prod.detail.contains("users"));
+        
+        let not_found = Problem::from_error(&CoreError::not_found("Produto 42"), true);
+        assert_eq!(not_found.detail, "Produto 42");
+    }
+    
+    #[actix_web::test]
+    async fn test_problem_response_with_fields_and_correlation_id() {
+        async fn handler() -> Result<HttpResponse, ApiError> {
+            Err(CoreError::invalid_fields(vec![FieldError::new("price", "deve ser maior que zero")]).into())
+        }
+        
+        let app = init_service(
+            App::new()
+                .wrap(CorrelationId)
+                .wrap(tracing_actix_web::TracingLogger::default())
+                .route("/", web::get().to(handler)),
+        )
+        .await;
+        
+        let resp = call_service(&app, TestRequest::get().uri("/").to_request()).await;
+        assert_eq!(resp.status(), 422);
+        assert_eq!(resp.headers().get("content-type").unwrap(), PROBLEM_CONTENT_TYPE);
+        let header = resp.headers().get(correlation::HEADER).unwrap().to_str().unwrap().to_string();
+        
+        let body: Value = serde_json::from_slice(&to_bytes(resp.into_body()).await.unwrap()).unwrap();
+        assert_eq!(body["type"], "/problems/validation-error");
+        assert_eq!(body["code"], "validation_error");
+        assert_eq!(body["errors"][0]["field"], "price");
+        assert_eq!(body["correlation_id"], header.as_str());
+    }
+}
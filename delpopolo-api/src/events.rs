@@ -0,0 +1,265 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::broadcast;
+use delpopolo_domain::{Order, OrderStatus, Permission, User};
+use delpopolo_infrastructure::Cache;
+use delpopolo_inventory::StockAlert;
+use crate::dto::kitchen::TicketResponse;
+use crate::dto::orders::OrderResponse;
+
+/// Canal do Redis que distribui os eventos entre as instâncias
+const CHANNEL: &str = "delpopolo:events";
+
+/// Eventos guardados por assinante lento antes de ele perder mensagens
+const CAPACITY: usize = 1024;
+
+/// Além deste tempo a publicação no Redis é abandonada e o evento é entregue só localmente
+const REDIS_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// Intervalo máximo entre tentativas de reassinar o canal
+const MAX_RELAY_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Tópicos assináveis; cada um exige uma permissão de leitura
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Topic {
+    /// Pedidos criados e mudanças de status
+    Orders,
+    /// Pedidos que a produção precisa ver: confirmados, em preparo, prontos e cancelados
+    Kitchen,
+    /// Alertas de estoque baixo
+    Stock,
+}
+
+impl Topic {
+    pub const ALL: [Topic; 3] = [Topic::Orders, Topic::Kitchen, Topic::Stock];
+    
+    pub fn permission(self) -> Permission {
+        match self {
+            Topic::Orders => Permission::OrdersRead,
+            Topic::Kitchen => Permission::KitchenOperate,
+            Topic::Stock => Permission::InventoryRead,
+        }
+    }
+    
+    pub fn parse(name: &str) -> Option<Self> {
+        Topic::ALL.into_iter().find(|topic| topic.name() == name)
+    }
+    
+    pub fn name(self) -> &'static str {
+        match self {
+            Topic::Orders => "orders",
+            Topic::Kitchen => "kitchen",
+            Topic::Stock => "stock",
+        }
+    }
+    
+    /// Tópicos que o usuário pode assinar
+    pub fn allowed_for(user: &User) -> Vec<Topic> {
+        Topic::ALL.into_iter().filter(|topic| user.has_permission(topic.permission())).collect()
+    }
+}
+
+/// Evento publicado; chega uma vez a quem assina qualquer um dos `topics`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Event {
+    /// Nome do evento no SSE e campo `type` no WebSocket
+    pub kind: String,
+    pub topics: Vec<Topic>,
+    pub data: Value,
+}
+
+impl Event {
+    /// Pedido recém-criado ou que mudou de status
+    pub fn order(order: &Order, created: bool) -> Self {
+        let mut topics = vec![Topic::Orders];
+        if matches!(
+            order.status,
+            OrderStatus::Confirmed | OrderStatus::Preparing | OrderStatus::Ready | OrderStatus::Cancelled
+        ) {
+            topics.push(Topic::Kitchen);
+        }
+        Self {
+            kind: if created { "order.created" } else { "order.status_changed" }.to_string(),
+            topics,
+            data: serde_json::to_value(OrderResponse::from(order)).unwrap_or_default(),
+        }
+    }
+    
+    pub fn stock_alert(alert: &StockAlert) -> Self {
+        Self {
+            kind: "stock.alert".to_string(),
+            topics: vec![Topic::Stock],
+            data: serde_json::to_value(alert).unwrap_or_default(),
+        }
+    }
+    
+    /// Bump ou recall numa estação do KDS, que não necessariamente muda o status do pedido
+    pub fn kitchen_ticket(kind: &str, ticket: &TicketResponse) -> Self {
+        Self {
+            kind: kind.to_string(),
+            topics: vec![Topic::Kitchen],
+            data: serde_json::to_value(ticket).unwrap_or_default(),
+        }
+    }
+    
+    /// Eventos de outras instâncias podem ter se perdido: o cliente deve recarregar o estado
+    pub fn resync() -> Self {
+        Self {
+            kind: "resync".to_string(),
+            topics: Topic::ALL.to_vec(),
+            data: serde_json::json!({"missed": null}),
+        }
+    }
+    
+    pub fn matches(&self, topics: &[Topic]) -> bool {
+        self.topics.iter().any(|topic| topics.contains(topic))
+    }
+}
+
+/// Distribui eventos para as conexões SSE/WebSocket. Com Redis, publica no canal e cada
+/// instância entrega o que recebe dele (`relay`); sem Redis, ou com o `relay` fora do canal,
+/// entrega direto nesta instância.
+pub struct EventBus {
+    local: broadcast::Sender<Arc<Event>>,
+    redis: Option<RedisPublisher>,
+    /// O `relay` está assinado e vai trazer de volta o que for publicado no canal
+    relay_up: AtomicBool,
+}
+
+struct RedisPublisher {
+    url: String,
+    cache: tokio::sync::Mutex<Option<Cache>>,
+}
+
+impl EventBus {
+    pub fn local() -> Self {
+        Self { local: broadcast::channel(CAPACITY).0, redis: None, relay_up: AtomicBool::new(false) }
+    }
+    
+    pub fn with_redis(url: impl Into<String>) -> Self {
+        Self {
+            redis: Some(RedisPublisher { url: url.into(), cache: tokio::sync::Mutex::new(None) }),
+            ..Self::local()
+        }
+    }
+    
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<Event>> {
+        self.local.subscribe()
+    }
+    
+    /// Não falha a requisição que publicou: sem Redis o evento ao menos chega a esta instância
+    pub async fn publish(&self, event: Event) {
+        if let Some(redis) = &self.redis {
+            match redis.publish(&event).await {
+                Ok(()) if self.relay_up.load(Ordering::Acquire) => return,
+                Ok(()) => tracing::debug!("Redis relay is down, delivering {} locally", event.kind),
+                Err(e) => tracing::warn!("Could not publish {} to Redis, delivering locally only: {:#}", event.kind, e),
+            }
+        }
+        self.deliver(event);
+    }
+    
+    fn deliver(&self, event: Event) {
+        // Sem assinantes o envio falha, e tudo bem
+        let _ = self.local.send(Arc::new(event));
+    }
+    
+    /// Assina o canal do Redis e entrega os eventos às conexões desta instância, reassinando
+    /// quando a conexão cai. Só faz algo quando o barramento foi criado `with_redis`.
+    pub async fn relay(self: Arc<Self>) {
+        let Some(url) = self.redis.as_ref().map(|redis| redis.url.clone()) else {
+            return;
+        };
+        let mut backoff = Duration::from_secs(1);
+        let mut resubscribing = false;
+        loop {
+            match Cache::subscribe(&url, CHANNEL).await {
+                Ok(messages) => {
+                    tracing::info!("Subscribed to Redis channel {}", CHANNEL);
+                    backoff = Duration::from_secs(1);
+                    self.relay_up.store(true, Ordering::Release);
+                    // Fora do canal, os eventos das outras instâncias não chegaram aqui
+                    if resubscribing {
+                        self.deliver(Event::resync());
+                    }
+                    resubscribing = true;
+                    let mut messages = std::pin::pin!(messages);
+                    while let Some(payload) = messages.next().await {
+                        match serde_json::from_str::<Event>(&payload) {
+                            Ok(event) => self.deliver(event),
+                            Err(e) => tracing::warn!("Ignoring malformed event from Redis: {}", e),
+                        }
+                    }
+                    self.relay_up.store(false, Ordering::Release);
+                    tracing::warn!("Redis subscription to {} ended, resubscribing", CHANNEL);
+                }
+                Err(e) => tracing::warn!("Could not subscribe to Redis channel {}: {:#}", CHANNEL, e),
+            }
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(MAX_RELAY_BACKOFF);
+        }
+    }
+}
+
+impl RedisPublisher {
+    async fn publish(&self, event: &Event) -> anyhow::Result<()> {
+        let attempt = async {
+            let mut cache = {
+                let mut cache = self.cache.lock().await;
+                if cache.is_none() {
+                    *cache = Some(Cache::new(&self.url).await?);
+                }
+                cache.clone().expect("conectado acima")
+            };
+            cache.publish(CHANNEL, event).await
+        };
+        tokio::time::timeout(REDIS_TIMEOUT, attempt)
+            .await
+            .unwrap_or_else(|_| Err(anyhow::anyhow!("Redis did not answer in {:?}", REDIS_TIMEOUT)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use delpopolo_domain::OrderSource;
+    use super::*;
+    
+    #[actix_web::test]
+    async fn test_order_events_reach_kitchen_only_once_confirmed() {
+        let bus = EventBus::local();
+        let mut receiver = bus.subscribe();
+        
+        let mut order = Order::new(OrderSource::InStore);
+        bus.publish(Event::order(&order, true)).await;
+        order.status = OrderStatus::Confirmed;
+        bus.publish(Event::order(&order, false)).await;
+        
+        let created = receiver.recv().await.unwrap();
+        assert_eq!(created.kind, "order.created");
+        assert!(created.matches(&[Topic::Orders]));
+        assert!(!created.matches(&[Topic::Kitchen, Topic::Stock]));
+        
+        let confirmed = receiver.recv().await.unwrap();
+        assert_eq!(confirmed.kind, "order.status_changed");
+        assert!(confirmed.matches(&[Topic::Kitchen]));
+        assert_eq!(confirmed.data["id"], order.id.to_string());
+    }
+    
+    #[actix_web::test]
+    async fn test_unresponsive_redis_falls_back_to_local_delivery() {
+        // Aceita a conexão e nunca responde
+        let silent = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let bus = EventBus::with_redis(format!("redis://{}", silent.local_addr().unwrap()));
+        let mut receiver = bus.subscribe();
+        let order = Order::new(OrderSource::InStore);
+        
+        let published = tokio::time::timeout(Duration::from_secs(2), bus.publish(Event::order(&order, true))).await;
+        assert!(published.is_ok());
+        assert_eq!(receiver.try_recv().unwrap().kind, "order.created");
+    }
+}
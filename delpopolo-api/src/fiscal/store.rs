@@ -0,0 +1,54 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use delpopolo_core::CoreError;
+use delpopolo_domain::FiscalEntry;
+use delpopolo_infrastructure::repositories::FiscalRepository;
+
+/// Livro fiscal persistido: notas importadas com os tributos de cada item
+#[async_trait]
+pub trait FiscalStore: Send + Sync {
+    /// `false` quando a chave de acesso já estava lançada
+    async fn save(&self, entry: &FiscalEntry) -> Result<bool, CoreError>;
+    /// Lançamentos emitidos em `[start, end)`
+    async fn entries(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Result<Vec<FiscalEntry>, CoreError>;
+}
+
+#[async_trait]
+impl FiscalStore for FiscalRepository {
+    async fn save(&self, entry: &FiscalEntry) -> Result<bool, CoreError> {
+        FiscalRepository::save(self, entry).await.map_err(CoreError::database)
+    }
+    
+    async fn entries(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Result<Vec<FiscalEntry>, CoreError> {
+        self.find_by_period(start, end).await.map_err(CoreError::database)
+    }
+}
+
+#[cfg(test)]
+pub mod memory {
+    use std::sync::Mutex;
+    use super::*;
+    
+    /// Livro fiscal em memória para os testes
+    #[derive(Default)]
+    pub struct InMemoryFiscalStore {
+        entries: Mutex<Vec<FiscalEntry>>,
+    }
+    
+    #[async_trait]
+    impl FiscalStore for InMemoryFiscalStore {
+        async fn save(&self, entry: &FiscalEntry) -> Result<bool, CoreError> {
+            let mut entries = self.entries.lock().unwrap();
+            if entries.iter().any(|e| e.nfe_key == entry.nfe_key) {
+                return Ok(false);
+            }
+            entries.push(entry.clone());
+            Ok(true)
+        }
+        
+        async fn entries(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Result<Vec<FiscalEntry>, CoreError> {
+            let entries = self.entries.lock().unwrap();
+            Ok(entries.iter().filter(|e| e.issued_at >= start && e.issued_at < end).cloned().collect())
+        }
+    }
+}
@@ -1,5 +0,0 @@
-pub mod health;
-pub mod products;
-
-pub use health::*;
-pub use products::*;
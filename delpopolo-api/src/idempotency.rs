@@ -0,0 +1,521 @@
+use std::collections::HashMap;
+use std::future::{ready, Ready};
+use std::rc::Rc;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use actix_web::{
+    body::{self, BoxBody, MessageBody},
+    dev::{forward_ready, Payload, Service, ServiceRequest, ServiceResponse, Transform},
+    http::{header, Method, StatusCode},
+    web, Error, HttpMessage, HttpResponse, ResponseError,
+};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use futures_util::future::LocalBoxFuture;
+use futures_util::StreamExt;
+use ring::digest;
+use serde::{Deserialize, Serialize};
+use delpopolo_core::CoreError;
+use delpopolo_infrastructure::Cache;
+use crate::auth::extractor::bearer_token;
+use crate::auth::AuthService;
+use crate::error::ApiError;
+
+pub const HEADER: &str = "Idempotency-Key";
+
+/// Indica que a resposta veio de uma execução anterior com a mesma chave
+pub const REPLAYED_HEADER: &str = "Idempotent-Replayed";
+
+const MAX_KEY_LEN: usize = 255;
+
+/// Além deste tempo o Redis é tratado como fora do ar e a requisição segue sem a chave
+const REDIS_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// Maior corpo guardado em memória para calcular a impressão digital (`idempotency.max_body_bytes`)
+#[derive(Debug, Clone, Copy)]
+pub struct IdempotencyBodyLimit(pub usize);
+
+impl Default for IdempotencyBodyLimit {
+    fn default() -> Self {
+        Self(10 * 1024 * 1024)
+    }
+}
+
+/// Cabeçalhos da resposta original que voltam na repetição
+const KEPT_HEADERS: [header::HeaderName; 3] = [header::CONTENT_TYPE, header::LOCATION, header::ETAG];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    /// Corpo em base64
+    pub body: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdempotencyRecord {
+    /// Hash do método, caminho e corpo da primeira requisição
+    pub fingerprint: String,
+    /// `None` enquanto a primeira execução está em andamento
+    pub response: Option<StoredResponse>,
+}
+
+/// Chaves já usadas, com validade definida por quem implementa
+#[async_trait]
+pub trait IdempotencyStore: Send + Sync {
+    /// Reserva a chave pelo prazo de `lease`; devolve o registro anterior quando ela já foi usada.
+    /// Se a execução morrer sem `complete` nem `abandon`, a chave fica livre de novo ao fim do prazo
+    async fn begin(&self, key: &str, fingerprint: &str) -> Result<Option<IdempotencyRecord>>;
+    async fn complete(&self, key: &str, record: &IdempotencyRecord) -> Result<()>;
+    /// Libera a chave para que a repetição execute de novo
+    async fn abandon(&self, key: &str) -> Result<()>;
+}
+
+/// Compartilhado entre as instâncias da API; conecta no primeiro uso
+pub struct RedisIdempotencyStore {
+    url: String,
+    ttl: Duration,
+    lease: Duration,
+    cache: tokio::sync::Mutex<Option<Cache>>,
+}
+
+impl RedisIdempotencyStore {
+    pub fn new(url: impl Into<String>, ttl: Duration, lease: Duration) -> Self {
+        Self { url: url.into(), ttl, lease, cache: tokio::sync::Mutex::new(None) }
+    }
+    
+    async fn cache(&self) -> Result<Cache> {
+        let mut cache = self.cache.lock().await;
+        if cache.is_none() {
+            *cache = Some(Cache::new(&self.url).await?);
+        }
+        Ok(cache.clone().expect("conectado acima"))
+    }
+    
+    fn key(key: &str) -> String {
+        format!("idempotency:{}", key)
+    }
+    
+    async fn timed<T>(attempt: impl std::future::Future<Output = Result<T>>) -> Result<T> {
+        tokio::time::timeout(REDIS_TIMEOUT, attempt)
+            .await
+            .unwrap_or_else(|_| Err(anyhow!("Redis did not answer in {:?}", REDIS_TIMEOUT)))
+    }
+}
+
+#[async_trait]
+impl IdempotencyStore for RedisIdempotencyStore {
+    async fn begin(&self, key: &str, fingerprint: &str) -> Result<Option<IdempotencyRecord>> {
+        Self::timed(async {
+            let mut cache = self.cache().await?;
+            let record = IdempotencyRecord { fingerprint: fingerprint.to_string(), response: None };
+            loop {
+                if cache.set_if_absent(&Self::key(key), &record, self.lease.as_secs().max(1)).await? {
+                    return Ok(None);
+                }
+                // Sem registro, expirou entre o SET e o GET: tenta reservar de novo
+                if let Some(previous) = cache.get(&Self::key(key)).await? {
+                    return Ok(Some(previous));
+                }
+            }
+        })
+        .await
+    }
+    
+    async fn complete(&self, key: &str, record: &IdempotencyRecord) -> Result<()> {
+        let ttl = self.ttl.as_secs() as usize;
+        Self::timed(async { self.cache().await?.set(&Self::key(key), record, Some(ttl)).await }).await
+    }
+    
+    async fn abandon(&self, key: &str) -> Result<()> {
+        Self::timed(async { self.cache().await?.delete(&Self::key(key)).await }).await
+    }
+}
+
+/// Usado sem Redis configurado: vale só para esta instância
+pub struct MemoryIdempotencyStore {
+    ttl: Duration,
+    lease: Duration,
+    records: Mutex<HashMap<String, (Instant, IdempotencyRecord)>>,
+}
+
+impl MemoryIdempotencyStore {
+    pub fn new(ttl: Duration, lease: Duration) -> Self {
+        Self { ttl, lease, records: Mutex::default() }
+    }
+}
+
+#[async_trait]
+impl IdempotencyStore for MemoryIdempotencyStore {
+    async fn begin(&self, key: &str, fingerprint: &str) -> Result<Option<IdempotencyRecord>> {
+        let mut records = self.records.lock().unwrap();
+        let now = Instant::now();
+        records.retain(|_, (expires_at, _)| *expires_at > now);
+        if let Some((_, previous)) = records.get(key) {
+            return Ok(Some(previous.clone()));
+        }
+        let record = IdempotencyRecord { fingerprint: fingerprint.to_string(), response: None };
+        records.insert(key.to_string(), (now + self.lease, record));
+        Ok(None)
+    }
+    
+    async fn complete(&self, key: &str, record: &IdempotencyRecord) -> Result<()> {
+        let expires_at = Instant::now() + self.ttl;
+        self.records.lock().unwrap().insert(key.to_string(), (expires_at, record.clone()));
+        Ok(())
+    }
+    
+    async fn abandon(&self, key: &str) -> Result<()> {
+        self.records.lock().unwrap().remove(key);
+        Ok(())
+    }
+}
+
+/// Honra o header `Idempotency-Key` em POST/PUT/PATCH: a primeira resposta fica guardada e
+/// é devolvida nas repetições; a mesma chave com outra requisição responde 409. A chave vale
+/// por usuário. Sem `IdempotencyStore` registrado, ou com ele fora do ar, a requisição segue normal.
+pub struct Idempotency;
+
+impl<S, B> Transform<S, ServiceRequest> for Idempotency
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = IdempotencyMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+    
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(IdempotencyMiddleware { service: Rc::new(service) }))
+    }
+}
+
+pub struct IdempotencyMiddleware<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for IdempotencyMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+    
+    forward_ready!(service);
+    
+    fn call(&self, mut req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+        let store = req.app_data::<web::Data<dyn IdempotencyStore>>().cloned();
+        let limit = req.app_data::<web::Data<IdempotencyBodyLimit>>().map(|l| *l.get_ref()).unwrap_or_default();
+        let key = req.headers().get(HEADER).map(|v| v.to_str().map(|k| k.trim().to_string()));
+        
+        Box::pin(async move {
+            let (store, key) = match (store, key) {
+                (Some(store), Some(key)) if is_mutating(req.method()) => (store, key),
+                _ => return service.call(req).await.map(ServiceResponse::map_into_boxed_body),
+            };
+            let key = match key {
+                Ok(key) if !key.is_empty() && key.len() <= MAX_KEY_LEN => key,
+                _ => {
+                    let error = CoreError::validation(format!("{} deve ter de 1 a {} caracteres ASCII", HEADER, MAX_KEY_LEN));
+                    return Ok(reject(req, error));
+                }
+            };
+            
+            let Some(body) = read_body(&mut req, limit.0).await? else {
+                let error = CoreError::payload_too_large(format!("Requisições com {} aceitam até {} bytes", HEADER, limit.0));
+                return Ok(reject(req, error));
+            };
+            let fingerprint = fingerprint(&req, &body);
+            let scoped = format!("{}:{}", principal(&req).await, key);
+            req.set_payload(Payload::from(body));
+            
+            match store.begin(&scoped, &fingerprint).await {
+                Ok(None) => {}
+                Ok(Some(previous)) if previous.fingerprint != fingerprint => {
+                    let error = CoreError::conflict(format!("{} {} já foi usada em outra requisição", HEADER, key));
+                    return Ok(reject(req, error));
+                }
+                Ok(Some(IdempotencyRecord { response: Some(response), .. })) => {
+                    return Ok(req.into_response(replay(&response)));
+                }
+                Ok(Some(_)) => {
+                    let error = CoreError::conflict(format!("Requisição com {} {} ainda está em processamento", HEADER, key));
+                    return Ok(reject(req, error));
+                }
+                Err(e) => {
+                    tracing::warn!("Idempotency store unavailable, executing request without it: {:#}", e);
+                    return service.call(req).await.map(ServiceResponse::map_into_boxed_body);
+                }
+            }
+            
+            let res = match service.call(req).await {
+                Ok(res) if is_kept(res.status()) => res,
+                other => {
+                    abandon(&**store, &scoped).await;
+                    return other.map(ServiceResponse::map_into_boxed_body);
+                }
+            };
+            
+            let (http_req, res) = res.into_parts();
+            let (res, body) = res.into_parts();
+            let body = match body::to_bytes(body).await {
+                Ok(body) => body,
+                Err(e) => {
+                    abandon(&**store, &scoped).await;
+                    return Err(actix_web::error::ErrorInternalServerError(e.into()));
+                }
+            };
+            let record = IdempotencyRecord {
+                fingerprint,
+                response: Some(StoredResponse {
+                    status: res.status().as_u16(),
+                    headers: KEPT_HEADERS
+                        .iter()
+                        .filter_map(|name| {
+                            let value = res.headers().get(name)?.to_str().ok()?;
+                            Some((name.to_string(), value.to_string()))
+                        })
+                        .collect(),
+                    body: STANDARD.encode(&body),
+                }),
+            };
+            if let Err(e) = store.complete(&scoped, &record).await {
+                tracing::error!("Failed to store idempotent response for key {}: {:#}", key, e);
+            }
+            
+            Ok(ServiceResponse::new(http_req, res.set_body(body).map_into_boxed_body()))
+        })
+    }
+}
+
+fn is_mutating(method: &Method) -> bool {
+    [Method::POST, Method::PUT, Method::PATCH].contains(method)
+}
+
+/// Erros de servidor e recusas antes de executar (autenticação, limite) não prendem a chave
+fn is_kept(status: StatusCode) -> bool {
+    !status.is_server_error()
+        && ![StatusCode::UNAUTHORIZED, StatusCode::FORBIDDEN, StatusCode::TOO_MANY_REQUESTS].contains(&status)
+}
+
+/// `None` quando o corpo passa de `limit`, pelo `Content-Length` ou durante a leitura
+async fn read_body(req: &mut ServiceRequest, limit: usize) -> Result<Option<web::Bytes>, Error> {
+    let declared = req
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok()?.parse::<usize>().ok());
+    if declared.is_some_and(|length| length > limit) {
+        return Ok(None);
+    }
+    
+    let mut payload = req.take_payload();
+    let mut body = web::BytesMut::new();
+    while let Some(chunk) = payload.next().await {
+        let chunk = chunk?;
+        if body.len() + chunk.len() > limit {
+            return Ok(None);
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(Some(body.freeze()))
+}
+
+fn fingerprint(req: &ServiceRequest, body: &[u8]) -> String {
+    let mut context = digest::Context::new(&digest::SHA256);
+    context.update(req.method().as_str().as_bytes());
+    context.update(b" ");
+    context.update(req.uri().path_and_query().map(|p| p.as_str()).unwrap_or_default().as_bytes());
+    context.update(b"\n");
+    context.update(body);
+    STANDARD.encode(context.finish())
+}
+
+/// Usuário do access token; o validado fica nas extensions e `RequirePermission` o reaproveita
+async fn principal(req: &ServiceRequest) -> String {
+    let auth = req.app_data::<web::Data<AuthService>>().cloned();
+    let (Some(auth), Some(token)) = (auth, bearer_token(req.request())) else {
+        return "anonymous".to_string();
+    };
+    match auth.authenticate(&token).await {
+        Ok(user) => {
+            let id = user.id;
+            req.extensions_mut().insert(user);
+            id.to_string()
+        }
+        Err(_) => "anonymous".to_string(),
+    }
+}
+
+fn replay(response: &StoredResponse) -> HttpResponse {
+    let status = StatusCode::from_u16(response.status).unwrap_or(StatusCode::OK);
+    let mut builder = HttpResponse::build(status);
+    for (name, value) in &response.headers {
+        builder.insert_header((name.as_str(), value.as_str()));
+    }
+    builder
+        .insert_header((REPLAYED_HEADER, "true"))
+        .body(STANDARD.decode(&response.body).unwrap_or_default())
+}
+
+fn reject(req: ServiceRequest, error: CoreError) -> ServiceResponse<BoxBody> {
+    req.into_response(ApiError(error).error_response())
+}
+
+async fn abandon(store: &dyn IdempotencyStore, key: &str) {
+    if let Err(e) = store.abandon(key).await {
+        tracing::error!("Failed to release idempotency key {}: {:#}", key, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use actix_web::{test, App};
+    use serde_json::{json, Value};
+    use super::*;
+    
+    /// Conta as execuções; a primeira falha com 500 quando `fail_first`
+    #[derive(Default)]
+    struct Calls {
+        count: AtomicUsize,
+        fail_first: bool,
+    }
+    
+    async fn create(calls: web::Data<Calls>, body: web::Json<Value>) -> HttpResponse {
+        let n = calls.count.fetch_add(1, Ordering::SeqCst) + 1;
+        if calls.fail_first && n == 1 {
+            return HttpResponse::InternalServerError().finish();
+        }
+        HttpResponse::Created()
+            .insert_header((header::LOCATION, format!("/orders/{n}")))
+            .json(json!({"n": n, "total": body["total"]}))
+    }
+    
+    fn store(ttl: Duration) -> web::Data<dyn IdempotencyStore> {
+        web::Data::from(Arc::new(MemoryIdempotencyStore::new(ttl, Duration::from_secs(30))) as Arc<dyn IdempotencyStore>)
+    }
+    
+    fn orders(calls: Calls, ttl: Duration) -> App<impl actix_web::dev::ServiceFactory<
+        ServiceRequest,
+        Config = (),
+        Response = ServiceResponse<BoxBody>,
+        Error = Error,
+        InitError = (),
+    >> {
+        App::new()
+            .app_data(store(ttl))
+            .app_data(web::Data::new(calls))
+            .service(web::scope("").wrap(Idempotency).route("/orders", web::post().to(create)))
+    }
+    
+    fn post(key: Option<&str>, total: f64) -> test::TestRequest {
+        let req = test::TestRequest::post().uri("/orders").set_json(json!({"total": total}));
+        match key {
+            Some(key) => req.insert_header((HEADER, key)),
+            None => req,
+        }
+    }
+    
+    #[actix_web::test]
+    async fn test_retry_replays_first_response_and_other_body_conflicts() {
+        let app = test::init_service(orders(Calls::default(), Duration::from_secs(60))).await;
+        
+        let first = test::call_service(&app, post(Some("tablet-1"), 10.0).to_request()).await;
+        assert_eq!(first.status(), 201);
+        assert!(first.headers().get(REPLAYED_HEADER).is_none());
+        let first_body = test::read_body(first).await;
+        
+        let retry = test::call_service(&app, post(Some("tablet-1"), 10.0).to_request()).await;
+        assert_eq!(retry.status(), 201);
+        assert_eq!(retry.headers().get(REPLAYED_HEADER).unwrap(), "true");
+        assert_eq!(retry.headers().get(header::LOCATION).unwrap(), "/orders/1");
+        assert_eq!(retry.headers().get(header::CONTENT_TYPE).unwrap(), "application/json");
+        assert_eq!(test::read_body(retry).await, first_body);
+        
+        let other = test::call_service(&app, post(Some("tablet-1"), 12.0).to_request()).await;
+        assert_eq!(other.status(), 409);
+        let problem: Value = test::read_body_json(other).await;
+        assert_eq!(problem["code"], "conflict");
+        
+        // Sem chave, cada requisição executa
+        let plain = test::call_service(&app, post(None, 10.0).to_request()).await;
+        let plain: Value = test::read_body_json(plain).await;
+        assert_eq!(plain["n"], 2);
+    }
+    
+    #[actix_web::test]
+    async fn test_server_errors_and_expired_keys_execute_again() {
+        let app = test::init_service(orders(Calls { fail_first: true, ..Default::default() }, Duration::from_secs(60))).await;
+        let failed = test::call_service(&app, post(Some("kiosk-7"), 10.0).to_request()).await;
+        assert_eq!(failed.status(), 500);
+        let retry = test::call_service(&app, post(Some("kiosk-7"), 10.0).to_request()).await;
+        assert_eq!(retry.status(), 201);
+        assert!(retry.headers().get(REPLAYED_HEADER).is_none());
+        
+        let app = test::init_service(orders(Calls::default(), Duration::ZERO)).await;
+        test::call_service(&app, post(Some("kiosk-7"), 10.0).to_request()).await;
+        let again = test::call_service(&app, post(Some("kiosk-7"), 10.0).to_request()).await;
+        let again: Value = test::read_body_json(again).await;
+        assert_eq!(again["n"], 2);
+    }
+    
+    #[actix_web::test]
+    async fn test_oversized_body_is_rejected_before_buffering() {
+        let app = test::init_service(
+            orders(Calls::default(), Duration::from_secs(60)).app_data(web::Data::new(IdempotencyBodyLimit(32))),
+        )
+        .await;
+        let small = test::call_service(&app, post(Some("pos-1"), 10.0).to_request()).await;
+        assert_eq!(small.status(), 201);
+        
+        let big = test::TestRequest::post()
+            .uri("/orders")
+            .insert_header((HEADER, "pos-2"))
+            .set_json(json!({"total": 10.0, "notes": "x".repeat(64)}))
+            .to_request();
+        let resp = test::call_service(&app, big).await;
+        assert_eq!(resp.status(), 413);
+        let problem: Value = test::read_body_json(resp).await;
+        assert_eq!(problem["code"], "payload_too_large");
+    }
+    
+    #[actix_web::test]
+    async fn test_pending_key_is_released_after_the_lease() {
+        let store = MemoryIdempotencyStore::new(Duration::from_secs(60), Duration::from_secs(60));
+        assert!(store.begin("pos-3", "f").await.unwrap().is_none());
+        assert!(store.begin("pos-3", "f").await.unwrap().unwrap().response.is_none());
+        
+        // Execução que morreu sem `complete`: ao fim do prazo a chave pode ser usada de novo
+        let store = MemoryIdempotencyStore::new(Duration::from_secs(60), Duration::ZERO);
+        assert!(store.begin("pos-3", "f").await.unwrap().is_none());
+        assert!(store.begin("pos-3", "f").await.unwrap().is_none());
+    }    
+    #[actix_web::test]
+    async fn test_unresponsive_redis_does_not_hold_the_request() {
+        // Aceita a conexão e nunca responde
+        let silent = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("redis://{}", silent.local_addr().unwrap());
+        let store = RedisIdempotencyStore::new(url, Duration::from_secs(60), Duration::from_secs(30));
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::from(Arc::new(store) as Arc<dyn IdempotencyStore>))
+                .app_data(web::Data::new(Calls::default()))
+                .service(web::scope("").wrap(Idempotency).route("/orders", web::post().to(create))),
+        )
+        .await;
+        
+        let started = Instant::now();
+        let resp = test::call_service(&app, post(Some("pos-4"), 10.0).to_request()).await;
+        assert_eq!(resp.status(), 201);
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+}
@@ -0,0 +1,280 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use delpopolo_core::CoreError;
+use delpopolo_domain::{Inventory, InventoryMovement};
+use delpopolo_infrastructure::repositories::inventory_repository::{MovementQuery, ProcessedRequest};
+use delpopolo_inventory::{InventoryService, StockAlert};
+
+/// Operação de estoque pedida pela API; é o que fica registrado para o `request_id`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "operation", rename_all = "snake_case")]
+pub enum StockOperation {
+    Entry {
+        product_id: Uuid,
+        quantity: f64,
+        unit_cost: Option<f64>,
+        nfe_key: Option<String>,
+        supplier_id: Option<Uuid>,
+    },
+    Withdrawal {
+        product_id: Uuid,
+        quantity: f64,
+        order_id: Option<Uuid>,
+    },
+    Reservation {
+        product_id: Uuid,
+        quantity: f64,
+        order_id: Uuid,
+    },
+    Release {
+        product_id: Uuid,
+        quantity: f64,
+    },
+    Adjustment {
+        product_id: Uuid,
+        new_quantity: f64,
+        reason: String,
+    },
+    Loss {
+        product_id: Uuid,
+        quantity: f64,
+        reason: String,
+    },
+}
+
+/// Operações, consultas e registro de idempotência do estoque
+#[async_trait]
+pub trait InventoryStore: Send + Sync {
+    /// Executa a operação e devolve o saldo resultante
+    async fn execute(&self, operation: &StockOperation, performed_by: Uuid) -> Result<Inventory, CoreError>;
+    async fn levels(&self, product_ids: &[Uuid]) -> Result<Vec<Inventory>, CoreError>;
+    async fn movements(&self, query: &MovementQuery) -> Result<Vec<InventoryMovement>, CoreError>;
+    async fn alerts(&self) -> Result<Vec<StockAlert>, CoreError>;
+    /// `None` quando o id é novo, ou retomado de uma execução sem resposta que passou do
+    /// prazo, e fica reservado para esta execução
+    async fn begin_request(&self, request_id: Uuid, payload: &serde_json::Value) -> Result<Option<ProcessedRequest>, CoreError>;
+    async fn complete_request(&self, request_id: Uuid, response: &serde_json::Value) -> Result<(), CoreError>;
+    async fn abandon_request(&self, request_id: Uuid) -> Result<(), CoreError>;
+}
+
+/// O serviço sinaliza saldo ausente e insuficiente com `CoreError`; o resto é falha de banco
+fn inventory_error(e: anyhow::Error) -> CoreError {
+    e.downcast::<CoreError>().unwrap_or_else(CoreError::database)
+}
+
+#[async_trait]
+impl InventoryStore for InventoryService {
+    async fn execute(&self, operation: &StockOperation, performed_by: Uuid) -> Result<Inventory, CoreError> {
+        let result = match operation.clone() {
+            StockOperation::Entry { product_id, quantity, unit_cost, nfe_key, supplier_id } => {
+                self.add_stock(product_id, quantity, unit_cost, nfe_key, supplier_id, Some(performed_by)).await
+            }
+            StockOperation::Withdrawal { product_id, quantity, order_id } => {
+                self.remove_stock(product_id, quantity, order_id, Some(performed_by)).await
+            }
+            StockOperation::Reservation { product_id, quantity, order_id } => {
+                self.reserve_stock(product_id, quantity, order_id).await
+            }
+            StockOperation::Release { product_id, quantity } => self.release_reservation(product_id, quantity).await,
+            StockOperation::Adjustment { product_id, new_quantity, reason } => {
+                self.adjust_stock(product_id, new_quantity, reason, performed_by).await
+            }
+            StockOperation::Loss { product_id, quantity, reason } => {
+                self.register_loss(product_id, quantity, reason, performed_by).await
+            }
+        };
+        result.map_err(inventory_error)
+    }
+    
+    async fn levels(&self, product_ids: &[Uuid]) -> Result<Vec<Inventory>, CoreError> {
+        self.get_levels(product_ids).await.map_err(inventory_error)
+    }
+    
+    async fn movements(&self, query: &MovementQuery) -> Result<Vec<InventoryMovement>, CoreError> {
+        self.get_movements(query).await.map_err(inventory_error)
+    }
+    
+    async fn alerts(&self) -> Result<Vec<StockAlert>, CoreError> {
+        self.stock_alerts().await.map_err(inventory_error)
+    }
+    
+    async fn begin_request(&self, request_id: Uuid, payload: &serde_json::Value) -> Result<Option<ProcessedRequest>, CoreError> {
+        InventoryService::begin_request(self, request_id, payload).await.map_err(inventory_error)
+    }
+    
+    async fn complete_request(&self, request_id: Uuid, response: &serde_json::Value) -> Result<(), CoreError> {
+        InventoryService::complete_request(self, request_id, response).await.map_err(inventory_error)
+    }
+    
+    async fn abandon_request(&self, request_id: Uuid) -> Result<(), CoreError> {
+        InventoryService::abandon_request(self, request_id).await.map_err(inventory_error)
+    }
+}
+
+#[cfg(test)]
+pub mod memory {
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+    use chrono::{DateTime, Duration, Utc};
+    use delpopolo_domain::MovementType;
+    use delpopolo_infrastructure::repositories::inventory_repository::REQUEST_LEASE_SECS;
+    use delpopolo_inventory::AlertManager;
+    use super::*;
+    
+    /// Saldos, razão e idempotência em memória, com as mesmas regras do `InventoryService`
+    #[derive(Default)]
+    pub struct InMemoryInventoryStore {
+        levels: Mutex<HashMap<Uuid, Inventory>>,
+        movements: Mutex<Vec<InventoryMovement>>,
+        requests: Mutex<HashMap<Uuid, (DateTime<Utc>, ProcessedRequest)>>,
+        minimums: HashMap<Uuid, (String, f64)>,
+    }
+    
+    impl InMemoryInventoryStore {
+        /// Produto com estoque mínimo, para os alertas
+        pub fn with_minimum(mut self, product_id: Uuid, name: &str, min_stock_level: f64) -> Self {
+            self.minimums.insert(product_id, (name.to_string(), min_stock_level));
+            self
+        }
+        
+        /// Execução que parou antes de gravar a resposta, iniciada em `started_at`
+        pub fn with_pending_request(self, request_id: Uuid, operation: &StockOperation, started_at: DateTime<Utc>) -> Self {
+            let request = ProcessedRequest { payload: serde_json::to_value(operation).unwrap(), response: None };
+            self.requests.lock().unwrap().insert(request_id, (started_at, request));
+            self
+        }
+        
+        pub fn movement_count(&self) -> usize {
+            self.movements.lock().unwrap().len()
+        }
+    }
+    
+    #[async_trait]
+    impl InventoryStore for InMemoryInventoryStore {
+        async fn execute(&self, operation: &StockOperation, performed_by: Uuid) -> Result<Inventory, CoreError> {
+            let mut levels = self.levels.lock().unwrap();
+            let existing = |levels: &HashMap<Uuid, Inventory>, id: Uuid| {
+                levels
+                    .get(&id)
+                    .cloned()
+                    .ok_or_else(|| CoreError::not_found(format!("Produto {} sem registro de estoque", id)))
+            };
+            
+            let (mut inventory, movement) = match operation.clone() {
+                StockOperation::Entry { product_id, quantity, unit_cost, nfe_key, supplier_id } => {
+                    let mut inventory = levels.get(&product_id).cloned().unwrap_or_else(|| Inventory::new(product_id));
+                    inventory.add_quantity(quantity);
+                    let mut movement = InventoryMovement::new(product_id, MovementType::Purchase, quantity);
+                    movement.unit_cost = unit_cost;
+                    movement.nfe_key = nfe_key;
+                    movement.supplier_id = supplier_id;
+                    (inventory, Some(movement))
+                }
+                StockOperation::Withdrawal { product_id, quantity, order_id } => {
+                    let mut inventory = existing(&levels, product_id)?;
+                    inventory.remove_quantity(quantity).map_err(CoreError::conflict)?;
+                    let mut movement = InventoryMovement::new(product_id, MovementType::Sale, quantity);
+                    movement.order_id = order_id;
+                    (inventory, Some(movement))
+                }
+                StockOperation::Reservation { product_id, quantity, .. } => {
+                    let mut inventory = existing(&levels, product_id)?;
+                    inventory.reserve(quantity).map_err(CoreError::conflict)?;
+                    (inventory, None)
+                }
+                StockOperation::Release { product_id, quantity } => {
+                    let mut inventory = existing(&levels, product_id)?;
+                    inventory.release_reservation(quantity);
+                    (inventory, None)
+                }
+                StockOperation::Adjustment { product_id, new_quantity, reason } => {
+                    let mut inventory = levels.get(&product_id).cloned().unwrap_or_else(|| Inventory::new(product_id));
+                    let mut movement =
+                        InventoryMovement::new(product_id, MovementType::Adjustment, new_quantity - inventory.quantity);
+                    movement.notes = Some(reason);
+                    inventory.quantity = new_quantity;
+                    inventory.recalculate_available();
+                    (inventory, Some(movement))
+                }
+                StockOperation::Loss { product_id, quantity, reason } => {
+                    let mut inventory = existing(&levels, product_id)?;
+                    inventory.remove_quantity(quantity).map_err(CoreError::conflict)?;
+                    let mut movement = InventoryMovement::new(product_id, MovementType::Loss, quantity);
+                    movement.notes = Some(reason);
+                    (inventory, Some(movement))
+                }
+            };
+            
+            inventory.updated_at = chrono::Utc::now();
+            if let Some(mut movement) = movement {
+                movement.performed_by = Some(performed_by);
+                self.movements.lock().unwrap().push(movement);
+            }
+            levels.insert(inventory.product_id, inventory.clone());
+            Ok(inventory)
+        }
+        
+        async fn levels(&self, product_ids: &[Uuid]) -> Result<Vec<Inventory>, CoreError> {
+            let levels = self.levels.lock().unwrap();
+            Ok(product_ids
+                .iter()
+                .map(|id| levels.get(id).cloned().unwrap_or_else(|| Inventory::new(*id)))
+                .collect())
+        }
+        
+        async fn movements(&self, query: &MovementQuery) -> Result<Vec<InventoryMovement>, CoreError> {
+            let mut items: Vec<InventoryMovement> =
+                self.movements.lock().unwrap().iter().filter(|m| query.matches(m)).cloned().collect();
+            items.sort_by_key(|m| std::cmp::Reverse((m.created_at, m.id)));
+            
+            if let Some(cursor) = &query.after {
+                items.retain(|m| (m.created_at, m.id) < (cursor.created_at, cursor.id));
+            }
+            items.truncate(query.limit as usize);
+            Ok(items)
+        }
+        
+        async fn alerts(&self) -> Result<Vec<StockAlert>, CoreError> {
+            let levels = self.levels.lock().unwrap();
+            let mut manager = AlertManager::new();
+            for (product_id, (name, min)) in &self.minimums {
+                let available = levels.get(product_id).map_or(0.0, |i| i.available_quantity);
+                if available <= min * 1.2 {
+                    manager.add_alert(StockAlert::new(*product_id, name.clone(), available, *min));
+                }
+            }
+            Ok(manager.get_all_unacknowledged().into_iter().cloned().collect())
+        }
+        
+        async fn begin_request(&self, request_id: Uuid, payload: &serde_json::Value) -> Result<Option<ProcessedRequest>, CoreError> {
+            let mut requests = self.requests.lock().unwrap();
+            let now = Utc::now();
+            if let Some((started_at, existing)) = requests.get(&request_id) {
+                let stale = existing.response.is_none()
+                    && existing.payload == *payload
+                    && *started_at < now - Duration::seconds(REQUEST_LEASE_SECS);
+                if !stale {
+                    return Ok(Some(existing.clone()));
+                }
+            }
+            requests.insert(request_id, (now, ProcessedRequest { payload: payload.clone(), response: None }));
+            Ok(None)
+        }
+        
+        async fn complete_request(&self, request_id: Uuid, response: &serde_json::Value) -> Result<(), CoreError> {
+            if let Some((_, request)) = self.requests.lock().unwrap().get_mut(&request_id) {
+                request.response = Some(response.clone());
+            }
+            Ok(())
+        }
+        
+        async fn abandon_request(&self, request_id: Uuid) -> Result<(), CoreError> {
+            let mut requests = self.requests.lock().unwrap();
+            if requests.get(&request_id).is_some_and(|(_, r)| r.response.is_none()) {
+                requests.remove(&request_id);
+            }
+            Ok(())
+        }
+    }
+}
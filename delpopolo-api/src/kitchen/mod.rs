@@ -0,0 +1,255 @@
+pub mod store;
+
+pub use store::KitchenStore;
+
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use delpopolo_core::CoreError;
+use delpopolo_domain::{KitchenStation, KitchenTicket, Order, OrderItem, OrderStatus, TicketStage};
+use delpopolo_infrastructure::config::KitchenConfig;
+use delpopolo_infrastructure::repositories::order_repository::OrderQuery;
+use crate::catalog::ProductStore;
+use crate::orders::OrderStore;
+
+/// Confirmados e em preparo aparecem nas filas; prontos ficam disponíveis para o recall
+const BOARD_STATUSES: [OrderStatus; 3] = [OrderStatus::Confirmed, OrderStatus::Preparing, OrderStatus::Ready];
+
+/// Pedidos carregados por status ao montar o painel
+const BOARD_LIMIT: i64 = 200;
+
+/// Finalizados mostrados por estação para o recall
+const RECALL_LIMIT: usize = 10;
+
+/// Prazos usados para ordenar as filas e destacar atrasos
+#[derive(Debug, Clone, Copy)]
+pub struct KitchenSettings {
+    pub default_preparation_minutes: i64,
+    pub due_soon_minutes: i64,
+}
+
+impl From<&KitchenConfig> for KitchenSettings {
+    fn from(config: &KitchenConfig) -> Self {
+        Self {
+            default_preparation_minutes: config.default_preparation_minutes,
+            due_soon_minutes: config.due_soon_minutes,
+        }
+    }
+}
+
+/// Destaque do pedido na tela conforme o horário prometido
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+pub enum Urgency {
+    OnTime,
+    DueSoon,
+    Late,
+}
+
+impl KitchenSettings {
+    pub fn promised_at(&self, order: &Order) -> DateTime<Utc> {
+        order.promised_at(self.default_preparation_minutes)
+    }
+    
+    pub fn urgency(&self, promised_at: DateTime<Utc>, now: DateTime<Utc>) -> Urgency {
+        if now > promised_at {
+            Urgency::Late
+        } else if now + Duration::minutes(self.due_soon_minutes) >= promised_at {
+            Urgency::DueSoon
+        } else {
+            Urgency::OnTime
+        }
+    }
+}
+
+/// Pedido visto por uma estação: só os itens que ela prepara e o andamento dela
+#[derive(Debug, Clone)]
+pub struct StationTicket {
+    pub order: Order,
+    pub items: Vec<OrderItem>,
+    pub ticket: KitchenTicket,
+}
+
+/// Pedidos em produção com a estação de cada produto e o andamento por estação
+pub struct Board {
+    orders: Vec<Order>,
+    routes: HashMap<Uuid, Option<KitchenStation>>,
+    tickets: HashMap<(Uuid, KitchenStation), KitchenTicket>,
+}
+
+impl Board {
+    pub async fn load(
+        orders: &dyn OrderStore,
+        products: &dyn ProductStore,
+        kitchen: &dyn KitchenStore,
+    ) -> Result<Self, CoreError> {
+        let mut loaded = Vec::new();
+        for status in BOARD_STATUSES {
+            // Mais antigos primeiro: acima do limite, quem fica de fora são os que chegaram por último
+            let query = OrderQuery { status: Some(status), limit: BOARD_LIMIT, oldest_first: true, ..OrderQuery::default() };
+            loaded.extend(orders.list(&query).await?);
+        }
+        Self::for_orders(loaded, products, kitchen).await
+    }
+    
+    /// Roteia pela categoria do produto, consultando o catálogo uma vez por produto
+    pub async fn for_orders(
+        orders: Vec<Order>,
+        products: &dyn ProductStore,
+        kitchen: &dyn KitchenStore,
+    ) -> Result<Self, CoreError> {
+        let mut routes = HashMap::new();
+        for item in orders.iter().flat_map(|order| &order.items) {
+            if let Entry::Vacant(entry) = routes.entry(item.product_id) {
+                let product = products.find(item.product_id).await?;
+                entry.insert(product.and_then(|product| KitchenStation::for_category(product.category)));
+            }
+        }
+        
+        let mut board = Self { orders, routes, tickets: HashMap::new() };
+        board.refresh_tickets(kitchen).await?;
+        Ok(board)
+    }
+    
+    pub async fn refresh_tickets(&mut self, kitchen: &dyn KitchenStore) -> Result<(), CoreError> {
+        let ids: Vec<Uuid> = self.orders.iter().map(|order| order.id).collect();
+        self.tickets = kitchen.tickets(&ids).await?
+            .into_iter()
+            .map(|ticket| ((ticket.order_id, ticket.station), ticket))
+            .collect();
+        Ok(())
+    }
+    
+    fn items_for(&self, order: &Order, station: KitchenStation) -> Vec<OrderItem> {
+        order.items.iter()
+            .filter(|item| self.routes.get(&item.product_id).copied().flatten() == Some(station))
+            .cloned()
+            .collect()
+    }
+    
+    /// Estações que preparam algum item do pedido
+    pub fn stations_of(&self, order: &Order) -> Vec<KitchenStation> {
+        KitchenStation::ALL.into_iter().filter(|&station| !self.items_for(order, station).is_empty()).collect()
+    }
+    
+    fn station_ticket(&self, order: &Order, station: KitchenStation) -> Option<StationTicket> {
+        let items = self.items_for(order, station);
+        if items.is_empty() {
+            return None;
+        }
+        let ticket = self.tickets.get(&(order.id, station)).cloned()
+            .unwrap_or_else(|| KitchenTicket::new(order.id, station));
+        Some(StationTicket { order: order.clone(), items, ticket })
+    }
+    
+    /// `None` quando o pedido não está no painel ou a estação não prepara nada dele
+    pub fn ticket(&self, order_id: Uuid, station: KitchenStation) -> Option<StationTicket> {
+        let order = self.orders.iter().find(|order| order.id == order_id)?;
+        self.station_ticket(order, station)
+    }
+    
+    /// Todas as estações do pedido já finalizaram a sua parte
+    pub fn is_finished(&self, order_id: Uuid) -> bool {
+        self.orders.iter().find(|order| order.id == order_id).is_some_and(|order| {
+            self.stations_of(order).into_iter().all(|station| {
+                self.tickets.get(&(order.id, station)).is_some_and(|ticket| ticket.stage() == TicketStage::Done)
+            })
+        })
+    }
+    
+    /// Pedidos a fazer na estação, do horário prometido mais cedo ao mais tarde
+    pub fn queue(&self, station: KitchenStation, settings: &KitchenSettings) -> Vec<StationTicket> {
+        let mut queue: Vec<StationTicket> = self.orders.iter()
+            .filter(|order| matches!(order.status, OrderStatus::Confirmed | OrderStatus::Preparing))
+            .filter_map(|order| self.station_ticket(order, station))
+            .filter(|ticket| ticket.ticket.stage() != TicketStage::Done)
+            .collect();
+        queue.sort_by_key(|ticket| (settings.promised_at(&ticket.order), ticket.order.created_at));
+        queue
+    }
+    
+    /// Finalizados na estação que ainda podem voltar ao preparo, mais recentes primeiro
+    pub fn done(&self, station: KitchenStation) -> Vec<StationTicket> {
+        let mut done: Vec<StationTicket> = self.orders.iter()
+            .filter(|order| matches!(order.status, OrderStatus::Preparing | OrderStatus::Ready))
+            .filter_map(|order| self.station_ticket(order, station))
+            .filter(|ticket| ticket.ticket.stage() == TicketStage::Done)
+            .collect();
+        done.sort_by_key(|ticket| std::cmp::Reverse(ticket.ticket.bumped_at));
+        done.truncate(RECALL_LIMIT);
+        done
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use delpopolo_domain::{Money, OrderSource, Product, ProductCategory, UnitOfMeasure};
+    use crate::catalog::store::memory::InMemoryProductStore;
+    use crate::orders::store::memory::InMemoryOrderStore;
+    use super::store::memory::InMemoryKitchenStore;
+    use super::*;
+    
+    const SETTINGS: KitchenSettings = KitchenSettings { default_preparation_minutes: 20, due_soon_minutes: 5 };
+    
+    fn product(name: &str, category: ProductCategory) -> Product {
+        Product::new(name.to_string(), category, UnitOfMeasure::Unit, Money::brl(10.0), Money::brl(4.0))
+    }
+    
+    #[actix_web::test]
+    async fn test_items_are_routed_and_queued_by_promised_time() {
+        let (baguete, misto, suco) = (
+            product("Baguete", ProductCategory::Bread),
+            product("Misto quente", ProductCategory::Sandwich),
+            product("Refrigerante lata", ProductCategory::Other),
+        );
+        let products = Arc::new(InMemoryProductStore::default().with_products(vec![baguete.clone(), misto.clone(), suco.clone()]));
+        
+        let mut balcao = Order::new(OrderSource::InStore);
+        balcao.add_item(OrderItem::from_product(&baguete, 2.0, None));
+        balcao.add_item(OrderItem::from_product(&misto, 1.0, Some("sem manteiga".to_string())));
+        balcao.add_item(OrderItem::from_product(&suco, 1.0, None));
+        balcao.status = OrderStatus::Confirmed;
+        // Agendado para daqui a pouco: passa à frente do pedido de balcão
+        let mut ifood = Order::new(OrderSource::IFood);
+        ifood.add_item(OrderItem::from_product(&baguete, 1.0, None));
+        ifood.status = OrderStatus::Preparing;
+        ifood.delivery_time = Some(Utc::now() + Duration::minutes(3));
+        
+        let board = Board::for_orders(vec![balcao.clone(), ifood.clone()], products.as_ref(), &InMemoryKitchenStore::default())
+            .await
+            .unwrap();
+        
+        assert_eq!(board.stations_of(&balcao), vec![KitchenStation::Bakery, KitchenStation::Sandwich]);
+        let bakery = board.queue(KitchenStation::Bakery, &SETTINGS);
+        assert_eq!(bakery.iter().map(|t| t.order.id).collect::<Vec<_>>(), vec![ifood.id, balcao.id]);
+        let sandwich = board.queue(KitchenStation::Sandwich, &SETTINGS);
+        assert_eq!(sandwich.len(), 1);
+        assert_eq!(sandwich[0].items[0].product_name, "Misto quente");
+        assert!(board.queue(KitchenStation::Beverage, &SETTINGS).is_empty());
+        
+        let now = Utc::now();
+        assert_eq!(SETTINGS.urgency(SETTINGS.promised_at(&ifood), now), Urgency::DueSoon);
+        assert_eq!(SETTINGS.urgency(SETTINGS.promised_at(&balcao), now), Urgency::OnTime);
+        assert_eq!(SETTINGS.urgency(now - Duration::minutes(1), now), Urgency::Late);
+    }    
+    #[actix_web::test]
+    async fn test_board_keeps_the_oldest_orders_above_the_limit() {
+        let orders = InMemoryOrderStore::default();
+        let start = Utc::now() - Duration::hours(5);
+        let mut oldest = None;
+        for minute in 0..=BOARD_LIMIT {
+            let mut order = Order::new(OrderSource::InStore);
+            order.status = OrderStatus::Confirmed;
+            order.created_at = start + Duration::minutes(minute);
+            orders.insert(&order).await.unwrap();
+            oldest.get_or_insert(order.id);
+        }
+        
+        let board = Board::load(&orders, &InMemoryProductStore::default(), &InMemoryKitchenStore::default()).await.unwrap();
+        assert_eq!(board.orders.len() as i64, BOARD_LIMIT);
+        assert!(board.orders.iter().any(|order| Some(order.id) == oldest));
+    }
+}
@@ -0,0 +1,50 @@
+use async_trait::async_trait;
+use uuid::Uuid;
+use delpopolo_core::CoreError;
+use delpopolo_domain::KitchenTicket;
+use delpopolo_infrastructure::repositories::KitchenRepository;
+
+/// Andamento dos pedidos nas estações da cozinha
+#[async_trait]
+pub trait KitchenStore: Send + Sync {
+    /// Tickets já movimentados; estação sem ticket ainda não começou o pedido
+    async fn tickets(&self, order_ids: &[Uuid]) -> Result<Vec<KitchenTicket>, CoreError>;
+    async fn save(&self, ticket: &KitchenTicket) -> Result<(), CoreError>;
+}
+
+#[async_trait]
+impl KitchenStore for KitchenRepository {
+    async fn tickets(&self, order_ids: &[Uuid]) -> Result<Vec<KitchenTicket>, CoreError> {
+        self.find_by_orders(order_ids).await.map_err(CoreError::database)
+    }
+    
+    async fn save(&self, ticket: &KitchenTicket) -> Result<(), CoreError> {
+        KitchenRepository::save(self, ticket).await.map_err(CoreError::database)
+    }
+}
+
+#[cfg(test)]
+pub mod memory {
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+    use delpopolo_domain::KitchenStation;
+    use super::*;
+    
+    /// Tickets em memória para os testes
+    #[derive(Default)]
+    pub struct InMemoryKitchenStore {
+        tickets: Mutex<HashMap<(Uuid, KitchenStation), KitchenTicket>>,
+    }
+    
+    #[async_trait]
+    impl KitchenStore for InMemoryKitchenStore {
+        async fn tickets(&self, order_ids: &[Uuid]) -> Result<Vec<KitchenTicket>, CoreError> {
+            Ok(self.tickets.lock().unwrap().values().filter(|t| order_ids.contains(&t.order_id)).cloned().collect())
+        }
+        
+        async fn save(&self, ticket: &KitchenTicket) -> Result<(), CoreError> {
+            self.tickets.lock().unwrap().insert((ticket.order_id, ticket.station), ticket.clone());
+            Ok(())
+        }
+    }
+}
@@ -1,12 +1,53 @@
 mod routes;
 mod state;
+mod avila_client;
 mod avila_logger;
+mod auth;
+mod catalog;
+mod conditional;
+mod correlation;
+mod customers;
+mod dto;
+mod error;
+mod events;
+mod fiscal;
+mod idempotency;
+mod inventory;
+mod kitchen;
+mod metrics;
+mod middleware;
+mod orders;
+mod pagination;
+mod rate_limit;
+mod readiness;
+mod suppliers;
+mod validation;
 
-use actix_web::{web, App, HttpServer, middleware};
+use actix_web::{http::header, web, App, HttpServer};
 use actix_cors::Cors;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
-use anyhow::Result;
+use anyhow::{Context, Result};
 use state::AppState;
+use auth::AuthService;
+use delpopolo_infrastructure::repositories::{CustomerRepository, FiscalRepository, InventoryRepository, KitchenRepository, OrderRepository, ProductRepository, SupplierRepository, UserRepository};
+use delpopolo_inventory::InventoryService;
+use catalog::{ImageStorage, ProductStore};
+use customers::CustomerStore;
+use fiscal::FiscalStore;
+use inventory::InventoryStore;
+use kitchen::{KitchenSettings, KitchenStore};
+use orders::{OrderStore, StockReservations};
+use avila_client::AvilaClient;
+use avila_logger::AvilaLogger;
+use events::EventBus;
+use idempotency::{IdempotencyBodyLimit, IdempotencyStore, MemoryIdempotencyStore, RedisIdempotencyStore};
+use delpopolo_infrastructure::config::AvilaMode;
+use metrics::MetricsSources;
+use rate_limit::RateLimiter;
+use readiness::{QueueDependency, Readiness, RedisDependency};
+use routes::products::UploadLimit;
+use routes::webhooks::WhatsAppVerifyToken;
+use suppliers::SupplierStore;
 
 #[actix_web::main]
 async fn main() -> Result<()> {
@@ -22,16 +63,29 @@ async fn main() -> Result<()> {
     
     tracing::info!("?? DelPopolo Panificadora API starting...");
     
-    let config = delpopolo_infrastructure::Config::load()
-        .expect("Failed to load configuration");
+    // Falha de configuração lista todos os problemas de uma vez, sem expor segredos
+    let config = delpopolo_infrastructure::Config::load().context("Failed to load configuration")?;
     
-    // ? Autenticao centralizada com api.avila.inc
-    tracing::info!("?? Authenticating with Avila Framework at api.avila.inc...");
-    let avila_token = authenticate_with_avila(&config).await?;
-    tracing::info!("? Authenticated with Avila Framework");
+    // Em standalone nenhuma chamada sai para o Avila; conectado, a API sobe mesmo com ele fora
+    // e o token é pedido de novo no próximo uso
+    let avila = match config.avila.mode {
+        AvilaMode::Standalone => {
+            tracing::info!("Running standalone: Avila integration disabled");
+            None
+        }
+        AvilaMode::AvilaConnected => {
+            let client = std::sync::Arc::new(AvilaClient::new(&config.avila)?);
+            tracing::info!("Authenticating with Avila Framework at {}...", client.api_url());
+            match client.access_token().await {
+                Ok(_) => tracing::info!("Authenticated with Avila Framework"),
+                Err(e) => tracing::warn!("Avila authentication failed, will retry on next use: {:#}", e),
+            }
+            Some(client)
+        }
+    };
     
-    tracing::info!("?? Connecting to SQLite database...");
-    let database = delpopolo_infrastructure::Database::new(&config.database.url)
+    tracing::info!("?? Connecting to PostgreSQL database...");
+    let database = delpopolo_infrastructure::Database::new(config.database.url.expose())
         .await
         .expect("Failed to connect to database");
     
@@ -40,62 +94,200 @@ async fn main() -> Result<()> {
         .await
         .expect("Failed to run migrations");
     
-    let app_state = web::Data::new(AppState::new(database, config.clone(), avila_token));
+    // Redis e RabbitMQ só entram na prontidão quando configurados
+    let mut readiness = Readiness::new(std::time::Duration::from_millis(config.health.timeout_ms))
+        .with(std::sync::Arc::new(database.clone()), config.database.required);
+    if let Some(redis) = &config.redis {
+        readiness = readiness.with(std::sync::Arc::new(RedisDependency::new(redis.url.expose())), redis.required);
+    }
+    // Mesma conexão com o RabbitMQ para a sonda e para a profundidade das filas
+    let queue = config.rabbitmq.as_ref().map(|rabbitmq| std::sync::Arc::new(QueueDependency::new(rabbitmq.url.expose())));
+    if let (Some(rabbitmq), Some(queue)) = (&config.rabbitmq, &queue) {
+        readiness = readiness.with(queue.clone(), rabbitmq.required);
+    }
+    
+    let metrics_sources = web::Data::new(MetricsSources {
+        token: config.metrics.token.as_ref().map(|t| t.expose().to_string()),
+        database: Some(database.clone()),
+        queue,
+        queues: config.metrics.queue_names(),
+    });
+    // Logs e métricas saem por um worker próprio; as requisições só enfileiram
+    let (avila_logger, logger_worker) = match &avila {
+        Some(client) => {
+            let (logger, worker) = AvilaLogger::start(&config.avila_logger, client.clone());
+            readiness = readiness.with_logger(logger.clone());
+            (logger, Some(worker))
+        }
+        None => (AvilaLogger::disabled(), None),
+    };
+    let readiness = web::Data::new(readiness);
+    if config.metrics.avila_export && avila.is_none() {
+        tracing::warn!("METRICS__AVILA_EXPORT ignored in standalone mode");
+    } else if config.metrics.avila_export {
+        tokio::spawn(metrics::export_to_avila(
+            avila_logger.clone(),
+            std::time::Duration::from_secs(config.metrics.export_interval_secs),
+        ));
+    }
+    
+    let auth_service = web::Data::new(AuthService::new(
+        std::sync::Arc::new(UserRepository::new(database.pool().clone())),
+        &config.auth,
+    ));
+    if let Some(admin) = &config.auth.bootstrap_admin {
+        if auth_service.bootstrap_admin(&admin.name, &admin.email, admin.password.expose()).await
+            .context("Failed to create bootstrap admin")?
+        {
+            tracing::warn!("Bootstrap admin {} created; change its password and remove AUTH__BOOTSTRAP_ADMIN__*", admin.email);
+        }
+    }
+    
+    let product_store: web::Data<dyn ProductStore> = web::Data::from(
+        std::sync::Arc::new(ProductRepository::new(database.pool().clone())) as std::sync::Arc<dyn ProductStore>
+    );
+    let order_store: web::Data<dyn OrderStore> = web::Data::from(
+        std::sync::Arc::new(OrderRepository::new(database.pool().clone())) as std::sync::Arc<dyn OrderStore>
+    );
+    let customer_store: web::Data<dyn CustomerStore> = web::Data::from(
+        std::sync::Arc::new(CustomerRepository::new(database.pool().clone())) as std::sync::Arc<dyn CustomerStore>
+    );
+    let supplier_store: web::Data<dyn SupplierStore> = web::Data::from(
+        std::sync::Arc::new(SupplierRepository::new(database.pool().clone())) as std::sync::Arc<dyn SupplierStore>
+    );
+    let fiscal_store: web::Data<dyn FiscalStore> = web::Data::from(
+        std::sync::Arc::new(FiscalRepository::new(database.pool().clone())) as std::sync::Arc<dyn FiscalStore>
+    );
+    let kitchen_store: web::Data<dyn KitchenStore> = web::Data::from(
+        std::sync::Arc::new(KitchenRepository::new(database.pool().clone())) as std::sync::Arc<dyn KitchenStore>
+    );
+    let kitchen_settings = web::Data::new(KitchenSettings::from(&config.kitchen));
+    let inventory_service = std::sync::Arc::new(InventoryService::new(InventoryRepository::new(database.pool().clone())));
+    tokio::spawn(orders::stock::retry_pending_sales(
+        inventory_service.clone(),
+        std::time::Duration::from_secs(60),
+    ));
+    let stock_reservations: web::Data<dyn StockReservations> =
+        web::Data::from(inventory_service.clone() as std::sync::Arc<dyn StockReservations>);
+    let inventory_store: web::Data<dyn InventoryStore> =
+        web::Data::from(inventory_service as std::sync::Arc<dyn InventoryStore>);
+    let image_storage = web::Data::new(ImageStorage::from_config(&config.storage));
+    let upload_limit = web::Data::new(UploadLimit(config.storage.max_upload_bytes));
+    let whatsapp_verify_token = web::Data::new(WhatsAppVerifyToken(
+        config.whatsapp.as_ref().map(|whatsapp| whatsapp.webhook_verify_token.clone()),
+    ));
+    // Com Redis as chaves valem para todas as instâncias; sem ele, só para esta
+    let idempotency_ttl = std::time::Duration::from_secs(config.idempotency.ttl_secs);
+    let idempotency_lease = std::time::Duration::from_secs(config.idempotency.lease_secs);
+    let idempotency_store: web::Data<dyn IdempotencyStore> = match &config.redis {
+        Some(redis) => web::Data::from(
+            std::sync::Arc::new(RedisIdempotencyStore::new(redis.url.expose(), idempotency_ttl, idempotency_lease)) as std::sync::Arc<dyn IdempotencyStore>
+        ),
+        None => web::Data::from(
+            std::sync::Arc::new(MemoryIdempotencyStore::new(idempotency_ttl, idempotency_lease)) as std::sync::Arc<dyn IdempotencyStore>
+        ),
+    };
+    let idempotency_body_limit = web::Data::new(IdempotencyBodyLimit(config.idempotency.max_body_bytes));
+    // Contagem no Redis quando configurado; com ele fora do ar, em memória por instância
+    let rate_limiter = web::Data::new(RateLimiter::new(
+        &config.rate_limit,
+        config.redis.as_ref().map(|redis| redis.url.expose()),
+    ));
+    // Com Redis os eventos chegam às conexões de todas as instâncias; sem ele, só às desta
+    let event_bus = web::Data::new(match &config.redis {
+        Some(redis) => EventBus::with_redis(redis.url.expose()),
+        None => EventBus::local(),
+    });
+    if config.redis.is_some() {
+        tokio::spawn(event_bus.clone().into_inner().relay());
+    }
+    let cors_origins = config.cors.origins();
+    if cors_origins.is_empty() {
+        tracing::warn!("CORS__ALLOWED_ORIGINS is empty: cross-origin requests will be rejected");
+    }
+    let storage = config.storage.clone();
+    
+    error::hide_internal_details(config.app.is_production());
+    
+    let app_state = web::Data::new(AppState::new(avila));
+    let avila_logger = web::Data::new(avila_logger);
     
     let host = config.app.host.clone();
     let port = config.app.port;
     
     tracing::info!("?? Server starting at {}:{}", host, port);
     tracing::info!("?? Domain: {}", config.app.domain);
-    tracing::info!("?? Avila API: api.avila.inc");
+    tracing::info!("Avila mode: {:?}", config.avila.mode);
     
-    HttpServer::new(move || {
-        let cors = Cors::default()
-            .allow_any_origin()
-            .allow_any_method()
-            .allow_any_header()
+    let server = HttpServer::new(move || {
+        let cors = cors_origins
+            .iter()
+            .fold(Cors::default(), |cors, origin| cors.allowed_origin(origin))
+            .allowed_methods(["GET", "POST", "PUT", "PATCH", "DELETE"])
+            .allowed_headers([
+                header::AUTHORIZATION,
+                header::CONTENT_TYPE,
+                header::IF_NONE_MATCH,
+                header::HeaderName::from_static("idempotency-key"),
+                header::HeaderName::from_static("x-api-key"),
+                header::HeaderName::from_static("x-request-id"),
+            ])
+            .expose_headers([
+                header::ETAG,
+                header::LOCATION,
+                header::RETRY_AFTER,
+                header::HeaderName::from_static("api-version"),
+                header::HeaderName::from_static("idempotent-replayed"),
+                header::HeaderName::from_static("ratelimit-limit"),
+                header::HeaderName::from_static("ratelimit-remaining"),
+                header::HeaderName::from_static("ratelimit-reset"),
+                header::HeaderName::from_static("ratelimit-policy"),
+                header::HeaderName::from_static("x-request-id"),
+            ])
             .max_age(3600);
         
         App::new()
             .app_data(app_state.clone())
+            .app_data(avila_logger.clone())
+            .app_data(readiness.clone())
+            .app_data(metrics_sources.clone())
+            .app_data(auth_service.clone())
+            .app_data(product_store.clone())
+            .app_data(order_store.clone())
+            .app_data(customer_store.clone())
+            .app_data(supplier_store.clone())
+            .app_data(stock_reservations.clone())
+            .app_data(inventory_store.clone())
+            .app_data(kitchen_store.clone())
+            .app_data(fiscal_store.clone())
+            .app_data(kitchen_settings.clone())
+            .app_data(idempotency_store.clone())
+            .app_data(idempotency_body_limit.clone())
+            .app_data(rate_limiter.clone())
+            .app_data(event_bus.clone())
+            .app_data(image_storage.clone())
+            .app_data(upload_limit.clone())
+            .app_data(whatsapp_verify_token.clone())
+            .app_data(error::json_config())
+            .wrap(rate_limit::RateLimit)
+            .wrap(metrics::HttpMetrics)
+            .wrap(correlation::CorrelationId)
             .wrap(cors)
-            .wrap(middleware::Logger::default())
+            .wrap(actix_web::middleware::Logger::default())
             .wrap(tracing_actix_web::TracingLogger::default())
+            .wrap(auth::QueryAccessToken("/api/v1/events"))
             .configure(routes::configure)
+            .service(actix_files::Files::new(&storage.public_url, &storage.path))
     })
     .bind((host, port))?
     .run()
-    .await?;
+    .await;
     
-    Ok(())
-}
-
-async fn authenticate_with_avila(config: &delpopolo_infrastructure::Config) -> Result<String> {
-    use reqwest::Client;
-    use serde_json::json;
-    
-    let client = Client::new();
-    
-    let response = client
-        .post("https://api.avila.inc/auth/token")
-        .json(&json!({
-            "client_id": "delpopolo-panificadora",
-            "client_secret": std::env::var("AVILA_CLIENT_SECRET").unwrap_or_default(),
-            "grant_type": "client_credentials"
-        }))
-        .send()
-        .await?;
-    
-    if !response.status().is_success() {
-        tracing::warn!("??  Avila authentication failed - running standalone");
-        return Ok("standalone-mode".to_string());
+    // Depois que o servidor parou de aceitar requisições: envia ou grava no spool o que ficou na fila
+    if let Some(worker) = logger_worker {
+        worker.shutdown().await;
     }
+    server?;
     
-    let token_data: serde_json::Value = response.json().await?;
-    let token = token_data["access_token"]
-        .as_str()
-        .unwrap_or("standalone-mode")
-        .to_string();
-    
-    Ok(token)
+    Ok(())
 }
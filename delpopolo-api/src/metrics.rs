@@ -0,0 +1,312 @@
+use std::future::{ready, Ready};
+use std::rc::Rc;
+use std::sync::{Arc, LazyLock};
+use std::time::{Duration, Instant};
+use actix_web::{
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    Error,
+};
+use futures_util::future::LocalBoxFuture;
+use prometheus::proto::MetricType;
+use prometheus::{
+    CounterVec, Encoder, HistogramOpts, HistogramVec, IntCounterVec, IntGaugeVec, Opts, Registry, TextEncoder,
+};
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use delpopolo_domain::{Order, OrderStatus};
+use delpopolo_infrastructure::Database;
+use delpopolo_inventory::StockAlert;
+use crate::avila_logger::AvilaLogger;
+use crate::readiness::QueueDependency;
+
+/// Registro usado pelo `/metrics`, pelo middleware HTTP e pelos handlers
+pub static METRICS: LazyLock<Metrics> = LazyLock::new(Metrics::new);
+
+const NAMESPACE: &str = "delpopolo";
+
+/// Métricas HTTP, de infraestrutura e de negócio num registro próprio (sem as do processo)
+pub struct Metrics {
+    registry: Registry,
+    http_requests: HistogramVec,
+    db_connections: IntGaugeVec,
+    queue_depth: IntGaugeVec,
+    orders: IntCounterVec,
+    revenue: CounterVec,
+    stock_alerts: IntGaugeVec,
+    avila_records: IntCounterVec,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        let registry = Registry::new();
+        let opts = |name: &str, help: &str| Opts::new(name, help).namespace(NAMESPACE);
+        
+        let http_requests = HistogramVec::new(
+            HistogramOpts::from(opts("http_request_duration_seconds", "Latência das requisições HTTP por rota"))
+                .buckets(vec![0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0]),
+            &["method", "route", "status"],
+        )
+        .expect("métrica válida");
+        let db_connections = IntGaugeVec::new(
+            opts("db_pool_connections", "Conexões do pool do PostgreSQL por estado"),
+            &["state"],
+        )
+        .expect("métrica válida");
+        let queue_depth = IntGaugeVec::new(
+            opts("queue_depth", "Mensagens prontas para entrega por fila do RabbitMQ"),
+            &["queue"],
+        )
+        .expect("métrica válida");
+        let orders = IntCounterVec::new(
+            opts("orders_total", "Pedidos que chegaram a cada status, por origem"),
+            &["source", "status"],
+        )
+        .expect("métrica válida");
+        let revenue = CounterVec::new(
+            opts("revenue_brl_total", "Faturamento dos pedidos concluídos, em reais"),
+            &["source"],
+        )
+        .expect("métrica válida");
+        let stock_alerts = IntGaugeVec::new(
+            opts("stock_alerts", "Alertas de estoque abertos por nível"),
+            &["level"],
+        )
+        .expect("métrica válida");
+        let avila_records = IntCounterVec::new(
+            opts("avila_logger_records_total", "Registros do AvilaLogger por destino (sent, spooled, rejected, dropped)"),
+            &["outcome"],
+        )
+        .expect("métrica válida");
+        
+        for collector in [
+            Box::new(http_requests.clone()) as Box<dyn prometheus::core::Collector>,
+            Box::new(db_connections.clone()),
+            Box::new(queue_depth.clone()),
+            Box::new(orders.clone()),
+            Box::new(revenue.clone()),
+            Box::new(stock_alerts.clone()),
+            Box::new(avila_records.clone()),
+        ] {
+            registry.register(collector).expect("nomes de métrica únicos");
+        }
+        
+        Self {
+            registry,
+            http_requests,
+            db_connections,
+            queue_depth,
+            orders,
+            revenue,
+            stock_alerts,
+            avila_records,
+        }
+    }
+    
+    pub fn observe_request(&self, method: &str, route: &str, status: u16, elapsed: Duration) {
+        self.http_requests
+            .with_label_values(&[method, route, &status.to_string()])
+            .observe(elapsed.as_secs_f64());
+    }
+    
+    /// Conta o pedido no status atual; a receita entra quando ele é concluído
+    pub fn order_reached(&self, order: &Order) {
+        let source = label(&order.source);
+        self.orders.with_label_values(&[&source, &label(&order.status)]).inc();
+        if order.status == OrderStatus::Completed {
+            self.revenue.with_label_values(&[&source]).inc_by(order.total.amount);
+        }
+    }
+    
+    /// Substitui os valores anteriores: um nível sem alertas volta a zero
+    pub fn set_stock_alerts(&self, alerts: &[StockAlert]) {
+        self.stock_alerts.reset();
+        for alert in alerts {
+            self.stock_alerts.with_label_values(&[&label(&alert.alert_level)]).inc();
+        }
+    }
+    
+    pub fn avila_records(&self, outcome: &str, count: u64) {
+        self.avila_records.with_label_values(&[outcome]).inc_by(count);
+    }
+    
+    pub fn set_pool(&self, database: &Database) {
+        let pool = database.pool();
+        let idle = pool.num_idle() as i64;
+        self.db_connections.with_label_values(&["idle"]).set(idle);
+        self.db_connections.with_label_values(&["active"]).set(pool.size() as i64 - idle);
+        self.db_connections.with_label_values(&["max"]).set(pool.options().get_max_connections() as i64);
+    }
+    
+    pub fn set_queue_depth(&self, queue: &str, depth: u32) {
+        self.queue_depth.with_label_values(&[queue]).set(depth as i64);
+    }
+    
+    /// Formato texto do Prometheus
+    pub fn render(&self) -> String {
+        let mut buffer = Vec::new();
+        TextEncoder::new()
+            .encode(&self.registry.gather(), &mut buffer)
+            .expect("codificação em memória não falha");
+        String::from_utf8(buffer).expect("formato texto é UTF-8")
+    }
+    
+    /// Amostras achatadas para o exportador do Avila; histogramas viram `_sum` e `_count`
+    pub fn samples(&self) -> Vec<(String, f64, Value)> {
+        let mut samples = Vec::new();
+        for family in self.registry.gather() {
+            for metric in family.get_metric() {
+                let tags: Map<String, Value> = metric
+                    .get_label()
+                    .iter()
+                    .map(|l| (l.get_name().to_string(), json!(l.get_value())))
+                    .collect();
+                let tags = Value::Object(tags);
+                let name = family.get_name();
+                match family.get_field_type() {
+                    MetricType::COUNTER => samples.push((name.to_string(), metric.get_counter().get_value(), tags)),
+                    MetricType::GAUGE => samples.push((name.to_string(), metric.get_gauge().get_value(), tags)),
+                    MetricType::HISTOGRAM => {
+                        let histogram = metric.get_histogram();
+                        samples.push((format!("{}_sum", name), histogram.get_sample_sum(), tags.clone()));
+                        samples.push((format!("{}_count", name), histogram.get_sample_count() as f64, tags));
+                    }
+                    _ => {}
+                }
+            }
+        }
+        samples
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Rótulo com o mesmo nome que o enum tem no JSON da API
+fn label<T: Serialize>(value: &T) -> String {
+    match serde_json::to_value(value) {
+        Ok(Value::String(s)) => s,
+        _ => "unknown".to_string(),
+    }
+}
+
+/// Fontes consultadas no momento do scrape: gauges de pool, filas e alertas
+#[derive(Default)]
+pub struct MetricsSources {
+    /// Exige `Authorization: Bearer <token>` quando definido
+    pub token: Option<String>,
+    pub database: Option<Database>,
+    pub queue: Option<Arc<QueueDependency>>,
+    pub queues: Vec<String>,
+}
+
+impl MetricsSources {
+    pub async fn refresh(&self, metrics: &Metrics) {
+        if let Some(database) = &self.database {
+            metrics.set_pool(database);
+        }
+        if let Some(queue) = &self.queue {
+            for name in &self.queues {
+                match queue.message_count(name).await {
+                    Ok(depth) => metrics.set_queue_depth(name, depth),
+                    Err(e) => tracing::warn!("Could not read depth of queue {}: {:#}", name, e),
+                }
+            }
+        }
+    }
+}
+
+/// Latência por rota registrada (`/api/v1/orders/{id}`), nunca pelo caminho com ids
+pub struct HttpMetrics;
+
+impl<S, B> Transform<S, ServiceRequest> for HttpMetrics
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = HttpMetricsMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+    
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(HttpMetricsMiddleware { service: Rc::new(service) }))
+    }
+}
+
+pub struct HttpMetricsMiddleware<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for HttpMetricsMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+    
+    forward_ready!(service);
+    
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+        let method = req.method().to_string();
+        let started = Instant::now();
+        
+        Box::pin(async move {
+            let result = service.call(req).await;
+            let (route, status) = match &result {
+                Ok(res) => (
+                    res.request().match_pattern().unwrap_or_else(|| "unmatched".to_string()),
+                    res.status().as_u16(),
+                ),
+                Err(e) => ("unmatched".to_string(), e.as_response_error().status_code().as_u16()),
+            };
+            METRICS.observe_request(&method, &route, status, started.elapsed());
+            result
+        })
+    }
+}
+
+/// Enfileira periodicamente as amostras no `AvilaLogger`; o `/metrics` continua sendo a fonte principal
+pub async fn export_to_avila(logger: AvilaLogger, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        ticker.tick().await;
+        for (name, value, tags) in METRICS.samples() {
+            logger.send_metric(&name, value, tags);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use delpopolo_domain::OrderSource;
+    use super::*;
+    
+    #[test]
+    fn test_orders_and_revenue_are_labelled_by_source() {
+        let metrics = Metrics::new();
+        let mut order = Order::new(OrderSource::IFood);
+        metrics.order_reached(&order);
+        order.status = OrderStatus::Completed;
+        order.total = delpopolo_domain::Money::brl(42.5);
+        metrics.order_reached(&order);
+        
+        let text = metrics.render();
+        assert!(text.contains(r#"delpopolo_orders_total{source="IFood",status="Pending"} 1"#), "{text}");
+        assert!(text.contains(r#"delpopolo_orders_total{source="IFood",status="Completed"} 1"#), "{text}");
+        assert!(text.contains(r#"delpopolo_revenue_brl_total{source="IFood"} 42.5"#), "{text}");
+        
+        let samples = metrics.samples();
+        assert!(samples.iter().any(|(name, value, tags)| name == "delpopolo_revenue_brl_total"
+            && *value == 42.5
+            && tags["source"] == "IFood"));
+    }
+}
@@ -41,10 +41,11 @@ where
     forward_ready!(service);
 
     fn call(&self, req: ServiceRequest) -> Self::Future {
-        // Injeta o token Avila em todas as requisies
+        // Injeta o cliente Avila (com o token renovado sob demanda) quando conectado
         if let Some(state) = req.app_data::<actix_web::web::Data<crate::state::AppState>>() {
-            let token = state.avila_token.clone();
-            req.extensions_mut().insert(token);
+            if let Some(avila) = state.avila.clone() {
+                req.extensions_mut().insert(avila);
+            }
         }
         
         let fut = self.service.call(req);
@@ -0,0 +1,5 @@
+pub mod store;
+pub mod stock;
+
+pub use stock::StockReservations;
+pub use store::OrderStore;
@@ -0,0 +1,128 @@
+use std::sync::Arc;
+use std::time::Duration;
+use async_trait::async_trait;
+use uuid::Uuid;
+use delpopolo_core::CoreError;
+use delpopolo_domain::OrderItem;
+use delpopolo_inventory::InventoryService;
+
+/// Reserva de estoque dos itens de pedido
+#[async_trait]
+pub trait StockReservations: Send + Sync {
+    async fn reserve(&self, product_id: Uuid, quantity: f64, order_id: Uuid) -> Result<(), CoreError>;
+    async fn release(&self, product_id: Uuid, quantity: f64) -> Result<(), CoreError>;
+    /// Converte as reservas do pedido concluído em baixa de venda. As baixas foram gravadas
+    /// junto com o pedido; as que falharem aqui são reaplicadas por `retry_pending_sales`
+    async fn consume_order(&self, order_id: Uuid, items: &[OrderItem]) -> Result<(), CoreError>;
+}
+
+#[async_trait]
+impl StockReservations for InventoryService {
+    async fn reserve(&self, product_id: Uuid, quantity: f64, order_id: Uuid) -> Result<(), CoreError> {
+        self.reserve_stock(product_id, quantity, order_id).await.map(|_| ()).map_err(CoreError::conflict)
+    }
+    
+    async fn release(&self, product_id: Uuid, quantity: f64) -> Result<(), CoreError> {
+        self.release_reservation(product_id, quantity).await.map(|_| ()).map_err(CoreError::database)
+    }
+    
+    async fn consume_order(&self, order_id: Uuid, _items: &[OrderItem]) -> Result<(), CoreError> {
+        InventoryService::consume_order(self, order_id).await.map(|_| ()).map_err(CoreError::database)
+    }
+}
+
+/// Reaplica periodicamente as baixas de venda que falharam na conclusão do pedido
+pub async fn retry_pending_sales(inventory: Arc<InventoryService>, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        ticker.tick().await;
+        match inventory.consume_pending(100).await {
+            Ok(0) => {}
+            Ok(applied) => tracing::info!("Applied {} pending sales", applied),
+            Err(e) => tracing::error!("Pending sales still failing: {}", e),
+        }
+    }
+}
+
+/// Reserva todos os itens ou nenhum: desfaz as reservas já feitas se uma falhar
+pub async fn reserve_items(stock: &dyn StockReservations, order_id: Uuid, items: &[OrderItem]) -> Result<(), CoreError> {
+    for (done, item) in items.iter().enumerate() {
+        if let Err(e) = stock.reserve(item.product_id, item.quantity, order_id).await {
+            tracing::warn!("Reservation of product {} for order {} failed: {}", item.product_id, order_id, e);
+            release_items(stock, &items[..done]).await;
+            return Err(CoreError::conflict(format!("Estoque insuficiente para {}", item.product_name)));
+        }
+    }
+    Ok(())
+}
+
+/// Libera reservas depois que o pedido já foi gravado; falhas só são registradas
+pub async fn release_items(stock: &dyn StockReservations, items: &[OrderItem]) {
+    for item in items {
+        if let Err(e) = stock.release(item.product_id, item.quantity).await {
+            tracing::error!("Failed to release {} units of product {}: {}", item.quantity, item.product_id, e);
+        }
+    }
+}
+
+/// Dá baixa de venda dos itens de um pedido concluído; o que falhar fica pendente e é reaplicado depois
+pub async fn consume_items(stock: &dyn StockReservations, order_id: Uuid, items: &[OrderItem]) {
+    if let Err(e) = stock.consume_order(order_id, items).await {
+        tracing::warn!("Sale of order {} kept pending for retry: {}", order_id, e);
+    }
+}
+
+#[cfg(test)]
+pub mod memory {
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+    use super::*;
+    
+    /// Saldo disponível e reservado por produto, em memória
+    #[derive(Default)]
+    pub struct InMemoryStock {
+        levels: Mutex<HashMap<Uuid, (f64, f64)>>,
+    }
+    
+    impl InMemoryStock {
+        pub fn with_stock(self, product_id: Uuid, quantity: f64) -> Self {
+            self.levels.lock().unwrap().insert(product_id, (quantity, 0.0));
+            self
+        }
+        
+        /// (quantidade em estoque, quantidade reservada)
+        pub fn level(&self, product_id: Uuid) -> (f64, f64) {
+            self.levels.lock().unwrap().get(&product_id).copied().unwrap_or_default()
+        }
+    }
+    
+    #[async_trait]
+    impl StockReservations for InMemoryStock {
+        async fn reserve(&self, product_id: Uuid, quantity: f64, _order_id: Uuid) -> Result<(), CoreError> {
+            let mut levels = self.levels.lock().unwrap();
+            let (on_hand, reserved) = levels.get_mut(&product_id).ok_or_else(|| CoreError::conflict("Inventory not found"))?;
+            if *on_hand - *reserved < quantity {
+                return Err(CoreError::conflict("Insufficient available quantity to reserve"));
+            }
+            *reserved += quantity;
+            Ok(())
+        }
+        
+        async fn release(&self, product_id: Uuid, quantity: f64) -> Result<(), CoreError> {
+            if let Some((_, reserved)) = self.levels.lock().unwrap().get_mut(&product_id) {
+                *reserved = (*reserved - quantity).max(0.0);
+            }
+            Ok(())
+        }
+        
+        async fn consume_order(&self, _order_id: Uuid, items: &[OrderItem]) -> Result<(), CoreError> {
+            for item in items {
+                self.release(item.product_id, item.quantity).await?;
+                if let Some((on_hand, _)) = self.levels.lock().unwrap().get_mut(&item.product_id) {
+                    *on_hand -= item.quantity;
+                }
+            }
+            Ok(())
+        }
+    }
+}
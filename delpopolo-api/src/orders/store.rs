@@ -0,0 +1,95 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+use delpopolo_core::CoreError;
+use delpopolo_domain::Order;
+use delpopolo_infrastructure::repositories::order_repository::OrderQuery;
+use delpopolo_infrastructure::repositories::OrderRepository;
+
+/// Persistência dos pedidos usada pelas rotas de pedidos
+#[async_trait]
+pub trait OrderStore: Send + Sync {
+    async fn find(&self, id: Uuid) -> Result<Option<Order>, CoreError>;
+    async fn list(&self, query: &OrderQuery) -> Result<Vec<Order>, CoreError>;
+    async fn insert(&self, order: &Order) -> Result<(), CoreError>;
+    /// Grava pedido e itens juntos; `Conflict` se outra requisição alterou o pedido antes
+    async fn update(&self, order: &Order, expected_updated_at: DateTime<Utc>) -> Result<(), CoreError>;
+}
+
+fn stale(order: &Order) -> CoreError {
+    CoreError::conflict(format!("Pedido {} foi alterado por outra operação; recarregue e tente novamente", order.order_number))
+}
+
+#[async_trait]
+impl OrderStore for OrderRepository {
+    async fn find(&self, id: Uuid) -> Result<Option<Order>, CoreError> {
+        self.find_by_id(id).await.map_err(CoreError::database)
+    }
+    
+    async fn list(&self, query: &OrderQuery) -> Result<Vec<Order>, CoreError> {
+        OrderRepository::list(self, query).await.map_err(CoreError::database)
+    }
+    
+    async fn insert(&self, order: &Order) -> Result<(), CoreError> {
+        OrderRepository::insert(self, order).await.map_err(CoreError::database)
+    }
+    
+    async fn update(&self, order: &Order, expected_updated_at: DateTime<Utc>) -> Result<(), CoreError> {
+        match OrderRepository::update(self, order, expected_updated_at).await {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(stale(order)),
+            Err(e) => Err(CoreError::database(e)),
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod memory {
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+    use super::*;
+    
+    /// Pedidos em memória para os testes
+    #[derive(Default)]
+    pub struct InMemoryOrderStore {
+        orders: Mutex<HashMap<Uuid, Order>>,
+    }
+    
+    #[async_trait]
+    impl OrderStore for InMemoryOrderStore {
+        async fn find(&self, id: Uuid) -> Result<Option<Order>, CoreError> {
+            Ok(self.orders.lock().unwrap().get(&id).cloned())
+        }
+        
+        async fn list(&self, query: &OrderQuery) -> Result<Vec<Order>, CoreError> {
+            let mut items: Vec<Order> = self.orders.lock().unwrap().values().filter(|o| query.matches(o)).cloned().collect();
+            items.sort_by_key(|o| (o.created_at, o.id));
+            if !query.oldest_first {
+                items.reverse();
+            }
+            
+            if let Some(cursor) = &query.after {
+                let cursor = (cursor.created_at, cursor.id);
+                items.retain(|o| if query.oldest_first { (o.created_at, o.id) > cursor } else { (o.created_at, o.id) < cursor });
+            }
+            items.truncate(query.limit as usize);
+            Ok(items)
+        }
+        
+        async fn insert(&self, order: &Order) -> Result<(), CoreError> {
+            self.orders.lock().unwrap().insert(order.id, order.clone());
+            Ok(())
+        }
+        
+        async fn update(&self, order: &Order, expected_updated_at: DateTime<Utc>) -> Result<(), CoreError> {
+            let mut orders = self.orders.lock().unwrap();
+            match orders.get(&order.id) {
+                Some(current) if current.updated_at == expected_updated_at => {
+                    orders.insert(order.id, order.clone());
+                    Ok(())
+                }
+                _ => Err(stale(order)),
+            }
+        }
+    }
+}
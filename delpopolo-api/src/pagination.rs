@@ -0,0 +1,66 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use utoipa::ToSchema;
+use delpopolo_core::CoreError;
+
+pub const DEFAULT_LIMIT: i64 = 50;
+pub const MAX_LIMIT: i64 = 200;
+
+/// Página de resultados; `next_cursor` ausente indica a última página
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next_cursor: Option<String>,
+}
+
+pub fn clamp_limit(limit: Option<i64>) -> i64 {
+    limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT)
+}
+
+/// Cursor opaco para o cliente (JSON em base64url)
+pub fn encode_cursor<C: Serialize>(cursor: &C) -> String {
+    URL_SAFE_NO_PAD.encode(serde_json::to_vec(cursor).unwrap_or_default())
+}
+
+pub fn decode_cursor<C: DeserializeOwned>(cursor: &str) -> Result<C, CoreError> {
+    URL_SAFE_NO_PAD
+        .decode(cursor)
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .ok_or_else(|| CoreError::validation("Cursor de paginação inválido"))
+}
+
+/// Monta a página a partir de uma busca feita com `limit + 1` itens
+pub fn paginate<T, C, F>(mut items: Vec<T>, limit: i64, cursor_of: F) -> Page<T>
+where
+    C: Serialize,
+    F: Fn(&T) -> C,
+{
+    let has_more = items.len() as i64 > limit;
+    items.truncate(limit as usize);
+    
+    let next_cursor = if has_more {
+        items.last().map(|last| encode_cursor(&cursor_of(last)))
+    } else {
+        None
+    };
+    
+    Page { items, next_cursor }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_paginate_and_cursor_roundtrip() {
+        let page = paginate(vec![1, 2, 3], 2, |n| *n);
+        assert_eq!(page.items, vec![1, 2]);
+        let cursor: i32 = decode_cursor(page.next_cursor.as_deref().unwrap()).unwrap();
+        assert_eq!(cursor, 2);
+        
+        assert!(paginate(vec![1, 2], 2, |n| *n).next_cursor.is_none());
+        assert!(decode_cursor::<i32>("não-é-cursor").is_err());
+        assert_eq!(clamp_limit(Some(10_000)), MAX_LIMIT);
+    }
+}
@@ -0,0 +1,381 @@
+use std::collections::HashMap;
+use std::future::{ready, Ready};
+use std::rc::Rc;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use actix_web::{
+    body::{BoxBody, MessageBody},
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    http::{header, Method},
+    web, Error, ResponseError,
+};
+use anyhow::{anyhow, Result};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use futures_util::future::LocalBoxFuture;
+use ring::digest;
+use delpopolo_core::CoreError;
+use delpopolo_infrastructure::config::{RateLimitConfig, RateLimitPolicy};
+use delpopolo_infrastructure::Cache;
+use crate::auth::extractor::bearer_token;
+use crate::auth::AuthService;
+use crate::error::ApiError;
+
+pub const API_KEY_HEADER: &str = "X-Api-Key";
+
+/// Além deste tempo o Redis é tratado como fora do ar e a contagem cai para a memória
+const REDIS_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// Intervalo sem tentar o Redis depois de uma falha
+const REDIS_RETRY_AFTER: Duration = Duration::from_secs(5);
+
+/// Acima disso os baldes já cheios em memória são descartados
+const MEMORY_SWEEP_THRESHOLD: usize = 10_000;
+
+/// Decisão de uma requisição: o que vai nos cabeçalhos `RateLimit-*`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Decision {
+    pub policy: RateLimitPolicy,
+    pub remaining: u64,
+    /// Segundos até o balde encher de novo; recusada, até a próxima ficha
+    pub reset: u64,
+    pub allowed: bool,
+}
+
+/// Balde de `requests` fichas por cliente que ganha uma ficha a cada `window_secs / requests`,
+/// sem rajada dobrada na virada de janela. Fica no Redis para valer entre instâncias; com o
+/// Redis fora do ar, em memória, por instância.
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    api_keys: Vec<String>,
+    redis: Option<RedisBuckets>,
+    memory: Mutex<HashMap<String, Bucket>>,
+}
+
+/// Fichas que sobraram na última requisição e quando ela ocorreu
+struct Bucket {
+    tokens: f64,
+    at: Instant,
+    /// Dali em diante o balde está cheio e pode ser descartado
+    full_at: Instant,
+}
+
+impl RateLimiter {
+    pub fn new(config: &RateLimitConfig, redis_url: Option<&str>) -> Self {
+        Self {
+            config: config.clone(),
+            api_keys: config.api_keys(),
+            redis: redis_url.map(RedisBuckets::new),
+            memory: Mutex::default(),
+        }
+    }
+    
+    /// Consome uma ficha do balde `bucket`
+    pub async fn check(&self, bucket: &str, policy: RateLimitPolicy) -> Decision {
+        let key = format!("ratelimit:{}", bucket);
+        let capacity = policy.requests.max(1);
+        let interval_ms = (policy.window_secs * 1000) as f64 / capacity as f64;
+        
+        let (allowed, tokens) = match &self.redis {
+            Some(redis) => match redis.take(&key, capacity, interval_ms).await {
+                Ok(taken) => taken,
+                Err(e) => {
+                    redis.failed(e);
+                    self.take_in_memory(&key, capacity, interval_ms)
+                }
+            },
+            None => self.take_in_memory(&key, capacity, interval_ms),
+        };
+        // Aceita: até encher de novo; recusada: até a próxima ficha
+        let missing = if allowed { capacity as f64 - tokens } else { 1.0 - tokens };
+        Decision {
+            policy,
+            remaining: tokens.floor() as u64,
+            reset: (missing * interval_ms / 1000.0).ceil() as u64,
+            allowed,
+        }
+    }
+    
+    /// Mesma conta do script do Redis (`Cache::take_token`)
+    fn take_in_memory(&self, key: &str, capacity: u64, interval_ms: f64) -> (bool, f64) {
+        let mut buckets = self.memory.lock().unwrap();
+        let now = Instant::now();
+        if buckets.len() > MEMORY_SWEEP_THRESHOLD {
+            buckets.retain(|_, bucket| bucket.full_at > now);
+        }
+        let bucket = buckets
+            .entry(key.to_string())
+            .or_insert(Bucket { tokens: capacity as f64, at: now, full_at: now });
+        let elapsed_ms = now.duration_since(bucket.at).as_secs_f64() * 1000.0;
+        let mut tokens = (bucket.tokens + elapsed_ms / interval_ms).min(capacity as f64);
+        let taken = tokens >= 1.0;
+        if taken {
+            tokens -= 1.0;
+        }
+        let refill = Duration::from_secs_f64((capacity as f64 - tokens) * interval_ms / 1000.0);
+        *bucket = Bucket { tokens, at: now, full_at: now + refill };
+        (taken, tokens)
+    }
+    
+    /// Política da rota; health e métricas ficam de fora (sondas e scrape)
+    fn policy(&self, req: &ServiceRequest) -> Option<(&'static str, RateLimitPolicy)> {
+        let path = req.path();
+        if path.starts_with("/health") || path.starts_with("/metrics") {
+            return None;
+        }
+        if path.starts_with("/webhooks") {
+            return Some(("webhooks", self.config.webhooks));
+        }
+        if path.starts_with("/api/v") && path.ends_with("/auth/login") {
+            return Some(("login", self.config.login));
+        }
+        if req.method() == Method::GET && path.starts_with("/api/v") && path.contains("/products") {
+            return Some(("menu", self.config.menu));
+        }
+        Some(("default", self.config.default))
+    }
+    
+    /// Usuário do token ou chave de API reconhecida; senão o IP. Login é sempre por IP.
+    fn client(&self, req: &ServiceRequest, policy: &str) -> String {
+        if policy != "login" {
+            let auth = req.app_data::<web::Data<AuthService>>();
+            let token = bearer_token(req.request());
+            if let (Some(auth), Some(token)) = (auth, token) {
+                if let Ok(claims) = auth.verify_access(&token) {
+                    return format!("user:{}", claims.sub);
+                }
+            }
+            let api_key = req.headers().get(API_KEY_HEADER).and_then(|v| v.to_str().ok());
+            if let Some(api_key) = api_key.filter(|key| self.api_keys.iter().any(|known| known == key)) {
+                let hash = digest::digest(&digest::SHA256, api_key.as_bytes());
+                return format!("key:{}", &URL_SAFE_NO_PAD.encode(hash)[..16]);
+            }
+        }
+        
+        let info = req.connection_info();
+        let ip = if self.config.trust_proxy {
+            info.realip_remote_addr()
+        } else {
+            info.peer_addr()
+        };
+        format!("ip:{}", ip.unwrap_or("unknown"))
+    }
+}
+
+/// Baldes no Redis (`Cache::take_token`); conecta no primeiro uso
+struct RedisBuckets {
+    url: String,
+    cache: tokio::sync::Mutex<Option<Cache>>,
+    retry_at: Mutex<Option<Instant>>,
+}
+
+impl RedisBuckets {
+    fn new(url: &str) -> Self {
+        Self { url: url.to_string(), cache: tokio::sync::Mutex::new(None), retry_at: Mutex::new(None) }
+    }
+    
+    async fn take(&self, key: &str, capacity: u64, interval_ms: f64) -> Result<(bool, f64)> {
+        if self.retry_at.lock().unwrap().is_some_and(|at| Instant::now() < at) {
+            return Err(anyhow!("Redis unavailable, retrying later"));
+        }
+        let attempt = async {
+            let mut cache = {
+                let mut cache = self.cache.lock().await;
+                if cache.is_none() {
+                    *cache = Some(Cache::new(&self.url).await?);
+                }
+                cache.clone().expect("conectado acima")
+            };
+            cache.take_token(key, capacity, interval_ms).await
+        };
+        tokio::time::timeout(REDIS_TIMEOUT, attempt)
+            .await
+            .unwrap_or_else(|_| Err(anyhow!("Redis did not answer in {:?}", REDIS_TIMEOUT)))
+    }
+    
+    /// Só avisa na transição para fora do ar, não a cada requisição
+    fn failed(&self, error: anyhow::Error) {
+        let mut retry_at = self.retry_at.lock().unwrap();
+        let was_up = retry_at.is_none_or(|at| Instant::now() >= at + REDIS_RETRY_AFTER);
+        if was_up {
+            tracing::warn!("Rate limiter falling back to in-memory counters: {:#}", error);
+        }
+        *retry_at = Some(Instant::now() + REDIS_RETRY_AFTER);
+    }
+}
+
+/// Aplica o `RateLimiter` registrado em `app_data` e envia `RateLimit-Limit`, `RateLimit-Remaining`,
+/// `RateLimit-Reset` e `RateLimit-Policy`; acima do limite responde 429 com `Retry-After`
+pub struct RateLimit;
+
+impl<S, B> Transform<S, ServiceRequest> for RateLimit
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = RateLimitMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+    
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RateLimitMiddleware { service: Rc::new(service) }))
+    }
+}
+
+pub struct RateLimitMiddleware<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for RateLimitMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+    
+    forward_ready!(service);
+    
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+        let limiter = req
+            .app_data::<web::Data<RateLimiter>>()
+            .filter(|limiter| limiter.config.enabled)
+            .cloned();
+        
+        Box::pin(async move {
+            // Pré-voo do CORS não consome ficha
+            let limiter = limiter.filter(|_| req.method() != Method::OPTIONS);
+            let Some((limiter, (name, policy))) = limiter.and_then(|l| l.policy(&req).map(|p| (l, p))) else {
+                return service.call(req).await.map(ServiceResponse::map_into_boxed_body);
+            };
+            let client = limiter.client(&req, name);
+            let decision = limiter.check(&format!("{}:{}", name, client), policy).await;
+            
+            let mut res = if decision.allowed {
+                service.call(req).await?.map_into_boxed_body()
+            } else {
+                tracing::info!("Rate limit exceeded for {} on {} policy", client, name);
+                let error = CoreError::rate_limited(format!(
+                    "Limite de {} requisições em {}s atingido; tente de novo em {}s",
+                    policy.requests, policy.window_secs, decision.reset
+                ));
+                let mut res = ApiError(error).error_response();
+                res.headers_mut().insert(header::RETRY_AFTER, decision.reset.into());
+                req.into_response(res)
+            };
+            let headers = res.headers_mut();
+            headers.insert(header::HeaderName::from_static("ratelimit-limit"), policy.requests.into());
+            headers.insert(header::HeaderName::from_static("ratelimit-remaining"), decision.remaining.into());
+            headers.insert(header::HeaderName::from_static("ratelimit-reset"), decision.reset.into());
+            if let Ok(value) = format!("{};w={}", policy.requests, policy.window_secs).parse() {
+                headers.insert(header::HeaderName::from_static("ratelimit-policy"), value);
+            }
+            Ok(res)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use actix_web::{test, App, HttpResponse};
+    use delpopolo_domain::UserRole;
+    use crate::auth::service::tests::{config, user};
+    use crate::auth::store::memory::InMemoryAuthStore;
+    use super::*;
+    
+    fn limiter(config: RateLimitConfig) -> web::Data<RateLimiter> {
+        web::Data::new(RateLimiter::new(&config, None))
+    }
+    
+    #[actix_web::test]
+    async fn test_login_is_limited_per_ip_with_ratelimit_headers() {
+        let config = RateLimitConfig { login: RateLimitPolicy::new(2, 60), ..Default::default() };
+        let app = test::init_service(
+            App::new()
+                .app_data(limiter(config))
+                .wrap(RateLimit)
+                .route("/api/v1/auth/login", web::post().to(HttpResponse::Ok))
+                .route("/health", web::get().to(HttpResponse::Ok)),
+        )
+        .await;
+        let login = |ip: &str| test::TestRequest::post().uri("/api/v1/auth/login").peer_addr(ip.parse().unwrap()).to_request();
+        
+        let first = test::call_service(&app, login("10.0.0.1:5000")).await;
+        assert_eq!(first.status(), 200);
+        assert_eq!(first.headers().get("RateLimit-Limit").unwrap(), "2");
+        assert_eq!(first.headers().get("RateLimit-Remaining").unwrap(), "1");
+        assert_eq!(first.headers().get("RateLimit-Policy").unwrap(), "2;w=60");
+        test::call_service(&app, login("10.0.0.1:5001")).await;
+        
+        let blocked = test::call_service(&app, login("10.0.0.1:5002")).await;
+        assert_eq!(blocked.status(), 429);
+        assert_eq!(blocked.headers().get("RateLimit-Remaining").unwrap(), "0");
+        assert!(blocked.headers().get(header::RETRY_AFTER).is_some());
+        let problem: serde_json::Value = test::read_body_json(blocked).await;
+        assert_eq!(problem["code"], "rate_limited");
+        
+        // Outro IP tem o próprio balde; health não é limitado
+        assert_eq!(test::call_service(&app, login("10.0.0.2:5000")).await.status(), 200);
+        let health = test::call_service(&app, test::TestRequest::get().uri("/health").to_request()).await;
+        assert!(health.headers().get("RateLimit-Limit").is_none());
+    }
+    
+    #[actix_web::test]
+    async fn test_bucket_refills_one_token_at_a_time() {
+        let limiter = RateLimiter::new(&RateLimitConfig::default(), None);
+        let policy = RateLimitPolicy::new(2, 1);
+        assert!(limiter.check("ip:10.0.0.3", policy).await.allowed);
+        assert!(limiter.check("ip:10.0.0.3", policy).await.allowed);
+        let blocked = limiter.check("ip:10.0.0.3", policy).await;
+        assert!(!blocked.allowed);
+        assert_eq!(blocked.reset, 1);
+        
+        // Uma ficha a cada 500ms: depois de 600ms passa uma requisição, não o balde inteiro
+        tokio::time::sleep(Duration::from_millis(600)).await;
+        assert!(limiter.check("ip:10.0.0.3", policy).await.allowed);
+        assert!(!limiter.check("ip:10.0.0.3", policy).await.allowed);
+    }
+    
+    #[actix_web::test]
+    async fn test_users_and_api_keys_have_their_own_buckets() {
+        let cashier = user(UserRole::Cashier);
+        let auth = AuthService::new(Arc::new(InMemoryAuthStore::default().with_user(cashier.clone())), &config());
+        let token = format!("Bearer {}", auth.login(&cashier.email, "fermento123").await.unwrap().access_token);
+        let config = RateLimitConfig {
+            default: RateLimitPolicy::new(1, 60),
+            api_keys: Some("quiosque-1".into()),
+            ..Default::default()
+        };
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(auth))
+                .app_data(limiter(config))
+                .wrap(RateLimit)
+                .route("/api/v1/orders", web::post().to(HttpResponse::Created)),
+        )
+        .await;
+        let order = || test::TestRequest::post().uri("/api/v1/orders").peer_addr("10.0.0.9:5000".parse().unwrap());
+        
+        // Mesmo IP, clientes diferentes: usuário, chave reconhecida e anônimo
+        let statuses = [
+            order().insert_header(("Authorization", token.clone())).to_request(),
+            order().insert_header((API_KEY_HEADER, "quiosque-1")).to_request(),
+            order().to_request(),
+        ];
+        for req in statuses {
+            assert_eq!(test::call_service(&app, req).await.status(), 201);
+        }
+        
+        // Chave desconhecida conta como o IP, que já gastou a ficha
+        let unknown = order().insert_header((API_KEY_HEADER, "inventada")).to_request();
+        assert_eq!(test::call_service(&app, unknown).await.status(), 429);
+        let again = order().insert_header(("Authorization", token)).to_request();
+        assert_eq!(test::call_service(&app, again).await.status(), 429);
+    }
+}
@@ -0,0 +1,241 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use anyhow::Result;
+use async_trait::async_trait;
+use futures_util::future::join_all;
+use serde::Serialize;
+use tokio::sync::{Mutex, MutexGuard};
+use delpopolo_infrastructure::{Cache, Database, Queue};
+use crate::avila_logger::{AvilaLogger, LoggerSnapshot};
+
+/// Dependência externa verificada pela sonda de prontidão
+#[async_trait]
+pub trait Dependency: Send + Sync {
+    fn name(&self) -> &'static str;
+    async fn ping(&self) -> Result<()>;
+}
+
+#[async_trait]
+impl Dependency for Database {
+    fn name(&self) -> &'static str {
+        "database"
+    }
+    
+    async fn ping(&self) -> Result<()> {
+        self.health_check().await.map(|_| ())
+    }
+}
+
+/// Redis conectado na primeira verificação; sobe mesmo com o Redis fora do ar
+pub struct RedisDependency {
+    url: String,
+    cache: Mutex<Option<Cache>>,
+}
+
+impl RedisDependency {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self { url: url.into(), cache: Mutex::new(None) }
+    }
+}
+
+#[async_trait]
+impl Dependency for RedisDependency {
+    fn name(&self) -> &'static str {
+        "redis"
+    }
+    
+    async fn ping(&self) -> Result<()> {
+        let mut cache = self.cache.lock().await;
+        if cache.is_none() {
+            *cache = Some(Cache::new(&self.url).await?);
+        }
+        let result = cache.as_mut().expect("conectado acima").health_check().await;
+        if result.is_err() {
+            *cache = None;
+        }
+        result
+    }
+}
+
+/// RabbitMQ conectado na primeira verificação e reconectado quando o canal cai
+pub struct QueueDependency {
+    url: String,
+    queue: Mutex<Option<Queue>>,
+}
+
+impl QueueDependency {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self { url: url.into(), queue: Mutex::new(None) }
+    }
+    
+    /// Conexão atual, aberta na hora se ainda não existir
+    async fn connected(&self) -> Result<MutexGuard<'_, Option<Queue>>> {
+        let mut queue = self.queue.lock().await;
+        if queue.is_none() {
+            *queue = Some(Queue::new(&self.url).await?);
+        }
+        Ok(queue)
+    }
+    
+    /// Profundidade da fila, reaproveitando a conexão da sonda de prontidão
+    pub async fn message_count(&self, name: &str) -> Result<u32> {
+        let mut queue = self.connected().await?;
+        let result = queue.as_ref().expect("conectado acima").message_count(name).await;
+        if result.is_err() {
+            *queue = None;
+        }
+        result
+    }
+}
+
+#[async_trait]
+impl Dependency for QueueDependency {
+    fn name(&self) -> &'static str {
+        "rabbitmq"
+    }
+    
+    async fn ping(&self) -> Result<()> {
+        let mut queue = self.connected().await?;
+        let result = queue.as_ref().expect("conectado acima").health_check().await;
+        if result.is_err() {
+            *queue = None;
+        }
+        result
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ComponentState {
+    Up,
+    Down,
+    Timeout,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ComponentStatus {
+    pub status: ComponentState,
+    pub required: bool,
+    pub latency_ms: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReadinessState {
+    Ready,
+    /// Só dependências opcionais fora do ar
+    Degraded,
+    NotReady,
+}
+
+/// Corpo do `/health/ready`; o detalhe das falhas fica no log, não na resposta pública
+#[derive(Debug, Serialize)]
+pub struct ReadinessReport {
+    pub status: ReadinessState,
+    pub checks: BTreeMap<&'static str, ComponentStatus>,
+    /// Contadores do exportador de logs, quando conectado ao Avila; não afetam o status
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avila_logger: Option<LoggerSnapshot>,
+}
+
+struct Check {
+    dependency: Arc<dyn Dependency>,
+    required: bool,
+}
+
+/// Dependências configuradas, verificadas em paralelo com o mesmo timeout
+pub struct Readiness {
+    checks: Vec<Check>,
+    timeout: Duration,
+    logger: Option<AvilaLogger>,
+}
+
+impl Readiness {
+    pub fn new(timeout: Duration) -> Self {
+        Self { checks: Vec::new(), timeout, logger: None }
+    }
+    
+    pub fn with(mut self, dependency: Arc<dyn Dependency>, required: bool) -> Self {
+        self.checks.push(Check { dependency, required });
+        self
+    }
+    
+    pub fn with_logger(mut self, logger: AvilaLogger) -> Self {
+        self.logger = Some(logger);
+        self
+    }
+    
+    pub async fn check(&self) -> ReadinessReport {
+        let results = join_all(self.checks.iter().map(|check| async move {
+            let started = Instant::now();
+            let state = match tokio::time::timeout(self.timeout, check.dependency.ping()).await {
+                Ok(Ok(())) => ComponentState::Up,
+                Ok(Err(e)) => {
+                    tracing::warn!("Readiness check for {} failed: {:#}", check.dependency.name(), e);
+                    ComponentState::Down
+                }
+                Err(_) => {
+                    tracing::warn!("Readiness check for {} timed out after {:?}", check.dependency.name(), self.timeout);
+                    ComponentState::Timeout
+                }
+            };
+            let status = ComponentStatus {
+                status: state,
+                required: check.required,
+                latency_ms: started.elapsed().as_millis() as u64,
+            };
+            (check.dependency.name(), status)
+        }))
+        .await;
+        
+        let failing: Vec<bool> = results
+            .iter()
+            .filter(|(_, c)| c.status != ComponentState::Up)
+            .map(|(_, c)| c.required)
+            .collect();
+        let status = if failing.contains(&true) {
+            ReadinessState::NotReady
+        } else if !failing.is_empty() {
+            ReadinessState::Degraded
+        } else {
+            ReadinessState::Ready
+        };
+        ReadinessReport {
+            status,
+            checks: results.into_iter().collect(),
+            avila_logger: self.logger.as_ref().map(|logger| logger.stats().snapshot()),
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod fake {
+    use super::*;
+    
+    /// Dependência com resposta e atraso controlados pelo teste
+    pub struct FakeDependency {
+        pub name: &'static str,
+        pub healthy: bool,
+        pub delay: Duration,
+    }
+    
+    #[async_trait]
+    impl Dependency for FakeDependency {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+        
+        async fn ping(&self) -> Result<()> {
+            tokio::time::sleep(self.delay).await;
+            if !self.healthy {
+                anyhow::bail!("{} is down", self.name);
+            }
+            Ok(())
+        }
+    }
+    
+    pub fn dependency(name: &'static str, healthy: bool, delay_ms: u64) -> Arc<dyn Dependency> {
+        Arc::new(FakeDependency { name, healthy, delay: Duration::from_millis(delay_ms) })
+    }
+}
@@ -1,5 +1,148 @@
-use actix_web::web;
+use actix_web::{web, HttpResponse};
+use utoipa::OpenApi;
+use crate::auth::{AuthService, AuthenticatedUser};
+use crate::dto::auth::{ChangePasswordRequest, LoginRequest, RefreshRequest, TokenResponse, UserResponse};
+use crate::error::ApiError;
+use crate::validation::validate;
 
-pub fn configure(_cfg: &mut web::ServiceConfig) {
-    // TODO: Implementar rotas de autenticao
+#[derive(OpenApi)]
+#[openapi(paths(login, refresh, logout, change_password, me))]
+pub struct AuthApi;
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::resource("/login").route(web::post().to(login)))
+        .service(web::resource("/refresh").route(web::post().to(refresh)))
+        .service(web::resource("/logout").route(web::post().to(logout)))
+        .service(web::resource("/change-password").route(web::post().to(change_password)))
+        .service(web::resource("/me").route(web::get().to(me)));
+}
+
+#[utoipa::path(
+    post, path = "/login", tag = "auth", security(()),
+    request_body = LoginRequest,
+    responses((status = 200, body = TokenResponse), (status = 401, description = "Credenciais inválidas ou conta bloqueada")),
+)]
+async fn login(
+    auth: web::Data<AuthService>,
+    body: web::Json<LoginRequest>,
+) -> Result<HttpResponse, ApiError> {
+    validate(&*body)?;
+    let pair = auth.login(&body.email, &body.password).await?;
+    Ok(HttpResponse::Ok().json(TokenResponse::from(pair)))
+}
+
+/// Troca o token de renovação por um novo par; o anterior deixa de valer
+#[utoipa::path(
+    post, path = "/refresh", tag = "auth", security(()),
+    request_body = RefreshRequest,
+    responses((status = 200, body = TokenResponse), (status = 401, description = "Token de renovação inválido ou revogado")),
+)]
+async fn refresh(
+    auth: web::Data<AuthService>,
+    body: web::Json<RefreshRequest>,
+) -> Result<HttpResponse, ApiError> {
+    validate(&*body)?;
+    let pair = auth.refresh(&body.refresh_token).await?;
+    Ok(HttpResponse::Ok().json(TokenResponse::from(pair)))
+}
+
+#[utoipa::path(
+    post, path = "/logout", tag = "auth", security(()),
+    request_body = RefreshRequest,
+    responses((status = 204, description = "Sessão encerrada")),
+)]
+async fn logout(
+    auth: web::Data<AuthService>,
+    body: web::Json<RefreshRequest>,
+) -> Result<HttpResponse, ApiError> {
+    validate(&*body)?;
+    auth.logout(&body.refresh_token).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Revoga todas as sessões do usuário
+#[utoipa::path(
+    post, path = "/change-password", tag = "auth",
+    request_body = ChangePasswordRequest,
+    responses(
+        (status = 204, description = "Senha alterada"),
+        (status = 401, description = "Senha atual incorreta"),
+        (status = 422, description = "Nova senha fora das regras mínimas"),
+    ),
+)]
+async fn change_password(
+    auth: web::Data<AuthService>,
+    user: AuthenticatedUser,
+    body: web::Json<ChangePasswordRequest>,
+) -> Result<HttpResponse, ApiError> {
+    validate(&*body)?;
+    auth.change_password(&user, &body.current_password, &body.new_password).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[utoipa::path(get, path = "/me", tag = "auth", responses((status = 200, body = UserResponse)))]
+async fn me(user: AuthenticatedUser) -> HttpResponse {
+    HttpResponse::Ok().json(UserResponse::from(&user.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use actix_web::{test, App};
+    use serde_json::{json, Value};
+    use delpopolo_domain::UserRole;
+    use crate::auth::service::tests::{config, user};
+    use crate::auth::store::memory::InMemoryAuthStore;
+    use super::*;
+    
+    #[actix_web::test]
+    async fn test_login_me_and_logout() {
+        let store = Arc::new(InMemoryAuthStore::default().with_user(user(UserRole::Kitchen)));
+        let auth = web::Data::new(AuthService::new(store, &config()));
+        let app = test::init_service(
+            App::new()
+                .app_data(auth)
+                .service(web::scope("/auth").configure(configure)),
+        )
+        .await;
+        
+        let req = test::TestRequest::post()
+            .uri("/auth/login")
+            .set_json(json!({"email": "maria@delpopolo.com.br", "password": "errada"}))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 401);
+        let body: Value = test::read_body_json(resp).await;
+        assert_eq!(body["code"], "unauthorized");
+        
+        let req = test::TestRequest::post()
+            .uri("/auth/login")
+            .set_json(json!({"email": "maria@delpopolo.com.br", "password": "fermento123"}))
+            .to_request();
+        let tokens: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(tokens["token_type"], "Bearer");
+        assert_eq!(tokens["user"]["role"], "Kitchen");
+        
+        let req = test::TestRequest::get().uri("/auth/me").to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 401);
+        
+        let req = test::TestRequest::get()
+            .uri("/auth/me")
+            .insert_header(("Authorization", format!("Bearer {}", tokens["access_token"].as_str().unwrap())))
+            .to_request();
+        let me: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(me["email"], "maria@delpopolo.com.br");
+        
+        let req = test::TestRequest::post()
+            .uri("/auth/logout")
+            .set_json(json!({"refresh_token": tokens["refresh_token"]}))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 204);
+        
+        let req = test::TestRequest::post()
+            .uri("/auth/refresh")
+            .set_json(json!({"refresh_token": tokens["refresh_token"]}))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 401);
+    }
 }
@@ -1,5 +1,388 @@
-use actix_web::web;
+use actix_web::{http::header, web, HttpRequest, HttpResponse};
+use chrono::Utc;
+use utoipa::OpenApi;
+use uuid::Uuid;
+use delpopolo_core::CoreError;
+use delpopolo_domain::{ConsentRecord, Customer, Permission};
+use delpopolo_infrastructure::repositories::order_repository::{OrderCursor, OrderQuery};
+use crate::auth::extractor::AuthenticatedUser;
+use crate::auth::RequirePermission;
+use crate::conditional::{conditional_json, entity_tag};
+use crate::customers::CustomerStore;
+use crate::dto::customers::{
+    ConsentRequest, CustomerCursorToken, CustomerExport, CustomerListQuery, CustomerRequest, CustomerResponse,
+};
+use crate::dto::orders::OrderResponse;
+use crate::error::ApiError;
+use crate::orders::OrderStore;
+use crate::pagination::{paginate, Page, MAX_LIMIT};
 
-pub fn configure(_cfg: &mut web::ServiceConfig) {
-    // TODO: Implementar rotas de clientes
+#[derive(OpenApi)]
+#[openapi(paths(
+    list_customers, create_customer, get_customer, update_customer, deactivate_customer,
+    list_consents, update_consents, export_customer, anonymize_customer,
+))]
+pub struct CustomersApi;
+
+/// Leitura já é exigida no escopo `/customers`; exportação e anonimização exigem `CustomersPrivacy`
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    let write = || RequirePermission(Permission::CustomersWrite);
+    let privacy = || RequirePermission(Permission::CustomersPrivacy);
+    
+    cfg.service(
+        web::resource("")
+            .route(web::get().to(list_customers))
+            .route(web::post().to(create_customer).wrap(write())),
+    )
+    .service(
+        web::resource("/{id}")
+            .route(web::get().to(get_customer))
+            .route(web::put().to(update_customer).wrap(write()))
+            .route(web::delete().to(deactivate_customer).wrap(write())),
+    )
+    .service(
+        web::resource("/{id}/consents")
+            .route(web::get().to(list_consents))
+            .route(web::put().to(update_consents).wrap(write())),
+    )
+    .service(web::resource("/{id}/export").route(web::get().to(export_customer).wrap(privacy())))
+    .service(web::resource("/{id}/anonymize").route(web::post().to(anonymize_customer).wrap(privacy())));
+}
+
+async fn load(customers: &dyn CustomerStore, id: Uuid) -> Result<Customer, CoreError> {
+    customers
+        .find(id)
+        .await?
+        .ok_or_else(|| CoreError::not_found(format!("Cliente {} não encontrado", id)))
+}
+
+/// CPF é único entre os clientes; `current` é o próprio cliente numa atualização
+async fn ensure_unique_cpf(customers: &dyn CustomerStore, customer: &Customer, current: Option<Uuid>) -> Result<(), CoreError> {
+    let Some(cpf) = &customer.cpf else {
+        return Ok(());
+    };
+    match customers.find_by_cpf(cpf.value()).await? {
+        Some(existing) if Some(existing.id) != current => {
+            Err(CoreError::conflict(format!("CPF {} já cadastrado", cpf.formatted())))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn customer_json(customer: &Customer) -> HttpResponse {
+    HttpResponse::Ok()
+        .insert_header(header::ETag(entity_tag(customer.id, customer.updated_at)))
+        .json(CustomerResponse::from(customer))
+}
+
+#[utoipa::path(
+    get, path = "", tag = "customers",
+    params(CustomerListQuery),
+    responses((status = 200, body = Page<CustomerResponse>), (status = 422)),
+)]
+async fn list_customers(
+    customers: web::Data<dyn CustomerStore>,
+    query: web::Query<CustomerListQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let mut customer_query = query.to_query()?;
+    let limit = customer_query.limit;
+    customer_query.limit += 1;
+    
+    let found = customers.list(&customer_query).await?;
+    let page = paginate(found, limit, |c| CustomerCursorToken { n: c.name.clone(), id: c.id });
+    
+    Ok(HttpResponse::Ok().json(Page {
+        items: page.items.iter().map(CustomerResponse::from).collect::<Vec<_>>(),
+        next_cursor: page.next_cursor,
+    }))
+}
+
+#[utoipa::path(
+    get, path = "/{id}", tag = "customers",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = CustomerResponse), (status = 304, description = "Não modificado (`If-None-Match`)"), (status = 404)),
+)]
+async fn get_customer(
+    req: HttpRequest,
+    customers: web::Data<dyn CustomerStore>,
+    id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let customer = load(customers.get_ref(), *id).await?;
+    Ok(conditional_json(&req, entity_tag(customer.id, customer.updated_at), &CustomerResponse::from(&customer)))
+}
+
+#[utoipa::path(
+    post, path = "", tag = "customers",
+    request_body = CustomerRequest,
+    responses((status = 201, body = CustomerResponse), (status = 409, description = "CPF já cadastrado"), (status = 422)),
+)]
+async fn create_customer(
+    req: HttpRequest,
+    user: AuthenticatedUser,
+    customers: web::Data<dyn CustomerStore>,
+    body: web::Json<CustomerRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let body = body.into_inner();
+    let consents = body.consents.clone().unwrap_or_default();
+    
+    let mut customer = body.into_customer()?;
+    ensure_unique_cpf(customers.get_ref(), &customer, None).await?;
+    let records = consents.apply(&mut customer, Some(user.id))?;
+    customers.insert(&customer, &records).await?;
+    
+    Ok(HttpResponse::Created()
+        .insert_header((header::LOCATION, format!("{}/{}", req.path().trim_end_matches('/'), customer.id)))
+        .insert_header(header::ETag(entity_tag(customer.id, customer.updated_at)))
+        .json(CustomerResponse::from(&customer)))
+}
+
+#[utoipa::path(
+    put, path = "/{id}", tag = "customers",
+    params(("id" = Uuid, Path)),
+    request_body = CustomerRequest,
+    responses((status = 200, body = CustomerResponse), (status = 404), (status = 409, description = "CPF já cadastrado ou cliente anonimizado"), (status = 422)),
+)]
+async fn update_customer(
+    user: AuthenticatedUser,
+    customers: web::Data<dyn CustomerStore>,
+    id: web::Path<Uuid>,
+    body: web::Json<CustomerRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let mut customer = load(customers.get_ref(), *id).await?;
+    customer.ensure_not_anonymized()?;
+    
+    let body = body.into_inner();
+    let consents = body.consents.clone().unwrap_or_default();
+    body.apply_to(&mut customer)?;
+    ensure_unique_cpf(customers.get_ref(), &customer, Some(customer.id)).await?;
+    let records = consents.apply(&mut customer, Some(user.id))?;
+    customers.update(&customer, &records).await?;
+    
+    Ok(customer_json(&customer))
+}
+
+/// Desativa sem apagar; para eliminar os dados use `/anonymize`
+#[utoipa::path(
+    delete, path = "/{id}", tag = "customers",
+    params(("id" = Uuid, Path)),
+    responses((status = 204, description = "Cliente desativado"), (status = 404)),
+)]
+async fn deactivate_customer(
+    customers: web::Data<dyn CustomerStore>,
+    id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let mut customer = load(customers.get_ref(), *id).await?;
+    
+    if customer.is_active {
+        customer.is_active = false;
+        customer.updated_at = Utc::now();
+        customers.update(&customer, &[]).await?;
+    }
+    
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[utoipa::path(
+    get, path = "/{id}/consents", tag = "customers",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = Vec<ConsentRecord>), (status = 404)),
+)]
+async fn list_consents(
+    customers: web::Data<dyn CustomerStore>,
+    id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let customer = load(customers.get_ref(), *id).await?;
+    Ok(HttpResponse::Ok().json(customers.consents(customer.id).await?))
+}
+
+#[utoipa::path(
+    put, path = "/{id}/consents", tag = "customers",
+    params(("id" = Uuid, Path)),
+    request_body = ConsentRequest,
+    responses((status = 200, body = CustomerResponse), (status = 404), (status = 409, description = "Cliente anonimizado")),
+)]
+async fn update_consents(
+    user: AuthenticatedUser,
+    customers: web::Data<dyn CustomerStore>,
+    id: web::Path<Uuid>,
+    body: web::Json<ConsentRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let mut customer = load(customers.get_ref(), *id).await?;
+    
+    let records = body.apply(&mut customer, Some(user.id))?;
+    if !records.is_empty() {
+        customers.update(&customer, &records).await?;
+    }
+    
+    Ok(customer_json(&customer))
+}
+
+/// Relatório do titular (LGPD art. 18): cadastro, histórico de consentimentos e pedidos
+#[utoipa::path(
+    get, path = "/{id}/export", tag = "customers",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = CustomerExport), (status = 404)),
+)]
+async fn export_customer(
+    user: AuthenticatedUser,
+    customers: web::Data<dyn CustomerStore>,
+    orders: web::Data<dyn OrderStore>,
+    id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let customer = load(customers.get_ref(), *id).await?;
+    let consents = customers.consents(customer.id).await?;
+    
+    let mut query = OrderQuery {
+        customer_id: Some(customer.id),
+        limit: MAX_LIMIT,
+        ..OrderQuery::default()
+    };
+    let mut customer_orders = Vec::new();
+    loop {
+        let found = orders.list(&query).await?;
+        let done = (found.len() as i64) < query.limit;
+        query.after = found.last().map(|o| OrderCursor { created_at: o.created_at, id: o.id });
+        customer_orders.extend(found.iter().map(OrderResponse::from));
+        if done {
+            break;
+        }
+    }
+    tracing::info!("Customer {} data exported by user {}", customer.id, user.id);
+    
+    Ok(HttpResponse::Ok()
+        .insert_header((
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"cliente-{}.json\"", customer.id),
+        ))
+        .json(CustomerExport {
+            generated_at: Utc::now(),
+            customer,
+            consents,
+            orders: customer_orders,
+        }))
+}
+
+/// Pedidos e documentos fiscais não são alterados: a guarda é obrigação legal
+#[utoipa::path(
+    post, path = "/{id}/anonymize", tag = "customers",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = CustomerResponse), (status = 404), (status = 409, description = "Cliente já anonimizado")),
+)]
+async fn anonymize_customer(
+    user: AuthenticatedUser,
+    customers: web::Data<dyn CustomerStore>,
+    id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let mut customer = load(customers.get_ref(), *id).await?;
+    
+    let records = customer.anonymize(Some(user.id))?;
+    customers.update(&customer, &records).await?;
+    tracing::info!("Customer {} anonymized by user {}", customer.id, user.id);
+    
+    Ok(customer_json(&customer))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use actix_web::{test, App};
+    use serde_json::{json, Value};
+    use delpopolo_domain::{Order, OrderSource, UserRole};
+    use crate::auth::service::tests::{config, user};
+    use crate::auth::store::memory::InMemoryAuthStore;
+    use crate::auth::AuthService;
+    use crate::customers::store::memory::InMemoryCustomerStore;
+    use crate::orders::store::memory::InMemoryOrderStore;
+    use super::*;
+    
+    async fn token(auth: &AuthService, email: &str) -> String {
+        format!("Bearer {}", auth.login(email, "fermento123").await.unwrap().access_token)
+    }
+    
+    #[actix_web::test]
+    async fn test_customer_consents_export_and_anonymization() {
+        let cashier = user(UserRole::Cashier);
+        let mut manager = user(UserRole::Manager);
+        manager.email = "gerente@delpopolo.com.br".to_string();
+        let auth = AuthService::new(
+            Arc::new(InMemoryAuthStore::default().with_user(cashier.clone()).with_user(manager.clone())),
+            &config(),
+        );
+        let (cashier_token, manager_token) = (token(&auth, &cashier.email).await, token(&auth, &manager.email).await);
+        
+        let customers: Arc<dyn CustomerStore> = Arc::new(InMemoryCustomerStore::default());
+        let orders = Arc::new(InMemoryOrderStore::default());
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(auth))
+                .app_data(web::Data::from(customers))
+                .app_data(web::Data::from(orders.clone() as Arc<dyn OrderStore>))
+                .service(
+                    web::scope("/customers")
+                        .wrap(RequirePermission(Permission::CustomersRead))
+                        .configure(configure),
+                ),
+        )
+        .await;
+        let request = |method: test::TestRequest, uri: &str, token: &str| {
+            method.uri(uri).insert_header(("Authorization", token.to_string()))
+        };
+        
+        // CPF inválido e CPF duplicado
+        let body = json!({
+            "name": "Maria Souza",
+            "cpf": "529.982.247-25",
+            "whatsapp_number": "11987654321",
+            "consents": {"accepts_marketing": true, "channel": "balcão"}
+        });
+        let invalid = json!({"name": "Maria Souza", "cpf": "123.456.789-00"});
+        let req = request(test::TestRequest::post(), "/customers", &cashier_token).set_json(invalid).to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 422);
+        let req = request(test::TestRequest::post(), "/customers", &cashier_token).set_json(body.clone()).to_request();
+        let customer: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(customer["cpf"], "529.982.247-25");
+        assert_eq!(customer["accepts_marketing"], true);
+        let req = request(test::TestRequest::post(), "/customers", &cashier_token).set_json(body).to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 409);
+        let id: Uuid = customer["id"].as_str().unwrap().parse().unwrap();
+        let uri = format!("/customers/{}", id);
+        
+        // Busca por CPF sem máscara
+        let req = request(test::TestRequest::get(), "/customers?cpf=52998224725", &cashier_token).to_request();
+        let page: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(page["items"].as_array().unwrap().len(), 1);
+        
+        // Cada mudança de consentimento fica no histórico
+        let consents = json!({"accepts_marketing": false, "whatsapp_optin": true, "channel": "whatsapp"});
+        let req = request(test::TestRequest::put(), &format!("{}/consents", uri), &cashier_token).set_json(consents).to_request();
+        let customer: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(customer["whatsapp_optin"], true);
+        let req = request(test::TestRequest::get(), &format!("{}/consents", uri), &cashier_token).to_request();
+        let history: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(history.as_array().unwrap().len(), 3);
+        
+        // Exportação e anonimização só para quem trata dados pessoais
+        let mut order = Order::new(OrderSource::InStore);
+        order.customer_id = Some(id);
+        orders.insert(&order).await.unwrap();
+        let req = request(test::TestRequest::get(), &format!("{}/export", uri), &cashier_token).to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 403);
+        let req = request(test::TestRequest::get(), &format!("{}/export", uri), &manager_token).to_request();
+        let export: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(export["customer"]["name"], "Maria Souza");
+        assert_eq!(export["consents"].as_array().unwrap().len(), 3);
+        assert_eq!(export["orders"].as_array().unwrap().len(), 1);
+        
+        let req = request(test::TestRequest::post(), &format!("{}/anonymize", uri), &manager_token).to_request();
+        let customer: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(customer["name"], delpopolo_domain::entities::customer::ANONYMIZED_NAME);
+        assert!(customer["cpf"].is_null() && customer["whatsapp_number"].is_null());
+        assert_eq!(customer["whatsapp_optin"], false);
+        
+        // Pedido continua lá; cadastro anonimizado não volta a ser editado
+        assert!(orders.find(order.id).await.unwrap().is_some_and(|o| o.customer_id == Some(id)));
+        let body = json!({"name": "Maria", "cpf": "529.982.247-25"});
+        let req = request(test::TestRequest::put(), &uri, &cashier_token).set_json(body).to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 409);
+    }
 }
@@ -0,0 +1,257 @@
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::time::Duration;
+use actix_web::{http::header, web, HttpRequest, HttpResponse};
+use actix_ws::{CloseCode, CloseReason, Message};
+use chrono::Utc;
+use futures_util::{stream, Stream, StreamExt};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::time::{Instant, Interval};
+use utoipa::OpenApi;
+use delpopolo_core::CoreError;
+use crate::auth::extractor::bearer_token;
+use crate::auth::AuthService;
+use crate::dto::events::EventsQuery;
+use crate::error::ApiError;
+use crate::events::{Event, EventBus, Topic};
+
+#[derive(OpenApi)]
+#[openapi(paths(stream_events, websocket))]
+pub struct EventsApi;
+
+/// Intervalo dos pings que mantêm a conexão aberta atrás de proxies
+const KEEP_ALIVE: Duration = Duration::from_secs(15);
+
+/// A autenticação é feita aqui: `EventSource` e WebSocket do navegador mandam o token na query
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::resource("/stream").route(web::get().to(stream_events)))
+        .service(web::resource("/ws").route(web::get().to(websocket)));
+}
+
+/// Assinatura validada: tópicos permitidos e até quando o token vale
+struct Subscription {
+    topics: Vec<Topic>,
+    expires_at: Instant,
+}
+
+/// Cada tópico pedido exige a permissão correspondente; sem `topics`, assina todos os permitidos
+async fn subscription(req: &HttpRequest, auth: &AuthService, query: &EventsQuery) -> Result<Subscription, CoreError> {
+    let token = bearer_token(req)
+        .or_else(|| query.access_token.clone())
+        .ok_or_else(|| CoreError::unauthorized("Token de acesso ausente"))?;
+    let claims = auth.verify_access(&token)?;
+    let user = auth.authenticate(&token).await?;
+    
+    let allowed = Topic::allowed_for(&user);
+    let topics = match query.topics()? {
+        Some(requested) => {
+            if let Some(denied) = requested.iter().find(|topic| !allowed.contains(topic)) {
+                return Err(CoreError::forbidden(format!("Sem permissão para o tópico {}", denied.name())));
+            }
+            requested
+        }
+        None => allowed,
+    };
+    if topics.is_empty() {
+        return Err(CoreError::forbidden("Nenhum tópico disponível para este usuário"));
+    }
+    
+    let remaining = (claims.exp - Utc::now().timestamp()).max(0) as u64;
+    Ok(Subscription { topics, expires_at: Instant::now() + Duration::from_secs(remaining) })
+}
+
+/// O que sai para o cliente entre um evento e outro
+enum Outgoing {
+    Event(Arc<Event>),
+    /// O cliente ficou para trás e perdeu eventos: deve recarregar o estado
+    Resync(u64),
+    KeepAlive,
+}
+
+struct Listener {
+    receiver: broadcast::Receiver<Arc<Event>>,
+    keep_alive: Interval,
+    subscription: Subscription,
+}
+
+impl Listener {
+    fn new(bus: &EventBus, subscription: Subscription) -> Self {
+        let mut keep_alive = tokio::time::interval(KEEP_ALIVE);
+        keep_alive.reset();
+        Self { receiver: bus.subscribe(), keep_alive, subscription }
+    }
+    
+    /// `None` quando o token expira ou o barramento fecha; o cliente reconecta com um token novo
+    async fn next(&mut self) -> Option<Outgoing> {
+        loop {
+            tokio::select! {
+                _ = tokio::time::sleep_until(self.subscription.expires_at) => return None,
+                _ = self.keep_alive.tick() => return Some(Outgoing::KeepAlive),
+                received = self.receiver.recv() => match received {
+                    Ok(event) if event.matches(&self.subscription.topics) => return Some(Outgoing::Event(event)),
+                    Ok(_) => continue,
+                    Err(RecvError::Lagged(missed)) => return Some(Outgoing::Resync(missed)),
+                    Err(RecvError::Closed) => return None,
+                },
+            }
+        }
+    }
+}
+
+fn sse_frame(outgoing: Outgoing) -> web::Bytes {
+    let frame = match outgoing {
+        Outgoing::Event(event) => format!("event: {}\ndata: {}\n\n", event.kind, event.data),
+        Outgoing::Resync(missed) => format!("event: resync\ndata: {{\"missed\":{}}}\n\n", missed),
+        Outgoing::KeepAlive => ": keep-alive\n\n".to_string(),
+    };
+    web::Bytes::from(frame)
+}
+
+fn sse_stream(listener: Listener) -> impl Stream<Item = Result<web::Bytes, Infallible>> {
+    stream::unfold(listener, |mut listener| async move {
+        let outgoing = listener.next().await?;
+        Some((Ok(sse_frame(outgoing)), listener))
+    })
+}
+
+/// Server-Sent Events: `event: order.created`, `order.status_changed`, `stock.alert` ou `resync`,
+/// com o JSON em `data`. A conexão termina quando o access token expira.
+#[utoipa::path(
+    get, path = "/stream", tag = "events",
+    params(EventsQuery),
+    responses(
+        (status = 200, content_type = "text/event-stream", body = String),
+        (status = 401), (status = 403, description = "Tópico não permitido para o perfil"), (status = 422),
+    ),
+)]
+async fn stream_events(
+    req: HttpRequest,
+    auth: web::Data<AuthService>,
+    bus: web::Data<EventBus>,
+    query: web::Query<EventsQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let subscription = subscription(&req, &auth, &query).await?;
+    
+    Ok(HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header((header::CACHE_CONTROL, "no-cache"))
+        // Sem buffer no nginx, senão os eventos chegam em blocos
+        .insert_header(("X-Accel-Buffering", "no"))
+        .streaming(sse_stream(Listener::new(&bus, subscription))))
+}
+
+/// WebSocket com os mesmos eventos do SSE em mensagens `{"type": ..., "data": ...}`
+#[utoipa::path(
+    get, path = "/ws", tag = "events",
+    params(EventsQuery),
+    responses(
+        (status = 101, description = "Conexão WebSocket aberta"),
+        (status = 401), (status = 403, description = "Tópico não permitido para o perfil"), (status = 422),
+    ),
+)]
+async fn websocket(
+    req: HttpRequest,
+    body: web::Payload,
+    auth: web::Data<AuthService>,
+    bus: web::Data<EventBus>,
+    query: web::Query<EventsQuery>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let subscription = subscription(&req, &auth, &query).await.map_err(ApiError)?;
+    let (response, mut session, mut messages) = actix_ws::handle(&req, body)?;
+    let mut listener = Listener::new(&bus, subscription);
+    
+    actix_web::rt::spawn(async move {
+        let reason = loop {
+            tokio::select! {
+                outgoing = listener.next() => {
+                    let sent = match outgoing {
+                        Some(Outgoing::Event(event)) => {
+                            let message = serde_json::json!({"type": event.kind, "data": event.data});
+                            session.text(message.to_string()).await
+                        }
+                        Some(Outgoing::Resync(missed)) => {
+                            session.text(serde_json::json!({"type": "resync", "data": {"missed": missed}}).to_string()).await
+                        }
+                        Some(Outgoing::KeepAlive) => session.ping(b"").await,
+                        None => break Some(CloseReason::from((CloseCode::Policy, "Token expirado"))),
+                    };
+                    if sent.is_err() {
+                        return;
+                    }
+                }
+                message = messages.next() => match message {
+                    Some(Ok(Message::Ping(bytes))) => {
+                        if session.pong(&bytes).await.is_err() {
+                            return;
+                        }
+                    }
+                    Some(Ok(Message::Close(reason))) => break reason,
+                    Some(Ok(_)) => {}
+                    Some(Err(_)) | None => break None,
+                },
+            }
+        };
+        let _ = session.close(reason).await;
+    });
+    
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::future::poll_fn;
+    use std::pin::pin;
+    use actix_web::body::MessageBody;
+    use actix_web::{test, App};
+    use delpopolo_domain::{Order, OrderSource, OrderStatus, UserRole};
+    use crate::auth::service::tests::{config, user};
+    use crate::auth::store::memory::InMemoryAuthStore;
+    use super::*;
+    
+    #[actix_web::test]
+    async fn test_stream_delivers_only_permitted_topics() {
+        let cashier = user(UserRole::Cashier);
+        let mut cook = user(UserRole::Kitchen);
+        cook.email = "cozinha@delpopolo.com.br".to_string();
+        let auth = AuthService::new(
+            Arc::new(InMemoryAuthStore::default().with_user(cashier.clone()).with_user(cook.clone())),
+            &config(),
+        );
+        let cashier_token = auth.login(&cashier.email, "fermento123").await.unwrap().access_token;
+        let token = auth.login(&cook.email, "fermento123").await.unwrap().access_token;
+        let bus = web::Data::new(EventBus::local());
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(auth))
+                .app_data(bus.clone())
+                .service(web::scope("/events").configure(configure)),
+        )
+        .await;
+        
+        // Caixa não opera a cozinha; sem token não há assinatura
+        let req = test::TestRequest::get().uri(&format!("/events/stream?topics=kitchen&access_token={cashier_token}")).to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 403);
+        let req = test::TestRequest::get().uri("/events/stream?topics=kitchen").to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 401);
+        
+        let req = test::TestRequest::get()
+            .uri("/events/stream?topics=kitchen")
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+        assert_eq!(res.status(), 200);
+        assert_eq!(res.headers().get(header::CONTENT_TYPE).unwrap(), "text/event-stream");
+        
+        // Pedido ainda pendente não vai para a cozinha; o confirmado vai
+        let mut order = Order::new(OrderSource::WhatsApp);
+        bus.publish(Event::order(&order, true)).await;
+        order.status = OrderStatus::Confirmed;
+        bus.publish(Event::order(&order, false)).await;
+        
+        let mut body = pin!(res.into_body());
+        let chunk = poll_fn(|cx| body.as_mut().poll_next(cx)).await.unwrap().unwrap();
+        let frame = String::from_utf8(chunk.to_vec()).unwrap();
+        assert!(frame.starts_with("event: order.status_changed\ndata: "), "{frame}");
+        assert!(frame.contains(&order.id.to_string()));
+    }
+}
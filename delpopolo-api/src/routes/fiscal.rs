@@ -0,0 +1,171 @@
+use actix_web::{http::header, web, HttpResponse};
+use utoipa::OpenApi;
+use delpopolo_core::CoreError;
+use delpopolo_domain::Permission;
+use delpopolo_nfe::{FiscalLedger, NFeParser, NFeValidator, ResumoFiscal};
+use crate::auth::RequirePermission;
+use crate::dto::fiscal::{FiscalEntryResponse, FiscalPeriodQuery};
+use crate::error::ApiError;
+use crate::fiscal::FiscalStore;
+use crate::validation::validate;
+
+#[derive(OpenApi)]
+#[openapi(paths(import_entry, monthly_summary, summary_csv, sped_export))]
+pub struct FiscalApi;
+
+/// Leitura já é exigida no escopo `/fiscal`; lançar a nota de compra faz parte do
+/// recebimento e exige `InventoryWrite`
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::resource("/entries")
+            .route(web::post().to(import_entry).wrap(RequirePermission(Permission::InventoryWrite))),
+    )
+    .service(web::resource("/summary").route(web::get().to(monthly_summary)))
+    .service(web::resource("/summary/csv").route(web::get().to(summary_csv)))
+    .service(web::resource("/sped").route(web::get().to(sped_export)));
+}
+
+/// Lança no livro fiscal o XML de uma NF-e recebida, com os tributos de cada item
+#[utoipa::path(
+    post, path = "/entries", tag = "fiscal",
+    request_body(content = String, content_type = "application/xml"),
+    responses(
+        (status = 201, body = FiscalEntryResponse),
+        (status = 409, description = "Chave de acesso já lançada"),
+        (status = 422, description = "XML ilegível ou NF-e inválida"),
+    ),
+)]
+async fn import_entry(fiscal: web::Data<dyn FiscalStore>, body: String) -> Result<HttpResponse, ApiError> {
+    let nfe = NFeParser::parse_xml(&body).map_err(|_| CoreError::validation("XML da NF-e ilegível"))?;
+    NFeValidator::validate(&nfe).map_err(|e| CoreError::validation(e.to_string()))?;
+    
+    let entry = FiscalLedger::extract(&nfe);
+    if !fiscal.save(&entry).await? {
+        return Err(CoreError::conflict(format!("NF-e {} já lançada no livro fiscal", entry.nfe_key)).into());
+    }
+    tracing::info!("NFe {} recorded in the fiscal ledger with {} items", entry.nfe_key, entry.items.len());
+    
+    Ok(HttpResponse::Created().json(FiscalEntryResponse::from(&entry)))
+}
+
+async fn summary_for(fiscal: &dyn FiscalStore, query: &FiscalPeriodQuery) -> Result<Vec<ResumoFiscal>, CoreError> {
+    validate(query)?;
+    let (start, end) = query.range()?;
+    let entries = fiscal.entries(start, end).await?;
+    Ok(FiscalLedger::monthly_summary(&entries, query.year, query.month))
+}
+
+/// Resumo do mês por CFOP, CST do ICMS e alíquota, com os créditos das entradas
+#[utoipa::path(
+    get, path = "/summary", tag = "fiscal",
+    params(FiscalPeriodQuery),
+    responses((status = 200, body = Vec<ResumoFiscal>), (status = 422)),
+)]
+async fn monthly_summary(
+    fiscal: web::Data<dyn FiscalStore>,
+    query: web::Query<FiscalPeriodQuery>,
+) -> Result<HttpResponse, ApiError> {
+    Ok(HttpResponse::Ok().json(summary_for(fiscal.get_ref(), &query).await?))
+}
+
+/// O mesmo resumo em CSV, para a contabilidade
+#[utoipa::path(
+    get, path = "/summary/csv", tag = "fiscal",
+    params(FiscalPeriodQuery),
+    responses((status = 200, content_type = "text/csv", body = String), (status = 422)),
+)]
+async fn summary_csv(
+    fiscal: web::Data<dyn FiscalStore>,
+    query: web::Query<FiscalPeriodQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let resumo = summary_for(fiscal.get_ref(), &query).await?;
+    Ok(HttpResponse::Ok()
+        .content_type("text/csv; charset=utf-8")
+        .insert_header((
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"resumo-fiscal-{}.csv\"", query.label()),
+        ))
+        .body(FiscalLedger::summary_to_csv(&resumo)))
+}
+
+/// Registros C100/C170/C190 do SPED Fiscal para as notas do mês
+#[utoipa::path(
+    get, path = "/sped", tag = "fiscal",
+    params(FiscalPeriodQuery),
+    responses((status = 200, content_type = "text/plain", body = String), (status = 422)),
+)]
+async fn sped_export(
+    fiscal: web::Data<dyn FiscalStore>,
+    query: web::Query<FiscalPeriodQuery>,
+) -> Result<HttpResponse, ApiError> {
+    validate(&*query)?;
+    let (start, end) = query.range()?;
+    let entries = fiscal.entries(start, end).await?;
+    let month = FiscalLedger::entries_in_month(&entries, query.year, query.month);
+    
+    Ok(HttpResponse::Ok()
+        .content_type("text/plain; charset=utf-8")
+        .insert_header((
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"sped-{}.txt\"", query.label()),
+        ))
+        .body(FiscalLedger::to_sped(&month)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use actix_web::{test, App};
+    use serde_json::Value;
+    use delpopolo_domain::UserRole;
+    use crate::auth::service::tests::{config, user};
+    use crate::auth::store::memory::InMemoryAuthStore;
+    use crate::auth::AuthService;
+    use crate::fiscal::store::memory::InMemoryFiscalStore;
+    use super::*;
+    
+    const NFE_XML: &str = "<NFe><chave>35250111222333000181550010000000011123456780</chave><numero>1</numero><serie>1</serie><data_emissao>2026-10-15T12:00:00Z</data_emissao><emitente><cnpj>11222333000181</cnpj><razao_social>Moinho Paulista LTDA</razao_social><endereco><logradouro>Rua do Trigo</logradouro><numero>100</numero><bairro>Centro</bairro><municipio>Sao Paulo</municipio><uf>SP</uf><cep>01000000</cep></endereco></emitente><destinatario><cnpj_cpf>98765432000110</cnpj_cpf><razao_social>DelPopolo Panificadora</razao_social><endereco><logradouro>Rua do Trigo</logradouro><numero>100</numero><bairro>Centro</bairro><municipio>Sao Paulo</municipio><uf>SP</uf><cep>01000000</cep></endereco></destinatario><itens><numero_item>1</numero_item><codigo_produto>FT01</codigo_produto><descricao>Farinha de trigo 25kg</descricao><ncm>11010010</ncm><cfop>1102</cfop><unidade_comercial>UN</unidade_comercial><quantidade_comercial>10</quantidade_comercial><valor_unitario_comercial>95</valor_unitario_comercial><valor_total_bruto>950</valor_total_bruto></itens><totais><base_calculo_icms>0</base_calculo_icms><valor_icms>0</valor_icms><valor_icms_desonerado>0</valor_icms_desonerado><base_calculo_icms_st>0</base_calculo_icms_st><valor_icms_st>0</valor_icms_st><valor_total_produtos>950</valor_total_produtos><valor_frete>0</valor_frete><valor_seguro>0</valor_seguro><valor_desconto>0</valor_desconto><valor_total_ii>0</valor_total_ii><valor_ipi>0</valor_ipi><valor_pis>0</valor_pis><valor_cofins>0</valor_cofins><valor_outras_despesas>0</valor_outras_despesas><valor_total_nota>950</valor_total_nota></totais></NFe>";
+    
+    #[actix_web::test]
+    async fn test_imported_nfe_feeds_summary_and_sped() {
+        let manager = user(UserRole::InventoryManager);
+        let auth = AuthService::new(Arc::new(InMemoryAuthStore::default().with_user(manager.clone())), &config());
+        let token = format!("Bearer {}", auth.login(&manager.email, "fermento123").await.unwrap().access_token);
+        let fiscal: Arc<dyn FiscalStore> = Arc::new(InMemoryFiscalStore::default());
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(auth))
+                .app_data(web::Data::from(fiscal))
+                .service(
+                    web::scope("/fiscal")
+                        .wrap(RequirePermission(Permission::FiscalRead))
+                        .configure(configure),
+                ),
+        )
+        .await;
+        let request = |method: test::TestRequest, uri: &str| {
+            method.uri(uri).insert_header(("Authorization", token.clone()))
+        };
+        
+        let req = request(test::TestRequest::post(), "/fiscal/entries").set_payload("<NFe>").to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 422);
+        
+        let req = request(test::TestRequest::post(), "/fiscal/entries").set_payload(NFE_XML).to_request();
+        let entry: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(entry["nfe_key"], "35250111222333000181550010000000011123456780");
+        let req = request(test::TestRequest::post(), "/fiscal/entries").set_payload(NFE_XML).to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 409);
+        
+        let req = request(test::TestRequest::get(), "/fiscal/summary?year=2026&month=10").to_request();
+        let resumo: Vec<Value> = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resumo.len(), 1);
+        assert_eq!(resumo[0]["cfop"], "1102");
+        
+        let req = request(test::TestRequest::get(), "/fiscal/sped?year=2026&month=10").to_request();
+        let sped = String::from_utf8(test::call_and_read_body(&app, req).await.to_vec()).unwrap();
+        assert!(sped.contains("|C100|0|1|"));
+        
+        let req = request(test::TestRequest::get(), "/fiscal/summary?year=2026&month=13").to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 422);
+    }
+}
@@ -1,5 +1,6 @@
 use actix_web::{web, HttpResponse};
 use serde_json::json;
+use crate::readiness::{Readiness, ReadinessState};
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(
@@ -12,6 +13,7 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
     );
 }
 
+/// Liveness: só indica que o processo responde, sem consultar dependências
 async fn health_check() -> HttpResponse {
     HttpResponse::Ok().json(json!({
         "status": "ok",
@@ -20,9 +22,64 @@ async fn health_check() -> HttpResponse {
     }))
 }
 
-async fn readiness_check() -> HttpResponse {
-    // TODO: Check database, redis, rabbitmq connections
-    HttpResponse::Ok().json(json!({
-        "status": "ready"
-    }))
+/// Readiness: 503 quando alguma dependência obrigatória está fora do ar
+async fn readiness_check(readiness: web::Data<Readiness>) -> HttpResponse {
+    let report = readiness.check().await;
+    match report.status {
+        ReadinessState::NotReady => HttpResponse::ServiceUnavailable().json(report),
+        ReadinessState::Ready | ReadinessState::Degraded => HttpResponse::Ok().json(report),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use actix_web::{test, App};
+    use serde_json::Value;
+    use crate::avila_logger::AvilaLogger;
+    use crate::readiness::fake::dependency;
+    use super::*;
+    
+    async fn ready(readiness: Readiness) -> (u16, Value) {
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(readiness))
+                .service(web::scope("/health").configure(configure)),
+        )
+        .await;
+        let resp = test::call_service(&app, test::TestRequest::get().uri("/health/ready").to_request()).await;
+        (resp.status().as_u16(), test::read_body_json(resp).await)
+    }
+    
+    #[actix_web::test]
+    async fn test_readiness_reports_each_dependency() {
+        let timeout = Duration::from_millis(50);
+        
+        let (status, body) = ready(
+            Readiness::new(timeout)
+                .with(dependency("database", true, 0), true)
+                .with(dependency("redis", false, 0), false),
+        )
+        .await;
+        assert_eq!(status, 200);
+        assert_eq!(body["status"], "degraded");
+        assert_eq!(body["checks"]["database"]["status"], "up");
+        assert_eq!(body["checks"]["redis"]["status"], "down");
+        assert!(body.get("avila_logger").is_none());
+        
+        // Dependência obrigatória lenta estoura o timeout e derruba a prontidão
+        let (status, body) = ready(
+            Readiness::new(timeout)
+                .with(dependency("database", true, 500), true)
+                .with(dependency("rabbitmq", true, 0), false),
+        )
+        .await;
+        assert_eq!(status, 503);
+        assert_eq!(body["status"], "not_ready");
+        assert_eq!(body["checks"]["database"]["status"], "timeout");
+        assert!(body["checks"]["database"]["latency_ms"].as_u64().unwrap() < 500);
+        
+        let (_, body) = ready(Readiness::new(timeout).with_logger(AvilaLogger::disabled())).await;
+        assert_eq!(body["avila_logger"]["dropped"], 0);
+    }
 }
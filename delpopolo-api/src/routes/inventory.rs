@@ -1,5 +1,415 @@
-use actix_web::web;
+use actix_web::{web, HttpResponse};
+use utoipa::OpenApi;
+use uuid::Uuid;
+use delpopolo_core::CoreError;
+use delpopolo_domain::{InventoryMovement, Permission};
+use delpopolo_infrastructure::repositories::inventory_repository::ProcessedRequest;
+use delpopolo_inventory::StockAlert;
+use crate::auth::extractor::AuthenticatedUser;
+use crate::auth::RequirePermission;
+use crate::dto::inventory::{
+    AdjustmentRequest, AvailabilityQuery, EntryRequest, LossRequest, MovementCursorToken, MovementListQuery,
+    ReleaseRequest, ReservationRequest, StockLevelResponse, WithdrawalRequest,
+};
+use crate::error::ApiError;
+use crate::events::{Event, EventBus};
+use crate::idempotency::REPLAYED_HEADER;
+use crate::inventory::{InventoryStore, StockOperation};
+use crate::pagination::{paginate, Page};
+use crate::validation::validate;
 
-pub fn configure(_cfg: &mut web::ServiceConfig) {
-    // TODO: Implementar rotas de inventrio
+#[derive(OpenApi)]
+#[openapi(paths(availability, alerts, list_movements, add_entry, withdraw, reserve, release, adjust, register_loss))]
+pub struct InventoryApi;
+
+/// Leitura já é exigida no escopo `/inventory`; toda operação exige `InventoryWrite`
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    let write = || RequirePermission(Permission::InventoryWrite);
+    
+    cfg.service(web::resource("/availability").route(web::get().to(availability)))
+        .service(web::resource("/alerts").route(web::get().to(alerts)))
+        .service(web::resource("/{product_id}/movements").route(web::get().to(list_movements)))
+        .service(web::resource("/{product_id}/entries").route(web::post().to(add_entry).wrap(write())))
+        .service(web::resource("/{product_id}/withdrawals").route(web::post().to(withdraw).wrap(write())))
+        .service(web::resource("/{product_id}/reservations").route(web::post().to(reserve).wrap(write())))
+        .service(web::resource("/{product_id}/releases").route(web::post().to(release).wrap(write())))
+        .service(web::resource("/{product_id}/adjustments").route(web::post().to(adjust).wrap(write())))
+        .service(web::resource("/{product_id}/losses").route(web::post().to(register_loss).wrap(write())));
+}
+
+/// Executa a operação uma única vez por `request_id`; repetições devolvem o saldo
+/// da primeira execução, e o mesmo id com outra operação é recusado
+async fn execute_once(
+    inventory: &dyn InventoryStore,
+    events: &EventBus,
+    user: &AuthenticatedUser,
+    request_id: Uuid,
+    operation: StockOperation,
+) -> Result<HttpResponse, ApiError> {
+    let payload = serde_json::to_value(&operation).map_err(CoreError::serialization)?;
+    
+    match inventory.begin_request(request_id, &payload).await? {
+        Some(previous) if previous.payload != payload => {
+            Err(CoreError::conflict(format!("request_id {} já foi usado em outra operação", request_id)).into())
+        }
+        Some(ProcessedRequest { response: Some(response), .. }) => {
+            Ok(HttpResponse::Ok().insert_header((REPLAYED_HEADER, "true")).json(response))
+        }
+        Some(_) => Err(CoreError::conflict(format!("Requisição {} ainda está em processamento", request_id)).into()),
+        None => match inventory.execute(&operation, user.id).await {
+            Ok(level) => {
+                let response = serde_json::to_value(StockLevelResponse::from(&level)).map_err(CoreError::serialization)?;
+                // O saldo já foi gravado: sem a resposta registrada, uma repetição só é
+                // executada de novo depois do prazo da reserva do id
+                if let Err(e) = inventory.complete_request(request_id, &response).await {
+                    tracing::error!("Failed to record the response of inventory request {}: {}", request_id, e);
+                }
+                publish_alert(inventory, events, level.product_id).await;
+                Ok(HttpResponse::Ok().json(response))
+            }
+            Err(e) => {
+                if let Err(abandon) = inventory.abandon_request(request_id).await {
+                    tracing::error!("Failed to release inventory request {}: {}", request_id, abandon);
+                }
+                Err(e.into())
+            }
+        },
+    }
+}
+
+/// Avisa o tópico `stock` quando o produto ficou com alerta depois da operação
+async fn publish_alert(inventory: &dyn InventoryStore, events: &EventBus, product_id: Uuid) {
+    match inventory.alerts().await {
+        Ok(alerts) => {
+            if let Some(alert) = alerts.iter().find(|alert| alert.product_id == product_id) {
+                events.publish(Event::stock_alert(alert)).await;
+            }
+        }
+        Err(e) => tracing::warn!("Could not check stock alerts for {}: {}", product_id, e),
+    }
+}
+
+#[utoipa::path(
+    post, path = "/{product_id}/entries", tag = "inventory",
+    params(("product_id" = Uuid, Path)),
+    request_body = EntryRequest,
+    responses(
+        (status = 200, body = StockLevelResponse, headers(("Idempotent-Replayed" = String, description = "`true` quando repetida"))),
+        (status = 404), (status = 409, description = "`request_id` já usado em outra operação"), (status = 422),
+    ),
+)]
+async fn add_entry(
+    user: AuthenticatedUser,
+    inventory: web::Data<dyn InventoryStore>,
+    events: web::Data<EventBus>,
+    product_id: web::Path<Uuid>,
+    body: web::Json<EntryRequest>,
+) -> Result<HttpResponse, ApiError> {
+    validate(&*body)?;
+    let body = body.into_inner();
+    let operation = StockOperation::Entry {
+        product_id: *product_id,
+        quantity: body.quantity,
+        unit_cost: body.unit_cost,
+        nfe_key: body.nfe_key,
+        supplier_id: body.supplier_id,
+    };
+    execute_once(inventory.get_ref(), &events, &user, body.request_id, operation).await
+}
+
+#[utoipa::path(
+    post, path = "/{product_id}/withdrawals", tag = "inventory",
+    params(("product_id" = Uuid, Path)),
+    request_body = WithdrawalRequest,
+    responses(
+        (status = 200, body = StockLevelResponse, headers(("Idempotent-Replayed" = String, description = "`true` quando repetida"))),
+        (status = 404), (status = 409, description = "`request_id` já usado em outra operação ou saldo insuficiente"), (status = 422),
+    ),
+)]
+async fn withdraw(
+    user: AuthenticatedUser,
+    inventory: web::Data<dyn InventoryStore>,
+    events: web::Data<EventBus>,
+    product_id: web::Path<Uuid>,
+    body: web::Json<WithdrawalRequest>,
+) -> Result<HttpResponse, ApiError> {
+    validate(&*body)?;
+    let operation = StockOperation::Withdrawal {
+        product_id: *product_id,
+        quantity: body.quantity,
+        order_id: body.order_id,
+    };
+    execute_once(inventory.get_ref(), &events, &user, body.request_id, operation).await
+}
+
+#[utoipa::path(
+    post, path = "/{product_id}/reservations", tag = "inventory",
+    params(("product_id" = Uuid, Path)),
+    request_body = ReservationRequest,
+    responses(
+        (status = 200, body = StockLevelResponse, headers(("Idempotent-Replayed" = String, description = "`true` quando repetida"))),
+        (status = 404), (status = 409, description = "`request_id` já usado em outra operação ou saldo insuficiente"), (status = 422),
+    ),
+)]
+async fn reserve(
+    user: AuthenticatedUser,
+    inventory: web::Data<dyn InventoryStore>,
+    events: web::Data<EventBus>,
+    product_id: web::Path<Uuid>,
+    body: web::Json<ReservationRequest>,
+) -> Result<HttpResponse, ApiError> {
+    validate(&*body)?;
+    let operation = StockOperation::Reservation {
+        product_id: *product_id,
+        quantity: body.quantity,
+        order_id: body.order_id,
+    };
+    execute_once(inventory.get_ref(), &events, &user, body.request_id, operation).await
+}
+
+#[utoipa::path(
+    post, path = "/{product_id}/releases", tag = "inventory",
+    params(("product_id" = Uuid, Path)),
+    request_body = ReleaseRequest,
+    responses(
+        (status = 200, body = StockLevelResponse, headers(("Idempotent-Replayed" = String, description = "`true` quando repetida"))),
+        (status = 404), (status = 409, description = "`request_id` já usado em outra operação"), (status = 422),
+    ),
+)]
+async fn release(
+    user: AuthenticatedUser,
+    inventory: web::Data<dyn InventoryStore>,
+    events: web::Data<EventBus>,
+    product_id: web::Path<Uuid>,
+    body: web::Json<ReleaseRequest>,
+) -> Result<HttpResponse, ApiError> {
+    validate(&*body)?;
+    let operation = StockOperation::Release {
+        product_id: *product_id,
+        quantity: body.quantity,
+    };
+    execute_once(inventory.get_ref(), &events, &user, body.request_id, operation).await
+}
+
+#[utoipa::path(
+    post, path = "/{product_id}/adjustments", tag = "inventory",
+    params(("product_id" = Uuid, Path)),
+    request_body = AdjustmentRequest,
+    responses(
+        (status = 200, body = StockLevelResponse, headers(("Idempotent-Replayed" = String, description = "`true` quando repetida"))),
+        (status = 404), (status = 409, description = "`request_id` já usado em outra operação"), (status = 422),
+    ),
+)]
+async fn adjust(
+    user: AuthenticatedUser,
+    inventory: web::Data<dyn InventoryStore>,
+    events: web::Data<EventBus>,
+    product_id: web::Path<Uuid>,
+    body: web::Json<AdjustmentRequest>,
+) -> Result<HttpResponse, ApiError> {
+    validate(&*body)?;
+    let body = body.into_inner();
+    let operation = StockOperation::Adjustment {
+        product_id: *product_id,
+        new_quantity: body.new_quantity,
+        reason: body.reason,
+    };
+    execute_once(inventory.get_ref(), &events, &user, body.request_id, operation).await
+}
+
+#[utoipa::path(
+    post, path = "/{product_id}/losses", tag = "inventory",
+    params(("product_id" = Uuid, Path)),
+    request_body = LossRequest,
+    responses(
+        (status = 200, body = StockLevelResponse, headers(("Idempotent-Replayed" = String, description = "`true` quando repetida"))),
+        (status = 404), (status = 409, description = "`request_id` já usado em outra operação ou saldo insuficiente"), (status = 422),
+    ),
+)]
+async fn register_loss(
+    user: AuthenticatedUser,
+    inventory: web::Data<dyn InventoryStore>,
+    events: web::Data<EventBus>,
+    product_id: web::Path<Uuid>,
+    body: web::Json<LossRequest>,
+) -> Result<HttpResponse, ApiError> {
+    validate(&*body)?;
+    let body = body.into_inner();
+    let operation = StockOperation::Loss {
+        product_id: *product_id,
+        quantity: body.quantity,
+        reason: body.reason,
+    };
+    execute_once(inventory.get_ref(), &events, &user, body.request_id, operation).await
+}
+
+/// Razão de movimentações do produto, do mais recente para o mais antigo
+#[utoipa::path(
+    get, path = "/{product_id}/movements", tag = "inventory",
+    params(("product_id" = Uuid, Path), MovementListQuery),
+    responses((status = 200, body = Page<InventoryMovement>), (status = 422)),
+)]
+async fn list_movements(
+    inventory: web::Data<dyn InventoryStore>,
+    product_id: web::Path<Uuid>,
+    query: web::Query<MovementListQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let mut movement_query = query.to_query(*product_id)?;
+    let limit = movement_query.limit;
+    movement_query.limit += 1;
+    
+    let found = inventory.movements(&movement_query).await?;
+    Ok(HttpResponse::Ok().json(paginate(found, limit, |m| MovementCursorToken { t: m.created_at, id: m.id })))
+}
+
+#[utoipa::path(
+    get, path = "/availability", tag = "inventory",
+    params(AvailabilityQuery),
+    responses((status = 200, body = Vec<StockLevelResponse>), (status = 422)),
+)]
+async fn availability(
+    inventory: web::Data<dyn InventoryStore>,
+    query: web::Query<AvailabilityQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let levels = inventory.levels(&query.product_ids()?).await?;
+    Ok(HttpResponse::Ok().json(levels.iter().map(StockLevelResponse::from).collect::<Vec<_>>()))
+}
+
+#[utoipa::path(get, path = "/alerts", tag = "inventory", responses((status = 200, body = Vec<StockAlert>)))]
+async fn alerts(inventory: web::Data<dyn InventoryStore>) -> Result<HttpResponse, ApiError> {
+    Ok(HttpResponse::Ok().json(inventory.alerts().await?))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use actix_web::{test, App};
+    use serde_json::{json, Value};
+    use delpopolo_domain::UserRole;
+    use delpopolo_infrastructure::repositories::inventory_repository::REQUEST_LEASE_SECS;
+    use crate::auth::service::tests::{config, user};
+    use crate::auth::store::memory::InMemoryAuthStore;
+    use crate::auth::AuthService;
+    use crate::inventory::store::memory::InMemoryInventoryStore;
+    use super::*;
+    
+    async fn token(auth: &AuthService, email: &str) -> String {
+        format!("Bearer {}", auth.login(email, "fermento123").await.unwrap().access_token)
+    }
+    
+    #[actix_web::test]
+    async fn test_inventory_operations_are_idempotent() {
+        let stockist = user(UserRole::InventoryManager);
+        let mut cook = user(UserRole::Kitchen);
+        cook.email = "cozinha@delpopolo.com.br".to_string();
+        let auth = AuthService::new(
+            Arc::new(InMemoryAuthStore::default().with_user(stockist.clone()).with_user(cook.clone())),
+            &config(),
+        );
+        let (stockist_token, cook_token) = (token(&auth, &stockist.email).await, token(&auth, &cook.email).await);
+        
+        let (farinha, fermento) = (Uuid::new_v4(), Uuid::new_v4());
+        let inventory = Arc::new(InMemoryInventoryStore::default().with_minimum(farinha, "Farinha", 20.0));
+        let events = web::Data::new(EventBus::local());
+        let mut alerts_feed = events.subscribe();
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(auth))
+                .app_data(web::Data::from(inventory.clone() as Arc<dyn InventoryStore>))
+                .app_data(events.clone())
+                .service(
+                    web::scope("/inventory")
+                        .wrap(RequirePermission(Permission::InventoryRead))
+                        .configure(configure),
+                ),
+        )
+        .await;
+        let post = |uri: String, token: &str, body: Value| {
+            test::TestRequest::post().uri(&uri).insert_header(("Authorization", token.to_string())).set_json(body).to_request()
+        };
+        let get = |uri: String, token: &str| {
+            test::TestRequest::get().uri(&uri).insert_header(("Authorization", token.to_string())).to_request()
+        };
+        
+        // Entrada repetida com o mesmo id só é aplicada uma vez
+        let entry = json!({"request_id": Uuid::new_v4(), "quantity": 50, "unit_cost": 4.5, "nfe_key": "3".repeat(44)});
+        assert_eq!(test::call_service(&app, post(format!("/inventory/{}/entries", farinha), &cook_token, entry.clone())).await.status(), 403);
+        let level: Value = test::call_and_read_body_json(&app, post(format!("/inventory/{}/entries", farinha), &stockist_token, entry.clone())).await;
+        assert_eq!(level["available_quantity"], 50.0);
+        let resp = test::call_service(&app, post(format!("/inventory/{}/entries", farinha), &stockist_token, entry.clone())).await;
+        assert_eq!(resp.headers().get(REPLAYED_HEADER).unwrap(), "true");
+        let level: Value = test::read_body_json(resp).await;
+        assert_eq!(level["available_quantity"], 50.0);
+        assert_eq!(inventory.movement_count(), 1);
+        
+        // Mesmo id em outra operação é conflito; falha libera o id para nova tentativa
+        let loss = json!({"request_id": entry["request_id"], "quantity": 1, "reason": "saco rasgado"});
+        assert_eq!(test::call_service(&app, post(format!("/inventory/{}/losses", farinha), &stockist_token, loss)).await.status(), 409);
+        let loss = json!({"request_id": Uuid::new_v4(), "quantity": 45, "reason": "umidade"});
+        let level: Value = test::call_and_read_body_json(&app, post(format!("/inventory/{}/losses", farinha), &stockist_token, loss)).await;
+        assert_eq!(level["available_quantity"], 5.0);
+        // Só a perda deixou o saldo abaixo do mínimo
+        let alert = alerts_feed.try_recv().unwrap();
+        assert_eq!((alert.kind.as_str(), alert.data["product_id"].clone()), ("stock.alert", json!(farinha)));
+        assert!(alerts_feed.try_recv().is_err());
+        let reservation = json!({"request_id": Uuid::new_v4(), "quantity": 10, "order_id": Uuid::new_v4()});
+        let resp = test::call_service(&app, post(format!("/inventory/{}/reservations", farinha), &stockist_token, reservation.clone())).await;
+        assert_eq!(resp.status(), 409);
+        let adjustment = json!({"request_id": Uuid::new_v4(), "new_quantity": 12, "reason": "contagem"});
+        test::call_service(&app, post(format!("/inventory/{}/adjustments", farinha), &stockist_token, adjustment)).await;
+        let level: Value = test::call_and_read_body_json(&app, post(format!("/inventory/{}/reservations", farinha), &stockist_token, reservation)).await;
+        assert_eq!(level["reserved_quantity"], 10.0);
+        
+        // Razão filtrado, saldo em lote e alertas
+        let page: Value = test::call_and_read_body_json(&app, get(format!("/inventory/{}/movements?type=Adjustment", farinha), &cook_token)).await;
+        assert_eq!(page["items"][0]["quantity"], 7.0);
+        let page: Value = test::call_and_read_body_json(&app, get(format!("/inventory/{}/movements?nfe_key={}", farinha, "3".repeat(44)), &cook_token)).await;
+        assert_eq!(page["items"].as_array().unwrap().len(), 1);
+        let levels: Value = test::call_and_read_body_json(&app, get(format!("/inventory/availability?product_ids={},{}", farinha, fermento), &cook_token)).await;
+        assert_eq!(levels[0]["available_quantity"], 2.0);
+        assert_eq!(levels[1]["available_quantity"], 0.0);
+        let alerts: Value = test::call_and_read_body_json(&app, get("/inventory/alerts".to_string(), &cook_token)).await;
+        assert_eq!(alerts[0]["product_id"], farinha.to_string());
+        assert_eq!(alerts[0]["alert_level"], "High");
+    }
+    
+    #[actix_web::test]
+    async fn test_request_without_response_is_retaken_after_the_lease() {
+        let stockist = user(UserRole::InventoryManager);
+        let auth = AuthService::new(Arc::new(InMemoryAuthStore::default().with_user(stockist.clone())), &config());
+        let token = token(&auth, &stockist.email).await;
+        
+        let farinha = Uuid::new_v4();
+        let (recent, stale) = (Uuid::new_v4(), Uuid::new_v4());
+        let entry = |quantity: f64| StockOperation::Entry { product_id: farinha, quantity, unit_cost: None, nfe_key: None, supplier_id: None };
+        let lease = chrono::Duration::seconds(REQUEST_LEASE_SECS);
+        let inventory = InMemoryInventoryStore::default()
+            .with_pending_request(recent, &entry(10.0), chrono::Utc::now())
+            .with_pending_request(stale, &entry(10.0), chrono::Utc::now() - lease - chrono::Duration::seconds(1));
+        let inventory: Arc<dyn InventoryStore> = Arc::new(inventory);
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(auth))
+                .app_data(web::Data::from(inventory))
+                .app_data(web::Data::new(EventBus::local()))
+                .service(web::scope("/inventory").configure(configure)),
+        )
+        .await;
+        let post = |request_id: Uuid, quantity: f64| {
+            test::TestRequest::post()
+                .uri(&format!("/inventory/{}/entries", farinha))
+                .insert_header(("Authorization", token.clone()))
+                .set_json(json!({"request_id": request_id, "quantity": quantity}))
+                .to_request()
+        };
+        
+        // Dentro do prazo a execução ainda pode estar em andamento
+        assert_eq!(test::call_service(&app, post(recent, 10.0)).await.status(), 409);
+        // Vencida, só a mesma operação retoma o id
+        assert_eq!(test::call_service(&app, post(stale, 5.0)).await.status(), 409);
+        let level: Value = test::call_and_read_body_json(&app, post(stale, 10.0)).await;
+        assert_eq!(level["available_quantity"], 10.0);
+        let resp = test::call_service(&app, post(stale, 10.0)).await;
+        assert_eq!(resp.headers().get(REPLAYED_HEADER).unwrap(), "true");
+    }
 }
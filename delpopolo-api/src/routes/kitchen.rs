@@ -0,0 +1,342 @@
+use actix_web::{web, HttpResponse};
+use chrono::Utc;
+use utoipa::OpenApi;
+use uuid::Uuid;
+use delpopolo_core::CoreError;
+use delpopolo_domain::{KitchenStation, Order, OrderStatus, TicketStage};
+use crate::catalog::ProductStore;
+use crate::dto::kitchen::{StationQueueResponse, StationSummaryResponse, TicketResponse};
+use crate::error::ApiError;
+use crate::events::{Event, EventBus};
+use crate::kitchen::{Board, KitchenSettings, KitchenStore, StationTicket, Urgency};
+use crate::metrics::METRICS;
+use crate::orders::OrderStore;
+
+#[derive(OpenApi)]
+#[openapi(paths(list_stations, station_queue, bump, recall))]
+pub struct KitchenApi;
+
+/// A permissão de operar a cozinha é exigida no escopo `/kitchen`
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::resource("/stations").route(web::get().to(list_stations)))
+        .service(web::resource("/stations/{station}/queue").route(web::get().to(station_queue)))
+        .service(web::resource("/stations/{station}/tickets/{order_id}/bump").route(web::post().to(bump)))
+        .service(web::resource("/stations/{station}/tickets/{order_id}/recall").route(web::post().to(recall)));
+}
+
+/// Resumo de cada estação para a tela inicial do tablet
+#[utoipa::path(
+    get, path = "/stations", tag = "kitchen",
+    responses((status = 200, body = Vec<StationSummaryResponse>)),
+)]
+async fn list_stations(
+    orders: web::Data<dyn OrderStore>,
+    products: web::Data<dyn ProductStore>,
+    kitchen: web::Data<dyn KitchenStore>,
+    settings: web::Data<KitchenSettings>,
+) -> Result<HttpResponse, ApiError> {
+    let board = Board::load(orders.get_ref(), products.get_ref(), kitchen.get_ref()).await?;
+    let now = Utc::now();
+    
+    let summaries: Vec<StationSummaryResponse> = KitchenStation::ALL
+        .into_iter()
+        .map(|station| {
+            let queue = board.queue(station, &settings);
+            let count = |stage| queue.iter().filter(|ticket| ticket.ticket.stage() == stage).count();
+            StationSummaryResponse {
+                station,
+                waiting: count(TicketStage::Waiting),
+                preparing: count(TicketStage::Preparing),
+                late: queue
+                    .iter()
+                    .filter(|ticket| settings.urgency(settings.promised_at(&ticket.order), now) == Urgency::Late)
+                    .count(),
+            }
+        })
+        .collect();
+    Ok(HttpResponse::Ok().json(summaries))
+}
+
+/// Fila da estação pelo horário prometido (entrega agendada ou criação mais o tempo estimado),
+/// com os atrasados destacados em `urgency`, e os últimos finalizados para o recall
+#[utoipa::path(
+    get, path = "/stations/{station}/queue", tag = "kitchen",
+    params(("station" = KitchenStation, Path)),
+    responses((status = 200, body = StationQueueResponse)),
+)]
+async fn station_queue(
+    orders: web::Data<dyn OrderStore>,
+    products: web::Data<dyn ProductStore>,
+    kitchen: web::Data<dyn KitchenStore>,
+    settings: web::Data<KitchenSettings>,
+    station: web::Path<KitchenStation>,
+) -> Result<HttpResponse, ApiError> {
+    let station = station.into_inner();
+    let board = Board::load(orders.get_ref(), products.get_ref(), kitchen.get_ref()).await?;
+    let now = Utc::now();
+    let render = |tickets: Vec<StationTicket>| {
+        tickets.iter().map(|ticket| TicketResponse::new(ticket, &settings, now)).collect()
+    };
+    
+    Ok(HttpResponse::Ok().json(StationQueueResponse {
+        station,
+        generated_at: now,
+        tickets: render(board.queue(station, &settings)),
+        done: render(board.done(station)),
+    }))
+}
+
+/// O pedido com o andamento de todas as estações dele e o ticket da estação pedida
+async fn load_ticket(
+    orders: &dyn OrderStore,
+    products: &dyn ProductStore,
+    kitchen: &dyn KitchenStore,
+    station: KitchenStation,
+    order_id: Uuid,
+) -> Result<(Board, StationTicket), CoreError> {
+    let order = orders
+        .find(order_id)
+        .await?
+        .ok_or_else(|| CoreError::not_found(format!("Pedido {} não encontrado", order_id)))?;
+    let order_number = order.order_number.clone();
+    
+    let board = Board::for_orders(vec![order], products, kitchen).await?;
+    let ticket = board
+        .ticket(order_id, station)
+        .ok_or_else(|| CoreError::not_found(format!("Pedido {} não tem itens para a estação {:?}", order_number, station)))?;
+    Ok((board, ticket))
+}
+
+/// Grava a mudança de status do pedido; se outra estação já o levou ao mesmo status, só recarrega
+async fn advance(
+    orders: &dyn OrderStore,
+    events: &EventBus,
+    order: &mut Order,
+    next: OrderStatus,
+    apply: impl FnOnce(&mut Order) -> Result<(), CoreError>,
+) -> Result<(), CoreError> {
+    let previous = order.updated_at;
+    let mut moved = order.clone();
+    apply(&mut moved)?;
+    
+    match orders.update(&moved, previous).await {
+        Ok(()) => {
+            tracing::info!("Order {} moved to {:?} from the kitchen", moved.order_number, moved.status);
+            METRICS.order_reached(&moved);
+            events.publish(Event::order(&moved, false)).await;
+            *order = moved;
+            Ok(())
+        }
+        Err(CoreError::Conflict(message)) => match orders.find(order.id).await? {
+            Some(current) if current.status == next => {
+                *order = current;
+                Ok(())
+            }
+            _ => Err(CoreError::Conflict(message)),
+        },
+        Err(e) => Err(e),
+    }
+}
+
+async fn respond(
+    events: &EventBus,
+    kind: &str,
+    ticket: &StationTicket,
+    settings: &KitchenSettings,
+) -> Result<HttpResponse, ApiError> {
+    let response = TicketResponse::new(ticket, settings, Utc::now());
+    events.publish(Event::kitchen_ticket(kind, &response)).await;
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// O primeiro bump começa o preparo na estação (e o do pedido, se ainda estava confirmado);
+/// o segundo finaliza, e a última estação a finalizar deixa o pedido pronto. Repetir o bump de
+/// um ticket finalizado só volta a tentar deixar o pedido pronto.
+#[utoipa::path(
+    post, path = "/stations/{station}/tickets/{order_id}/bump", tag = "kitchen",
+    params(("station" = KitchenStation, Path), ("order_id" = Uuid, Path)),
+    responses(
+        (status = 200, body = TicketResponse),
+        (status = 404, description = "Pedido inexistente ou sem itens para a estação"),
+        (status = 409, description = "Pedido fora da produção ou já finalizado na estação"),
+    ),
+)]
+async fn bump(
+    orders: web::Data<dyn OrderStore>,
+    products: web::Data<dyn ProductStore>,
+    kitchen: web::Data<dyn KitchenStore>,
+    events: web::Data<EventBus>,
+    settings: web::Data<KitchenSettings>,
+    path: web::Path<(KitchenStation, Uuid)>,
+) -> Result<HttpResponse, ApiError> {
+    let (station, order_id) = path.into_inner();
+    let (mut board, mut ticket) = load_ticket(orders.get_ref(), products.get_ref(), kitchen.get_ref(), station, order_id).await?;
+    if !matches!(ticket.order.status, OrderStatus::Confirmed | OrderStatus::Preparing) {
+        return Err(CoreError::conflict(format!("Pedido {} não está em produção", ticket.order.order_number)).into());
+    }
+    
+    // Ticket já finalizado com o pedido ainda em produção: a gravação anterior passou, mas o
+    // pedido pode não ter avançado. O bump repetido tenta avançá-lo de novo.
+    let retry = ticket.ticket.stage() == TicketStage::Done;
+    let stage = if retry {
+        TicketStage::Done
+    } else {
+        let stage = ticket.ticket.bump()?;
+        kitchen.save(&ticket.ticket).await?;
+        stage
+    };
+    
+    if ticket.order.status == OrderStatus::Confirmed {
+        advance(orders.get_ref(), &events, &mut ticket.order, OrderStatus::Preparing, |order| {
+            order.transition_to(OrderStatus::Preparing, None)
+        })
+        .await?;
+    }
+    if stage == TicketStage::Done {
+        // Relido depois de gravar: de duas estações finalizando juntas, ao menos a última vê as duas
+        board.refresh_tickets(kitchen.get_ref()).await?;
+        if board.is_finished(order_id) {
+            advance(orders.get_ref(), &events, &mut ticket.order, OrderStatus::Ready, |order| {
+                order.transition_to(OrderStatus::Ready, None)
+            })
+            .await?;
+        } else if retry {
+            return Err(CoreError::conflict("Pedido já finalizado nesta estação").into());
+        }
+    }
+    respond(&events, "kitchen.ticket_bumped", &ticket, &settings).await
+}
+
+/// Devolve ao preparo um pedido finalizado por engano na estação; se ele já estava pronto,
+/// volta a ficar em preparo
+#[utoipa::path(
+    post, path = "/stations/{station}/tickets/{order_id}/recall", tag = "kitchen",
+    params(("station" = KitchenStation, Path), ("order_id" = Uuid, Path)),
+    responses(
+        (status = 200, body = TicketResponse),
+        (status = 404, description = "Pedido inexistente ou sem itens para a estação"),
+        (status = 409, description = "Pedido já saiu da cozinha ou não foi finalizado na estação"),
+    ),
+)]
+async fn recall(
+    orders: web::Data<dyn OrderStore>,
+    products: web::Data<dyn ProductStore>,
+    kitchen: web::Data<dyn KitchenStore>,
+    events: web::Data<EventBus>,
+    settings: web::Data<KitchenSettings>,
+    path: web::Path<(KitchenStation, Uuid)>,
+) -> Result<HttpResponse, ApiError> {
+    let (station, order_id) = path.into_inner();
+    let (_, mut ticket) = load_ticket(orders.get_ref(), products.get_ref(), kitchen.get_ref(), station, order_id).await?;
+    if !matches!(ticket.order.status, OrderStatus::Preparing | OrderStatus::Ready) {
+        return Err(CoreError::conflict(format!("Pedido {} já saiu da cozinha", ticket.order.order_number)).into());
+    }
+    
+    ticket.ticket.recall()?;
+    kitchen.save(&ticket.ticket).await?;
+    
+    if ticket.order.status == OrderStatus::Ready {
+        advance(orders.get_ref(), &events, &mut ticket.order, OrderStatus::Preparing, Order::reopen_preparation).await?;
+    }
+    respond(&events, "kitchen.ticket_recalled", &ticket, &settings).await
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use actix_web::{test, App};
+    use serde_json::{json, Value};
+    use delpopolo_domain::{Money, OrderItem, OrderSource, Permission, Product, ProductCategory, UnitOfMeasure, UserRole};
+    use crate::auth::service::tests::{config, user};
+    use crate::auth::store::memory::InMemoryAuthStore;
+    use crate::auth::{AuthService, RequirePermission};
+    use crate::catalog::store::memory::InMemoryProductStore;
+    use crate::kitchen::store::memory::InMemoryKitchenStore;
+    use crate::orders::store::memory::InMemoryOrderStore;
+    use super::*;
+    
+    fn product(name: &str, category: ProductCategory) -> Product {
+        Product::new(name.to_string(), category, UnitOfMeasure::Unit, Money::brl(12.0), Money::brl(5.0))
+    }
+    
+    #[actix_web::test]
+    async fn test_bump_and_recall_drive_the_order() {
+        let cashier = user(UserRole::Cashier);
+        let mut cook = user(UserRole::Kitchen);
+        cook.email = "cozinha@delpopolo.com.br".to_string();
+        let auth = AuthService::new(
+            Arc::new(InMemoryAuthStore::default().with_user(cashier.clone()).with_user(cook.clone())),
+            &config(),
+        );
+        let cashier_token = format!("Bearer {}", auth.login(&cashier.email, "fermento123").await.unwrap().access_token);
+        let token = format!("Bearer {}", auth.login(&cook.email, "fermento123").await.unwrap().access_token);
+        
+        let (baguete, misto) = (product("Baguete", ProductCategory::Bread), product("Misto quente", ProductCategory::Sandwich));
+        let mut order = Order::new(OrderSource::WhatsApp);
+        order.add_item(OrderItem::from_product(&baguete, 2.0, None));
+        order.add_item(OrderItem::from_product(&misto, 1.0, None));
+        order.confirm();
+        let orders = Arc::new(InMemoryOrderStore::default());
+        orders.insert(&order).await.unwrap();
+        let kitchen = Arc::new(InMemoryKitchenStore::default());
+        
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(auth))
+                .app_data(web::Data::from(Arc::new(InMemoryProductStore::default().with_products(vec![baguete, misto])) as Arc<dyn ProductStore>))
+                .app_data(web::Data::from(orders.clone() as Arc<dyn OrderStore>))
+                .app_data(web::Data::from(kitchen.clone() as Arc<dyn KitchenStore>))
+                .app_data(web::Data::new(EventBus::local()))
+                .app_data(web::Data::new(KitchenSettings { default_preparation_minutes: 20, due_soon_minutes: 5 }))
+                .service(
+                    web::scope("/kitchen")
+                        .wrap(RequirePermission(Permission::KitchenOperate))
+                        .configure(configure),
+                ),
+        )
+        .await;
+        let post = |uri: String| test::TestRequest::post().uri(&uri).insert_header(("Authorization", token.clone())).to_request();
+        let ticket = |station: &str, action: &str| format!("/kitchen/stations/{}/tickets/{}/{}", station, order.id, action);
+        
+        // Caixa não opera a cozinha
+        let req = test::TestRequest::get().uri("/kitchen/stations").insert_header(("Authorization", cashier_token)).to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 403);
+        
+        let req = test::TestRequest::get().uri("/kitchen/stations/Bakery/queue").insert_header(("Authorization", token.clone())).to_request();
+        let queue: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(queue["tickets"][0]["stage"], "Waiting");
+        assert_eq!(queue["tickets"][0]["items"].as_array().unwrap().len(), 1);
+        assert_eq!(queue["tickets"][0]["urgency"], "OnTime");
+        
+        // Começar numa estação coloca o pedido em preparo; finalizar só ela não o deixa pronto
+        let res: Value = test::call_and_read_body_json(&app, post(ticket("Bakery", "bump"))).await;
+        assert_eq!((res["stage"].clone(), res["order_status"].clone()), (json!("Preparing"), json!("Preparing")));
+        let res: Value = test::call_and_read_body_json(&app, post(ticket("Bakery", "bump"))).await;
+        assert_eq!((res["stage"].clone(), res["order_status"].clone()), (json!("Done"), json!("Preparing")));
+        assert_eq!(test::call_service(&app, post(ticket("Bakery", "bump"))).await.status(), 409);
+        assert_eq!(test::call_service(&app, post(ticket("Beverage", "bump"))).await.status(), 404);
+        
+        test::call_service(&app, post(ticket("Sandwich", "bump"))).await;
+        let res: Value = test::call_and_read_body_json(&app, post(ticket("Sandwich", "bump"))).await;
+        assert_eq!(res["order_status"], "Ready");
+        
+        let req = test::TestRequest::get().uri("/kitchen/stations/Sandwich/queue").insert_header(("Authorization", token.clone())).to_request();
+        let queue: Value = test::call_and_read_body_json(&app, req).await;
+        assert!(queue["tickets"].as_array().unwrap().is_empty());
+        assert_eq!(queue["done"][0]["order_id"], order.id.to_string());
+        
+        // Recall devolve o pedido pronto ao preparo
+        let res: Value = test::call_and_read_body_json(&app, post(ticket("Sandwich", "recall"))).await;
+        assert_eq!((res["stage"].clone(), res["order_status"].clone()), (json!("Preparing"), json!("Preparing")));
+        assert_eq!(orders.find(order.id).await.unwrap().unwrap().status, OrderStatus::Preparing);
+        assert_eq!(test::call_service(&app, post(ticket("Sandwich", "recall"))).await.status(), 409);
+        
+        // Ticket gravado como finalizado sem o pedido avançar: o bump repetido o deixa pronto
+        let mut sandwich = kitchen.tickets(&[order.id]).await.unwrap().into_iter()
+            .find(|t| t.station == KitchenStation::Sandwich).unwrap();
+        sandwich.bump().unwrap();
+        kitchen.save(&sandwich).await.unwrap();
+        let res: Value = test::call_and_read_body_json(&app, post(ticket("Sandwich", "bump"))).await;
+        assert_eq!((res["stage"].clone(), res["order_status"].clone()), (json!("Done"), json!("Ready")));
+    }
+}
@@ -0,0 +1,84 @@
+use std::time::Duration;
+use actix_web::{http::header, web, HttpRequest, HttpResponse};
+use crate::inventory::InventoryStore;
+use crate::metrics::{MetricsSources, METRICS};
+
+/// Teto para as consultas feitas durante o scrape, abaixo do timeout padrão do Prometheus
+const REFRESH_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::resource("")
+            .route(web::get().to(scrape))
+    );
+}
+
+/// Formato texto do Prometheus; gauges de pool, filas e alertas são lidos na hora
+async fn scrape(
+    req: HttpRequest,
+    sources: web::Data<MetricsSources>,
+    inventory: Option<web::Data<dyn InventoryStore>>,
+) -> HttpResponse {
+    if let Some(token) = &sources.token {
+        let expected = format!("Bearer {}", token);
+        let given = req.headers().get(header::AUTHORIZATION).and_then(|v| v.to_str().ok());
+        if given != Some(expected.as_str()) {
+            return HttpResponse::Unauthorized().finish();
+        }
+    }
+    
+    let refresh = async {
+        sources.refresh(&METRICS).await;
+        if let Some(inventory) = &inventory {
+            match inventory.alerts().await {
+                Ok(alerts) => METRICS.set_stock_alerts(&alerts),
+                Err(e) => tracing::warn!("Could not load stock alerts for metrics: {}", e),
+            }
+        }
+    };
+    if tokio::time::timeout(REFRESH_TIMEOUT, refresh).await.is_err() {
+        tracing::warn!("Metrics refresh timed out after {:?}; serving last values", REFRESH_TIMEOUT);
+    }
+    
+    HttpResponse::Ok()
+        .content_type("text/plain; version=0.0.4; charset=utf-8")
+        .body(METRICS.render())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use actix_web::{test, App};
+    use uuid::Uuid;
+    use crate::inventory::store::memory::InMemoryInventoryStore;
+    use crate::metrics::HttpMetrics;
+    use super::*;
+    
+    #[actix_web::test]
+    async fn test_scrape_requires_token_and_exposes_metrics() {
+        let inventory = InMemoryInventoryStore::default().with_minimum(Uuid::new_v4(), "Farinha", 10.0);
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(MetricsSources { token: Some("segredo".into()), ..Default::default() }))
+                .app_data(web::Data::from(Arc::new(inventory) as Arc<dyn InventoryStore>))
+                .wrap(HttpMetrics)
+                .service(web::scope("/metrics").configure(configure)),
+        )
+        .await;
+        
+        let req = test::TestRequest::get().uri("/metrics").to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 401);
+        
+        let req = test::TestRequest::get()
+            .uri("/metrics")
+            .insert_header(("Authorization", "Bearer segredo"))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+        assert!(resp.headers().get(header::CONTENT_TYPE).unwrap().to_str().unwrap().starts_with("text/plain"));
+        let body = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+        assert!(body.contains(r#"delpopolo_stock_alerts{level="Critical"} 1"#), "{body}");
+        // A requisição recusada acima já foi medida pela rota registrada
+        assert!(body.contains(r#"route="/metrics",status="401""#), "{body}");
+    }
+}
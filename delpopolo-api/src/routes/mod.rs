@@ -1,10 +1,219 @@
-use actix_web::web;
+use actix_web::{middleware::DefaultHeaders, web};
+use utoipa::OpenApi;
+use utoipa_swagger_ui::SwaggerUi;
+use crate::idempotency::Idempotency;
+use crate::middleware::AvilaAuth;
 
 pub mod health;
+pub mod metrics;
+pub mod auth;
+pub mod products;
+pub mod orders;
+pub mod customers;
+pub mod inventory;
+pub mod suppliers;
+pub mod webhooks;
+pub mod events;
+pub mod kitchen;
+pub mod fiscal;
+pub mod v1;
 
+/// Cabeçalho com a versão que atendeu a requisição
+pub const API_VERSION_HEADER: &str = "Api-Version";
+
+/// Cada versão é um escopo `/api/vN` com a sua própria composição de rotas. Os módulos de
+/// recurso não conhecem a versão: a `v2` entra ao lado da `v1` reaproveitando os que não
+/// mudarem e trocando só os que quebram contrato.
 pub fn configure(cfg: &mut web::ServiceConfig) {
+    // Fora da versão: sondas de orquestração e URLs cadastradas nos provedores
     cfg.service(
         web::scope("/health")
             .configure(health::configure)
+    )
+    // Scrape do Prometheus, protegido por token próprio quando configurado
+    .service(
+        web::scope("/metrics")
+            .configure(metrics::configure)
+    )
+    // Webhooks são autenticados pelo próprio provedor
+    .service(
+        web::scope("/webhooks")
+            .configure(webhooks::configure)
+    )
+    // Documentação pública, registrada antes do escopo autenticado que cobre o prefixo
+    .service(
+        SwaggerUi::new("/api/v1/docs/{_:.*}").url("/api/v1/openapi.json", v1::ApiDoc::openapi())
+    )
+    .service(
+        web::scope("/api/v1")
+            .wrap(Idempotency)
+            .wrap(AvilaAuth)
+            .wrap(DefaultHeaders::new().add((API_VERSION_HEADER, "1")))
+            .configure(v1::configure)
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use actix_web::{http::StatusCode, test, App, HttpResponse};
+    use serde_json::json;
+    use uuid::Uuid;
+    use delpopolo_domain::UserRole;
+    use crate::auth::service::tests::{config, user};
+    use crate::auth::store::memory::InMemoryAuthStore;
+    use crate::auth::AuthService;
+    use crate::avila_logger::AvilaLogger;
+    use crate::catalog::store::memory::InMemoryProductStore;
+    use crate::catalog::{ImageStorage, ProductStore};
+    use crate::customers::store::memory::InMemoryCustomerStore;
+    use crate::customers::CustomerStore;
+    use crate::fiscal::store::memory::InMemoryFiscalStore;
+    use crate::fiscal::FiscalStore;
+    use crate::inventory::store::memory::InMemoryInventoryStore;
+    use crate::inventory::InventoryStore;
+    use crate::kitchen::store::memory::InMemoryKitchenStore;
+    use crate::kitchen::{KitchenSettings, KitchenStore};
+    use crate::orders::stock::memory::InMemoryStock;
+    use crate::orders::store::memory::InMemoryOrderStore;
+    use crate::orders::{OrderStore, StockReservations};
+    use crate::metrics::MetricsSources;
+    use crate::readiness::Readiness;
+    use crate::suppliers::store::memory::InMemorySupplierStore;
+    use crate::suppliers::SupplierStore;
+    use super::products::UploadLimit;
+    use super::webhooks::WhatsAppVerifyToken;
+    use super::*;
+    
+    /// Sem rota correspondente a aplicação responde 418, distinguível do 404 dos handlers
+    const UNROUTED: StatusCode = StatusCode::IM_A_TEAPOT;
+    
+    #[actix_web::test]
+    async fn test_every_route_is_mounted() {
+        let admin = user(UserRole::Admin);
+        let auth = AuthService::new(Arc::new(InMemoryAuthStore::default().with_user(admin.clone())), &config());
+        let token = format!("Bearer {}", auth.login(&admin.email, "fermento123").await.unwrap().access_token);
+        
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(auth))
+                .app_data(web::Data::from(Arc::new(InMemoryProductStore::default()) as Arc<dyn ProductStore>))
+                .app_data(web::Data::from(Arc::new(InMemoryOrderStore::default()) as Arc<dyn OrderStore>))
+                .app_data(web::Data::from(Arc::new(InMemoryCustomerStore::default()) as Arc<dyn CustomerStore>))
+                .app_data(web::Data::from(Arc::new(InMemorySupplierStore::default()) as Arc<dyn SupplierStore>))
+                .app_data(web::Data::from(Arc::new(InMemoryInventoryStore::default()) as Arc<dyn InventoryStore>))
+                .app_data(web::Data::from(Arc::new(InMemoryStock::default()) as Arc<dyn StockReservations>))
+                .app_data(web::Data::new(ImageStorage::new(std::env::temp_dir(), "/media/products/")))
+                .app_data(web::Data::new(UploadLimit(1024)))
+                .app_data(web::Data::new(WhatsAppVerifyToken(None)))
+                .app_data(web::Data::new(Readiness::new(std::time::Duration::from_secs(1))))
+                .app_data(web::Data::new(MetricsSources::default()))
+                .app_data(web::Data::new(crate::events::EventBus::local()))
+                .app_data(web::Data::new(AvilaLogger::disabled()))
+                .app_data(web::Data::from(Arc::new(InMemoryKitchenStore::default()) as Arc<dyn KitchenStore>))
+                .app_data(web::Data::new(KitchenSettings { default_preparation_minutes: 20, due_soon_minutes: 5 }))
+                .app_data(web::Data::from(Arc::new(InMemoryFiscalStore::default()) as Arc<dyn FiscalStore>))
+                .configure(configure)
+                .default_service(web::to(|| async { HttpResponse::build(UNROUTED).finish() })),
+        )
+        .await;
+        
+        let id = Uuid::new_v4();
+        let routes = [
+            ("GET", "/health".to_string()),
+            ("GET", "/health/ready".to_string()),
+            ("GET", "/metrics".to_string()),
+            ("POST", "/webhooks/ifood".to_string()),
+            ("GET", "/webhooks/whatsapp".to_string()),
+            ("POST", "/webhooks/whatsapp".to_string()),
+            ("GET", "/api/v1/openapi.json".to_string()),
+            ("GET", "/api/v1/docs/".to_string()),
+            ("POST", "/api/v1/auth/login".to_string()),
+            ("POST", "/api/v1/auth/refresh".to_string()),
+            ("POST", "/api/v1/auth/logout".to_string()),
+            ("POST", "/api/v1/auth/change-password".to_string()),
+            ("GET", "/api/v1/auth/me".to_string()),
+            ("GET", "/api/v1/products".to_string()),
+            ("POST", "/api/v1/products".to_string()),
+            ("GET", "/api/v1/products/low-stock".to_string()),
+            ("GET", "/api/v1/products/barcode/7891234567895".to_string()),
+            ("GET", format!("/api/v1/products/{id}")),
+            ("PUT", format!("/api/v1/products/{id}")),
+            ("DELETE", format!("/api/v1/products/{id}")),
+            ("POST", format!("/api/v1/products/{id}/image")),
+            ("GET", "/api/v1/orders".to_string()),
+            ("POST", "/api/v1/orders".to_string()),
+            ("GET", format!("/api/v1/orders/{id}")),
+            ("POST", format!("/api/v1/orders/{id}/items")),
+            ("DELETE", format!("/api/v1/orders/{id}/items/{id}")),
+            ("PUT", format!("/api/v1/orders/{id}/discount")),
+            ("POST", format!("/api/v1/orders/{id}/confirm")),
+            ("POST", format!("/api/v1/orders/{id}/start-preparation")),
+            ("POST", format!("/api/v1/orders/{id}/ready")),
+            ("POST", format!("/api/v1/orders/{id}/dispatch")),
+            ("POST", format!("/api/v1/orders/{id}/complete")),
+            ("POST", format!("/api/v1/orders/{id}/cancel")),
+            ("GET", "/api/v1/customers".to_string()),
+            ("POST", "/api/v1/customers".to_string()),
+            ("GET", format!("/api/v1/customers/{id}")),
+            ("PUT", format!("/api/v1/customers/{id}")),
+            ("DELETE", format!("/api/v1/customers/{id}")),
+            ("GET", format!("/api/v1/customers/{id}/consents")),
+            ("PUT", format!("/api/v1/customers/{id}/consents")),
+            ("GET", format!("/api/v1/customers/{id}/export")),
+            ("POST", format!("/api/v1/customers/{id}/anonymize")),
+            ("GET", format!("/api/v1/inventory/availability?product_ids={id}")),
+            ("GET", "/api/v1/inventory/alerts".to_string()),
+            ("GET", format!("/api/v1/inventory/{id}/movements")),
+            ("POST", format!("/api/v1/inventory/{id}/entries")),
+            ("POST", format!("/api/v1/inventory/{id}/withdrawals")),
+            ("POST", format!("/api/v1/inventory/{id}/reservations")),
+            ("POST", format!("/api/v1/inventory/{id}/releases")),
+            ("POST", format!("/api/v1/inventory/{id}/adjustments")),
+            ("POST", format!("/api/v1/inventory/{id}/losses")),
+            ("GET", "/api/v1/suppliers".to_string()),
+            ("POST", "/api/v1/suppliers".to_string()),
+            ("GET", "/api/v1/suppliers/replenishment".to_string()),
+            ("GET", format!("/api/v1/suppliers/replenishment/{id}")),
+            ("GET", "/api/v1/suppliers/by-cnpj/11222333000181".to_string()),
+            ("GET", format!("/api/v1/suppliers/{id}")),
+            ("PUT", format!("/api/v1/suppliers/{id}")),
+            ("DELETE", format!("/api/v1/suppliers/{id}")),
+            ("GET", format!("/api/v1/suppliers/{id}/products")),
+            ("PUT", format!("/api/v1/suppliers/{id}/products/{id}")),
+            ("DELETE", format!("/api/v1/suppliers/{id}/products/{id}")),
+            ("GET", "/api/v1/kitchen/stations".to_string()),
+            ("GET", "/api/v1/kitchen/stations/Bakery/queue".to_string()),
+            ("POST", format!("/api/v1/kitchen/stations/Bakery/tickets/{id}/bump")),
+            ("POST", format!("/api/v1/kitchen/stations/Bakery/tickets/{id}/recall")),
+            ("POST", "/api/v1/fiscal/entries".to_string()),
+            ("GET", "/api/v1/fiscal/summary?year=2025&month=1".to_string()),
+            ("GET", "/api/v1/fiscal/summary/csv?year=2025&month=1".to_string()),
+            ("GET", "/api/v1/fiscal/sped?year=2025&month=1".to_string()),
+            ("GET", "/api/v1/events/stream".to_string()),
+            ("GET", "/api/v1/events/ws".to_string()),
+        ];
+        
+        for (method, uri) in &routes {
+            let req = test::TestRequest::default()
+                .method(method.parse().unwrap())
+                .uri(uri)
+                .insert_header(("Authorization", token.clone()))
+                .set_json(json!({}))
+                .to_request();
+            let status = test::call_service(&app, req).await.status();
+            assert!(status != UNROUTED && status != StatusCode::METHOD_NOT_ALLOWED, "{method} {uri} -> {status}");
+        }
+        
+        // Respostas da v1 dizem a versão; sem prefixo não há rota
+        let req = test::TestRequest::get().uri("/api/v1/products").insert_header(("Authorization", token.clone())).to_request();
+        let res = test::call_service(&app, req).await;
+        assert_eq!(res.status(), 200);
+        assert_eq!(res.headers().get(API_VERSION_HEADER).unwrap(), "1");
+        let req = test::TestRequest::get().uri("/products").insert_header(("Authorization", token)).to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), UNROUTED);
+        
+        let req = test::TestRequest::get().uri("/api/v1/orders").to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 401);
+    }
+}
@@ -1,5 +1,477 @@
-use actix_web::web;
+use actix_web::{http::header, web, HttpRequest, HttpResponse};
+use utoipa::OpenApi;
+use uuid::Uuid;
+use delpopolo_core::CoreError;
+use delpopolo_domain::{Money, Order, OrderItem, OrderStatus, Permission, Product};
+use crate::auth::RequirePermission;
+use crate::avila_logger::AvilaLogger;
+use crate::catalog::ProductStore;
+use crate::conditional::{conditional_json, entity_tag};
+use crate::dto::orders::{
+    CancelOrderRequest, CreateOrderRequest, DiscountRequest, OrderCursorToken, OrderItemRequest, OrderListQuery,
+    OrderResponse,
+};
+use crate::error::ApiError;
+use crate::events::{Event, EventBus};
+use crate::metrics::METRICS;
+use crate::orders::stock::{consume_items, release_items, reserve_items};
+use crate::orders::{OrderStore, StockReservations};
+use crate::pagination::{paginate, Page};
+use crate::validation::validate;
 
-pub fn configure(_cfg: &mut web::ServiceConfig) {
-    // TODO: Implementar rotas de pedidos
+#[derive(OpenApi)]
+#[openapi(paths(
+    list_orders, create_order, get_order, add_item, remove_item, apply_discount,
+    confirm, start_preparation, mark_ready, dispatch, complete, cancel,
+))]
+pub struct OrdersApi;
+
+/// Leitura já é exigida no escopo `/orders`; cada alteração exige a permissão de quem a executa
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    let write = || RequirePermission(Permission::OrdersWrite);
+    let kitchen = || RequirePermission(Permission::KitchenOperate);
+    
+    cfg.service(
+        web::resource("")
+            .route(web::get().to(list_orders))
+            .route(web::post().to(create_order).wrap(write())),
+    )
+    .service(web::resource("/{id}").route(web::get().to(get_order)))
+    .service(web::resource("/{id}/items").route(web::post().to(add_item).wrap(write())))
+    .service(web::resource("/{id}/items/{item_id}").route(web::delete().to(remove_item).wrap(write())))
+    .service(web::resource("/{id}/discount").route(web::put().to(apply_discount).wrap(write())))
+    .service(web::resource("/{id}/confirm").route(web::post().to(confirm).wrap(write())))
+    .service(web::resource("/{id}/start-preparation").route(web::post().to(start_preparation).wrap(kitchen())))
+    .service(web::resource("/{id}/ready").route(web::post().to(mark_ready).wrap(kitchen())))
+    .service(
+        web::resource("/{id}/dispatch")
+            .route(web::post().to(dispatch).wrap(RequirePermission(Permission::DeliveriesManage))),
+    )
+    .service(web::resource("/{id}/complete").route(web::post().to(complete).wrap(write())))
+    .service(web::resource("/{id}/cancel").route(web::post().to(cancel).wrap(write())));
+}
+
+async fn load(orders: &dyn OrderStore, id: Uuid) -> Result<Order, CoreError> {
+    orders
+        .find(id)
+        .await?
+        .ok_or_else(|| CoreError::not_found(format!("Pedido {} não encontrado", id)))
+}
+
+/// Item com o preço vigente do catálogo; produto precisa existir e estar ativo
+async fn priced_item(products: &dyn ProductStore, request: &OrderItemRequest) -> Result<OrderItem, CoreError> {
+    let product: Product = products
+        .find(request.product_id)
+        .await?
+        .ok_or_else(|| CoreError::validation(format!("Produto {} não existe", request.product_id)))?;
+    
+    if !product.is_active {
+        return Err(CoreError::validation(format!("Produto {} está inativo", product.name)));
+    }
+    
+    Ok(OrderItem::from_product(&product, request.quantity, request.notes.clone()))
+}
+
+fn order_json(order: &Order) -> HttpResponse {
+    HttpResponse::Ok()
+        .insert_header(header::ETag(entity_tag(order.id, order.updated_at)))
+        .json(OrderResponse::from(order))
+}
+
+#[utoipa::path(
+    get, path = "", tag = "orders",
+    params(OrderListQuery),
+    responses((status = 200, body = Page<OrderResponse>), (status = 422)),
+)]
+async fn list_orders(
+    orders: web::Data<dyn OrderStore>,
+    query: web::Query<OrderListQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let mut order_query = query.to_query()?;
+    let limit = order_query.limit;
+    order_query.limit += 1;
+    
+    let found = orders.list(&order_query).await?;
+    let page = paginate(found, limit, |o| OrderCursorToken { t: o.created_at, id: o.id });
+    
+    Ok(HttpResponse::Ok().json(Page {
+        items: page.items.iter().map(OrderResponse::from).collect::<Vec<_>>(),
+        next_cursor: page.next_cursor,
+    }))
+}
+
+#[utoipa::path(
+    get, path = "/{id}", tag = "orders",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = OrderResponse), (status = 304, description = "Não modificado (`If-None-Match`)"), (status = 404)),
+)]
+async fn get_order(
+    req: HttpRequest,
+    orders: web::Data<dyn OrderStore>,
+    id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let order = load(orders.get_ref(), *id).await?;
+    Ok(conditional_json(&req, entity_tag(order.id, order.updated_at), &OrderResponse::from(&order)))
+}
+
+/// Preços vêm do catálogo e o estoque dos itens fica reservado até a conclusão ou o cancelamento
+#[utoipa::path(
+    post, path = "", tag = "orders",
+    request_body = CreateOrderRequest,
+    responses((status = 201, body = OrderResponse), (status = 409, description = "Estoque insuficiente"), (status = 422)),
+)]
+async fn create_order(
+    req: HttpRequest,
+    orders: web::Data<dyn OrderStore>,
+    products: web::Data<dyn ProductStore>,
+    stock: web::Data<dyn StockReservations>,
+    events: web::Data<EventBus>,
+    logger: web::Data<AvilaLogger>,
+    body: web::Json<CreateOrderRequest>,
+) -> Result<HttpResponse, ApiError> {
+    validate(&*body)?;
+    let body = body.into_inner();
+    
+    let mut order = Order::new(body.source);
+    for request in &body.items {
+        order.add_item(priced_item(products.get_ref(), request).await?);
+    }
+    order.customer_id = body.customer_id;
+    order.customer_name = body.customer_name;
+    order.customer_cpf = body.customer_cpf;
+    order.delivery_fee = Money::brl(body.delivery_fee);
+    order.delivery_address = body.delivery_address;
+    order.delivery_time = body.delivery_time;
+    order.table_number = body.table_number;
+    order.payment_method = body.payment_method;
+    order.notes = body.notes;
+    order.recalculate_totals();
+    
+    reserve_items(stock.get_ref(), order.id, &order.items).await?;
+    if let Err(e) = orders.insert(&order).await {
+        release_items(stock.get_ref(), &order.items).await;
+        return Err(e.into());
+    }
+    tracing::info!("Order {} created with {} items", order.order_number, order.items.len());
+    METRICS.order_reached(&order);
+    logger.log_business_event("order_created", order_event(&order));
+    events.publish(Event::order(&order, true)).await;
+    
+    Ok(HttpResponse::Created()
+        .insert_header((header::LOCATION, format!("{}/{}", req.path().trim_end_matches('/'), order.id)))
+        .insert_header(header::ETag(entity_tag(order.id, order.updated_at)))
+        .json(OrderResponse::from(&order)))
+}
+
+#[utoipa::path(
+    post, path = "/{id}/items", tag = "orders",
+    params(("id" = Uuid, Path)),
+    request_body = OrderItemRequest,
+    responses((status = 200, body = OrderResponse), (status = 404), (status = 409, description = "Pedido não editável ou estoque insuficiente"), (status = 422)),
+)]
+async fn add_item(
+    orders: web::Data<dyn OrderStore>,
+    products: web::Data<dyn ProductStore>,
+    stock: web::Data<dyn StockReservations>,
+    id: web::Path<Uuid>,
+    body: web::Json<OrderItemRequest>,
+) -> Result<HttpResponse, ApiError> {
+    validate(&*body)?;
+    let mut order = load(orders.get_ref(), *id).await?;
+    order.ensure_editable()?;
+    
+    let item = priced_item(products.get_ref(), &body).await?;
+    let reserved = std::slice::from_ref(&item);
+    reserve_items(stock.get_ref(), order.id, reserved).await?;
+    
+    let previous = order.updated_at;
+    order.add_item(item.clone());
+    if let Err(e) = orders.update(&order, previous).await {
+        release_items(stock.get_ref(), reserved).await;
+        return Err(e.into());
+    }
+    
+    Ok(order_json(&order))
+}
+
+#[utoipa::path(
+    delete, path = "/{id}/items/{item_id}", tag = "orders",
+    params(("id" = Uuid, Path), ("item_id" = Uuid, Path)),
+    responses((status = 200, body = OrderResponse), (status = 404), (status = 409, description = "Pedido não editável")),
+)]
+async fn remove_item(
+    orders: web::Data<dyn OrderStore>,
+    stock: web::Data<dyn StockReservations>,
+    path: web::Path<(Uuid, Uuid)>,
+) -> Result<HttpResponse, ApiError> {
+    let (id, item_id) = path.into_inner();
+    let mut order = load(orders.get_ref(), id).await?;
+    order.ensure_editable()?;
+    
+    let previous = order.updated_at;
+    let item = order
+        .remove_item(item_id)
+        .ok_or_else(|| CoreError::not_found(format!("Item {} não encontrado no pedido", item_id)))?;
+    orders.update(&order, previous).await?;
+    release_items(stock.get_ref(), &[item]).await;
+    
+    Ok(order_json(&order))
+}
+
+#[utoipa::path(
+    put, path = "/{id}/discount", tag = "orders",
+    params(("id" = Uuid, Path)),
+    request_body = DiscountRequest,
+    responses((status = 200, body = OrderResponse), (status = 404), (status = 409, description = "Pedido não editável"), (status = 422)),
+)]
+async fn apply_discount(
+    orders: web::Data<dyn OrderStore>,
+    id: web::Path<Uuid>,
+    body: web::Json<DiscountRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let mut order = load(orders.get_ref(), *id).await?;
+    
+    let previous = order.updated_at;
+    order.apply_discount(Money::brl(body.amount))?;
+    orders.update(&order, previous).await?;
+    
+    Ok(order_json(&order))
+}
+
+/// Dados do pedido enviados ao Avila como evento de negócio
+fn order_event(order: &Order) -> serde_json::Value {
+    serde_json::json!({
+        "order_id": order.id,
+        "order_number": order.order_number,
+        "source": order.source,
+        "status": order.status,
+        "total": order.total.amount,
+    })
+}
+
+/// Aplica a transição e, depois de gravada, libera ou baixa o estoque reservado
+async fn transition(
+    orders: &dyn OrderStore,
+    stock: &dyn StockReservations,
+    events: &EventBus,
+    logger: &AvilaLogger,
+    id: Uuid,
+    next: OrderStatus,
+    reason: Option<String>,
+) -> Result<HttpResponse, ApiError> {
+    let mut order = load(orders, id).await?;
+    
+    let previous = order.updated_at;
+    order.transition_to(next, reason)?;
+    orders.update(&order, previous).await?;
+    
+    match next {
+        OrderStatus::Cancelled => release_items(stock, &order.items).await,
+        OrderStatus::Completed => consume_items(stock, order.id, &order.items).await,
+        _ => {}
+    }
+    tracing::info!("Order {} moved to {:?}", order.order_number, order.status);
+    METRICS.order_reached(&order);
+    logger.log_business_event("order_status_changed", order_event(&order));
+    events.publish(Event::order(&order, false)).await;
+    
+    Ok(order_json(&order))
+}
+
+#[utoipa::path(
+    post, path = "/{id}/confirm", tag = "orders",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = OrderResponse), (status = 404), (status = 409, description = "Transição inválida para o status atual")),
+)]
+async fn confirm(
+    orders: web::Data<dyn OrderStore>,
+    stock: web::Data<dyn StockReservations>,
+    events: web::Data<EventBus>,
+    logger: web::Data<AvilaLogger>,
+    id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    transition(orders.get_ref(), stock.get_ref(), &events, &logger, *id, OrderStatus::Confirmed, None).await
+}
+
+#[utoipa::path(
+    post, path = "/{id}/start-preparation", tag = "orders",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = OrderResponse), (status = 404), (status = 409, description = "Transição inválida para o status atual")),
+)]
+async fn start_preparation(
+    orders: web::Data<dyn OrderStore>,
+    stock: web::Data<dyn StockReservations>,
+    events: web::Data<EventBus>,
+    logger: web::Data<AvilaLogger>,
+    id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    transition(orders.get_ref(), stock.get_ref(), &events, &logger, *id, OrderStatus::Preparing, None).await
+}
+
+#[utoipa::path(
+    post, path = "/{id}/ready", tag = "orders",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = OrderResponse), (status = 404), (status = 409, description = "Transição inválida para o status atual")),
+)]
+async fn mark_ready(
+    orders: web::Data<dyn OrderStore>,
+    stock: web::Data<dyn StockReservations>,
+    events: web::Data<EventBus>,
+    logger: web::Data<AvilaLogger>,
+    id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    transition(orders.get_ref(), stock.get_ref(), &events, &logger, *id, OrderStatus::Ready, None).await
+}
+
+#[utoipa::path(
+    post, path = "/{id}/dispatch", tag = "orders",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = OrderResponse), (status = 404), (status = 409, description = "Transição inválida para o status atual")),
+)]
+async fn dispatch(
+    orders: web::Data<dyn OrderStore>,
+    stock: web::Data<dyn StockReservations>,
+    events: web::Data<EventBus>,
+    logger: web::Data<AvilaLogger>,
+    id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    transition(orders.get_ref(), stock.get_ref(), &events, &logger, *id, OrderStatus::InDelivery, None).await
+}
+
+/// Baixa o estoque reservado
+#[utoipa::path(
+    post, path = "/{id}/complete", tag = "orders",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = OrderResponse), (status = 404), (status = 409, description = "Transição inválida para o status atual")),
+)]
+async fn complete(
+    orders: web::Data<dyn OrderStore>,
+    stock: web::Data<dyn StockReservations>,
+    events: web::Data<EventBus>,
+    logger: web::Data<AvilaLogger>,
+    id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    transition(orders.get_ref(), stock.get_ref(), &events, &logger, *id, OrderStatus::Completed, None).await
+}
+
+/// Libera o estoque reservado
+#[utoipa::path(
+    post, path = "/{id}/cancel", tag = "orders",
+    params(("id" = Uuid, Path)),
+    request_body = CancelOrderRequest,
+    responses((status = 200, body = OrderResponse), (status = 404), (status = 409, description = "Transição inválida para o status atual"), (status = 422)),
+)]
+async fn cancel(
+    orders: web::Data<dyn OrderStore>,
+    stock: web::Data<dyn StockReservations>,
+    events: web::Data<EventBus>,
+    logger: web::Data<AvilaLogger>,
+    id: web::Path<Uuid>,
+    body: web::Json<CancelOrderRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let reason = body.into_inner().reason;
+    transition(orders.get_ref(), stock.get_ref(), &events, &logger, *id, OrderStatus::Cancelled, Some(reason)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use actix_web::{test, App};
+    use serde_json::{json, Value};
+    use delpopolo_domain::{ProductCategory, UnitOfMeasure, UserRole};
+    use crate::auth::service::tests::{config, user};
+    use crate::auth::store::memory::InMemoryAuthStore;
+    use crate::auth::AuthService;
+    use crate::catalog::store::memory::InMemoryProductStore;
+    use crate::orders::stock::memory::InMemoryStock;
+    use crate::orders::store::memory::InMemoryOrderStore;
+    use super::*;
+    
+    fn product(name: &str, price: f64) -> Product {
+        Product::new(name.to_string(), ProductCategory::Bread, UnitOfMeasure::Unit, Money::brl(price), Money::brl(price / 2.0))
+    }
+    
+    async fn token(auth: &AuthService, email: &str) -> String {
+        format!("Bearer {}", auth.login(email, "fermento123").await.unwrap().access_token)
+    }
+    
+    #[actix_web::test]
+    async fn test_order_lifecycle_with_stock_reservation() {
+        let cashier = user(UserRole::Cashier);
+        let mut cook = user(UserRole::Kitchen);
+        cook.email = "cozinha@delpopolo.com.br".to_string();
+        let auth = AuthService::new(
+            Arc::new(InMemoryAuthStore::default().with_user(cashier.clone()).with_user(cook.clone())),
+            &config(),
+        );
+        let (cashier_token, cook_token) = (token(&auth, &cashier.email).await, token(&auth, &cook.email).await);
+        
+        let (baguete, sonho) = (product("Baguete", 9.0), product("Sonho", 6.5));
+        let stock = Arc::new(InMemoryStock::default().with_stock(baguete.id, 10.0).with_stock(sonho.id, 1.0));
+        let products: Arc<dyn ProductStore> = Arc::new(InMemoryProductStore::default().with_products(vec![baguete.clone(), sonho.clone()]));
+        let orders: Arc<dyn OrderStore> = Arc::new(InMemoryOrderStore::default());
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(auth))
+                .app_data(web::Data::from(products))
+                .app_data(web::Data::from(orders))
+                .app_data(web::Data::from(stock.clone() as Arc<dyn StockReservations>))
+                .app_data(web::Data::new(EventBus::local()))
+                .app_data(web::Data::new(AvilaLogger::disabled()))
+                .service(
+                    web::scope("/orders")
+                        .wrap(RequirePermission(Permission::OrdersRead))
+                        .configure(configure),
+                ),
+        )
+        .await;
+        let post = |uri: &str, token: &str, body: Value| {
+            test::TestRequest::post().uri(uri).insert_header(("Authorization", token.to_string())).set_json(body).to_request()
+        };
+        
+        // Sem estoque suficiente nada fica reservado
+        let body = json!({"source": "InStore", "items": [{"product_id": baguete.id, "quantity": 2}, {"product_id": sonho.id, "quantity": 3}]});
+        assert_eq!(test::call_service(&app, post("/orders", &cashier_token, body)).await.status(), 409);
+        assert_eq!(stock.level(baguete.id), (10.0, 0.0));
+        
+        // Cozinha não abre pedido; preço vem do catálogo
+        let body = json!({"source": "InStore", "items": [{"product_id": baguete.id, "quantity": 2, "unit_price": 0.01}]});
+        assert_eq!(test::call_service(&app, post("/orders", &cook_token, body.clone())).await.status(), 403);
+        let order: Value = test::call_and_read_body_json(&app, post("/orders", &cashier_token, body)).await;
+        assert_eq!(order["total"], 18.0);
+        assert_eq!(stock.level(baguete.id), (10.0, 2.0));
+        let uri = format!("/orders/{}", order["id"].as_str().unwrap());
+        
+        // Itens e desconto enquanto o pedido está aberto
+        let item = json!({"product_id": sonho.id, "quantity": 1});
+        let order: Value = test::call_and_read_body_json(&app, post(&format!("{}/items", uri), &cashier_token, item)).await;
+        assert_eq!(order["total"], 24.5);
+        let item_uri = format!("{}/items/{}", uri, order["items"][1]["id"].as_str().unwrap());
+        let req = test::TestRequest::delete().uri(&item_uri).insert_header(("Authorization", cashier_token.clone())).to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 200);
+        assert_eq!(stock.level(sonho.id), (1.0, 0.0));
+        let req = test::TestRequest::put().uri(&format!("{}/discount", uri)).insert_header(("Authorization", cashier_token.clone())).set_json(json!({"amount": 3})).to_request();
+        let order: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(order["total"], 15.0);
+        
+        // Produção pela cozinha, conclusão pelo caixa
+        assert_eq!(test::call_service(&app, post(&format!("{}/ready", uri), &cook_token, json!({}))).await.status(), 409);
+        for step in ["start-preparation", "ready"] {
+            assert_eq!(test::call_service(&app, post(&format!("{}/{}", uri, step), &cook_token, json!({}))).await.status(), 200);
+        }
+        let req = test::TestRequest::put().uri(&format!("{}/discount", uri)).insert_header(("Authorization", cashier_token.clone())).set_json(json!({"amount": 1})).to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 409);
+        let order: Value = test::call_and_read_body_json(&app, post(&format!("{}/complete", uri), &cashier_token, json!({}))).await;
+        assert_eq!(order["status"], "Completed");
+        assert_eq!(stock.level(baguete.id), (8.0, 0.0));
+        assert_eq!(test::call_service(&app, post(&format!("{}/cancel", uri), &cashier_token, json!({"reason": "erro"}))).await.status(), 409);
+        
+        // Listagem por status
+        let req = test::TestRequest::get().uri("/orders?status=Completed").insert_header(("Authorization", cook_token.clone())).to_request();
+        let page: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(page["items"].as_array().unwrap().len(), 1);
+        let req = test::TestRequest::get().uri("/orders?status=Pending&source=IFood").insert_header(("Authorization", cook_token)).to_request();
+        let page: Value = test::call_and_read_body_json(&app, req).await;
+        assert!(page["items"].as_array().unwrap().is_empty());
+    }
 }
@@ -1,5 +1,386 @@
-use actix_web::web;
+use actix_multipart::Multipart;
+use actix_web::{http::header, web, HttpRequest, HttpResponse};
+use futures_util::StreamExt;
+use serde_json::json;
+use utoipa::OpenApi;
+use uuid::Uuid;
+use delpopolo_core::CoreError;
+use delpopolo_domain::{Permission, Product};
+use crate::auth::RequirePermission;
+use crate::avila_logger::AvilaLogger;
+use crate::catalog::{ImageStorage, ProductStore, StoredImage};
+use crate::conditional::{conditional_json, entity_tag};
+use crate::dto::products::{ProductCursorToken, ProductListQuery, ProductRequest, ProductResponse};
+use crate::error::ApiError;
+use crate::pagination::{paginate, Page};
+use crate::validation::validate;
 
-pub fn configure(_cfg: &mut web::ServiceConfig) {
-    // TODO: Implementar rotas de produtos
+#[derive(OpenApi)]
+#[openapi(paths(list_products, create_product, low_stock, find_by_barcode, get_product, update_product, delete_product, upload_image))]
+pub struct ProductsApi;
+
+/// Leitura já é exigida no escopo `/products`; escrita em cada rota
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    let write = || RequirePermission(Permission::ProductsWrite);
+    
+    cfg.service(
+        web::resource("")
+            .route(web::get().to(list_products))
+            .route(web::post().to(create_product).wrap(write())),
+    )
+    .service(web::resource("/low-stock").route(web::get().to(low_stock)))
+    .service(web::resource("/barcode/{barcode}").route(web::get().to(find_by_barcode)))
+    .service(
+        web::resource("/{id}")
+            .route(web::get().to(get_product))
+            .route(web::put().to(update_product).wrap(write()))
+            .route(web::delete().to(delete_product).wrap(write())),
+    )
+    .service(web::resource("/{id}/image").route(web::post().to(upload_image).wrap(write())));
+}
+
+async fn load(store: &dyn ProductStore, id: Uuid) -> Result<Product, CoreError> {
+    store
+        .find(id)
+        .await?
+        .ok_or_else(|| CoreError::not_found(format!("Produto {} não encontrado", id)))
+}
+
+/// Código de barras é único no catálogo
+async fn ensure_barcode_free(store: &dyn ProductStore, product: &Product) -> Result<(), CoreError> {
+    if let Some(barcode) = &product.barcode {
+        if let Some(other) = store.find_by_barcode(barcode).await? {
+            if other.id != product.id {
+                return Err(CoreError::conflict(format!("Código de barras {} já cadastrado em {}", barcode, other.name)));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[utoipa::path(
+    get, path = "", tag = "products",
+    params(ProductListQuery),
+    responses((status = 200, body = Page<ProductResponse>)),
+)]
+async fn list_products(
+    store: web::Data<dyn ProductStore>,
+    query: web::Query<ProductListQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let mut product_query = query.to_query()?;
+    let limit = product_query.limit;
+    product_query.limit += 1;
+    
+    let products = store.list(&product_query).await?;
+    let page = paginate(products, limit, |p| {
+        let cursor = product_query.cursor_for(p);
+        ProductCursorToken { v: cursor.value, id: cursor.id }
+    });
+    
+    Ok(HttpResponse::Ok().json(Page {
+        items: page.items.iter().map(ProductResponse::from).collect::<Vec<_>>(),
+        next_cursor: page.next_cursor,
+    }))
+}
+
+/// Produtos ativos abaixo do estoque mínimo
+#[utoipa::path(get, path = "/low-stock", tag = "products", responses((status = 200, body = Vec<ProductResponse>)))]
+async fn low_stock(store: web::Data<dyn ProductStore>) -> Result<HttpResponse, ApiError> {
+    let products = store.low_stock().await?;
+    Ok(HttpResponse::Ok().json(products.iter().map(ProductResponse::from).collect::<Vec<_>>()))
+}
+
+#[utoipa::path(
+    get, path = "/barcode/{barcode}", tag = "products",
+    params(("barcode" = String, Path, description = "GTIN-8/12/13/14")),
+    responses((status = 200, body = ProductResponse), (status = 304, description = "Não modificado (`If-None-Match`)"), (status = 404)),
+)]
+async fn find_by_barcode(
+    req: HttpRequest,
+    store: web::Data<dyn ProductStore>,
+    barcode: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let product = store
+        .find_by_barcode(&barcode)
+        .await?
+        .ok_or_else(|| CoreError::not_found(format!("Nenhum produto com código de barras {}", barcode)))?;
+    
+    Ok(conditional_json(&req, entity_tag(product.id, product.updated_at), &ProductResponse::from(&product)))
+}
+
+#[utoipa::path(
+    get, path = "/{id}", tag = "products",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = ProductResponse), (status = 304, description = "Não modificado (`If-None-Match`)"), (status = 404)),
+)]
+async fn get_product(
+    req: HttpRequest,
+    store: web::Data<dyn ProductStore>,
+    id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let product = load(store.get_ref(), *id).await?;
+    Ok(conditional_json(&req, entity_tag(product.id, product.updated_at), &ProductResponse::from(&product)))
+}
+
+#[utoipa::path(
+    post, path = "", tag = "products",
+    request_body = ProductRequest,
+    responses((status = 201, body = ProductResponse), (status = 409, description = "Código de barras já cadastrado"), (status = 422)),
+)]
+async fn create_product(
+    req: HttpRequest,
+    store: web::Data<dyn ProductStore>,
+    logger: web::Data<AvilaLogger>,
+    body: web::Json<ProductRequest>,
+) -> Result<HttpResponse, ApiError> {
+    validate(&*body)?;
+    let product = body.into_inner().into_product();
+    ensure_barcode_free(store.get_ref(), &product).await?;
+    
+    store.insert(&product).await?;
+    tracing::info!("Product {} created ({})", product.id, product.sku);
+    logger.log_operation("products", "create", true, json!({"id": product.id, "sku": product.sku}));
+    
+    Ok(HttpResponse::Created()
+        .insert_header((header::LOCATION, format!("{}/{}", req.path().trim_end_matches('/'), product.id)))
+        .insert_header(header::ETag(entity_tag(product.id, product.updated_at)))
+        .json(ProductResponse::from(&product)))
+}
+
+#[utoipa::path(
+    put, path = "/{id}", tag = "products",
+    params(("id" = Uuid, Path)),
+    request_body = ProductRequest,
+    responses((status = 200, body = ProductResponse), (status = 404), (status = 409, description = "Código de barras já cadastrado"), (status = 422)),
+)]
+async fn update_product(
+    store: web::Data<dyn ProductStore>,
+    logger: web::Data<AvilaLogger>,
+    id: web::Path<Uuid>,
+    body: web::Json<ProductRequest>,
+) -> Result<HttpResponse, ApiError> {
+    validate(&*body)?;
+    let mut product = load(store.get_ref(), *id).await?;
+    body.into_inner().apply_to(&mut product);
+    ensure_barcode_free(store.get_ref(), &product).await?;
+    
+    store.update(&product).await?;
+    logger.log_operation("products", "update", true, json!({"id": product.id, "sku": product.sku}));
+    
+    Ok(HttpResponse::Ok()
+        .insert_header(header::ETag(entity_tag(product.id, product.updated_at)))
+        .json(ProductResponse::from(&product)))
+}
+
+#[utoipa::path(
+    delete, path = "/{id}", tag = "products",
+    params(("id" = Uuid, Path)),
+    responses((status = 204, description = "Produto desativado; continua acessível pelo id"), (status = 404)),
+)]
+async fn delete_product(
+    store: web::Data<dyn ProductStore>,
+    logger: web::Data<AvilaLogger>,
+    id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let product = load(store.get_ref(), *id).await?;
+    store.delete(product.id).await?;
+    tracing::info!("Product {} deactivated", product.id);
+    logger.log_operation("products", "delete", true, json!({"id": product.id, "sku": product.sku}));
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// `multipart/form-data` com o arquivo no campo `image`
+#[utoipa::path(
+    post, path = "/{id}/image", tag = "products",
+    params(("id" = Uuid, Path)),
+    request_body(content = ImageUpload, content_type = "multipart/form-data"),
+    responses((status = 200, body = StoredImage), (status = 404), (status = 413, description = "Imagem acima do limite"), (status = 422, description = "Arquivo ausente ou inválido")),
+)]
+async fn upload_image(
+    store: web::Data<dyn ProductStore>,
+    images: web::Data<ImageStorage>,
+    limits: web::Data<UploadLimit>,
+    id: web::Path<Uuid>,
+    mut payload: Multipart,
+) -> Result<HttpResponse, ApiError> {
+    let mut product = load(store.get_ref(), *id).await?;
+    
+    let mut bytes = None;
+    while let Some(field) = payload.next().await {
+        let mut field = field.map_err(|e| CoreError::validation(format!("Upload inválido: {}", e)))?;
+        if field.name() != Some("image") {
+            continue;
+        }
+        
+        let mut data = Vec::new();
+        while let Some(chunk) = field.next().await {
+            let chunk = chunk.map_err(|e| CoreError::validation(format!("Upload inválido: {}", e)))?;
+            if data.len() + chunk.len() > limits.0 {
+                return Err(CoreError::payload_too_large(format!("Imagem excede o limite de {} bytes", limits.0)).into());
+            }
+            data.extend_from_slice(&chunk);
+        }
+        bytes = Some(data);
+        break;
+    }
+    
+    let bytes = bytes.ok_or_else(|| CoreError::validation("Campo 'image' ausente no formulário"))?;
+    let stored = images.store(product.id, bytes).await?;
+    
+    product.set_image(stored.url.clone());
+    store.update(&product).await?;
+    
+    Ok(HttpResponse::Ok()
+        .insert_header(header::ETag(entity_tag(product.id, product.updated_at)))
+        .json(stored))
+}
+
+/// Formulário do upload, só para a documentação
+#[allow(dead_code)]
+#[derive(utoipa::ToSchema)]
+struct ImageUpload {
+    #[schema(value_type = String, format = Binary)]
+    image: Vec<u8>,
+}
+
+/// Tamanho máximo de upload em bytes (`storage.max_upload_bytes`)
+#[derive(Debug, Clone, Copy)]
+pub struct UploadLimit(pub usize);
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use actix_web::{test, App};
+    use serde_json::{json, Value};
+    use delpopolo_domain::{Money, ProductCategory, UnitOfMeasure, UserRole};
+    use crate::auth::service::tests::{config, user};
+    use crate::auth::store::memory::InMemoryAuthStore;
+    use crate::auth::AuthService;
+    use crate::catalog::store::memory::InMemoryProductStore;
+    use super::*;
+    
+    fn product(name: &str, price: f64) -> Product {
+        let mut p = Product::new(name.to_string(), ProductCategory::Bread, UnitOfMeasure::Unit, Money::brl(price), Money::brl(price / 2.0));
+        p.sku = name.to_uppercase().replace(' ', "-");
+        p
+    }
+    
+    async fn token(auth: &AuthService, email: &str) -> String {
+        format!("Bearer {}", auth.login(email, "fermento123").await.unwrap().access_token)
+    }
+    
+    #[actix_web::test]
+    async fn test_catalog_crud_pagination_and_etag() {
+        let manager = user(UserRole::Manager);
+        let mut cashier = user(UserRole::Cashier);
+        cashier.email = "caixa@delpopolo.com.br".to_string();
+        let auth = AuthService::new(
+            Arc::new(InMemoryAuthStore::default().with_user(manager.clone()).with_user(cashier.clone())),
+            &config(),
+        );
+        let (manager_token, cashier_token) = (token(&auth, &manager.email).await, token(&auth, &cashier.email).await);
+        
+        let store: Arc<dyn ProductStore> = Arc::new(InMemoryProductStore::default().with_products(vec![
+            product("Baguete", 9.0),
+            product("Ciabatta", 12.0),
+            product("Pão Francês", 0.8),
+        ]));
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(auth))
+                .app_data(web::Data::from(store))
+                .app_data(web::Data::new(AvilaLogger::disabled()))
+                .service(
+                    web::scope("/products")
+                        .wrap(RequirePermission(Permission::ProductsRead))
+                        .configure(configure),
+                ),
+        )
+        .await;
+        
+        // Página 1 e 2 ordenadas por preço decrescente
+        let req = test::TestRequest::get().uri("/products?sort=-price&limit=2").insert_header(("Authorization", cashier_token.clone())).to_request();
+        let page: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(page["items"][0]["name"], "Ciabatta");
+        assert_eq!(page["items"].as_array().unwrap().len(), 2);
+        let uri = format!("/products?sort=-price&limit=2&cursor={}", page["next_cursor"].as_str().unwrap());
+        let req = test::TestRequest::get().uri(&uri).insert_header(("Authorization", cashier_token.clone())).to_request();
+        let page: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(page["items"][0]["name"], "Pão Francês");
+        assert!(page["next_cursor"].is_null());
+        
+        // Caixa não cadastra produto
+        let body = json!({"name": "Sonho", "sku": "SONHO", "barcode": "7891000100103", "category": "Pastry", "unit_of_measure": "Unit", "price": 6.5, "cost": 2.0});
+        let req = test::TestRequest::post().uri("/products").insert_header(("Authorization", cashier_token.clone())).set_json(&body).to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 403);
+        
+        let req = test::TestRequest::post().uri("/products").insert_header(("Authorization", manager_token.clone())).set_json(json!({"name": "", "sku": "X", "category": "Pastry", "unit_of_measure": "Unit", "price": 0, "cost": 0})).to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 422);
+        let problem: Value = test::read_body_json(resp).await;
+        assert_eq!(problem["errors"].as_array().unwrap().len(), 2);
+        
+        let req = test::TestRequest::post().uri("/products").insert_header(("Authorization", manager_token.clone())).set_json(&body).to_request();
+        let created: Value = test::call_and_read_body_json(&app, req).await;
+        
+        // Leitura pelo código de barras com ETag e GET condicional
+        let req = test::TestRequest::get().uri("/products/barcode/7891000100103").insert_header(("Authorization", cashier_token.clone())).to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+        let etag = resp.headers().get(header::ETAG).unwrap().clone();
+        
+        let uri = format!("/products/{}", created["id"].as_str().unwrap());
+        let req = test::TestRequest::get().uri(&uri).insert_header(("Authorization", cashier_token.clone())).insert_header((header::IF_NONE_MATCH, etag)).to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 304);
+        
+        let req = test::TestRequest::delete().uri(&uri).insert_header(("Authorization", manager_token.clone())).to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 204);
+        
+        // Removido fica inativo: some da listagem, mas o histórico ainda o encontra
+        let req = test::TestRequest::get().uri(&uri).insert_header(("Authorization", cashier_token.clone())).to_request();
+        let removed: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(removed["is_active"], false);
+        let req = test::TestRequest::get().uri("/products?q=sonho").insert_header(("Authorization", cashier_token)).to_request();
+        let page: Value = test::call_and_read_body_json(&app, req).await;
+        assert!(page["items"].as_array().unwrap().is_empty());
+    }
+    
+    #[actix_web::test]
+    async fn test_oversized_image_is_payload_too_large() {
+        let manager = user(UserRole::Manager);
+        let auth = AuthService::new(Arc::new(InMemoryAuthStore::default().with_user(manager.clone())), &config());
+        let token = token(&auth, &manager.email).await;
+        
+        let baguete = product("Baguete", 9.0);
+        let store: Arc<dyn ProductStore> = Arc::new(InMemoryProductStore::default().with_products(vec![baguete.clone()]));
+        let dir = std::env::temp_dir().join(format!("delpopolo-upload-{}", Uuid::new_v4()));
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(auth))
+                .app_data(web::Data::from(store))
+                .app_data(web::Data::new(AvilaLogger::disabled()))
+                .app_data(web::Data::new(ImageStorage::new(&dir, "/media/products/")))
+                .app_data(web::Data::new(UploadLimit(1024)))
+                .service(web::scope("/products").configure(configure)),
+        )
+        .await;
+        
+        let boundary = "delpopolo";
+        let mut body = format!(
+            "--{boundary}\r\nContent-Disposition: form-data; name=\"image\"; filename=\"foto.png\"\r\nContent-Type: image/png\r\n\r\n"
+        )
+        .into_bytes();
+        body.extend(vec![0u8; 2048]);
+        body.extend(format!("\r\n--{boundary}--\r\n").into_bytes());
+        let req = test::TestRequest::post()
+            .uri(&format!("/products/{}/image", baguete.id))
+            .insert_header(("Authorization", token))
+            .insert_header(("Content-Type", format!("multipart/form-data; boundary={boundary}")))
+            .set_payload(body)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 413);
+        let problem: Value = test::read_body_json(resp).await;
+        assert_eq!(problem["code"], "payload_too_large");
+    }
 }
@@ -1,5 +1,439 @@
-use actix_web::web;
+use actix_web::{http::header, web, HttpRequest, HttpResponse};
+use chrono::Utc;
+use utoipa::OpenApi;
+use uuid::Uuid;
+use delpopolo_core::CoreError;
+use delpopolo_domain::{Permission, Product, Supplier, SupplierProduct};
+use delpopolo_inventory::{ReplenishmentEngine, ReplenishmentSuggestion};
+use crate::auth::RequirePermission;
+use crate::catalog::ProductStore;
+use crate::conditional::{conditional_json, entity_tag};
+use crate::dto::suppliers::{
+    parse_cnpj, SupplierCursorToken, SupplierListQuery, SupplierProductRequest, SupplierRequest, SupplierResponse,
+};
+use crate::error::ApiError;
+use crate::inventory::InventoryStore;
+use crate::pagination::{paginate, Page};
+use crate::suppliers::SupplierStore;
 
-pub fn configure(_cfg: &mut web::ServiceConfig) {
-    // TODO: Implementar rotas de fornecedores
+#[derive(OpenApi)]
+#[openapi(paths(
+    list_suppliers, create_supplier, list_replenishment, get_replenishment, get_supplier_by_cnpj,
+    get_supplier, update_supplier, deactivate_supplier, list_catalog, upsert_catalog_item, remove_catalog_item,
+))]
+pub struct SuppliersApi;
+
+/// Leitura já é exigida no escopo `/suppliers`; escrita exige `SuppliersWrite`
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    let write = || RequirePermission(Permission::SuppliersWrite);
+    
+    cfg.service(
+        web::resource("")
+            .route(web::get().to(list_suppliers))
+            .route(web::post().to(create_supplier).wrap(write())),
+    )
+    // Rotas fixas antes de `/{id}`
+    .service(web::resource("/replenishment").route(web::get().to(list_replenishment)))
+    .service(web::resource("/replenishment/{product_id}").route(web::get().to(get_replenishment)))
+    .service(web::resource("/by-cnpj/{cnpj}").route(web::get().to(get_supplier_by_cnpj)))
+    .service(
+        web::resource("/{id}")
+            .route(web::get().to(get_supplier))
+            .route(web::put().to(update_supplier).wrap(write()))
+            .route(web::delete().to(deactivate_supplier).wrap(write())),
+    )
+    .service(web::resource("/{id}/products").route(web::get().to(list_catalog)))
+    .service(
+        web::resource("/{id}/products/{product_id}")
+            .route(web::put().to(upsert_catalog_item).wrap(write()))
+            .route(web::delete().to(remove_catalog_item).wrap(write())),
+    );
+}
+
+async fn load(suppliers: &dyn SupplierStore, id: Uuid) -> Result<Supplier, CoreError> {
+    suppliers
+        .find(id)
+        .await?
+        .ok_or_else(|| CoreError::not_found(format!("Fornecedor {} não encontrado", id)))
+}
+
+async fn load_product(products: &dyn ProductStore, id: Uuid) -> Result<Product, CoreError> {
+    products
+        .find(id)
+        .await?
+        .ok_or_else(|| CoreError::not_found(format!("Produto {} não encontrado", id)))
+}
+
+/// CNPJ é único entre os fornecedores; `current` é o próprio fornecedor numa atualização
+async fn ensure_unique_cnpj(suppliers: &dyn SupplierStore, supplier: &Supplier, current: Option<Uuid>) -> Result<(), CoreError> {
+    let Some(cnpj) = &supplier.cnpj else {
+        return Ok(());
+    };
+    match suppliers.find_by_cnpj(cnpj.value()).await? {
+        Some(existing) if Some(existing.id) != current => {
+            Err(CoreError::conflict(format!("CNPJ {} já cadastrado", cnpj.formatted())))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn supplier_json(supplier: &Supplier) -> HttpResponse {
+    HttpResponse::Ok()
+        .insert_header(header::ETag(entity_tag(supplier.id, supplier.updated_at)))
+        .json(SupplierResponse::from(supplier))
+}
+
+#[utoipa::path(
+    get, path = "", tag = "suppliers",
+    params(SupplierListQuery),
+    responses((status = 200, body = Page<SupplierResponse>), (status = 422)),
+)]
+async fn list_suppliers(
+    suppliers: web::Data<dyn SupplierStore>,
+    query: web::Query<SupplierListQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let mut supplier_query = query.to_query()?;
+    let limit = supplier_query.limit;
+    supplier_query.limit += 1;
+    
+    let found = suppliers.list(&supplier_query).await?;
+    let page = paginate(found, limit, |s| SupplierCursorToken { n: s.name.clone(), id: s.id });
+    
+    Ok(HttpResponse::Ok().json(Page {
+        items: page.items.iter().map(SupplierResponse::from).collect::<Vec<_>>(),
+        next_cursor: page.next_cursor,
+    }))
+}
+
+#[utoipa::path(
+    get, path = "/{id}", tag = "suppliers",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = SupplierResponse), (status = 304, description = "Não modificado (`If-None-Match`)"), (status = 404)),
+)]
+async fn get_supplier(
+    req: HttpRequest,
+    suppliers: web::Data<dyn SupplierStore>,
+    id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let supplier = load(suppliers.get_ref(), *id).await?;
+    Ok(conditional_json(&req, entity_tag(supplier.id, supplier.updated_at), &SupplierResponse::from(&supplier)))
+}
+
+/// Aceita o CNPJ com ou sem máscara
+#[utoipa::path(
+    get, path = "/by-cnpj/{cnpj}", tag = "suppliers",
+    params(("cnpj" = String, Path)),
+    responses((status = 200, body = SupplierResponse), (status = 304, description = "Não modificado (`If-None-Match`)"), (status = 404), (status = 422)),
+)]
+async fn get_supplier_by_cnpj(
+    req: HttpRequest,
+    suppliers: web::Data<dyn SupplierStore>,
+    cnpj: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let cnpj = parse_cnpj(&cnpj)?;
+    let supplier = suppliers
+        .find_by_cnpj(&cnpj)
+        .await?
+        .ok_or_else(|| CoreError::not_found(format!("Nenhum fornecedor com CNPJ {}", cnpj)))?;
+    
+    Ok(conditional_json(&req, entity_tag(supplier.id, supplier.updated_at), &SupplierResponse::from(&supplier)))
+}
+
+#[utoipa::path(
+    post, path = "", tag = "suppliers",
+    request_body = SupplierRequest,
+    responses((status = 201, body = SupplierResponse), (status = 409, description = "CNPJ já cadastrado"), (status = 422)),
+)]
+async fn create_supplier(
+    req: HttpRequest,
+    suppliers: web::Data<dyn SupplierStore>,
+    body: web::Json<SupplierRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let supplier = body.into_inner().into_supplier()?;
+    ensure_unique_cnpj(suppliers.get_ref(), &supplier, None).await?;
+    suppliers.insert(&supplier).await?;
+    
+    Ok(HttpResponse::Created()
+        .insert_header((header::LOCATION, format!("{}/{}", req.path().trim_end_matches('/'), supplier.id)))
+        .insert_header(header::ETag(entity_tag(supplier.id, supplier.updated_at)))
+        .json(SupplierResponse::from(&supplier)))
+}
+
+#[utoipa::path(
+    put, path = "/{id}", tag = "suppliers",
+    params(("id" = Uuid, Path)),
+    request_body = SupplierRequest,
+    responses((status = 200, body = SupplierResponse), (status = 404), (status = 409, description = "CNPJ já cadastrado ou fornecedor alterado por outra operação"), (status = 422)),
+)]
+async fn update_supplier(
+    suppliers: web::Data<dyn SupplierStore>,
+    id: web::Path<Uuid>,
+    body: web::Json<SupplierRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let mut supplier = load(suppliers.get_ref(), *id).await?;
+    
+    let previous = supplier.updated_at;
+    body.into_inner().apply_to(&mut supplier)?;
+    ensure_unique_cnpj(suppliers.get_ref(), &supplier, Some(supplier.id)).await?;
+    suppliers.update(&supplier, previous).await?;
+    
+    Ok(supplier_json(&supplier))
+}
+
+/// Desativa sem apagar: entradas de estoque e notas continuam apontando para o fornecedor
+#[utoipa::path(
+    delete, path = "/{id}", tag = "suppliers",
+    params(("id" = Uuid, Path)),
+    responses((status = 204, description = "Fornecedor desativado"), (status = 404), (status = 409, description = "Fornecedor alterado por outra operação")),
+)]
+async fn deactivate_supplier(
+    suppliers: web::Data<dyn SupplierStore>,
+    id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let mut supplier = load(suppliers.get_ref(), *id).await?;
+    
+    if supplier.is_active {
+        let previous = supplier.updated_at;
+        supplier.is_active = false;
+        supplier.updated_at = Utc::now();
+        suppliers.update(&supplier, previous).await?;
+    }
+    
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[utoipa::path(
+    get, path = "/{id}/products", tag = "suppliers",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = Vec<SupplierProduct>), (status = 404)),
+)]
+async fn list_catalog(
+    suppliers: web::Data<dyn SupplierStore>,
+    id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let supplier = load(suppliers.get_ref(), *id).await?;
+    Ok(HttpResponse::Ok().json(&supplier.products))
+}
+
+#[utoipa::path(
+    put, path = "/{id}/products/{product_id}", tag = "suppliers",
+    params(("id" = Uuid, Path), ("product_id" = Uuid, Path)),
+    request_body = SupplierProductRequest,
+    responses((status = 200, body = SupplierResponse), (status = 404), (status = 409, description = "Fornecedor alterado por outra operação"), (status = 422)),
+)]
+async fn upsert_catalog_item(
+    suppliers: web::Data<dyn SupplierStore>,
+    products: web::Data<dyn ProductStore>,
+    path: web::Path<(Uuid, Uuid)>,
+    body: web::Json<SupplierProductRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let (id, product_id) = path.into_inner();
+    let mut supplier = load(suppliers.get_ref(), id).await?;
+    load_product(products.get_ref(), product_id).await?;
+    
+    let previous = supplier.updated_at;
+    supplier.upsert_product(body.into_inner().into_product(product_id))?;
+    suppliers.update(&supplier, previous).await?;
+    
+    Ok(supplier_json(&supplier))
+}
+
+#[utoipa::path(
+    delete, path = "/{id}/products/{product_id}", tag = "suppliers",
+    params(("id" = Uuid, Path), ("product_id" = Uuid, Path)),
+    responses((status = 204, description = "Produto removido do catálogo"), (status = 404), (status = 409, description = "Fornecedor alterado por outra operação")),
+)]
+async fn remove_catalog_item(
+    suppliers: web::Data<dyn SupplierStore>,
+    path: web::Path<(Uuid, Uuid)>,
+) -> Result<HttpResponse, ApiError> {
+    let (id, product_id) = path.into_inner();
+    let mut supplier = load(suppliers.get_ref(), id).await?;
+    
+    let previous = supplier.updated_at;
+    if supplier.remove_product(product_id).is_none() {
+        return Err(CoreError::not_found(format!("Produto {} não está no catálogo do fornecedor", product_id)).into());
+    }
+    suppliers.update(&supplier, previous).await?;
+    
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Sugestão com as cotações ranqueadas: preferido, menor custo total e melhor avaliação
+async fn suggestion(
+    suppliers: &dyn SupplierStore,
+    inventory: &dyn InventoryStore,
+    product: &Product,
+) -> Result<ReplenishmentSuggestion, CoreError> {
+    let available = inventory
+        .levels(&[product.id])
+        .await?
+        .first()
+        .map_or(0.0, |level| level.available_quantity);
+    let offering = suppliers.offering(product.id).await?;
+    
+    Ok(ReplenishmentEngine::generate_suggestion(product, available, offering.iter().collect()))
+}
+
+/// Produtos em alerta de estoque, do mais urgente para o menos urgente
+#[utoipa::path(
+    get, path = "/replenishment", tag = "suppliers",
+    responses((status = 200, body = Vec<ReplenishmentSuggestion>)),
+)]
+async fn list_replenishment(
+    suppliers: web::Data<dyn SupplierStore>,
+    products: web::Data<dyn ProductStore>,
+    inventory: web::Data<dyn InventoryStore>,
+) -> Result<HttpResponse, ApiError> {
+    let mut suggestions = Vec::new();
+    for alert in inventory.alerts().await? {
+        // Produto removido do catálogo não tem o que repor
+        let Some(product) = products.find(alert.product_id).await? else {
+            continue;
+        };
+        suggestions.push(suggestion(suppliers.get_ref(), inventory.get_ref(), &product).await?);
+    }
+    suggestions.sort_by(|a, b| b.urgency_score.total_cmp(&a.urgency_score).then_with(|| a.product_name.cmp(&b.product_name)));
+    
+    Ok(HttpResponse::Ok().json(suggestions))
+}
+
+/// Sugestão de reposição de um produto, com as cotações dos fornecedores
+#[utoipa::path(
+    get, path = "/replenishment/{product_id}", tag = "suppliers",
+    params(("product_id" = Uuid, Path)),
+    responses((status = 200, body = ReplenishmentSuggestion), (status = 404)),
+)]
+async fn get_replenishment(
+    suppliers: web::Data<dyn SupplierStore>,
+    products: web::Data<dyn ProductStore>,
+    inventory: web::Data<dyn InventoryStore>,
+    product_id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let product = load_product(products.get_ref(), *product_id).await?;
+    Ok(HttpResponse::Ok().json(suggestion(suppliers.get_ref(), inventory.get_ref(), &product).await?))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use actix_web::{test, App};
+    use serde_json::{json, Value};
+    use delpopolo_domain::{Money, ProductCategory, UnitOfMeasure, UserRole};
+    use crate::auth::service::tests::{config, user};
+    use crate::auth::store::memory::InMemoryAuthStore;
+    use crate::auth::AuthService;
+    use crate::catalog::store::memory::InMemoryProductStore;
+    use crate::inventory::store::memory::InMemoryInventoryStore;
+    use crate::suppliers::store::memory::InMemorySupplierStore;
+    use super::*;
+    
+    async fn token(auth: &AuthService, email: &str) -> String {
+        format!("Bearer {}", auth.login(email, "fermento123").await.unwrap().access_token)
+    }
+    
+    #[actix_web::test]
+    async fn test_supplier_catalog_and_ranked_replenishment() {
+        let manager = user(UserRole::InventoryManager);
+        let auth = AuthService::new(Arc::new(InMemoryAuthStore::default().with_user(manager.clone())), &config());
+        let token = token(&auth, &manager.email).await;
+        
+        let mut farinha = Product::new(
+            "Farinha de trigo".to_string(),
+            ProductCategory::Bread,
+            UnitOfMeasure::Unit,
+            Money::brl(5.0),
+            Money::brl(3.0),
+        );
+        farinha.min_stock_level = 50.0;
+        let inventory: Arc<dyn InventoryStore> =
+            Arc::new(InMemoryInventoryStore::default().with_minimum(farinha.id, &farinha.name, farinha.min_stock_level));
+        let products: Arc<dyn ProductStore> = Arc::new(InMemoryProductStore::default().with_products(vec![farinha.clone()]));
+        let suppliers: Arc<dyn SupplierStore> = Arc::new(InMemorySupplierStore::default());
+        let store = suppliers.clone();
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(auth))
+                .app_data(web::Data::from(suppliers))
+                .app_data(web::Data::from(products))
+                .app_data(web::Data::from(inventory))
+                .service(
+                    web::scope("/suppliers")
+                        .wrap(RequirePermission(Permission::SuppliersRead))
+                        .configure(configure),
+                ),
+        )
+        .await;
+        let request = |method: test::TestRequest, uri: &str| {
+            method.uri(uri).insert_header(("Authorization", token.clone()))
+        };
+        
+        // CNPJ inválido, cadastro e CNPJ duplicado
+        let invalid = json!({"name": "Moinho Paulista", "cnpj": "11.222.333/0001-00"});
+        let req = request(test::TestRequest::post(), "/suppliers").set_json(invalid).to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 422);
+        
+        let moinho = json!({
+            "name": "Moinho Paulista",
+            "cnpj": "11.222.333/0001-81",
+            "contacts": [{"name": "Carla", "role": "Comercial", "phone": "(11) 3333-4444"}]
+        });
+        let req = request(test::TestRequest::post(), "/suppliers").set_json(moinho.clone()).to_request();
+        let cheap: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(cheap["cnpj"], "11.222.333/0001-81");
+        let req = request(test::TestRequest::post(), "/suppliers").set_json(moinho).to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 409);
+        
+        let atacado = json!({"name": "Atacado Central", "cnpj": "11444777000161", "is_preferred": true, "rating": 4.5});
+        let req = request(test::TestRequest::post(), "/suppliers").set_json(atacado).to_request();
+        let preferred: Value = test::call_and_read_body_json(&app, req).await;
+        
+        let req = request(test::TestRequest::get(), "/suppliers/by-cnpj/11222333000181").to_request();
+        let resp = test::call_service(&app, req).await;
+        let etag = resp.headers().get(header::ETAG).unwrap().clone();
+        let found: Value = test::read_body_json(resp).await;
+        assert_eq!(found["id"], cheap["id"]);
+        let req = request(test::TestRequest::get(), "/suppliers/by-cnpj/11222333000181")
+            .insert_header((header::IF_NONE_MATCH, etag))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 304);
+        
+        // Catálogo: o preferido cobra mais, mas vem primeiro no ranking
+        for (supplier, price) in [(&cheap, 3.5), (&preferred, 4.0)] {
+            let uri = format!("/suppliers/{}/products/{}", supplier["id"].as_str().unwrap(), farinha.id);
+            let item = json!({"unit_price": price, "min_order_quantity": 25.0, "lead_time_days": 2});
+            let req = request(test::TestRequest::put(), &uri).set_json(item).to_request();
+            assert_eq!(test::call_service(&app, req).await.status(), 200);
+        }
+        let uri = format!("/suppliers/{}/products/{}", cheap["id"].as_str().unwrap(), Uuid::new_v4());
+        let req = request(test::TestRequest::put(), &uri).set_json(json!({"unit_price": 1.0})).to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 404);
+        
+        let req = request(test::TestRequest::get(), "/suppliers/replenishment").to_request();
+        let suggestions: Value = test::call_and_read_body_json(&app, req).await;
+        let quotes = suggestions[0]["quotes"].as_array().unwrap();
+        assert_eq!(quotes.len(), 2);
+        assert_eq!(quotes[0]["supplier_id"], preferred["id"]);
+        assert_eq!(suggestions[0]["best_quote"]["supplier_id"], preferred["id"]);
+        
+        // Fornecedor desativado sai das cotações
+        let uri = format!("/suppliers/{}", preferred["id"].as_str().unwrap());
+        let req = request(test::TestRequest::delete(), &uri).to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 204);
+        let req = request(test::TestRequest::get(), &format!("/suppliers/replenishment/{}", farinha.id)).to_request();
+        let suggestion: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(suggestion["best_quote"]["supplier_id"], cheap["id"]);
+        
+        // Gravação com a versão lida antes de outra alteração é recusada
+        let id: Uuid = cheap["id"].as_str().unwrap().parse().unwrap();
+        let read = store.find(id).await.unwrap().unwrap();
+        let req = request(test::TestRequest::put(), &format!("/suppliers/{}", id))
+            .set_json(json!({"name": "Moinho Paulista", "cnpj": "11222333000181", "rating": 4.0}))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 200);
+        let mut stale = read.clone();
+        stale.is_preferred = true;
+        stale.updated_at = Utc::now();
+        assert!(matches!(store.update(&stale, read.updated_at).await, Err(CoreError::Conflict(_))));
+    }
 }
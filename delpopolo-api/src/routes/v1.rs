@@ -0,0 +1,153 @@
+use actix_web::{http::StatusCode, web};
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::openapi::{Content, Ref, ResponseBuilder};
+use utoipa::{Modify, OpenApi};
+use delpopolo_domain::Permission;
+use crate::auth::RequirePermission;
+use crate::error::Problem;
+use super::{auth, customers, events, fiscal, inventory, kitchen, orders, products, suppliers};
+
+/// Especificação da `/api/v1`, servida em `/api/v1/openapi.json`. Os caminhos de cada
+/// módulo são montados nos mesmos prefixos de `configure`.
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Del Popolo API", description = "Padaria e confeitaria: catálogo, pedidos, clientes, estoque, fornecedores e livro fiscal"),
+    servers((url = "/api/v1")),
+    nest(
+        (path = "/auth", api = auth::AuthApi),
+        (path = "/products", api = products::ProductsApi),
+        (path = "/orders", api = orders::OrdersApi),
+        (path = "/customers", api = customers::CustomersApi),
+        (path = "/inventory", api = inventory::InventoryApi),
+        (path = "/suppliers", api = suppliers::SuppliersApi),
+        (path = "/kitchen", api = kitchen::KitchenApi),
+        (path = "/fiscal", api = fiscal::FiscalApi),
+        (path = "/events", api = events::EventsApi),
+    ),
+    components(schemas(Problem)),
+    security(("bearer_auth" = [])),
+    tags(
+        (name = "auth", description = "Login, renovação e encerramento de sessão"),
+        (name = "products", description = "Catálogo de produtos"),
+        (name = "orders", description = "Pedidos e ciclo de vida"),
+        (name = "customers", description = "Clientes, consentimentos e LGPD"),
+        (name = "inventory", description = "Operações idempotentes de estoque"),
+        (name = "suppliers", description = "Fornecedores, catálogos e reposição"),
+        (name = "kitchen", description = "Telas da cozinha (KDS): filas por estação, bump e recall"),
+        (name = "fiscal", description = "Livro fiscal das NF-e recebidas: resumo por CFOP/CST e SPED"),
+        (name = "events", description = "Eventos em tempo real (SSE e WebSocket)"),
+    ),
+    modifiers(&Conventions),
+)]
+pub struct ApiDoc;
+
+/// Convenções comuns a todas as operações: token JWT e erros em `application/problem+json`
+struct Conventions;
+
+impl Modify for Conventions {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer_auth",
+            SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).bearer_format("JWT").build()),
+        );
+        
+        let problem = || Content::new(Some(Ref::from_schema_name("Problem")));
+        for item in openapi.paths.paths.values_mut() {
+            let operations = [&mut item.get, &mut item.put, &mut item.post, &mut item.delete, &mut item.patch];
+            for operation in operations.into_iter().flatten() {
+                let responses = &mut operation.responses.responses;
+                for (status, response) in responses.iter_mut() {
+                    let utoipa::openapi::RefOr::T(response) = response else {
+                        continue;
+                    };
+                    let Some(code) = status.parse().ok().and_then(|s| StatusCode::from_u16(s).ok()) else {
+                        continue;
+                    };
+                    if response.description.is_empty() {
+                        response.description = code.canonical_reason().unwrap_or_default().to_string();
+                    }
+                    if code.as_u16() >= 400 && response.content.is_empty() {
+                        response.content.insert("application/problem+json".to_string(), problem());
+                    }
+                }
+                responses.entry("default".to_string()).or_insert_with(|| {
+                    ResponseBuilder::new()
+                        .description("Erro (RFC 7807)")
+                        .content("application/problem+json", problem())
+                        .build()
+                        .into()
+                });
+            }
+        }
+    }
+}
+
+/// Rotas da `/api/v1`
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/auth")
+            .configure(auth::configure)
+    )
+    // Leitura exigida no escopo; escrita é exigida em cada rota
+    .service(
+        web::scope("/products")
+            .wrap(RequirePermission(Permission::ProductsRead))
+            .configure(products::configure)
+    )
+    .service(
+        web::scope("/orders")
+            .wrap(RequirePermission(Permission::OrdersRead))
+            .configure(orders::configure)
+    )
+    .service(
+        web::scope("/customers")
+            .wrap(RequirePermission(Permission::CustomersRead))
+            .configure(customers::configure)
+    )
+    .service(
+        web::scope("/inventory")
+            .wrap(RequirePermission(Permission::InventoryRead))
+            .configure(inventory::configure)
+    )
+    .service(
+        web::scope("/suppliers")
+            .wrap(RequirePermission(Permission::SuppliersRead))
+            .configure(suppliers::configure)
+    )
+    .service(
+        web::scope("/kitchen")
+            .wrap(RequirePermission(Permission::KitchenOperate))
+            .configure(kitchen::configure)
+    )
+    .service(
+        web::scope("/fiscal")
+            .wrap(RequirePermission(Permission::FiscalRead))
+            .configure(fiscal::configure)
+    )
+    // Conexões longas: o token e os tópicos são conferidos ao abrir e o fim da validade encerra
+    .service(
+        web::scope("/events")
+            .configure(events::configure)
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    /// O `openapi.json` versionado é o contrato publicado: qualquer mudança nas rotas ou nos
+    /// DTOs precisa vir acompanhada do arquivo regenerado (`make openapi`)
+    #[test]
+    fn test_openapi_spec_matches_committed_file() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/openapi.json");
+        let spec = ApiDoc::openapi().to_pretty_json().unwrap() + "\n";
+        
+        if std::env::var_os("UPDATE_OPENAPI").is_some() {
+            std::fs::write(path, &spec).unwrap();
+            return;
+        }
+        let committed = std::fs::read_to_string(path).unwrap_or_default();
+        assert!(committed == spec, "openapi.json desatualizado; regenere com `make openapi`");
+    }
+}
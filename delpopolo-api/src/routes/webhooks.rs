@@ -1,5 +1,9 @@
 use actix_web::{web, HttpRequest, HttpResponse};
 use serde_json::json;
+use delpopolo_infrastructure::Secret;
+
+/// `WHATSAPP__WEBHOOK_VERIFY_TOKEN`; `None` quando o WhatsApp não está configurado
+pub struct WhatsAppVerifyToken(pub Option<Secret>);
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(
@@ -19,14 +23,18 @@ async fn ifood_webhook(body: web::Json<serde_json::Value>) -> HttpResponse {
     HttpResponse::Ok().json(json!({"status": "received"}))
 }
 
-async fn whatsapp_verify(req: HttpRequest) -> HttpResponse {
+async fn whatsapp_verify(req: HttpRequest, expected: web::Data<WhatsAppVerifyToken>) -> HttpResponse {
+    // Sem WhatsApp configurado nenhuma verificação é aceita
+    let Some(expected) = &expected.0 else {
+        return HttpResponse::Forbidden().finish();
+    };
     let query = web::Query::<std::collections::HashMap<String, String>>::from_query(req.query_string());
     
     if let Ok(params) = query {
         if let (Some(mode), Some(token), Some(challenge)) = 
             (params.get("hub.mode"), params.get("hub.verify_token"), params.get("hub.challenge")) 
         {
-            if mode == "subscribe" && token == "your-webhook-verify-token" {
+            if mode == "subscribe" && token == expected.expose() {
                 return HttpResponse::Ok().body(challenge.clone());
             }
         }
@@ -40,3 +48,29 @@ async fn whatsapp_webhook(body: web::Json<serde_json::Value>) -> HttpResponse {
     // TODO: Processar webhook do WhatsApp
     HttpResponse::Ok().json(json!({"status": "received"}))
 }
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{test, App};
+    use super::*;
+    
+    #[actix_web::test]
+    async fn test_whatsapp_verification_uses_configured_token() {
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(WhatsAppVerifyToken(Some("token-da-loja".into()))))
+                .service(web::scope("/webhooks").configure(configure)),
+        )
+        .await;
+        let verify = |token: &str| {
+            test::TestRequest::get()
+                .uri(&format!("/webhooks/whatsapp?hub.mode=subscribe&hub.verify_token={token}&hub.challenge=42"))
+                .to_request()
+        };
+        
+        let resp = test::call_service(&app, verify("token-da-loja")).await;
+        assert_eq!(resp.status(), 200);
+        assert_eq!(test::read_body(resp).await, "42");
+        assert_eq!(test::call_service(&app, verify("your-webhook-verify-token")).await.status(), 403);
+    }
+}
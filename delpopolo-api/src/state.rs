@@ -1,26 +1,13 @@
-use delpopolo_infrastructure::{Database, Config};
-use crate::avila_logger::AvilaLogger;
+use std::sync::Arc;
+use crate::avila_client::AvilaClient;
 
 pub struct AppState {
-    pub database: Database,
-    pub config: Config,
-    pub avila_token: String,
-    pub avila_logger: AvilaLogger,
+    /// `None` no modo standalone
+    pub avila: Option<Arc<AvilaClient>>,
 }
 
 impl AppState {
-    pub fn new(database: Database, config: Config, avila_token: String) -> Self {
-        let avila_logger = AvilaLogger::new(avila_token.clone());
-        
-        Self {
-            database,
-            config,
-            avila_token,
-            avila_logger,
-        }
-    }
-    
-    pub fn get_avila_auth_header(&self) -> String {
-        format!("Bearer {}", self.avila_token)
+    pub fn new(avila: Option<Arc<AvilaClient>>) -> Self {
+        Self { avila }
     }
 }
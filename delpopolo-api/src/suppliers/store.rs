@@ -0,0 +1,124 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+use delpopolo_core::CoreError;
+use delpopolo_domain::Supplier;
+use delpopolo_infrastructure::repositories::supplier_repository::SupplierQuery;
+use delpopolo_infrastructure::repositories::SupplierRepository;
+
+/// Persistência de fornecedores e dos seus catálogos
+#[async_trait]
+pub trait SupplierStore: Send + Sync {
+    async fn find(&self, id: Uuid) -> Result<Option<Supplier>, CoreError>;
+    async fn find_by_cnpj(&self, cnpj: &str) -> Result<Option<Supplier>, CoreError>;
+    async fn list(&self, query: &SupplierQuery) -> Result<Vec<Supplier>, CoreError>;
+    /// Fornecedores ativos que vendem o produto
+    async fn offering(&self, product_id: Uuid) -> Result<Vec<Supplier>, CoreError>;
+    /// Fornecedor e catálogo são gravados juntos
+    async fn insert(&self, supplier: &Supplier) -> Result<(), CoreError>;
+    /// `CoreError::Conflict` se o fornecedor mudou desde `expected_updated_at`
+    async fn update(&self, supplier: &Supplier, expected_updated_at: DateTime<Utc>) -> Result<(), CoreError>;
+}
+
+fn stale(supplier: &Supplier) -> CoreError {
+    CoreError::conflict(format!("Fornecedor {} foi alterado por outra operação; recarregue e tente novamente", supplier.name))
+}
+
+#[async_trait]
+impl SupplierStore for SupplierRepository {
+    async fn find(&self, id: Uuid) -> Result<Option<Supplier>, CoreError> {
+        self.find_by_id(id).await.map_err(CoreError::database)
+    }
+    
+    async fn find_by_cnpj(&self, cnpj: &str) -> Result<Option<Supplier>, CoreError> {
+        SupplierRepository::find_by_cnpj(self, cnpj).await.map_err(CoreError::database)
+    }
+    
+    async fn list(&self, query: &SupplierQuery) -> Result<Vec<Supplier>, CoreError> {
+        SupplierRepository::list(self, query).await.map_err(CoreError::database)
+    }
+    
+    async fn offering(&self, product_id: Uuid) -> Result<Vec<Supplier>, CoreError> {
+        self.find_offering(product_id).await.map_err(CoreError::database)
+    }
+    
+    async fn insert(&self, supplier: &Supplier) -> Result<(), CoreError> {
+        SupplierRepository::insert(self, supplier).await.map_err(CoreError::database)
+    }
+    
+    async fn update(&self, supplier: &Supplier, expected_updated_at: DateTime<Utc>) -> Result<(), CoreError> {
+        match SupplierRepository::update(self, supplier, expected_updated_at).await {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(stale(supplier)),
+            Err(e) => Err(CoreError::database(e)),
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod memory {
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+    use super::*;
+    
+    /// Fornecedores em memória para os testes
+    #[derive(Default)]
+    pub struct InMemorySupplierStore {
+        suppliers: Mutex<HashMap<Uuid, Supplier>>,
+    }
+    
+    #[async_trait]
+    impl SupplierStore for InMemorySupplierStore {
+        async fn find(&self, id: Uuid) -> Result<Option<Supplier>, CoreError> {
+            Ok(self.suppliers.lock().unwrap().get(&id).cloned())
+        }
+        
+        async fn find_by_cnpj(&self, cnpj: &str) -> Result<Option<Supplier>, CoreError> {
+            Ok(self
+                .suppliers
+                .lock()
+                .unwrap()
+                .values()
+                .find(|s| s.cnpj.as_ref().is_some_and(|v| v.value() == cnpj))
+                .cloned())
+        }
+        
+        async fn list(&self, query: &SupplierQuery) -> Result<Vec<Supplier>, CoreError> {
+            let mut items: Vec<Supplier> = self.suppliers.lock().unwrap().values().filter(|s| query.matches(s)).cloned().collect();
+            items.sort_by(|a, b| (&a.name, a.id).cmp(&(&b.name, b.id)));
+            
+            if let Some(cursor) = &query.after {
+                items.retain(|s| (&s.name, s.id) > (&cursor.name, cursor.id));
+            }
+            items.truncate(query.limit as usize);
+            Ok(items)
+        }
+        
+        async fn offering(&self, product_id: Uuid) -> Result<Vec<Supplier>, CoreError> {
+            Ok(self
+                .suppliers
+                .lock()
+                .unwrap()
+                .values()
+                .filter(|s| s.is_active && s.product(product_id).is_some_and(|p| p.is_available))
+                .cloned()
+                .collect())
+        }
+        
+        async fn insert(&self, supplier: &Supplier) -> Result<(), CoreError> {
+            self.suppliers.lock().unwrap().insert(supplier.id, supplier.clone());
+            Ok(())
+        }
+        
+        async fn update(&self, supplier: &Supplier, expected_updated_at: DateTime<Utc>) -> Result<(), CoreError> {
+            let mut suppliers = self.suppliers.lock().unwrap();
+            match suppliers.get(&supplier.id) {
+                Some(current) if current.updated_at == expected_updated_at => {
+                    suppliers.insert(supplier.id, supplier.clone());
+                    Ok(())
+                }
+                _ => Err(stale(supplier)),
+            }
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use std::borrow::Cow;
+use validator::{Validate, ValidationError, ValidationErrors, ValidationErrorsKind};
+use delpopolo_core::{CoreError, FieldError};
+use delpopolo_domain::{Cnpj, Cpf, Email, Phone};
+
+/// Valida o DTO e converte as falhas em `CoreError::InvalidFields` (422)
+pub fn validate<T: Validate>(value: &T) -> Result<(), CoreError> {
+    let errors = field_errors(value);
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(CoreError::invalid_fields(errors))
+    }
+}
+
+/// Falhas do `validator` como `FieldError`, com caminhos como `items[0].quantity`
+pub fn field_errors<T: Validate>(value: &T) -> Vec<FieldError> {
+    let mut errors = Vec::new();
+    if let Err(e) = value.validate() {
+        collect("", &e, &mut errors);
+    }
+    errors.sort_by(|a, b| a.field.cmp(&b.field));
+    errors
+}
+
+fn collect(prefix: &str, errors: &ValidationErrors, out: &mut Vec<FieldError>) {
+    let path = |name: &str| if prefix.is_empty() { name.to_string() } else { format!("{}.{}", prefix, name) };
+    
+    for (field, kind) in errors.errors() {
+        match kind {
+            ValidationErrorsKind::Field(list) => {
+                for error in list {
+                    // Validações de struct (`schema`) informam o campo no `code`
+                    let name = if *field == "__all__" { error.code.as_ref() } else { field };
+                    let message = error.message.as_deref().unwrap_or("Valor inválido");
+                    out.push(FieldError::new(path(name), message));
+                }
+            }
+            ValidationErrorsKind::Struct(inner) => collect(&path(field), inner, out),
+            ValidationErrorsKind::List(items) => {
+                for (i, inner) in items {
+                    collect(&format!("{}[{}]", path(field), i), inner, out);
+                }
+            }
+        }
+    }
+}
+
+/// Erro com mensagem fixa, para as funções `custom`/`schema`
+pub fn error(code: &'static str, message: &'static str) -> ValidationError {
+    ValidationError::new(code).with_message(Cow::Borrowed(message))
+}
+
+pub fn not_blank(value: &str) -> Result<(), ValidationError> {
+    if value.trim().is_empty() {
+        return Err(error("not_blank", "Campo obrigatório"));
+    }
+    Ok(())
+}
+
+pub fn cpf(value: &str) -> Result<(), ValidationError> {
+    Cpf::new(value.to_string()).map(|_| ()).map_err(|_| error("cpf", "CPF inválido"))
+}
+
+pub fn cnpj(value: &str) -> Result<(), ValidationError> {
+    Cnpj::new(value.to_string()).map(|_| ()).map_err(|_| error("cnpj", "CNPJ inválido"))
+}
+
+pub fn email(value: &str) -> Result<(), ValidationError> {
+    Email::new(value.to_string()).map(|_| ()).map_err(|_| error("email", "E-mail inválido"))
+}
+
+pub fn phone(value: &str) -> Result<(), ValidationError> {
+    Phone::new(value.to_string())
+        .map(|_| ())
+        .map_err(|_| error("phone", "Telefone deve ter DDD e 8 ou 9 dígitos"))
+}
+
+pub fn digits(value: &str) -> Result<(), ValidationError> {
+    if !value.chars().all(|c| c.is_ascii_digit()) {
+        return Err(error("digits", "Use apenas dígitos"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+    use super::*;
+    
+    #[derive(Deserialize, Validate)]
+    struct Item {
+        #[validate(range(exclusive_min = 0.0, message = "Quantidade deve ser maior que zero"))]
+        quantity: f64,
+    }
+    
+    #[derive(Deserialize, Validate)]
+    struct Body {
+        #[validate(custom(function = "not_blank"))]
+        name: String,
+        #[validate(nested)]
+        items: Vec<Item>,
+    }
+    
+    #[test]
+    fn test_nested_errors_become_field_paths() {
+        let body: Body = serde_json::from_value(serde_json::json!({
+            "name": " ",
+            "items": [{"quantity": 1.0}, {"quantity": 0.0}]
+        }))
+        .unwrap();
+        
+        let Err(CoreError::InvalidFields(errors)) = validate(&body) else {
+            panic!("esperava erros de campo");
+        };
+        assert_eq!(errors, vec![
+            FieldError::new("items[1].quantity", "Quantidade deve ser maior que zero"),
+            FieldError::new("name", "Campo obrigatório"),
+        ]);
+    }
+}
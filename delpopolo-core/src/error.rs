@@ -1,5 +1,23 @@
+use serde::Serialize;
 use thiserror::Error;
 
+/// Erro de validação associado a um campo da requisição
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new<F: ToString, M: ToString>(field: F, message: M) -> Self {
+        Self {
+            field: field.to_string(),
+            message: message.to_string(),
+        }
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum CoreError {
     #[error("Internal error: {0}")]
@@ -11,6 +29,9 @@ pub enum CoreError {
     #[error("Validation error: {0}")]
     Validation(String),
     
+    #[error("Validation error: {} invalid field(s)", .0.len())]
+    InvalidFields(Vec<FieldError>),
+    
     #[error("Unauthorized: {0}")]
     Unauthorized(String),
     
@@ -20,6 +41,12 @@ pub enum CoreError {
     #[error("Conflict: {0}")]
     Conflict(String),
     
+    #[error("Rate limited: {0}")]
+    RateLimited(String),
+    
+    #[error("Payload too large: {0}")]
+    PayloadTooLarge(String),
+    
     #[error("External service error: {0}")]
     ExternalService(String),
     
@@ -43,6 +70,10 @@ impl CoreError {
         CoreError::Validation(msg.to_string())
     }
     
+    pub fn invalid_fields(errors: Vec<FieldError>) -> Self {
+        CoreError::InvalidFields(errors)
+    }
+    
     pub fn unauthorized<T: ToString>(msg: T) -> Self {
         CoreError::Unauthorized(msg.to_string())
     }
@@ -55,6 +86,14 @@ impl CoreError {
         CoreError::Conflict(msg.to_string())
     }
     
+    pub fn rate_limited<T: ToString>(msg: T) -> Self {
+        CoreError::RateLimited(msg.to_string())
+    }
+    
+    pub fn payload_too_large<T: ToString>(msg: T) -> Self {
+        CoreError::PayloadTooLarge(msg.to_string())
+    }
+    
     pub fn external_service<T: ToString>(msg: T) -> Self {
         CoreError::ExternalService(msg.to_string())
     }
@@ -2,9 +2,6 @@ pub mod traits;
 pub mod error;
 pub mod result;
 
-#[cfg(test)]
-mod tests;
-
 pub use traits::*;
 pub use error::*;
 pub use result::*;
@@ -122,7 +122,7 @@ impl Campaign {
     pub fn is_active(&self) -> bool {
         self.status == CampaignStatus::Active && 
         self.start_date <= Utc::now() &&
-        self.end_date.map_or(true, |end| end > Utc::now())
+        self.end_date.is_none_or(|end| end > Utc::now())
     }
     
     pub fn engagement_rate(&self) -> f32 {
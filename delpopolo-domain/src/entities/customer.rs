@@ -1,16 +1,41 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
-use delpopolo_core::traits::Entity;
+use delpopolo_core::{traits::Entity, CoreError};
 use crate::value_objects::{Cpf, Email, Phone, Address};
 
+pub const ANONYMIZED_NAME: &str = "Cliente anonimizado";
+
+/// Consentimentos LGPD dados pelo titular
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum ConsentKind {
+    Marketing,
+    Whatsapp,
+}
+
+/// Registro imutável de concessão ou revogação de um consentimento
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ConsentRecord {
+    pub id: Uuid,
+    pub customer_id: Uuid,
+    pub kind: ConsentKind,
+    pub granted: bool,
+    pub channel: Option<String>, // balcão, site, WhatsApp...
+    pub recorded_by: Option<Uuid>, // User ID
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct Customer {
     pub id: Uuid,
     
     pub name: String,
     
-    pub cpf: Cpf,
+    pub cpf: Option<Cpf>, // removido na anonimização; as notas fiscais guardam o próprio CPF
     
     pub email: Option<Email>,
     
@@ -39,6 +64,7 @@ pub struct Customer {
     pub fcm_token: Option<String>,
     
     pub last_order_at: Option<DateTime<Utc>>,
+    pub anonymized_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -49,7 +75,7 @@ impl Customer {
         Self {
             id: Uuid::new_v4(),
             name,
-            cpf,
+            cpf: Some(cpf),
             email: None,
             phone: None,
             address: None,
@@ -65,6 +91,7 @@ impl Customer {
             whatsapp_number: None,
             fcm_token: None,
             last_order_at: None,
+            anonymized_at: None,
             created_at: now,
             updated_at: now,
         }
@@ -82,6 +109,85 @@ impl Customer {
     pub fn can_receive_whatsapp(&self) -> bool {
         self.whatsapp_optin && self.whatsapp_number.is_some()
     }
+    
+    pub fn consent(&self, kind: ConsentKind) -> bool {
+        match kind {
+            ConsentKind::Marketing => self.accepts_marketing,
+            ConsentKind::Whatsapp => self.whatsapp_optin,
+        }
+    }
+    
+    /// Altera o consentimento e devolve o registro para o histórico;
+    /// `None` se nada mudou
+    pub fn set_consent(
+        &mut self,
+        kind: ConsentKind,
+        granted: bool,
+        channel: Option<String>,
+        recorded_by: Option<Uuid>,
+    ) -> Result<Option<ConsentRecord>, CoreError> {
+        self.ensure_not_anonymized()?;
+        if self.consent(kind) == granted {
+            return Ok(None);
+        }
+        if kind == ConsentKind::Whatsapp && granted && self.whatsapp_number.is_none() {
+            return Err(CoreError::validation("Informe o número de WhatsApp antes do opt-in"));
+        }
+        
+        match kind {
+            ConsentKind::Marketing => self.accepts_marketing = granted,
+            ConsentKind::Whatsapp => self.whatsapp_optin = granted,
+        }
+        self.updated_at = Utc::now();
+        
+        Ok(Some(ConsentRecord {
+            id: Uuid::new_v4(),
+            customer_id: self.id,
+            kind,
+            granted,
+            channel,
+            recorded_by,
+            created_at: self.updated_at,
+        }))
+    }
+    
+    pub fn is_anonymized(&self) -> bool {
+        self.anonymized_at.is_some()
+    }
+    
+    pub fn ensure_not_anonymized(&self) -> Result<(), CoreError> {
+        if self.is_anonymized() {
+            return Err(CoreError::conflict(format!("Cliente {} foi anonimizado", self.id)));
+        }
+        Ok(())
+    }
+    
+    /// Eliminação de dados pessoais (LGPD art. 18): revoga os consentimentos e apaga
+    /// tudo que identifica o titular. Totais de compras ficam, pois não identificam ninguém.
+    pub fn anonymize(&mut self, recorded_by: Option<Uuid>) -> Result<Vec<ConsentRecord>, CoreError> {
+        let mut revoked = Vec::new();
+        for kind in [ConsentKind::Marketing, ConsentKind::Whatsapp] {
+            if let Some(record) = self.set_consent(kind, false, Some("anonimização".to_string()), recorded_by)? {
+                revoked.push(record);
+            }
+        }
+        
+        self.name = ANONYMIZED_NAME.to_string();
+        self.cpf = None;
+        self.email = None;
+        self.phone = None;
+        self.address = None;
+        self.birth_date = None;
+        self.favorite_products.clear();
+        self.dietary_restrictions.clear();
+        self.whatsapp_number = None;
+        self.fcm_token = None;
+        self.is_active = false;
+        self.anonymized_at = Some(Utc::now());
+        self.updated_at = Utc::now();
+        
+        Ok(revoked)
+    }
 }
 
 impl Entity for Customer {
@@ -97,3 +203,42 @@ impl Entity for Customer {
         self.updated_at
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn customer() -> Customer {
+        let mut customer = Customer::new("Maria Souza".to_string(), Cpf::new("529.982.247-25".to_string()).unwrap());
+        customer.email = Some(Email::new("maria@example.com".to_string()).unwrap());
+        customer.total_orders = 3;
+        customer
+    }
+    
+    #[test]
+    fn test_consent_changes_are_recorded() {
+        let mut customer = customer();
+        
+        let record = customer.set_consent(ConsentKind::Marketing, true, None, None).unwrap().unwrap();
+        assert!(record.granted && customer.accepts_marketing);
+        assert!(customer.set_consent(ConsentKind::Marketing, true, None, None).unwrap().is_none());
+        
+        assert!(customer.set_consent(ConsentKind::Whatsapp, true, None, None).is_err());
+        customer.whatsapp_number = Some("11987654321".to_string());
+        assert!(customer.set_consent(ConsentKind::Whatsapp, true, None, None).unwrap().is_some());
+    }
+    
+    #[test]
+    fn test_anonymize_erases_personal_data() {
+        let mut customer = customer();
+        customer.set_consent(ConsentKind::Marketing, true, None, None).unwrap();
+        
+        let revoked = customer.anonymize(None).unwrap();
+        assert_eq!(revoked.len(), 1);
+        assert!(!revoked[0].granted);
+        assert_eq!(customer.name, ANONYMIZED_NAME);
+        assert!(customer.cpf.is_none() && customer.email.is_none());
+        assert_eq!(customer.total_orders, 3);
+        assert!(customer.anonymize(None).is_err());
+    }
+}
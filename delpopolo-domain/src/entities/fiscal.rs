@@ -0,0 +1,97 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use delpopolo_core::traits::Entity;
+
+/// Lançamento do livro fiscal: uma nota (entrada ou saída) com seus tributos
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FiscalEntry {
+    pub id: Uuid,
+    pub nfe_key: String,
+    pub number: String,
+    pub series: String,
+    pub model: String, // 55 = NF-e, 65 = NFC-e
+    pub issued_at: DateTime<Utc>,
+    
+    // Participante (emitente na entrada, destinatário na saída)
+    pub issuer_cnpj: String,
+    pub issuer_name: String,
+    pub recipient_document: String,
+    
+    pub total_value: f64,
+    pub products_value: f64,
+    pub discount_value: f64,
+    pub freight_value: f64,
+    pub icms_base: f64,
+    pub icms_value: f64,
+    pub icms_st_base: f64,
+    pub icms_st_value: f64,
+    pub ipi_value: f64,
+    pub pis_value: f64,
+    pub cofins_value: f64,
+    
+    pub items: Vec<FiscalEntryItem>,
+    
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Tributos de um item da nota
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FiscalEntryItem {
+    pub item_number: i32,
+    pub product_code: String,
+    pub description: String,
+    pub ncm: String,
+    pub cfop: String,
+    pub unit: String,
+    pub quantity: f64,
+    pub total_value: f64,
+    
+    pub icms_cst: Option<String>,
+    pub icms_base: f64,
+    pub icms_rate: f64,
+    pub icms_value: f64,
+    pub ipi_cst: Option<String>,
+    pub ipi_value: f64,
+    pub pis_cst: Option<String>,
+    pub pis_value: f64,
+    pub cofins_cst: Option<String>,
+    pub cofins_value: f64,
+}
+
+impl FiscalEntry {
+    /// Entrada (CFOP iniciado por 1, 2 ou 3) gera crédito; saída gera débito
+    pub fn is_inbound(&self) -> bool {
+        self.items.iter().all(|item| item.is_inbound())
+    }
+    
+    /// Créditos de ICMS, PIS e COFINS aproveitáveis da nota
+    pub fn tax_credits(&self) -> f64 {
+        if !self.is_inbound() {
+            return 0.0;
+        }
+        
+        self.items.iter().map(|item| item.icms_value + item.pis_value + item.cofins_value).sum()
+    }
+}
+
+impl FiscalEntryItem {
+    pub fn is_inbound(&self) -> bool {
+        matches!(self.cfop.chars().next(), Some('1' | '2' | '3'))
+    }
+}
+
+impl Entity for FiscalEntry {
+    fn id(&self) -> Uuid {
+        self.id
+    }
+    
+    fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+    
+    fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+}
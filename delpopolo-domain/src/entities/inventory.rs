@@ -65,7 +65,7 @@ impl Inventory {
         self.updated_at = Utc::now();
     }
     
-    fn recalculate_available(&mut self) {
+    pub fn recalculate_available(&mut self) {
         self.available_quantity = self.quantity - self.reserved_quantity;
     }
 }
@@ -85,6 +85,7 @@ impl Entity for Inventory {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct InventoryMovement {
     pub id: Uuid,
     pub product_id: Uuid,
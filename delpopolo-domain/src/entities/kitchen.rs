@@ -0,0 +1,96 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use delpopolo_core::CoreError;
+use crate::enums::{KitchenStation, ProductCategory};
+
+impl KitchenStation {
+    pub const ALL: [KitchenStation; 4] = [
+        KitchenStation::Bakery,
+        KitchenStation::Confectionery,
+        KitchenStation::Sandwich,
+        KitchenStation::Beverage,
+    ];
+    
+    /// Estação que prepara a categoria; `None` para o que sai pronto do balcão
+    pub fn for_category(category: ProductCategory) -> Option<Self> {
+        match category {
+            ProductCategory::Bread | ProductCategory::Pastry | ProductCategory::Cookie => Some(KitchenStation::Bakery),
+            ProductCategory::Cake => Some(KitchenStation::Confectionery),
+            ProductCategory::Sandwich | ProductCategory::Snack => Some(KitchenStation::Sandwich),
+            ProductCategory::Beverage => Some(KitchenStation::Beverage),
+            ProductCategory::RawMaterial | ProductCategory::Other => None,
+        }
+    }
+}
+
+/// Etapa do pedido numa estação
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum TicketStage {
+    Waiting,   // Na fila, ainda não começou
+    Preparing,
+    Done,      // Finalizado (bump); volta ao preparo com recall
+}
+
+/// Andamento de um pedido numa estação da cozinha
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KitchenTicket {
+    pub order_id: Uuid,
+    pub station: KitchenStation,
+    pub started_at: Option<DateTime<Utc>>,
+    pub bumped_at: Option<DateTime<Utc>>,
+}
+
+impl KitchenTicket {
+    pub fn new(order_id: Uuid, station: KitchenStation) -> Self {
+        Self { order_id, station, started_at: None, bumped_at: None }
+    }
+    
+    pub fn stage(&self) -> TicketStage {
+        match (self.started_at, self.bumped_at) {
+            (_, Some(_)) => TicketStage::Done,
+            (Some(_), None) => TicketStage::Preparing,
+            (None, None) => TicketStage::Waiting,
+        }
+    }
+    
+    /// O primeiro bump começa o preparo na estação; o segundo o finaliza
+    pub fn bump(&mut self) -> Result<TicketStage, CoreError> {
+        let now = Utc::now();
+        match self.stage() {
+            TicketStage::Waiting => self.started_at = Some(now),
+            TicketStage::Preparing => self.bumped_at = Some(now),
+            TicketStage::Done => return Err(CoreError::conflict("Pedido já finalizado nesta estação")),
+        }
+        Ok(self.stage())
+    }
+    
+    /// Devolve um pedido finalizado por engano para o preparo
+    pub fn recall(&mut self) -> Result<(), CoreError> {
+        if self.stage() != TicketStage::Done {
+            return Err(CoreError::conflict("Só pedidos finalizados podem ser chamados de volta"));
+        }
+        self.bumped_at = None;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_bump_and_recall() {
+        let mut ticket = KitchenTicket::new(Uuid::new_v4(), KitchenStation::Bakery);
+        assert!(ticket.recall().is_err());
+        
+        assert_eq!(ticket.bump().unwrap(), TicketStage::Preparing);
+        assert_eq!(ticket.bump().unwrap(), TicketStage::Done);
+        assert!(matches!(ticket.bump(), Err(CoreError::Conflict(_))));
+        
+        ticket.recall().unwrap();
+        assert_eq!(ticket.stage(), TicketStage::Preparing);
+        assert!(ticket.started_at.is_some());
+    }
+}
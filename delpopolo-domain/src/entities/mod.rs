@@ -8,14 +8,18 @@ pub mod turnstile;
 pub mod payment;
 pub mod user;
 pub mod notification;
+pub mod fiscal;
+pub mod kitchen;
 
 pub use product::Product;
-pub use customer::Customer;
-pub use order::Order;
-pub use supplier::Supplier;
+pub use customer::{ConsentKind, ConsentRecord, Customer};
+pub use order::{Order, OrderItem};
+pub use supplier::{Supplier, SupplierContact, SupplierProduct};
 pub use inventory::{Inventory, InventoryMovement};
 pub use campaign::Campaign;
 pub use turnstile::TurnstileEntry;
 pub use payment::Payment;
-pub use user::User;
+pub use user::{RefreshToken, User};
 pub use notification::Notification;
+pub use fiscal::{FiscalEntry, FiscalEntryItem};
+pub use kitchen::{KitchenTicket, TicketStage};
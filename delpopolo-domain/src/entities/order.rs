@@ -1,7 +1,8 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
-use delpopolo_core::traits::Entity;
+use delpopolo_core::{traits::Entity, CoreError};
+use crate::entities::Product;
 use crate::enums::{OrderStatus, OrderSource, PaymentMethod};
 use crate::value_objects::Money;
 
@@ -16,6 +17,38 @@ pub struct OrderItem {
     pub notes: Option<String>,
 }
 
+impl OrderItem {
+    /// Item com o preço atual do catálogo
+    pub fn from_product(product: &Product, quantity: f64, notes: Option<String>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            product_id: product.id,
+            product_name: product.name.clone(),
+            quantity,
+            unit_price: product.price.clone(),
+            total_price: product.price.multiply(quantity),
+            notes,
+        }
+    }
+}
+
+impl OrderStatus {
+    /// Transições permitidas do ciclo de vida do pedido
+    pub fn can_transition_to(&self, next: OrderStatus) -> bool {
+        use OrderStatus::*;
+        
+        matches!(
+            (self, next),
+            (Pending, Confirmed)
+                | (Pending | Confirmed, Preparing)
+                | (Preparing, Ready)
+                | (Ready, InDelivery)
+                | (Ready | InDelivery, Completed)
+                | (Pending | Confirmed | Preparing | Ready | InDelivery, Cancelled)
+        )
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Order {
     pub id: Uuid,
@@ -108,6 +141,46 @@ impl Order {
         self.recalculate_totals();
     }
     
+    /// Remove o item e devolve-o para liberar a reserva de estoque
+    pub fn remove_item(&mut self, item_id: Uuid) -> Option<OrderItem> {
+        let position = self.items.iter().position(|item| item.id == item_id)?;
+        let item = self.items.remove(position);
+        self.recalculate_totals();
+        
+        // O desconto nunca passa do novo subtotal
+        if self.discount.amount > self.subtotal.amount {
+            self.discount = Money::brl(self.subtotal.amount);
+            self.recalculate_totals();
+        }
+        Some(item)
+    }
+    
+    /// Itens e desconto só mudam antes da produção começar
+    pub fn is_editable(&self) -> bool {
+        matches!(self.status, OrderStatus::Pending | OrderStatus::Confirmed)
+    }
+    
+    pub fn ensure_editable(&self) -> Result<(), CoreError> {
+        if self.is_editable() {
+            Ok(())
+        } else {
+            Err(CoreError::conflict(format!(
+                "Pedido {} não pode ser alterado no status {:?}",
+                self.order_number, self.status
+            )))
+        }
+    }
+    
+    pub fn apply_discount(&mut self, discount: Money) -> Result<(), CoreError> {
+        self.ensure_editable()?;
+        if discount.amount < 0.0 || discount.amount > self.subtotal.amount {
+            return Err(CoreError::validation("Desconto deve estar entre zero e o subtotal do pedido"));
+        }
+        self.discount = discount;
+        self.recalculate_totals();
+        Ok(())
+    }
+    
     pub fn recalculate_totals(&mut self) {
         self.subtotal = self.items.iter()
             .map(|item| item.total_price.clone())
@@ -120,6 +193,38 @@ impl Order {
         self.updated_at = Utc::now();
     }
     
+    /// Aplica uma transição validada por `OrderStatus::can_transition_to`;
+    /// cancelamento exige motivo
+    pub fn transition_to(&mut self, next: OrderStatus, reason: Option<String>) -> Result<(), CoreError> {
+        if !self.status.can_transition_to(next) {
+            return Err(CoreError::conflict(format!(
+                "Pedido {} não pode passar de {:?} para {:?}",
+                self.order_number, self.status, next
+            )));
+        }
+        
+        match next {
+            OrderStatus::Confirmed => self.confirm(),
+            OrderStatus::Preparing => self.start_preparation(),
+            OrderStatus::Ready => self.mark_ready(),
+            OrderStatus::InDelivery => self.dispatch(),
+            OrderStatus::Completed => self.complete(),
+            OrderStatus::Cancelled => {
+                let reason = reason
+                    .filter(|r| !r.trim().is_empty())
+                    .ok_or_else(|| CoreError::validation("Motivo do cancelamento é obrigatório"))?;
+                self.cancel(reason);
+            }
+            OrderStatus::Pending => unreachable!("nenhuma transição volta para Pending"),
+        }
+        Ok(())
+    }
+    
+    pub fn confirm(&mut self) {
+        self.status = OrderStatus::Confirmed;
+        self.updated_at = Utc::now();
+    }
+    
     pub fn start_preparation(&mut self) {
         self.status = OrderStatus::Preparing;
         self.preparation_started_at = Some(Utc::now());
@@ -132,6 +237,34 @@ impl Order {
         self.updated_at = Utc::now();
     }
     
+    /// Volta um pedido pronto para o preparo (recall na cozinha), mantendo o início do preparo
+    pub fn reopen_preparation(&mut self) -> Result<(), CoreError> {
+        if self.status != OrderStatus::Ready {
+            return Err(CoreError::conflict(format!(
+                "Pedido {} não pode voltar ao preparo no status {:?}",
+                self.order_number, self.status
+            )));
+        }
+        self.status = OrderStatus::Preparing;
+        self.ready_at = None;
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+    
+    /// Horário prometido ao cliente: a entrega agendada ou, sem ela, a criação mais o tempo
+    /// estimado de preparo (ou `default_preparation_minutes` quando não há estimativa)
+    pub fn promised_at(&self, default_preparation_minutes: i64) -> DateTime<Utc> {
+        self.delivery_time.unwrap_or_else(|| {
+            let minutes = self.estimated_preparation_time.map_or(default_preparation_minutes, i64::from);
+            self.created_at + Duration::minutes(minutes)
+        })
+    }
+    
+    pub fn dispatch(&mut self) {
+        self.status = OrderStatus::InDelivery;
+        self.updated_at = Utc::now();
+    }
+    
     pub fn complete(&mut self) {
         self.status = OrderStatus::Completed;
         self.delivered_at = Some(Utc::now());
@@ -167,3 +300,66 @@ impl Entity for Order {
         self.updated_at
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enums::{ProductCategory, UnitOfMeasure};
+    
+    fn order_with_bread() -> Order {
+        let bread = Product::new("Pão Francês".to_string(), ProductCategory::Bread, UnitOfMeasure::Kilogram, Money::brl(18.0), Money::brl(7.0));
+        let mut order = Order::new(OrderSource::InStore);
+        order.add_item(OrderItem::from_product(&bread, 0.5, None));
+        order
+    }
+    
+    #[test]
+    fn test_lifecycle_transitions() {
+        let mut order = order_with_bread();
+        
+        assert!(order.transition_to(OrderStatus::Ready, None).is_err());
+        order.transition_to(OrderStatus::Preparing, None).unwrap();
+        assert!(order.preparation_started_at.is_some());
+        assert!(order.ensure_editable().is_err());
+        
+        order.transition_to(OrderStatus::Ready, None).unwrap();
+        order.reopen_preparation().unwrap();
+        assert!(order.ready_at.is_none() && order.preparation_started_at.is_some());
+        assert!(order.reopen_preparation().is_err());
+        order.transition_to(OrderStatus::Ready, None).unwrap();
+        assert!(order.transition_to(OrderStatus::Cancelled, Some(" ".to_string())).is_err());
+        order.transition_to(OrderStatus::Completed, None).unwrap();
+        assert!(matches!(
+            order.transition_to(OrderStatus::Cancelled, Some("desistência".to_string())),
+            Err(CoreError::Conflict(_))
+        ));
+    }
+    
+    #[test]
+    fn test_promised_at_prefers_scheduled_delivery() {
+        let mut order = order_with_bread();
+        assert_eq!(order.promised_at(20), order.created_at + Duration::minutes(20));
+        
+        order.estimated_preparation_time = Some(45);
+        assert_eq!(order.promised_at(20), order.created_at + Duration::minutes(45));
+        
+        let scheduled = order.created_at + Duration::hours(3);
+        order.delivery_time = Some(scheduled);
+        assert_eq!(order.promised_at(20), scheduled);
+    }
+    
+    #[test]
+    fn test_items_and_discount() {
+        let mut order = order_with_bread();
+        assert_eq!(order.total.amount, 9.0);
+        
+        assert!(order.apply_discount(Money::brl(10.0)).is_err());
+        order.apply_discount(Money::brl(1.0)).unwrap();
+        assert_eq!(order.total.amount, 8.0);
+        
+        let item_id = order.items[0].id;
+        assert!(order.remove_item(item_id).is_some());
+        assert!(order.remove_item(item_id).is_none());
+        assert_eq!(order.subtotal.amount, 0.0);
+    }
+}
@@ -1 +1,106 @@
-pub struct Product{pub id:uuid::Uuid}
\ No newline at end of file
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use delpopolo_core::traits::Entity;
+use crate::enums::{ProductCategory, UnitOfMeasure};
+use crate::value_objects::Money;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Product {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub sku: String,
+    pub barcode: Option<String>, // EAN/GTIN
+    pub category: ProductCategory,
+    pub unit_of_measure: UnitOfMeasure,
+    pub price: Money,
+    pub cost: Money,
+    pub stock_quantity: f64,
+    pub min_stock_level: f64,
+    pub max_stock_level: Option<f64>,
+    pub is_active: bool,
+    pub is_available_online: bool,
+    pub image_url: Option<String>,
+    pub weight: Option<f64>, // kg
+    pub preparation_time_minutes: Option<i32>,
+    pub supplier_id: Option<Uuid>,
+    pub nfe_ncm: Option<String>,
+    pub nfe_cest: Option<String>,
+    pub nfe_cfop: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Product {
+    pub fn new(
+        name: String,
+        category: ProductCategory,
+        unit_of_measure: UnitOfMeasure,
+        price: Money,
+        cost: Money,
+    ) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            name,
+            description: None,
+            sku: String::new(),
+            barcode: None,
+            category,
+            unit_of_measure,
+            price,
+            cost,
+            stock_quantity: 0.0,
+            min_stock_level: 0.0,
+            max_stock_level: None,
+            is_active: true,
+            is_available_online: true,
+            image_url: None,
+            weight: None,
+            preparation_time_minutes: None,
+            supplier_id: None,
+            nfe_ncm: None,
+            nfe_cest: None,
+            nfe_cfop: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+    
+    pub fn is_low_stock(&self) -> bool {
+        self.stock_quantity <= self.min_stock_level
+    }
+    
+    pub fn update_price(&mut self, price: Money) {
+        self.price = price;
+        self.updated_at = Utc::now();
+    }
+    
+    pub fn set_image(&mut self, image_url: String) {
+        self.image_url = Some(image_url);
+        self.updated_at = Utc::now();
+    }
+    
+    /// Margem sobre o preço de venda (0.0 - 1.0)
+    pub fn margin(&self) -> f64 {
+        if self.price.amount <= 0.0 {
+            return 0.0;
+        }
+        (self.price.amount - self.cost.amount) / self.price.amount
+    }
+}
+
+impl Entity for Product {
+    fn id(&self) -> Uuid {
+        self.id
+    }
+    
+    fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+    
+    fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+}
@@ -1,8 +1,8 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
-use delpopolo_core::traits::Entity;
-use crate::value_objects::{Cnpj, Email, Phone};
+use delpopolo_core::{traits::Entity, CoreError};
+use crate::value_objects::{Address, Cnpj, Email, Phone};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Supplier {
@@ -19,6 +19,12 @@ pub struct Supplier {
     pub phone: Option<Phone>,
     pub whatsapp: Option<String>,
     pub contact_person: Option<String>,
+    pub contacts: Vec<SupplierContact>,
+    pub address: Option<Address>,
+    
+    // Catálogo: o que o fornecedor vende, por quanto e em quanto tempo entrega
+    pub products: Vec<SupplierProduct>,
+    pub rating: Option<f32>, // 0 a 5
     
     pub is_active: bool,
     pub is_preferred: bool,
@@ -27,8 +33,117 @@ pub struct Supplier {
     pub updated_at: DateTime<Utc>,
 }
 
+/// Pessoa de contato no fornecedor (comercial, financeiro, entrega...)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SupplierContact {
+    pub name: String,
+    pub role: Option<String>,
+    pub email: Option<Email>,
+    pub phone: Option<Phone>,
+}
+
+/// Item do catálogo do fornecedor
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SupplierProduct {
+    pub product_id: Uuid,
+    pub supplier_sku: Option<String>,
+    pub unit_price: f64,
+    pub min_order_quantity: Option<f64>,
+    pub lead_time_days: Option<i32>,
+    pub is_available: bool,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Supplier {
+    pub fn new(name: String) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            name,
+            trade_name: None,
+            cnpj: None,
+            email: None,
+            phone: None,
+            whatsapp: None,
+            contact_person: None,
+            contacts: Vec::new(),
+            address: None,
+            products: Vec::new(),
+            rating: None,
+            is_active: true,
+            is_preferred: false,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+    
+    pub fn product(&self, product_id: Uuid) -> Option<&SupplierProduct> {
+        self.products.iter().find(|p| p.product_id == product_id)
+    }
+    
+    /// Inclui ou substitui o item do catálogo
+    pub fn upsert_product(&mut self, product: SupplierProduct) -> Result<(), CoreError> {
+        if !product.unit_price.is_finite() || product.unit_price <= 0.0 {
+            return Err(CoreError::validation("Preço unitário deve ser maior que zero"));
+        }
+        if product.min_order_quantity.is_some_and(|q| !q.is_finite() || q <= 0.0) {
+            return Err(CoreError::validation("Pedido mínimo deve ser maior que zero"));
+        }
+        if product.lead_time_days.is_some_and(|d| d < 0) {
+            return Err(CoreError::validation("Prazo de entrega não pode ser negativo"));
+        }
+        
+        match self.products.iter_mut().find(|p| p.product_id == product.product_id) {
+            Some(existing) => *existing = product,
+            None => self.products.push(product),
+        }
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+    
+    pub fn remove_product(&mut self, product_id: Uuid) -> Option<SupplierProduct> {
+        let position = self.products.iter().position(|p| p.product_id == product_id)?;
+        self.updated_at = Utc::now();
+        Some(self.products.remove(position))
+    }
+}
+
 impl Entity for Supplier {
     fn id(&self) -> Uuid { self.id }
     fn created_at(&self) -> DateTime<Utc> { self.created_at }
     fn updated_at(&self) -> DateTime<Utc> { self.updated_at }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn item(product_id: Uuid, unit_price: f64) -> SupplierProduct {
+        SupplierProduct {
+            product_id,
+            supplier_sku: None,
+            unit_price,
+            min_order_quantity: Some(25.0),
+            lead_time_days: Some(2),
+            is_available: true,
+            updated_at: Utc::now(),
+        }
+    }
+    
+    #[test]
+    fn test_catalog_upsert_replaces_existing_item() {
+        let mut supplier = Supplier::new("Moinho Paulista".to_string());
+        let farinha = Uuid::new_v4();
+        
+        supplier.upsert_product(item(farinha, 4.2)).unwrap();
+        supplier.upsert_product(item(farinha, 3.9)).unwrap();
+        assert_eq!(supplier.products.len(), 1);
+        assert_eq!(supplier.product(farinha).unwrap().unit_price, 3.9);
+        
+        assert!(supplier.upsert_product(item(farinha, 0.0)).is_err());
+        assert!(supplier.remove_product(farinha).is_some());
+        assert!(supplier.product(farinha).is_none());
+    }
+}
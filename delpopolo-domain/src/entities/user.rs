@@ -1,8 +1,9 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use delpopolo_core::traits::Entity;
 use crate::enums::UserRole;
+use crate::permissions::Permission;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct User {
@@ -13,6 +14,8 @@ pub struct User {
     pub role: UserRole,
     pub is_active: bool,
     pub last_login_at: Option<DateTime<Utc>>,
+    pub failed_login_attempts: i32,
+    pub locked_until: Option<DateTime<Utc>>, // Bloqueio após tentativas de login malsucedidas
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -28,6 +31,8 @@ impl User {
             role,
             is_active: true,
             last_login_at: None,
+            failed_login_attempts: 0,
+            locked_until: None,
             created_at: now,
             updated_at: now,
         }
@@ -35,15 +40,41 @@ impl User {
     
     pub fn update_last_login(&mut self) {
         self.last_login_at = Some(Utc::now());
+        self.failed_login_attempts = 0;
+        self.locked_until = None;
         self.updated_at = Utc::now();
     }
     
+    pub fn is_locked(&self) -> bool {
+        self.locked_until.is_some_and(|until| until > Utc::now())
+    }
+    
+    /// Registra uma senha incorreta; ao atingir `max_attempts` bloqueia a conta
+    pub fn register_failed_login(&mut self, max_attempts: i32, lockout: Duration) {
+        self.failed_login_attempts += 1;
+        if self.failed_login_attempts >= max_attempts {
+            self.locked_until = Some(Utc::now() + lockout);
+            self.failed_login_attempts = 0;
+        }
+        self.updated_at = Utc::now();
+    }
+    
+    pub fn change_password(&mut self, password_hash: String) {
+        self.password_hash = password_hash;
+        self.updated_at = Utc::now();
+    }
+    
+    /// Usuários inativos não têm permissão alguma
+    pub fn has_permission(&self, permission: Permission) -> bool {
+        self.is_active && self.role.has_permission(permission)
+    }
+    
     pub fn can_manage_inventory(&self) -> bool {
-        matches!(self.role, UserRole::Admin | UserRole::Manager | UserRole::InventoryManager)
+        self.has_permission(Permission::InventoryWrite)
     }
     
     pub fn can_manage_orders(&self) -> bool {
-        matches!(self.role, UserRole::Admin | UserRole::Manager | UserRole::Cashier)
+        self.has_permission(Permission::OrdersWrite)
     }
 }
 
@@ -60,3 +91,34 @@ impl Entity for User {
         self.updated_at
     }
 }
+
+/// Refresh token emitido no login. Tokens da mesma `family_id` vêm de rotações
+/// sucessivas; reutilizar um token já rotacionado revoga a família inteira.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RefreshToken {
+    pub id: Uuid, // jti do JWT
+    pub user_id: Uuid,
+    pub family_id: Uuid,
+    pub expires_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub replaced_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl RefreshToken {
+    pub fn new(id: Uuid, user_id: Uuid, family_id: Uuid, expires_at: DateTime<Utc>) -> Self {
+        Self {
+            id,
+            user_id,
+            family_id,
+            expires_at,
+            revoked_at: None,
+            replaced_by: None,
+            created_at: Utc::now(),
+        }
+    }
+    
+    pub fn is_active(&self) -> bool {
+        self.revoked_at.is_none() && self.expires_at > Utc::now()
+    }
+}
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub enum ProductCategory {
     Bread,
     Cake,
@@ -14,6 +15,7 @@ pub enum ProductCategory {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub enum UnitOfMeasure {
     Unit,      // unidade
     Kilogram,  // kg
@@ -25,6 +27,7 @@ pub enum UnitOfMeasure {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub enum OrderStatus {
     Pending,
     Confirmed,
@@ -36,6 +39,7 @@ pub enum OrderStatus {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub enum OrderSource {
     InStore,
     IFood,
@@ -45,6 +49,7 @@ pub enum OrderSource {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub enum PaymentMethod {
     Cash,
     DebitCard,
@@ -66,6 +71,7 @@ pub enum PaymentStatus {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub enum MovementType {
     Purchase,      // Compra de fornecedor
     Sale,          // Venda para cliente
@@ -135,6 +141,7 @@ pub enum NotificationStatus {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub enum UserRole {
     Admin,
     Manager,
@@ -143,3 +150,12 @@ pub enum UserRole {
     Kitchen,
     Delivery,
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum KitchenStation {
+    Bakery,        // Pães, salgados assados e biscoitos
+    Confectionery, // Bolos e doces
+    Sandwich,      // Sanduíches e lanches
+    Beverage,      // Cafés e bebidas
+}
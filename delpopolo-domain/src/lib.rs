@@ -2,7 +2,9 @@ pub mod entities;
 pub mod value_objects;
 pub mod enums;
 pub mod repositories;
+pub mod permissions;
 
 pub use entities::*;
 pub use value_objects::*;
 pub use enums::*;
+pub use permissions::Permission;
@@ -0,0 +1,143 @@
+use serde::{Deserialize, Serialize};
+use crate::enums::UserRole;
+
+/// Ações protegidas da API; cada papel recebe um conjunto fixo delas
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Permission {
+    ProductsRead,
+    ProductsWrite,
+    OrdersRead,
+    OrdersWrite,
+    KitchenOperate,
+    DeliveriesManage,
+    CustomersRead,
+    CustomersWrite,
+    CustomersPrivacy, // exportação e anonimização (LGPD)
+    InventoryRead,
+    InventoryWrite,
+    SuppliersRead,
+    SuppliersWrite,
+    FiscalRead,
+    FiscalEmit,
+    ReportsRead,
+    UsersManage,
+}
+
+impl Permission {
+    pub const ALL: [Permission; 17] = [
+        Permission::ProductsRead,
+        Permission::ProductsWrite,
+        Permission::OrdersRead,
+        Permission::OrdersWrite,
+        Permission::KitchenOperate,
+        Permission::DeliveriesManage,
+        Permission::CustomersRead,
+        Permission::CustomersWrite,
+        Permission::CustomersPrivacy,
+        Permission::InventoryRead,
+        Permission::InventoryWrite,
+        Permission::SuppliersRead,
+        Permission::SuppliersWrite,
+        Permission::FiscalRead,
+        Permission::FiscalEmit,
+        Permission::ReportsRead,
+        Permission::UsersManage,
+    ];
+}
+
+const MANAGER: &[Permission] = &[
+    Permission::ProductsRead,
+    Permission::ProductsWrite,
+    Permission::OrdersRead,
+    Permission::OrdersWrite,
+    Permission::KitchenOperate,
+    Permission::DeliveriesManage,
+    Permission::CustomersRead,
+    Permission::CustomersWrite,
+    Permission::CustomersPrivacy,
+    Permission::InventoryRead,
+    Permission::InventoryWrite,
+    Permission::SuppliersRead,
+    Permission::SuppliersWrite,
+    Permission::FiscalRead,
+    Permission::FiscalEmit,
+    Permission::ReportsRead,
+];
+
+const CASHIER: &[Permission] = &[
+    Permission::ProductsRead,
+    Permission::OrdersRead,
+    Permission::OrdersWrite,
+    Permission::CustomersRead,
+    Permission::CustomersWrite,
+    Permission::FiscalEmit,
+];
+
+const INVENTORY_MANAGER: &[Permission] = &[
+    Permission::ProductsRead,
+    Permission::ProductsWrite,
+    Permission::InventoryRead,
+    Permission::InventoryWrite,
+    Permission::SuppliersRead,
+    Permission::SuppliersWrite,
+    Permission::FiscalRead,
+];
+
+const KITCHEN: &[Permission] = &[
+    Permission::ProductsRead,
+    Permission::OrdersRead,
+    Permission::KitchenOperate,
+    Permission::InventoryRead,
+];
+
+const DELIVERY: &[Permission] = &[
+    Permission::OrdersRead,
+    Permission::DeliveriesManage,
+    Permission::CustomersRead,
+];
+
+impl UserRole {
+    pub fn permissions(&self) -> &'static [Permission] {
+        match self {
+            UserRole::Admin => &Permission::ALL,
+            UserRole::Manager => MANAGER,
+            UserRole::Cashier => CASHIER,
+            UserRole::InventoryManager => INVENTORY_MANAGER,
+            UserRole::Kitchen => KITCHEN,
+            UserRole::Delivery => DELIVERY,
+        }
+    }
+    
+    pub fn has_permission(&self, permission: Permission) -> bool {
+        self.permissions().contains(&permission)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_admin_has_everything_and_only_admin_manages_users() {
+        for permission in Permission::ALL {
+            assert!(UserRole::Admin.has_permission(permission));
+        }
+        
+        for role in [UserRole::Manager, UserRole::Cashier, UserRole::InventoryManager, UserRole::Kitchen, UserRole::Delivery] {
+            assert!(!role.has_permission(Permission::UsersManage), "{:?}", role);
+        }
+    }
+    
+    #[test]
+    fn test_operational_roles_are_restricted() {
+        assert!(UserRole::Cashier.has_permission(Permission::OrdersWrite));
+        assert!(!UserRole::Cashier.has_permission(Permission::InventoryWrite));
+        assert!(!UserRole::Cashier.has_permission(Permission::CustomersPrivacy));
+        assert!(UserRole::Kitchen.has_permission(Permission::KitchenOperate));
+        assert!(!UserRole::Kitchen.has_permission(Permission::OrdersWrite));
+        assert!(!UserRole::Delivery.has_permission(Permission::ProductsRead));
+        assert!(UserRole::InventoryManager.has_permission(Permission::SuppliersWrite));
+        assert!(!UserRole::InventoryManager.has_permission(Permission::FiscalEmit));
+    }
+}
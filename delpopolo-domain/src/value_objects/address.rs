@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 use delpopolo_core::traits::ValueObject;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct Address {
     pub street: String,
     pub number: String,
@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 use delpopolo_core::traits::ValueObject;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct Cnpj(String);
 
 impl Cnpj {
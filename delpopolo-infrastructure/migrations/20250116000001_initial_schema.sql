@@ -1 +1 @@
-﻿-- SQLite migration
+-- SQLite migration
@@ -0,0 +1,53 @@
+-- Livro fiscal: tributos das NF-e importadas/emitidas
+
+CREATE TABLE IF NOT EXISTS fiscal_entries (
+    id UUID PRIMARY KEY,
+    nfe_key CHAR(44) NOT NULL UNIQUE,
+    number VARCHAR(9) NOT NULL,
+    series VARCHAR(3) NOT NULL,
+    model CHAR(2) NOT NULL,
+    issued_at TIMESTAMPTZ NOT NULL,
+    issuer_cnpj VARCHAR(14) NOT NULL,
+    issuer_name VARCHAR(255) NOT NULL,
+    recipient_document VARCHAR(14) NOT NULL,
+    total_value DOUBLE PRECISION NOT NULL,
+    products_value DOUBLE PRECISION NOT NULL,
+    discount_value DOUBLE PRECISION NOT NULL,
+    freight_value DOUBLE PRECISION NOT NULL,
+    icms_base DOUBLE PRECISION NOT NULL,
+    icms_value DOUBLE PRECISION NOT NULL,
+    icms_st_base DOUBLE PRECISION NOT NULL,
+    icms_st_value DOUBLE PRECISION NOT NULL,
+    ipi_value DOUBLE PRECISION NOT NULL,
+    pis_value DOUBLE PRECISION NOT NULL,
+    cofins_value DOUBLE PRECISION NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_fiscal_entries_issued_at ON fiscal_entries (issued_at);
+
+CREATE TABLE IF NOT EXISTS fiscal_entry_items (
+    entry_id UUID NOT NULL REFERENCES fiscal_entries (id) ON DELETE CASCADE,
+    item_number INTEGER NOT NULL,
+    product_code VARCHAR(60) NOT NULL,
+    description VARCHAR(120) NOT NULL,
+    ncm VARCHAR(8) NOT NULL,
+    cfop CHAR(4) NOT NULL,
+    unit VARCHAR(6) NOT NULL,
+    quantity DOUBLE PRECISION NOT NULL,
+    total_value DOUBLE PRECISION NOT NULL,
+    icms_cst VARCHAR(3),
+    icms_base DOUBLE PRECISION NOT NULL,
+    icms_rate DOUBLE PRECISION NOT NULL,
+    icms_value DOUBLE PRECISION NOT NULL,
+    ipi_cst VARCHAR(2),
+    ipi_value DOUBLE PRECISION NOT NULL,
+    pis_cst VARCHAR(2),
+    pis_value DOUBLE PRECISION NOT NULL,
+    cofins_cst VARCHAR(2),
+    cofins_value DOUBLE PRECISION NOT NULL,
+    PRIMARY KEY (entry_id, item_number)
+);
+
+CREATE INDEX IF NOT EXISTS idx_fiscal_entry_items_cfop ON fiscal_entry_items (cfop, icms_cst);
@@ -0,0 +1,38 @@
+-- Catálogo de produtos (categoria e unidade gravadas como texto snake_case)
+
+CREATE TABLE IF NOT EXISTS products (
+    id UUID PRIMARY KEY,
+    name VARCHAR(255) NOT NULL,
+    description TEXT,
+    sku VARCHAR(64) NOT NULL,
+    barcode VARCHAR(14),
+    category VARCHAR(32) NOT NULL,
+    unit_of_measure VARCHAR(16) NOT NULL,
+    price_amount DOUBLE PRECISION NOT NULL,
+    price_currency CHAR(3) NOT NULL DEFAULT 'BRL',
+    cost_amount DOUBLE PRECISION NOT NULL,
+    cost_currency CHAR(3) NOT NULL DEFAULT 'BRL',
+    stock_quantity DOUBLE PRECISION NOT NULL DEFAULT 0,
+    min_stock_level DOUBLE PRECISION NOT NULL DEFAULT 0,
+    max_stock_level DOUBLE PRECISION,
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    is_available_online BOOLEAN NOT NULL DEFAULT TRUE,
+    image_url TEXT,
+    weight DOUBLE PRECISION,
+    preparation_time_minutes INTEGER,
+    supplier_id UUID,
+    nfe_ncm VARCHAR(8),
+    nfe_cest VARCHAR(7),
+    nfe_cfop VARCHAR(4),
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_products_sku ON products (sku) WHERE sku <> '';
+CREATE UNIQUE INDEX IF NOT EXISTS idx_products_barcode ON products (barcode);
+
+-- Paginação por cursor: (coluna de ordenação, id)
+CREATE INDEX IF NOT EXISTS idx_products_name_id ON products (name, id);
+CREATE INDEX IF NOT EXISTS idx_products_price_id ON products (price_amount, id);
+CREATE INDEX IF NOT EXISTS idx_products_created_id ON products (created_at, id);
+CREATE INDEX IF NOT EXISTS idx_products_category ON products (category) WHERE is_active;
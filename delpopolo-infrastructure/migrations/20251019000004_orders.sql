@@ -0,0 +1,53 @@
+-- Pedidos e itens (status, origem e forma de pagamento gravados como texto snake_case)
+
+CREATE TABLE IF NOT EXISTS orders (
+    id UUID PRIMARY KEY,
+    order_number VARCHAR(32) NOT NULL,
+    customer_id UUID,
+    customer_name VARCHAR(255),
+    customer_cpf VARCHAR(14),
+    currency CHAR(3) NOT NULL DEFAULT 'BRL',
+    subtotal DOUBLE PRECISION NOT NULL,
+    discount DOUBLE PRECISION NOT NULL DEFAULT 0,
+    delivery_fee DOUBLE PRECISION NOT NULL DEFAULT 0,
+    total DOUBLE PRECISION NOT NULL,
+    status VARCHAR(16) NOT NULL,
+    source VARCHAR(16) NOT NULL,
+    payment_method VARCHAR(16),
+    payment_id UUID,
+    is_paid BOOLEAN NOT NULL DEFAULT FALSE,
+    delivery_address TEXT,
+    delivery_time TIMESTAMPTZ,
+    ifood_order_id VARCHAR(64),
+    ifood_reference VARCHAR(64),
+    table_number VARCHAR(16),
+    turnstile_entry_id UUID,
+    notes TEXT,
+    estimated_preparation_time INTEGER,
+    preparation_started_at TIMESTAMPTZ,
+    ready_at TIMESTAMPTZ,
+    delivered_at TIMESTAMPTZ,
+    cancelled_at TIMESTAMPTZ,
+    cancellation_reason TEXT,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_orders_number ON orders (order_number);
+CREATE INDEX IF NOT EXISTS idx_orders_created_id ON orders (created_at DESC, id DESC);
+CREATE INDEX IF NOT EXISTS idx_orders_status ON orders (status, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_orders_customer ON orders (customer_id, created_at DESC) WHERE customer_id IS NOT NULL;
+
+CREATE TABLE IF NOT EXISTS order_items (
+    id UUID PRIMARY KEY,
+    order_id UUID NOT NULL REFERENCES orders (id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    product_id UUID NOT NULL,
+    product_name VARCHAR(255) NOT NULL,
+    quantity DOUBLE PRECISION NOT NULL,
+    unit_price DOUBLE PRECISION NOT NULL,
+    total_price DOUBLE PRECISION NOT NULL,
+    notes TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_order_items_order ON order_items (order_id, position);
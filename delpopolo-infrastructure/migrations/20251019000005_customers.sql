@@ -0,0 +1,42 @@
+-- Clientes e histórico de consentimentos (LGPD)
+
+CREATE TABLE IF NOT EXISTS customers (
+    id UUID PRIMARY KEY,
+    name VARCHAR(255) NOT NULL,
+    cpf VARCHAR(11), -- NULL após anonimização
+    email VARCHAR(255),
+    phone VARCHAR(11),
+    address JSONB,
+    birth_date TIMESTAMPTZ,
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    accepts_marketing BOOLEAN NOT NULL DEFAULT FALSE,
+    loyalty_points INTEGER NOT NULL DEFAULT 0,
+    total_orders INTEGER NOT NULL DEFAULT 0,
+    total_spent DOUBLE PRECISION NOT NULL DEFAULT 0,
+    favorite_products UUID[] NOT NULL DEFAULT '{}',
+    dietary_restrictions TEXT[] NOT NULL DEFAULT '{}',
+    whatsapp_optin BOOLEAN NOT NULL DEFAULT FALSE,
+    whatsapp_number VARCHAR(20),
+    fcm_token TEXT,
+    last_order_at TIMESTAMPTZ,
+    anonymized_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_customers_cpf ON customers (cpf) WHERE cpf IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_customers_phone ON customers (phone);
+CREATE INDEX IF NOT EXISTS idx_customers_name_id ON customers (name, id);
+
+-- Somente inserção: cada concessão ou revogação vira uma linha
+CREATE TABLE IF NOT EXISTS customer_consents (
+    id UUID PRIMARY KEY,
+    customer_id UUID NOT NULL REFERENCES customers (id),
+    kind VARCHAR(16) NOT NULL,
+    granted BOOLEAN NOT NULL,
+    channel VARCHAR(64),
+    recorded_by UUID,
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_customer_consents_customer ON customer_consents (customer_id, created_at);
@@ -0,0 +1,39 @@
+-- Saldo por produto, razão de movimentações e requisições já processadas
+
+CREATE TABLE IF NOT EXISTS inventory (
+    id UUID PRIMARY KEY,
+    product_id UUID NOT NULL UNIQUE REFERENCES products (id),
+    quantity DOUBLE PRECISION NOT NULL DEFAULT 0,
+    reserved_quantity DOUBLE PRECISION NOT NULL DEFAULT 0,
+    available_quantity DOUBLE PRECISION NOT NULL DEFAULT 0,
+    last_movement_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS inventory_movements (
+    id UUID PRIMARY KEY,
+    product_id UUID NOT NULL REFERENCES products (id),
+    movement_type VARCHAR(16) NOT NULL,
+    quantity DOUBLE PRECISION NOT NULL, -- ajuste guarda a diferença com sinal
+    unit_cost DOUBLE PRECISION,
+    total_cost DOUBLE PRECISION,
+    order_id UUID,
+    supplier_id UUID,
+    nfe_key VARCHAR(44),
+    notes TEXT,
+    performed_by UUID,
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_inventory_movements_product ON inventory_movements (product_id, created_at, id);
+CREATE INDEX IF NOT EXISTS idx_inventory_movements_nfe ON inventory_movements (nfe_key) WHERE nfe_key IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_inventory_movements_supplier ON inventory_movements (supplier_id) WHERE supplier_id IS NOT NULL;
+
+-- Idempotência: o cliente envia um request_id por operação
+CREATE TABLE IF NOT EXISTS inventory_requests (
+    request_id UUID PRIMARY KEY,
+    payload JSONB NOT NULL,
+    response JSONB,
+    created_at TIMESTAMPTZ NOT NULL
+);
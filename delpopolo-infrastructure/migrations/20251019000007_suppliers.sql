@@ -0,0 +1,37 @@
+-- Fornecedores e catálogo de produtos por fornecedor
+
+CREATE TABLE IF NOT EXISTS suppliers (
+    id UUID PRIMARY KEY,
+    name VARCHAR(255) NOT NULL,
+    trade_name VARCHAR(255),
+    cnpj VARCHAR(14),
+    email VARCHAR(255),
+    phone VARCHAR(11),
+    whatsapp VARCHAR(20),
+    contact_person VARCHAR(255),
+    contacts JSONB NOT NULL DEFAULT '[]',
+    address JSONB,
+    rating REAL,
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    is_preferred BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_suppliers_cnpj ON suppliers (cnpj) WHERE cnpj IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_suppliers_name_id ON suppliers (name, id);
+
+CREATE TABLE IF NOT EXISTS supplier_products (
+    supplier_id UUID NOT NULL REFERENCES suppliers (id),
+    product_id UUID NOT NULL REFERENCES products (id),
+    supplier_sku VARCHAR(64),
+    unit_price DOUBLE PRECISION NOT NULL,
+    min_order_quantity DOUBLE PRECISION,
+    lead_time_days INTEGER,
+    is_available BOOLEAN NOT NULL DEFAULT TRUE,
+    updated_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (supplier_id, product_id)
+);
+
+-- Cotações de reposição partem do produto
+CREATE INDEX IF NOT EXISTS idx_supplier_products_product ON supplier_products (product_id) WHERE is_available;
@@ -0,0 +1,9 @@
+-- Andamento de cada pedido por estação da cozinha (KDS); estação sem linha ainda não começou
+
+CREATE TABLE IF NOT EXISTS kitchen_tickets (
+    order_id UUID NOT NULL REFERENCES orders (id) ON DELETE CASCADE,
+    station VARCHAR(16) NOT NULL,
+    started_at TIMESTAMPTZ,
+    bumped_at TIMESTAMPTZ,
+    PRIMARY KEY (order_id, station)
+);
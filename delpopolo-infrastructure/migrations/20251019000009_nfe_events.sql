@@ -0,0 +1,14 @@
+-- Eventos de NF-e já aplicados (cancelamento, CC-e, manifestação), um por (chave, tpEvento, nSeqEvento)
+
+CREATE TABLE IF NOT EXISTS nfe_events (
+    nfe_key VARCHAR(44) NOT NULL,
+    event_type VARCHAR(6) NOT NULL,
+    sequence INTEGER NOT NULL,
+    event_at TIMESTAMPTZ NOT NULL,
+    author_cnpj VARCHAR(14),
+    justification TEXT,
+    correction TEXT,             -- xCorrecao da CC-e
+    protocol VARCHAR(20),
+    processed_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (nfe_key, event_type, sequence)
+);
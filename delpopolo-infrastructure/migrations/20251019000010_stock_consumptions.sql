@@ -0,0 +1,15 @@
+-- Baixas de venda dos pedidos concluídos: gravadas na mesma transação que conclui o
+-- pedido e marcadas como feitas na mesma transação que atualiza o saldo
+
+CREATE TABLE IF NOT EXISTS stock_consumptions (
+    order_id UUID NOT NULL REFERENCES orders (id),
+    product_id UUID NOT NULL REFERENCES products (id),
+    quantity DOUBLE PRECISION NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT,
+    created_at TIMESTAMPTZ NOT NULL,
+    consumed_at TIMESTAMPTZ,
+    PRIMARY KEY (order_id, product_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_stock_consumptions_pending ON stock_consumptions (created_at) WHERE consumed_at IS NULL;
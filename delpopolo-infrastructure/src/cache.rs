@@ -1,7 +1,29 @@
-use redis::{Client, aio::ConnectionManager, AsyncCommands};
+use std::sync::OnceLock;
+use redis::{Client, aio::ConnectionManager, AsyncCommands, ExistenceCheck, Script, SetExpiry, SetOptions};
 use anyhow::Result;
+use futures_util::{Stream, StreamExt};
 use serde::{Serialize, Deserialize};
 
+/// Reabastece o balde pelo tempo decorrido (relógio do Redis) e consome uma ficha se houver
+const TAKE_TOKEN: &str = r#"
+local capacity = tonumber(ARGV[1])
+local interval = tonumber(ARGV[2])
+local time = redis.call('TIME')
+local now = tonumber(time[1]) * 1000 + math.floor(tonumber(time[2]) / 1000)
+local state = redis.call('HMGET', KEYS[1], 'tokens', 'at')
+local tokens = tonumber(state[1]) or capacity
+local at = tonumber(state[2]) or now
+tokens = math.min(capacity, tokens + math.max(0, now - at) / interval)
+local taken = 0
+if tokens >= 1 then
+    tokens = tokens - 1
+    taken = 1
+end
+redis.call('HSET', KEYS[1], 'tokens', tostring(tokens), 'at', now)
+redis.call('PEXPIRE', KEYS[1], math.ceil(capacity * interval))
+return {taken, tostring(tokens)}
+"#;
+
 #[derive(Clone)]
 pub struct Cache {
     manager: ConnectionManager,
@@ -25,15 +47,25 @@ impl Cache {
     pub async fn set<T: Serialize>(&mut self, key: &str, value: &T, ttl_seconds: Option<usize>) -> Result<()> {
         let serialized = serde_json::to_string(value)?;
         if let Some(ttl) = ttl_seconds {
-            self.manager.set_ex(key, serialized, ttl).await?;
+            self.manager.set_ex::<_, _, ()>(key, serialized, ttl as u64).await?;
         } else {
-            self.manager.set(key, serialized).await?;
+            self.manager.set::<_, _, ()>(key, serialized).await?;
         }
         Ok(())
     }
     
+    /// Grava só se a chave ainda não existir (`SET NX EX`); `false` quando já havia valor
+    pub async fn set_if_absent<T: Serialize>(&mut self, key: &str, value: &T, ttl_seconds: u64) -> Result<bool> {
+        let serialized = serde_json::to_string(value)?;
+        let options = SetOptions::default()
+            .conditional_set(ExistenceCheck::NX)
+            .with_expiration(SetExpiry::EX(ttl_seconds));
+        let created: Option<String> = self.manager.set_options(key, serialized, options).await?;
+        Ok(created.is_some())
+    }
+    
     pub async fn delete(&mut self, key: &str) -> Result<()> {
-        self.manager.del(key).await?;
+        self.manager.del::<_, ()>(key).await?;
         Ok(())
     }
     
@@ -47,11 +79,39 @@ impl Cache {
         Ok(value)
     }
     
+    /// Balde de `capacity` fichas que ganha uma a cada `interval_ms`, numa operação atômica.
+    /// Devolve se a ficha foi consumida e quantas sobraram
+    pub async fn take_token(&mut self, key: &str, capacity: u64, interval_ms: f64) -> Result<(bool, f64)> {
+        static SCRIPT: OnceLock<Script> = OnceLock::new();
+        let (taken, tokens): (i64, String) = SCRIPT
+            .get_or_init(|| Script::new(TAKE_TOKEN))
+            .key(key)
+            .arg(capacity)
+            .arg(interval_ms)
+            .invoke_async(&mut self.manager)
+            .await?;
+        Ok((taken == 1, tokens.parse()?))
+    }
+    
     pub async fn expire(&mut self, key: &str, seconds: usize) -> Result<()> {
-        self.manager.expire(key, seconds as i64).await?;
+        self.manager.expire::<_, ()>(key, seconds as i64).await?;
         Ok(())
     }
     
+    pub async fn publish<T: Serialize>(&mut self, channel: &str, message: &T) -> Result<()> {
+        let serialized = serde_json::to_string(message)?;
+        self.manager.publish::<_, _, ()>(channel, serialized).await?;
+        Ok(())
+    }
+    
+    /// Mensagens de um canal, numa conexão própria: a do `Cache` não pode ficar em modo pub/sub.
+    /// O stream termina quando a conexão cai.
+    pub async fn subscribe(redis_url: &str, channel: &str) -> Result<impl Stream<Item = String>> {
+        let mut pubsub = Client::open(redis_url)?.get_async_pubsub().await?;
+        pubsub.subscribe(channel).await?;
+        Ok(pubsub.into_on_message().filter_map(|message| async move { message.get_payload::<String>().ok() }))
+    }
+    
     pub async fn health_check(&mut self) -> Result<()> {
         let _: String = redis::cmd("PING").query_async(&mut self.manager).await?;
         Ok(())
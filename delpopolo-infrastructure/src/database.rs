@@ -1,19 +1,22 @@
-use sqlx::sqlite::SqlitePool;
+use sqlx::postgres::{PgPool, PgPoolOptions};
 use anyhow::Result;
 use tracing::{info, error};
 
 #[derive(Clone)]
 pub struct Database {
-    pool: SqlitePool,
+    pool: PgPool,
 }
 
 impl Database {
     pub async fn new(database_url: &str) -> Result<Self> {
-        info!("Connecting to SQLite database: {}", database_url);
+        info!("Connecting to PostgreSQL database");
         
-        let pool = SqlitePool::connect(database_url).await?;
+        let pool = PgPoolOptions::new()
+            .max_connections(10)
+            .connect(database_url)
+            .await?;
         
-        info!("Successfully connected to SQLite database");
+        info!("Successfully connected to PostgreSQL database");
         
         Ok(Self { pool })
     }
@@ -29,7 +32,7 @@ impl Database {
         Ok(())
     }
     
-    pub fn pool(&self) -> &SqlitePool {
+    pub fn pool(&self) -> &PgPool {
         &self.pool
     }
     
@@ -1,10 +1,16 @@
 pub mod database;
+#[cfg(feature = "redis")]
 pub mod cache;
+#[cfg(feature = "lapin")]
 pub mod queue;
 pub mod repositories;
 pub mod config;
+pub mod secret;
 
 pub use database::Database;
+#[cfg(feature = "redis")]
 pub use cache::Cache;
+#[cfg(feature = "lapin")]
 pub use queue::Queue;
 pub use config::Config;
+pub use secret::Secret;
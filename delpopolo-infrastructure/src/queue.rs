@@ -6,6 +6,7 @@ use lapin::{
 };
 use anyhow::Result;
 use serde::{Serialize, Deserialize};
+use futures_util::StreamExt;
 
 #[derive(Clone)]
 pub struct Queue {
@@ -19,6 +20,30 @@ impl Queue {
         Ok(Self { channel })
     }
     
+    /// O canal só fica aberto enquanto a conexão responde aos heartbeats
+    pub async fn health_check(&self) -> Result<()> {
+        if !self.channel.status().connected() {
+            anyhow::bail!("RabbitMQ channel is closed");
+        }
+        Ok(())
+    }
+    
+    /// Mensagens prontas para entrega (declaração passiva: falha se a fila não existe)
+    pub async fn message_count(&self, queue_name: &str) -> Result<u32> {
+        let queue = self
+            .channel
+            .queue_declare(
+                queue_name,
+                QueueDeclareOptions {
+                    passive: true,
+                    ..Default::default()
+                },
+                FieldTable::default(),
+            )
+            .await?;
+        Ok(queue.message_count())
+    }
+    
     pub async fn declare_queue(&self, queue_name: &str) -> Result<()> {
         self.channel
             .queue_declare(
@@ -75,16 +100,12 @@ impl Queue {
         
         tokio::spawn(async move {
             while let Some(delivery) = consumer.next().await {
-                if let Ok((channel, delivery)) = delivery {
+                if let Ok(delivery) = delivery {
                     if let Ok(message) = serde_json::from_slice::<T>(&delivery.data) {
                         if handler(message).is_ok() {
-                            let _ = channel.basic_ack(delivery.delivery_tag, Default::default()).await;
+                            let _ = delivery.acker.ack(Default::default()).await;
                         } else {
-                            let _ = channel.basic_nack(
-                                delivery.delivery_tag,
-                                Default::default(),
-                                Default::default(),
-                            ).await;
+                            let _ = delivery.acker.nack(Default::default()).await;
                         }
                     }
                 }
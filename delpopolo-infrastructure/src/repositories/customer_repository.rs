@@ -1,17 +1,382 @@
-use sqlx::PgPool;
-use delpopolo_domain::Customer;
+use sqlx::{PgPool, Postgres, QueryBuilder, Transaction};
+use uuid::Uuid;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use delpopolo_domain::{Address, ConsentKind, ConsentRecord, Cpf, Customer, Email, Phone};
+
+const CUSTOMER_COLUMNS: &str = r#"
+    id, name, cpf, email, phone, address, birth_date,
+    is_active, accepts_marketing, loyalty_points, total_orders, total_spent,
+    favorite_products, dietary_restrictions,
+    whatsapp_optin, whatsapp_number, fcm_token,
+    last_order_at, anonymized_at, created_at, updated_at
+"#;
+
+/// Posição após o último cliente da página anterior (ordem alfabética)
+#[derive(Debug, Clone, PartialEq)]
+pub struct CustomerCursor {
+    pub name: String,
+    pub id: Uuid,
+}
+
+/// Busca de clientes; os filtros informados são combinados
+#[derive(Debug, Clone)]
+pub struct CustomerQuery {
+    pub cpf: Option<String>, // só dígitos, comparação exata
+    pub phone: Option<String>, // só dígitos, telefone ou WhatsApp
+    pub name: Option<String>,
+    pub active: Option<bool>,
+    pub after: Option<CustomerCursor>,
+    pub limit: i64,
+}
+
+impl Default for CustomerQuery {
+    fn default() -> Self {
+        Self {
+            cpf: None,
+            phone: None,
+            name: None,
+            active: None,
+            after: None,
+            limit: 50,
+        }
+    }
+}
+
+impl CustomerQuery {
+    /// Mesmo critério do SQL, para armazenamentos em memória
+    pub fn matches(&self, customer: &Customer) -> bool {
+        let phone_ok = self.phone.as_deref().is_none_or(|phone| {
+            customer.phone.as_ref().is_some_and(|p| p.value().contains(phone))
+                || customer.whatsapp_number.as_deref().is_some_and(|w| w.contains(phone))
+        });
+        let name_ok = self
+            .name
+            .as_deref()
+            .is_none_or(|name| customer.name.to_lowercase().contains(&name.to_lowercase()));
+        
+        phone_ok
+            && name_ok
+            && self.cpf.as_deref().is_none_or(|cpf| customer.cpf.as_ref().is_some_and(|c| c.value() == cpf))
+            && self.active.is_none_or(|a| customer.is_active == a)
+    }
+}
 
 pub struct CustomerRepository {
-    _pool: PgPool,
+    pool: PgPool,
 }
 
 impl CustomerRepository {
     pub fn new(pool: PgPool) -> Self {
-        Self { _pool: pool }
+        Self { pool }
+    }
+    
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Option<Customer>> {
+        let customer = sqlx::query_as!(
+            CustomerRow,
+            r#"
+            SELECT
+                id, name, cpf, email, phone, address, birth_date,
+                is_active, accepts_marketing, loyalty_points, total_orders, total_spent,
+                favorite_products, dietary_restrictions,
+                whatsapp_optin, whatsapp_number, fcm_token,
+                last_order_at, anonymized_at, created_at, updated_at
+            FROM customers
+            WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        
+        customer.map(Customer::try_from).transpose()
+    }
+    
+    pub async fn find_by_cpf(&self, cpf: &str) -> Result<Option<Customer>> {
+        let customer = sqlx::query_as!(
+            CustomerRow,
+            r#"
+            SELECT
+                id, name, cpf, email, phone, address, birth_date,
+                is_active, accepts_marketing, loyalty_points, total_orders, total_spent,
+                favorite_products, dietary_restrictions,
+                whatsapp_optin, whatsapp_number, fcm_token,
+                last_order_at, anonymized_at, created_at, updated_at
+            FROM customers
+            WHERE cpf = $1
+            "#,
+            cpf
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        
+        customer.map(Customer::try_from).transpose()
+    }
+    
+    /// Listagem paginada por nome; retorna até `limit` clientes após o cursor
+    pub async fn list(&self, query: &CustomerQuery) -> Result<Vec<Customer>> {
+        let mut builder: QueryBuilder<Postgres> =
+            QueryBuilder::new(format!("SELECT {} FROM customers WHERE true", CUSTOMER_COLUMNS));
+        
+        if let Some(cpf) = &query.cpf {
+            builder.push(" AND cpf = ").push_bind(cpf.clone());
+        }
+        if let Some(phone) = &query.phone {
+            let pattern = format!("%{}%", phone);
+            builder
+                .push(" AND (phone LIKE ")
+                .push_bind(pattern.clone())
+                .push(" OR whatsapp_number LIKE ")
+                .push_bind(pattern)
+                .push(")");
+        }
+        if let Some(name) = &query.name {
+            builder.push(" AND name ILIKE ").push_bind(format!("%{}%", name));
+        }
+        if let Some(active) = query.active {
+            builder.push(" AND is_active = ").push_bind(active);
+        }
+        if let Some(cursor) = &query.after {
+            builder
+                .push(" AND (name, id) > (")
+                .push_bind(cursor.name.clone())
+                .push(", ")
+                .push_bind(cursor.id)
+                .push(")");
+        }
+        
+        builder.push(" ORDER BY name, id LIMIT ").push_bind(query.limit);
+        
+        let rows: Vec<CustomerRow> = builder.build_query_as().fetch_all(&self.pool).await?;
+        rows.into_iter().map(Customer::try_from).collect()
+    }
+    
+    /// Grava o cliente e os consentimentos iniciais numa única transação
+    pub async fn insert(&self, customer: &Customer, consents: &[ConsentRecord]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        
+        sqlx::query!(
+            r#"
+            INSERT INTO customers (
+                id, name, cpf, email, phone, address, birth_date,
+                is_active, accepts_marketing, loyalty_points, total_orders, total_spent,
+                favorite_products, dietary_restrictions,
+                whatsapp_optin, whatsapp_number, fcm_token,
+                last_order_at, anonymized_at, created_at, updated_at
+            ) VALUES (
+                $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11,
+                $12, $13, $14, $15, $16, $17, $18, $19, $20, $21
+            )
+            "#,
+            customer.id,
+            customer.name,
+            customer.cpf.as_ref().map(|c| c.value()),
+            customer.email.as_ref().map(|e| e.value()),
+            customer.phone.as_ref().map(|p| p.value()),
+            customer.address.as_ref().map(serde_json::to_value).transpose()?,
+            customer.birth_date,
+            customer.is_active,
+            customer.accepts_marketing,
+            customer.loyalty_points,
+            customer.total_orders,
+            customer.total_spent,
+            &customer.favorite_products,
+            &customer.dietary_restrictions,
+            customer.whatsapp_optin,
+            customer.whatsapp_number,
+            customer.fcm_token,
+            customer.last_order_at,
+            customer.anonymized_at,
+            customer.created_at,
+            customer.updated_at
+        )
+        .execute(&mut *tx)
+        .await?;
+        
+        Self::insert_consents(&mut tx, consents).await?;
+        tx.commit().await?;
+        
+        Ok(())
+    }
+    
+    /// Atualiza o cliente e acrescenta os consentimentos alterados numa única transação
+    pub async fn update(&self, customer: &Customer, consents: &[ConsentRecord]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        
+        sqlx::query!(
+            r#"
+            UPDATE customers SET
+                name = $2, cpf = $3, email = $4, phone = $5, address = $6, birth_date = $7,
+                is_active = $8, accepts_marketing = $9, loyalty_points = $10,
+                total_orders = $11, total_spent = $12,
+                favorite_products = $13, dietary_restrictions = $14,
+                whatsapp_optin = $15, whatsapp_number = $16, fcm_token = $17,
+                last_order_at = $18, anonymized_at = $19, updated_at = $20
+            WHERE id = $1
+            "#,
+            customer.id,
+            customer.name,
+            customer.cpf.as_ref().map(|c| c.value()),
+            customer.email.as_ref().map(|e| e.value()),
+            customer.phone.as_ref().map(|p| p.value()),
+            customer.address.as_ref().map(serde_json::to_value).transpose()?,
+            customer.birth_date,
+            customer.is_active,
+            customer.accepts_marketing,
+            customer.loyalty_points,
+            customer.total_orders,
+            customer.total_spent,
+            &customer.favorite_products,
+            &customer.dietary_restrictions,
+            customer.whatsapp_optin,
+            customer.whatsapp_number,
+            customer.fcm_token,
+            customer.last_order_at,
+            customer.anonymized_at,
+            customer.updated_at
+        )
+        .execute(&mut *tx)
+        .await?;
+        
+        Self::insert_consents(&mut tx, consents).await?;
+        tx.commit().await?;
+        
+        Ok(())
+    }
+    
+    /// Histórico de consentimentos em ordem cronológica
+    pub async fn consents(&self, customer_id: Uuid) -> Result<Vec<ConsentRecord>> {
+        let rows = sqlx::query_as!(
+            ConsentRow,
+            r#"
+            SELECT id, customer_id, kind, granted, channel, recorded_by, created_at
+            FROM customer_consents
+            WHERE customer_id = $1
+            ORDER BY created_at, id
+            "#,
+            customer_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        
+        rows.into_iter().map(ConsentRecord::try_from).collect()
+    }
+    
+    async fn insert_consents(tx: &mut Transaction<'_, Postgres>, consents: &[ConsentRecord]) -> Result<()> {
+        for consent in consents {
+            sqlx::query!(
+                r#"
+                INSERT INTO customer_consents (id, customer_id, kind, granted, channel, recorded_by, created_at)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
+                "#,
+                consent.id,
+                consent.customer_id,
+                consent_kind_to_str(consent.kind),
+                consent.granted,
+                consent.channel,
+                consent.recorded_by,
+                consent.created_at
+            )
+            .execute(&mut **tx)
+            .await?;
+        }
+        Ok(())
+    }
+}
+
+fn consent_kind_to_str(kind: ConsentKind) -> &'static str {
+    match kind {
+        ConsentKind::Marketing => "marketing",
+        ConsentKind::Whatsapp => "whatsapp",
+    }
+}
+
+fn consent_kind_from_str(kind: &str) -> Result<ConsentKind> {
+    Ok(match kind {
+        "marketing" => ConsentKind::Marketing,
+        "whatsapp" => ConsentKind::Whatsapp,
+        other => anyhow::bail!("Unknown consent kind: {}", other),
+    })
+}
+
+// Helper structs for sqlx mapping
+#[derive(sqlx::FromRow)]
+struct CustomerRow {
+    id: Uuid,
+    name: String,
+    cpf: Option<String>,
+    email: Option<String>,
+    phone: Option<String>,
+    address: Option<serde_json::Value>,
+    birth_date: Option<DateTime<Utc>>,
+    is_active: bool,
+    accepts_marketing: bool,
+    loyalty_points: i32,
+    total_orders: i32,
+    total_spent: f64,
+    favorite_products: Vec<Uuid>,
+    dietary_restrictions: Vec<String>,
+    whatsapp_optin: bool,
+    whatsapp_number: Option<String>,
+    fcm_token: Option<String>,
+    last_order_at: Option<DateTime<Utc>>,
+    anonymized_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl TryFrom<CustomerRow> for Customer {
+    type Error = anyhow::Error;
+    
+    fn try_from(row: CustomerRow) -> Result<Self> {
+        Ok(Customer {
+            id: row.id,
+            name: row.name,
+            cpf: row.cpf.map(Cpf::new).transpose().map_err(anyhow::Error::msg)?,
+            email: row.email.map(Email::new).transpose().map_err(anyhow::Error::msg)?,
+            phone: row.phone.map(Phone::new).transpose().map_err(anyhow::Error::msg)?,
+            address: row.address.map(serde_json::from_value::<Address>).transpose()?,
+            birth_date: row.birth_date,
+            is_active: row.is_active,
+            accepts_marketing: row.accepts_marketing,
+            loyalty_points: row.loyalty_points,
+            total_orders: row.total_orders,
+            total_spent: row.total_spent,
+            favorite_products: row.favorite_products,
+            dietary_restrictions: row.dietary_restrictions,
+            whatsapp_optin: row.whatsapp_optin,
+            whatsapp_number: row.whatsapp_number,
+            fcm_token: row.fcm_token,
+            last_order_at: row.last_order_at,
+            anonymized_at: row.anonymized_at,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        })
     }
+}
+
+struct ConsentRow {
+    id: Uuid,
+    customer_id: Uuid,
+    kind: String,
+    granted: bool,
+    channel: Option<String>,
+    recorded_by: Option<Uuid>,
+    created_at: DateTime<Utc>,
+}
+
+impl TryFrom<ConsentRow> for ConsentRecord {
+    type Error = anyhow::Error;
     
-    pub async fn find_by_cpf(&self, _cpf: &str) -> Option<Customer> {
-        // TODO: Implement
-        None
+    fn try_from(row: ConsentRow) -> Result<Self> {
+        Ok(ConsentRecord {
+            id: row.id,
+            customer_id: row.customer_id,
+            kind: consent_kind_from_str(&row.kind)?,
+            granted: row.granted,
+            channel: row.channel,
+            recorded_by: row.recorded_by,
+            created_at: row.created_at,
+        })
     }
 }
@@ -0,0 +1,275 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use delpopolo_domain::{FiscalEntry, FiscalEntryItem};
+
+pub struct FiscalRepository {
+    pool: PgPool,
+}
+
+impl FiscalRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+    
+    /// Grava a nota e seus itens; retorna `false` se a chave já estava lançada
+    pub async fn save(&self, entry: &FiscalEntry) -> Result<bool> {
+        let mut tx = self.pool.begin().await?;
+        
+        let inserted = sqlx::query!(
+            r#"
+            INSERT INTO fiscal_entries (
+                id, nfe_key, number, series, model, issued_at, issuer_cnpj, issuer_name,
+                recipient_document, total_value, products_value, discount_value, freight_value,
+                icms_base, icms_value, icms_st_base, icms_st_value, ipi_value, pis_value,
+                cofins_value, created_at, updated_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16,
+                      $17, $18, $19, $20, $21, $22)
+            ON CONFLICT (nfe_key) DO NOTHING
+            "#,
+            entry.id,
+            entry.nfe_key,
+            entry.number,
+            entry.series,
+            entry.model,
+            entry.issued_at,
+            entry.issuer_cnpj,
+            entry.issuer_name,
+            entry.recipient_document,
+            entry.total_value,
+            entry.products_value,
+            entry.discount_value,
+            entry.freight_value,
+            entry.icms_base,
+            entry.icms_value,
+            entry.icms_st_base,
+            entry.icms_st_value,
+            entry.ipi_value,
+            entry.pis_value,
+            entry.cofins_value,
+            entry.created_at,
+            entry.updated_at
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected() > 0;
+        
+        if !inserted {
+            tx.rollback().await?;
+            return Ok(false);
+        }
+        
+        for item in &entry.items {
+            sqlx::query!(
+                r#"
+                INSERT INTO fiscal_entry_items (
+                    entry_id, item_number, product_code, description, ncm, cfop, unit,
+                    quantity, total_value, icms_cst, icms_base, icms_rate, icms_value,
+                    ipi_cst, ipi_value, pis_cst, pis_value, cofins_cst, cofins_value
+                ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15,
+                          $16, $17, $18, $19)
+                "#,
+                entry.id,
+                item.item_number,
+                item.product_code,
+                item.description,
+                item.ncm,
+                item.cfop,
+                item.unit,
+                item.quantity,
+                item.total_value,
+                item.icms_cst,
+                item.icms_base,
+                item.icms_rate,
+                item.icms_value,
+                item.ipi_cst,
+                item.ipi_value,
+                item.pis_cst,
+                item.pis_value,
+                item.cofins_cst,
+                item.cofins_value
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        
+        tx.commit().await?;
+        
+        Ok(true)
+    }
+    
+    pub async fn find_by_nfe_key(&self, nfe_key: &str) -> Result<Option<FiscalEntry>> {
+        let row = sqlx::query_as!(
+            EntryRow,
+            r#"
+            SELECT id, nfe_key, number, series, model, issued_at, issuer_cnpj, issuer_name,
+                   recipient_document, total_value, products_value, discount_value, freight_value,
+                   icms_base, icms_value, icms_st_base, icms_st_value, ipi_value, pis_value,
+                   cofins_value, created_at, updated_at
+            FROM fiscal_entries
+            WHERE nfe_key = $1
+            "#,
+            nfe_key
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        
+        match row {
+            Some(row) => Ok(Some(self.with_items(vec![row]).await?.remove(0))),
+            None => Ok(None),
+        }
+    }
+    
+    /// Notas emitidas no intervalo `[start, end)`
+    pub async fn find_by_period(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Result<Vec<FiscalEntry>> {
+        let rows = sqlx::query_as!(
+            EntryRow,
+            r#"
+            SELECT id, nfe_key, number, series, model, issued_at, issuer_cnpj, issuer_name,
+                   recipient_document, total_value, products_value, discount_value, freight_value,
+                   icms_base, icms_value, icms_st_base, icms_st_value, ipi_value, pis_value,
+                   cofins_value, created_at, updated_at
+            FROM fiscal_entries
+            WHERE issued_at >= $1 AND issued_at < $2
+            ORDER BY issued_at, nfe_key
+            "#,
+            start,
+            end
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        
+        self.with_items(rows).await
+    }
+    
+    async fn with_items(&self, rows: Vec<EntryRow>) -> Result<Vec<FiscalEntry>> {
+        let ids: Vec<Uuid> = rows.iter().map(|row| row.id).collect();
+        
+        let items = sqlx::query_as!(
+            ItemRow,
+            r#"
+            SELECT entry_id, item_number, product_code, description, ncm, cfop, unit,
+                   quantity, total_value, icms_cst, icms_base, icms_rate, icms_value,
+                   ipi_cst, ipi_value, pis_cst, pis_value, cofins_cst, cofins_value
+            FROM fiscal_entry_items
+            WHERE entry_id = ANY($1)
+            ORDER BY entry_id, item_number
+            "#,
+            &ids
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        
+        let mut entries: Vec<FiscalEntry> = rows.into_iter().map(|row| row.into()).collect();
+        for item in items {
+            if let Some(entry) = entries.iter_mut().find(|e| e.id == item.entry_id) {
+                entry.items.push(item.into());
+            }
+        }
+        
+        Ok(entries)
+    }
+}
+
+struct EntryRow {
+    id: Uuid,
+    nfe_key: String,
+    number: String,
+    series: String,
+    model: String,
+    issued_at: DateTime<Utc>,
+    issuer_cnpj: String,
+    issuer_name: String,
+    recipient_document: String,
+    total_value: f64,
+    products_value: f64,
+    discount_value: f64,
+    freight_value: f64,
+    icms_base: f64,
+    icms_value: f64,
+    icms_st_base: f64,
+    icms_st_value: f64,
+    ipi_value: f64,
+    pis_value: f64,
+    cofins_value: f64,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<EntryRow> for FiscalEntry {
+    fn from(row: EntryRow) -> Self {
+        FiscalEntry {
+            id: row.id,
+            nfe_key: row.nfe_key,
+            number: row.number,
+            series: row.series,
+            model: row.model,
+            issued_at: row.issued_at,
+            issuer_cnpj: row.issuer_cnpj,
+            issuer_name: row.issuer_name,
+            recipient_document: row.recipient_document,
+            total_value: row.total_value,
+            products_value: row.products_value,
+            discount_value: row.discount_value,
+            freight_value: row.freight_value,
+            icms_base: row.icms_base,
+            icms_value: row.icms_value,
+            icms_st_base: row.icms_st_base,
+            icms_st_value: row.icms_st_value,
+            ipi_value: row.ipi_value,
+            pis_value: row.pis_value,
+            cofins_value: row.cofins_value,
+            items: Vec::new(),
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+struct ItemRow {
+    entry_id: Uuid,
+    item_number: i32,
+    product_code: String,
+    description: String,
+    ncm: String,
+    cfop: String,
+    unit: String,
+    quantity: f64,
+    total_value: f64,
+    icms_cst: Option<String>,
+    icms_base: f64,
+    icms_rate: f64,
+    icms_value: f64,
+    ipi_cst: Option<String>,
+    ipi_value: f64,
+    pis_cst: Option<String>,
+    pis_value: f64,
+    cofins_cst: Option<String>,
+    cofins_value: f64,
+}
+
+impl From<ItemRow> for FiscalEntryItem {
+    fn from(row: ItemRow) -> Self {
+        FiscalEntryItem {
+            item_number: row.item_number,
+            product_code: row.product_code,
+            description: row.description,
+            ncm: row.ncm,
+            cfop: row.cfop,
+            unit: row.unit,
+            quantity: row.quantity,
+            total_value: row.total_value,
+            icms_cst: row.icms_cst,
+            icms_base: row.icms_base,
+            icms_rate: row.icms_rate,
+            icms_value: row.icms_value,
+            ipi_cst: row.ipi_cst,
+            ipi_value: row.ipi_value,
+            pis_cst: row.pis_cst,
+            pis_value: row.pis_value,
+            cofins_cst: row.cofins_cst,
+            cofins_value: row.cofins_value,
+        }
+    }
+}
@@ -1,7 +1,85 @@
-use sqlx::PgPool;
+use sqlx::{PgConnection, PgPool, Postgres, QueryBuilder, Transaction};
 use uuid::Uuid;
 use anyhow::Result;
-use delpopolo_domain::{Inventory, InventoryMovement};
+use chrono::{DateTime, Utc};
+use delpopolo_domain::{Inventory, InventoryMovement, MovementType};
+
+const MOVEMENT_COLUMNS: &str = r#"
+    id, product_id, movement_type, quantity, unit_cost, total_cost,
+    order_id, supplier_id, nfe_key, notes, performed_by, created_at
+"#;
+
+/// Posição após a última movimentação da página anterior (mais recentes primeiro)
+#[derive(Debug, Clone, PartialEq)]
+pub struct MovementCursor {
+    pub created_at: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+/// Filtros do razão de movimentações de um produto
+#[derive(Debug, Clone)]
+pub struct MovementQuery {
+    pub product_id: Uuid,
+    pub movement_type: Option<MovementType>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>, // exclusivo
+    pub nfe_key: Option<String>,
+    pub supplier_id: Option<Uuid>,
+    pub after: Option<MovementCursor>,
+    pub limit: i64,
+}
+
+impl MovementQuery {
+    pub fn for_product(product_id: Uuid) -> Self {
+        Self {
+            product_id,
+            movement_type: None,
+            from: None,
+            to: None,
+            nfe_key: None,
+            supplier_id: None,
+            after: None,
+            limit: 50,
+        }
+    }
+    
+    /// Mesmo critério do SQL, para armazenamentos em memória
+    pub fn matches(&self, movement: &InventoryMovement) -> bool {
+        movement.product_id == self.product_id
+            && self.movement_type.is_none_or(|t| movement.movement_type == t)
+            && self.from.is_none_or(|from| movement.created_at >= from)
+            && self.to.is_none_or(|to| movement.created_at < to)
+            && self.nfe_key.as_ref().is_none_or(|key| movement.nfe_key.as_ref() == Some(key))
+            && self.supplier_id.is_none_or(|s| movement.supplier_id == Some(s))
+    }
+}
+
+/// Produto com saldo disponível perto ou abaixo do mínimo
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct LowStock {
+    pub product_id: Uuid,
+    pub product_name: String,
+    pub available_quantity: f64,
+    pub min_stock_level: f64,
+}
+
+/// Baixa de venda de um pedido concluído ainda não aplicada ao saldo
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct PendingConsumption {
+    pub order_id: Uuid,
+    pub product_id: Uuid,
+    pub quantity: f64,
+}
+
+/// Prazo de uma execução sem resposta; depois dele o id pode ser retomado
+pub const REQUEST_LEASE_SECS: i64 = 120;
+
+/// Requisição já recebida, identificada pelo id enviado pelo cliente
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ProcessedRequest {
+    pub payload: serde_json::Value,
+    pub response: Option<serde_json::Value>, // None enquanto a execução não terminou
+}
 
 pub struct InventoryRepository {
     pool: PgPool,
@@ -29,28 +107,80 @@ impl InventoryRepository {
         Ok(inventory.map(|row| row.into()))
     }
     
-    pub async fn save(&self, inventory: &Inventory) -> Result<()> {
+    pub async fn find_by_product_ids(&self, product_ids: &[Uuid]) -> Result<Vec<Inventory>> {
+        let rows = sqlx::query_as!(
+            InventoryRow,
+            r#"
+            SELECT id, product_id, quantity, reserved_quantity, available_quantity,
+                   last_movement_at, created_at, updated_at
+            FROM inventory
+            WHERE product_id = ANY($1)
+            "#,
+            product_ids
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(rows.into_iter().map(|row| row.into()).collect())
+    }
+    
+    /// Produtos ativos com mínimo definido e disponível até 20% acima dele
+    pub async fn find_low_stock(&self) -> Result<Vec<LowStock>> {
+        let rows = sqlx::query_as!(
+            LowStock,
+            r#"
+            SELECT p.id AS product_id, p.name AS product_name,
+                   COALESCE(i.available_quantity, 0) AS "available_quantity!",
+                   p.min_stock_level
+            FROM products p
+            LEFT JOIN inventory i ON i.product_id = p.id
+            WHERE p.is_active
+              AND p.min_stock_level > 0
+              AND COALESCE(i.available_quantity, 0) <= p.min_stock_level * 1.2
+            ORDER BY COALESCE(i.available_quantity, 0) / p.min_stock_level, p.name
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(rows)
+    }
+    
+    /// Abre uma transação para alterar saldos e gravar as movimentações juntos;
+    /// saldos só são gravados por ela
+    pub async fn begin(&self) -> Result<InventoryTransaction> {
+        Ok(InventoryTransaction { tx: self.pool.begin().await? })
+    }
+    
+    /// Baixas de venda ainda não aplicadas, mais antigas primeiro; só as do pedido, se informado
+    pub async fn pending_consumptions(&self, order_id: Option<Uuid>, limit: i64) -> Result<Vec<PendingConsumption>> {
+        let rows = sqlx::query_as!(
+            PendingConsumption,
+            r#"
+            SELECT order_id, product_id, quantity
+            FROM stock_consumptions
+            WHERE consumed_at IS NULL AND ($1::uuid IS NULL OR order_id = $1)
+            ORDER BY created_at
+            LIMIT $2
+            "#,
+            order_id,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(rows)
+    }
+    
+    pub async fn record_consumption_failure(&self, order_id: Uuid, product_id: Uuid, error: &str) -> Result<()> {
         sqlx::query!(
             r#"
-            INSERT INTO inventory (
-                id, product_id, quantity, reserved_quantity, available_quantity,
-                last_movement_at, created_at, updated_at
-            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
-            ON CONFLICT (product_id) DO UPDATE SET
-                quantity = $3,
-                reserved_quantity = $4,
-                available_quantity = $5,
-                last_movement_at = $6,
-                updated_at = $8
+            UPDATE stock_consumptions SET attempts = attempts + 1, last_error = $3
+            WHERE order_id = $1 AND product_id = $2
             "#,
-            inventory.id,
-            inventory.product_id,
-            inventory.quantity,
-            inventory.reserved_quantity,
-            inventory.available_quantity,
-            inventory.last_movement_at,
-            inventory.created_at,
-            inventory.updated_at
+            order_id,
+            product_id,
+            error
         )
         .execute(&self.pool)
         .await?;
@@ -58,26 +188,100 @@ impl InventoryRepository {
         Ok(())
     }
     
-    pub async fn save_movement(&self, movement: &InventoryMovement) -> Result<()> {
-        sqlx::query!(
+    /// Razão do produto, mais recentes primeiro; retorna até `limit` movimentações após o cursor
+    pub async fn list_movements(&self, query: &MovementQuery) -> Result<Vec<InventoryMovement>> {
+        let mut builder: QueryBuilder<Postgres> = QueryBuilder::new(format!(
+            "SELECT {} FROM inventory_movements WHERE product_id = ",
+            MOVEMENT_COLUMNS
+        ));
+        builder.push_bind(query.product_id);
+        
+        if let Some(movement_type) = query.movement_type {
+            builder.push(" AND movement_type = ").push_bind(movement_type_to_str(movement_type));
+        }
+        if let Some(from) = query.from {
+            builder.push(" AND created_at >= ").push_bind(from);
+        }
+        if let Some(to) = query.to {
+            builder.push(" AND created_at < ").push_bind(to);
+        }
+        if let Some(nfe_key) = &query.nfe_key {
+            builder.push(" AND nfe_key = ").push_bind(nfe_key.clone());
+        }
+        if let Some(supplier_id) = query.supplier_id {
+            builder.push(" AND supplier_id = ").push_bind(supplier_id);
+        }
+        if let Some(cursor) = &query.after {
+            builder
+                .push(" AND (created_at, id) < (")
+                .push_bind(cursor.created_at)
+                .push(", ")
+                .push_bind(cursor.id)
+                .push(")");
+        }
+        
+        builder
+            .push(" ORDER BY created_at DESC, id DESC LIMIT ")
+            .push_bind(query.limit);
+        
+        let rows: Vec<MovementRow> = builder.build_query_as().fetch_all(&self.pool).await?;
+        rows.into_iter().map(InventoryMovement::try_from).collect()
+    }
+    
+    /// Registra o id da requisição antes de executá-la. `None` quando o id é novo ou quando
+    /// a execução anterior, com o mesmo payload, passou de `REQUEST_LEASE_SECS` sem resposta;
+    /// caso contrário devolve o que já foi registrado para ele.
+    pub async fn begin_request(&self, request_id: Uuid, payload: &serde_json::Value) -> Result<Option<ProcessedRequest>> {
+        let now = Utc::now();
+        let inserted = sqlx::query!(
             r#"
-            INSERT INTO inventory_movements (
-                id, product_id, movement_type, quantity, unit_cost, total_cost,
-                order_id, supplier_id, nfe_key, notes, performed_by, created_at
-            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            INSERT INTO inventory_requests (request_id, payload, created_at)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (request_id) DO UPDATE SET created_at = EXCLUDED.created_at
+            WHERE inventory_requests.response IS NULL
+              AND inventory_requests.payload = EXCLUDED.payload
+              AND inventory_requests.created_at < $4
             "#,
-            movement.id,
-            movement.product_id,
-            movement.movement_type as _,
-            movement.quantity,
-            movement.unit_cost,
-            movement.total_cost,
-            movement.order_id,
-            movement.supplier_id,
-            movement.nfe_key,
-            movement.notes,
-            movement.performed_by,
-            movement.created_at
+            request_id,
+            payload,
+            now,
+            now - chrono::Duration::seconds(REQUEST_LEASE_SECS)
+        )
+        .execute(&self.pool)
+        .await?;
+        
+        if inserted.rows_affected() == 1 {
+            return Ok(None);
+        }
+        
+        let existing = sqlx::query_as!(
+            ProcessedRequest,
+            "SELECT payload, response FROM inventory_requests WHERE request_id = $1",
+            request_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        
+        Ok(Some(existing))
+    }
+    
+    pub async fn complete_request(&self, request_id: Uuid, response: &serde_json::Value) -> Result<()> {
+        sqlx::query!(
+            "UPDATE inventory_requests SET response = $2 WHERE request_id = $1",
+            request_id,
+            response
+        )
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(())
+    }
+    
+    /// Libera o id de uma execução que falhou, para o cliente poder tentar de novo
+    pub async fn abandon_request(&self, request_id: Uuid) -> Result<()> {
+        sqlx::query!(
+            "DELETE FROM inventory_requests WHERE request_id = $1 AND response IS NULL",
+            request_id
         )
         .execute(&self.pool)
         .await?;
@@ -86,6 +290,200 @@ impl InventoryRepository {
     }
 }
 
+/// Saldos lidos pela transação ficam travados (`FOR UPDATE`) até o commit
+pub struct InventoryTransaction {
+    tx: Transaction<'static, Postgres>,
+}
+
+impl InventoryTransaction {
+    /// Saldo travado do produto; `None` se ele nunca teve estoque
+    pub async fn lock(&mut self, product_id: Uuid) -> Result<Option<Inventory>> {
+        let row = sqlx::query_as!(
+            InventoryRow,
+            r#"
+            SELECT id, product_id, quantity, reserved_quantity, available_quantity,
+                   last_movement_at, created_at, updated_at
+            FROM inventory
+            WHERE product_id = $1
+            FOR UPDATE
+            "#,
+            product_id
+        )
+        .fetch_optional(&mut *self.tx)
+        .await?;
+        
+        Ok(row.map(|row| row.into()))
+    }
+    
+    /// Saldo travado do produto, criado zerado se ainda não existir
+    pub async fn lock_or_create(&mut self, product_id: Uuid) -> Result<Inventory> {
+        let empty = Inventory::new(product_id);
+        sqlx::query!(
+            r#"
+            INSERT INTO inventory (
+                id, product_id, quantity, reserved_quantity, available_quantity,
+                last_movement_at, created_at, updated_at
+            ) VALUES ($1, $2, 0, 0, 0, NULL, $3, $3)
+            ON CONFLICT (product_id) DO NOTHING
+            "#,
+            empty.id,
+            product_id,
+            empty.created_at
+        )
+        .execute(&mut *self.tx)
+        .await?;
+        
+        Ok(self.lock(product_id).await?.unwrap_or(empty))
+    }
+    
+    pub async fn save(&mut self, inventory: &Inventory) -> Result<()> {
+        save_inventory(&mut self.tx, inventory).await
+    }
+    
+    pub async fn save_movement(&mut self, movement: &InventoryMovement) -> Result<()> {
+        insert_movement(&mut self.tx, movement).await
+    }
+    
+    pub async fn find_movements_by_nfe_key(&mut self, nfe_key: &str) -> Result<Vec<InventoryMovement>> {
+        movements_by_nfe_key(&mut self.tx, nfe_key).await
+    }
+    
+    /// Trava a baixa pendente; `None` se já foi aplicada ou outra transação está aplicando
+    pub async fn claim_consumption(&mut self, order_id: Uuid, product_id: Uuid) -> Result<Option<PendingConsumption>> {
+        let pending = sqlx::query_as!(
+            PendingConsumption,
+            r#"
+            SELECT order_id, product_id, quantity
+            FROM stock_consumptions
+            WHERE order_id = $1 AND product_id = $2 AND consumed_at IS NULL
+            FOR UPDATE SKIP LOCKED
+            "#,
+            order_id,
+            product_id
+        )
+        .fetch_optional(&mut *self.tx)
+        .await?;
+        
+        Ok(pending)
+    }
+    
+    pub async fn mark_consumed(&mut self, order_id: Uuid, product_id: Uuid) -> Result<()> {
+        sqlx::query!(
+            "UPDATE stock_consumptions SET consumed_at = $3 WHERE order_id = $1 AND product_id = $2",
+            order_id,
+            product_id,
+            Utc::now()
+        )
+        .execute(&mut *self.tx)
+        .await?;
+        
+        Ok(())
+    }
+    
+    pub async fn commit(self) -> Result<()> {
+        self.tx.commit().await?;
+        Ok(())
+    }
+}
+
+async fn save_inventory(conn: &mut PgConnection, inventory: &Inventory) -> Result<()> {
+    sqlx::query!(
+        r#"
+        INSERT INTO inventory (
+            id, product_id, quantity, reserved_quantity, available_quantity,
+            last_movement_at, created_at, updated_at
+        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        ON CONFLICT (product_id) DO UPDATE SET
+            quantity = $3,
+            reserved_quantity = $4,
+            available_quantity = $5,
+            last_movement_at = $6,
+            updated_at = $8
+        "#,
+        inventory.id,
+        inventory.product_id,
+        inventory.quantity,
+        inventory.reserved_quantity,
+        inventory.available_quantity,
+        inventory.last_movement_at,
+        inventory.created_at,
+        inventory.updated_at
+    )
+    .execute(&mut *conn)
+    .await?;
+    
+    Ok(())
+}
+
+async fn insert_movement(conn: &mut PgConnection, movement: &InventoryMovement) -> Result<()> {
+    sqlx::query!(
+        r#"
+        INSERT INTO inventory_movements (
+            id, product_id, movement_type, quantity, unit_cost, total_cost,
+            order_id, supplier_id, nfe_key, notes, performed_by, created_at
+        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+        "#,
+        movement.id,
+        movement.product_id,
+        movement_type_to_str(movement.movement_type),
+        movement.quantity,
+        movement.unit_cost,
+        movement.total_cost,
+        movement.order_id,
+        movement.supplier_id,
+        movement.nfe_key,
+        movement.notes,
+        movement.performed_by,
+        movement.created_at
+    )
+    .execute(&mut *conn)
+    .await?;
+    
+    Ok(())
+}
+
+async fn movements_by_nfe_key(conn: &mut PgConnection, nfe_key: &str) -> Result<Vec<InventoryMovement>> {
+    let rows = sqlx::query_as!(
+        MovementRow,
+        r#"
+        SELECT id, product_id, movement_type, quantity,
+               unit_cost, total_cost, order_id, supplier_id, nfe_key, notes,
+               performed_by, created_at
+        FROM inventory_movements
+        WHERE nfe_key = $1
+        ORDER BY created_at
+        "#,
+        nfe_key
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+    
+    rows.into_iter().map(InventoryMovement::try_from).collect()
+}
+
+pub fn movement_type_to_str(movement_type: MovementType) -> &'static str {
+    match movement_type {
+        MovementType::Purchase => "purchase",
+        MovementType::Sale => "sale",
+        MovementType::Adjustment => "adjustment",
+        MovementType::Loss => "loss",
+        MovementType::Return => "return",
+        MovementType::Transfer => "transfer",
+    }
+}
+
+pub fn movement_type_from_str(movement_type: &str) -> Result<MovementType> {
+    Ok(match movement_type {
+        "purchase" => MovementType::Purchase,
+        "sale" => MovementType::Sale,
+        "adjustment" => MovementType::Adjustment,
+        "loss" => MovementType::Loss,
+        "return" => MovementType::Return,
+        "transfer" => MovementType::Transfer,
+        other => anyhow::bail!("Unknown movement type: {}", other),
+    })
+}
+
 struct InventoryRow {
     id: Uuid,
     product_id: Uuid,
@@ -111,3 +509,40 @@ impl From<InventoryRow> for Inventory {
         }
     }
 }
+
+#[derive(sqlx::FromRow)]
+struct MovementRow {
+    id: Uuid,
+    product_id: Uuid,
+    movement_type: String,
+    quantity: f64,
+    unit_cost: Option<f64>,
+    total_cost: Option<f64>,
+    order_id: Option<Uuid>,
+    supplier_id: Option<Uuid>,
+    nfe_key: Option<String>,
+    notes: Option<String>,
+    performed_by: Option<Uuid>,
+    created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl TryFrom<MovementRow> for InventoryMovement {
+    type Error = anyhow::Error;
+    
+    fn try_from(row: MovementRow) -> Result<Self> {
+        Ok(InventoryMovement {
+            id: row.id,
+            product_id: row.product_id,
+            movement_type: movement_type_from_str(&row.movement_type)?,
+            quantity: row.quantity,
+            unit_cost: row.unit_cost,
+            total_cost: row.total_cost,
+            order_id: row.order_id,
+            supplier_id: row.supplier_id,
+            nfe_key: row.nfe_key,
+            notes: row.notes,
+            performed_by: row.performed_by,
+            created_at: row.created_at,
+        })
+    }
+}
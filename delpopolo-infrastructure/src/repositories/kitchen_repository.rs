@@ -0,0 +1,96 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use delpopolo_domain::{KitchenStation, KitchenTicket};
+
+pub struct KitchenRepository {
+    pool: PgPool,
+}
+
+impl KitchenRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+    
+    /// Tickets já movimentados dos pedidos, com uma única consulta
+    pub async fn find_by_orders(&self, order_ids: &[Uuid]) -> Result<Vec<KitchenTicket>> {
+        if order_ids.is_empty() {
+            return Ok(vec![]);
+        }
+        
+        let rows = sqlx::query_as!(
+            KitchenTicketRow,
+            r#"
+            SELECT order_id, station, started_at, bumped_at
+            FROM kitchen_tickets
+            WHERE order_id = ANY($1)
+            "#,
+            order_ids
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        
+        rows.into_iter().map(KitchenTicket::try_from).collect()
+    }
+    
+    pub async fn save(&self, ticket: &KitchenTicket) -> Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO kitchen_tickets (order_id, station, started_at, bumped_at)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (order_id, station) DO UPDATE SET
+                started_at = EXCLUDED.started_at,
+                bumped_at = EXCLUDED.bumped_at
+            "#,
+            ticket.order_id,
+            station_to_str(ticket.station),
+            ticket.started_at,
+            ticket.bumped_at
+        )
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(())
+    }
+}
+
+fn station_to_str(station: KitchenStation) -> &'static str {
+    match station {
+        KitchenStation::Bakery => "bakery",
+        KitchenStation::Confectionery => "confectionery",
+        KitchenStation::Sandwich => "sandwich",
+        KitchenStation::Beverage => "beverage",
+    }
+}
+
+fn station_from_str(station: &str) -> Result<KitchenStation> {
+    Ok(match station {
+        "bakery" => KitchenStation::Bakery,
+        "confectionery" => KitchenStation::Confectionery,
+        "sandwich" => KitchenStation::Sandwich,
+        "beverage" => KitchenStation::Beverage,
+        other => anyhow::bail!("Unknown kitchen station: {}", other),
+    })
+}
+
+#[derive(sqlx::FromRow)]
+struct KitchenTicketRow {
+    order_id: Uuid,
+    station: String,
+    started_at: Option<DateTime<Utc>>,
+    bumped_at: Option<DateTime<Utc>>,
+}
+
+impl TryFrom<KitchenTicketRow> for KitchenTicket {
+    type Error = anyhow::Error;
+    
+    fn try_from(row: KitchenTicketRow) -> Result<Self> {
+        Ok(KitchenTicket {
+            order_id: row.order_id,
+            station: station_from_str(&row.station)?,
+            started_at: row.started_at,
+            bumped_at: row.bumped_at,
+        })
+    }
+}
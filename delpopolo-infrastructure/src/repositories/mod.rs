@@ -3,9 +3,17 @@ pub mod customer_repository;
 pub mod order_repository;
 pub mod supplier_repository;
 pub mod inventory_repository;
+pub mod fiscal_repository;
+pub mod user_repository;
+pub mod kitchen_repository;
+pub mod nfe_event_repository;
 
 pub use product_repository::ProductRepository;
 pub use customer_repository::CustomerRepository;
 pub use order_repository::OrderRepository;
 pub use supplier_repository::SupplierRepository;
 pub use inventory_repository::InventoryRepository;
+pub use fiscal_repository::FiscalRepository;
+pub use user_repository::UserRepository;
+pub use kitchen_repository::KitchenRepository;
+pub use nfe_event_repository::NFeEventRepository;
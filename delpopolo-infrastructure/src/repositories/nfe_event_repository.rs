@@ -0,0 +1,108 @@
+use sqlx::PgPool;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+
+/// Tipos de evento (tpEvento) usados nas consultas de estado da nota
+const CANCELAMENTO: &str = "110111";
+const CARTA_CORRECAO: &str = "110110";
+
+/// Evento de NF-e já aplicado; `event_type` é o código tpEvento
+#[derive(Debug, Clone)]
+pub struct NFeEventRecord {
+    pub nfe_key: String,
+    pub event_type: String,
+    pub sequence: i32,
+    pub event_at: DateTime<Utc>,
+    pub author_cnpj: Option<String>,
+    pub justification: Option<String>,
+    pub correction: Option<String>,
+    pub protocol: Option<String>,
+}
+
+pub struct NFeEventRepository {
+    pool: PgPool,
+}
+
+impl NFeEventRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+    
+    pub async fn exists(&self, nfe_key: &str, event_type: &str, sequence: i32) -> Result<bool> {
+        let found = sqlx::query!(
+            "SELECT 1 AS one FROM nfe_events WHERE nfe_key = $1 AND event_type = $2 AND sequence = $3",
+            nfe_key,
+            event_type,
+            sequence
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        
+        Ok(found.is_some())
+    }
+    
+    /// Grava o evento; repetir a gravação do mesmo evento não tem efeito
+    pub async fn record(&self, event: &NFeEventRecord) -> Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO nfe_events (
+                nfe_key, event_type, sequence, event_at, author_cnpj,
+                justification, correction, protocol, processed_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            ON CONFLICT (nfe_key, event_type, sequence) DO NOTHING
+            "#,
+            event.nfe_key,
+            event.event_type,
+            event.sequence,
+            event.event_at,
+            event.author_cnpj,
+            event.justification,
+            event.correction,
+            event.protocol,
+            Utc::now()
+        )
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(())
+    }
+    
+    pub async fn is_cancelled(&self, nfe_key: &str) -> Result<bool> {
+        self.exists_of_type(nfe_key, CANCELAMENTO).await
+    }
+    
+    /// Texto da CC-e de maior sequência, a que está valendo
+    pub async fn latest_correction(&self, nfe_key: &str) -> Result<Option<String>> {
+        let row = sqlx::query_as!(
+            CorrectionRow,
+            r#"
+            SELECT correction FROM nfe_events
+            WHERE nfe_key = $1 AND event_type = $2
+            ORDER BY sequence DESC
+            LIMIT 1
+            "#,
+            nfe_key,
+            CARTA_CORRECAO
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        
+        Ok(row.and_then(|row| row.correction))
+    }
+    
+    async fn exists_of_type(&self, nfe_key: &str, event_type: &str) -> Result<bool> {
+        let found = sqlx::query!(
+            "SELECT 1 AS one FROM nfe_events WHERE nfe_key = $1 AND event_type = $2 LIMIT 1",
+            nfe_key,
+            event_type
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        
+        Ok(found.is_some())
+    }
+}
+
+struct CorrectionRow {
+    correction: Option<String>,
+}
@@ -1,17 +1,542 @@
-use sqlx::PgPool;
-use delpopolo_domain::Order;
+use sqlx::{PgPool, Postgres, QueryBuilder, Transaction};
+use uuid::Uuid;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use delpopolo_domain::{Money, Order, OrderItem, OrderSource, OrderStatus, PaymentMethod};
+
+const ORDER_COLUMNS: &str = r#"
+    id, order_number, customer_id, customer_name, customer_cpf,
+    currency, subtotal, discount, delivery_fee, total,
+    status, source, payment_method, payment_id, is_paid,
+    delivery_address, delivery_time, ifood_order_id, ifood_reference,
+    table_number, turnstile_entry_id, notes,
+    estimated_preparation_time, preparation_started_at, ready_at,
+    delivered_at, cancelled_at, cancellation_reason,
+    created_at, updated_at
+"#;
+
+/// Posição após o último pedido da página anterior (mais recentes primeiro)
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderCursor {
+    pub created_at: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+/// Filtros e paginação por cursor da listagem de pedidos
+#[derive(Debug, Clone)]
+pub struct OrderQuery {
+    pub status: Option<OrderStatus>,
+    pub source: Option<OrderSource>,
+    pub customer_id: Option<Uuid>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>, // exclusivo
+    pub after: Option<OrderCursor>,
+    pub limit: i64,
+    /// Mais antigos primeiro, para quem precisa da fila e não da listagem
+    pub oldest_first: bool,
+}
+
+impl Default for OrderQuery {
+    fn default() -> Self {
+        Self {
+            status: None,
+            source: None,
+            customer_id: None,
+            from: None,
+            to: None,
+            after: None,
+            limit: 50,
+            oldest_first: false,
+        }
+    }
+}
+
+impl OrderQuery {
+    /// Mesmo critério do SQL, para armazenamentos em memória
+    pub fn matches(&self, order: &Order) -> bool {
+        self.status.is_none_or(|s| order.status == s)
+            && self.source.is_none_or(|s| order.source == s)
+            && self.customer_id.is_none_or(|c| order.customer_id == Some(c))
+            && self.from.is_none_or(|from| order.created_at >= from)
+            && self.to.is_none_or(|to| order.created_at < to)
+    }
+}
 
 pub struct OrderRepository {
-    _pool: PgPool,
+    pool: PgPool,
 }
 
 impl OrderRepository {
     pub fn new(pool: PgPool) -> Self {
-        Self { _pool: pool }
+        Self { pool }
+    }
+    
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Option<Order>> {
+        let row = sqlx::query_as!(
+            OrderRow,
+            r#"
+            SELECT
+                id, order_number, customer_id, customer_name, customer_cpf,
+                currency, subtotal, discount, delivery_fee, total,
+                status, source, payment_method, payment_id, is_paid,
+                delivery_address, delivery_time, ifood_order_id, ifood_reference,
+                table_number, turnstile_entry_id, notes,
+                estimated_preparation_time, preparation_started_at, ready_at,
+                delivered_at, cancelled_at, cancellation_reason,
+                created_at, updated_at
+            FROM orders
+            WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        
+        self.with_items(row.into_iter().collect()).await.map(|mut orders| orders.pop())
     }
     
-    pub async fn find_by_order_number(&self, _order_number: &str) -> Option<Order> {
-        // TODO: Implement
-        None
+    pub async fn find_by_order_number(&self, order_number: &str) -> Result<Option<Order>> {
+        let row = sqlx::query_as!(
+            OrderRow,
+            r#"
+            SELECT
+                id, order_number, customer_id, customer_name, customer_cpf,
+                currency, subtotal, discount, delivery_fee, total,
+                status, source, payment_method, payment_id, is_paid,
+                delivery_address, delivery_time, ifood_order_id, ifood_reference,
+                table_number, turnstile_entry_id, notes,
+                estimated_preparation_time, preparation_started_at, ready_at,
+                delivered_at, cancelled_at, cancellation_reason,
+                created_at, updated_at
+            FROM orders
+            WHERE order_number = $1
+            "#,
+            order_number
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        
+        self.with_items(row.into_iter().collect()).await.map(|mut orders| orders.pop())
+    }
+    
+    /// Listagem paginada, mais recentes primeiro (ou mais antigos, com `oldest_first`);
+    /// retorna até `limit` pedidos após o cursor
+    pub async fn list(&self, query: &OrderQuery) -> Result<Vec<Order>> {
+        let mut builder: QueryBuilder<Postgres> =
+            QueryBuilder::new(format!("SELECT {} FROM orders WHERE true", ORDER_COLUMNS));
+        
+        if let Some(status) = query.status {
+            builder.push(" AND status = ").push_bind(status_to_str(status));
+        }
+        if let Some(source) = query.source {
+            builder.push(" AND source = ").push_bind(source_to_str(source));
+        }
+        if let Some(customer_id) = query.customer_id {
+            builder.push(" AND customer_id = ").push_bind(customer_id);
+        }
+        if let Some(from) = query.from {
+            builder.push(" AND created_at >= ").push_bind(from);
+        }
+        if let Some(to) = query.to {
+            builder.push(" AND created_at < ").push_bind(to);
+        }
+        if let Some(cursor) = &query.after {
+            builder
+                .push(if query.oldest_first { " AND (created_at, id) > (" } else { " AND (created_at, id) < (" })
+                .push_bind(cursor.created_at)
+                .push(", ")
+                .push_bind(cursor.id)
+                .push(")");
+        }
+        
+        builder
+            .push(if query.oldest_first { " ORDER BY created_at, id" } else { " ORDER BY created_at DESC, id DESC" })
+            .push(" LIMIT ")
+            .push_bind(query.limit);
+        
+        let rows: Vec<OrderRow> = builder.build_query_as().fetch_all(&self.pool).await?;
+        self.with_items(rows).await
+    }
+    
+    /// Grava o pedido e os itens numa única transação
+    pub async fn insert(&self, order: &Order) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        
+        sqlx::query!(
+            r#"
+            INSERT INTO orders (
+                id, order_number, customer_id, customer_name, customer_cpf,
+                currency, subtotal, discount, delivery_fee, total,
+                status, source, payment_method, payment_id, is_paid,
+                delivery_address, delivery_time, ifood_order_id, ifood_reference,
+                table_number, turnstile_entry_id, notes,
+                estimated_preparation_time, preparation_started_at, ready_at,
+                delivered_at, cancelled_at, cancellation_reason,
+                created_at, updated_at
+            ) VALUES (
+                $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15,
+                $16, $17, $18, $19, $20, $21, $22, $23, $24, $25, $26, $27, $28, $29, $30
+            )
+            "#,
+            order.id,
+            order.order_number,
+            order.customer_id,
+            order.customer_name,
+            order.customer_cpf,
+            order.total.currency,
+            order.subtotal.amount,
+            order.discount.amount,
+            order.delivery_fee.amount,
+            order.total.amount,
+            status_to_str(order.status),
+            source_to_str(order.source),
+            order.payment_method.map(payment_method_to_str),
+            order.payment_id,
+            order.is_paid,
+            order.delivery_address,
+            order.delivery_time,
+            order.ifood_order_id,
+            order.ifood_reference,
+            order.table_number,
+            order.turnstile_entry_id,
+            order.notes,
+            order.estimated_preparation_time,
+            order.preparation_started_at,
+            order.ready_at,
+            order.delivered_at,
+            order.cancelled_at,
+            order.cancellation_reason,
+            order.created_at,
+            order.updated_at
+        )
+        .execute(&mut *tx)
+        .await?;
+        
+        Self::insert_items(&mut tx, order).await?;
+        tx.commit().await?;
+        
+        Ok(())
+    }
+    
+    /// Atualiza o pedido e substitui os itens numa única transação; ao concluir o pedido,
+    /// grava também as baixas de venda a fazer (`stock_consumptions`).
+    /// Retorna `false` sem gravar nada se o pedido mudou desde `expected_updated_at`
+    pub async fn update(&self, order: &Order, expected_updated_at: DateTime<Utc>) -> Result<bool> {
+        let mut tx = self.pool.begin().await?;
+        
+        let updated = sqlx::query!(
+            r#"
+            UPDATE orders SET
+                customer_id = $2, customer_name = $3, customer_cpf = $4,
+                subtotal = $5, discount = $6, delivery_fee = $7, total = $8,
+                status = $9, payment_method = $10, payment_id = $11, is_paid = $12,
+                delivery_address = $13, delivery_time = $14, table_number = $15, notes = $16,
+                estimated_preparation_time = $17, preparation_started_at = $18, ready_at = $19,
+                delivered_at = $20, cancelled_at = $21, cancellation_reason = $22,
+                updated_at = $23
+            WHERE id = $1 AND updated_at = $24
+            "#,
+            order.id,
+            order.customer_id,
+            order.customer_name,
+            order.customer_cpf,
+            order.subtotal.amount,
+            order.discount.amount,
+            order.delivery_fee.amount,
+            order.total.amount,
+            status_to_str(order.status),
+            order.payment_method.map(payment_method_to_str),
+            order.payment_id,
+            order.is_paid,
+            order.delivery_address,
+            order.delivery_time,
+            order.table_number,
+            order.notes,
+            order.estimated_preparation_time,
+            order.preparation_started_at,
+            order.ready_at,
+            order.delivered_at,
+            order.cancelled_at,
+            order.cancellation_reason,
+            order.updated_at,
+            expected_updated_at
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        
+        if updated == 0 {
+            tx.rollback().await?;
+            return Ok(false);
+        }
+        
+        sqlx::query!("DELETE FROM order_items WHERE order_id = $1", order.id)
+            .execute(&mut *tx)
+            .await?;
+        Self::insert_items(&mut tx, order).await?;
+        if order.status == OrderStatus::Completed {
+            Self::insert_stock_consumptions(&mut tx, order).await?;
+        }
+        tx.commit().await?;
+        
+        Ok(true)
+    }
+    
+    /// Uma baixa por produto; `ON CONFLICT` evita repetir a baixa se o pedido for regravado
+    async fn insert_stock_consumptions(tx: &mut Transaction<'_, Postgres>, order: &Order) -> Result<()> {
+        let mut quantities: Vec<(Uuid, f64)> = Vec::new();
+        for item in &order.items {
+            match quantities.iter_mut().find(|(product_id, _)| *product_id == item.product_id) {
+                Some((_, quantity)) => *quantity += item.quantity,
+                None => quantities.push((item.product_id, item.quantity)),
+            }
+        }
+        
+        for (product_id, quantity) in quantities {
+            sqlx::query!(
+                r#"
+                INSERT INTO stock_consumptions (order_id, product_id, quantity, created_at)
+                VALUES ($1, $2, $3, $4)
+                ON CONFLICT (order_id, product_id) DO NOTHING
+                "#,
+                order.id,
+                product_id,
+                quantity,
+                Utc::now()
+            )
+            .execute(&mut **tx)
+            .await?;
+        }
+        Ok(())
+    }
+    
+    async fn insert_items(tx: &mut Transaction<'_, Postgres>, order: &Order) -> Result<()> {
+        for (position, item) in order.items.iter().enumerate() {
+            sqlx::query!(
+                r#"
+                INSERT INTO order_items (
+                    id, order_id, position, product_id, product_name,
+                    quantity, unit_price, total_price, notes
+                ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                "#,
+                item.id,
+                order.id,
+                position as i32,
+                item.product_id,
+                item.product_name,
+                item.quantity,
+                item.unit_price.amount,
+                item.total_price.amount,
+                item.notes
+            )
+            .execute(&mut **tx)
+            .await?;
+        }
+        Ok(())
+    }
+    
+    /// Carrega os itens de todos os pedidos com uma única consulta
+    async fn with_items(&self, rows: Vec<OrderRow>) -> Result<Vec<Order>> {
+        if rows.is_empty() {
+            return Ok(vec![]);
+        }
+        
+        let ids: Vec<Uuid> = rows.iter().map(|row| row.id).collect();
+        let items = sqlx::query_as!(
+            OrderItemRow,
+            r#"
+            SELECT id, order_id, product_id, product_name, quantity, unit_price, total_price, notes
+            FROM order_items
+            WHERE order_id = ANY($1)
+            ORDER BY order_id, position
+            "#,
+            &ids
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        
+        rows.into_iter()
+            .map(|row| {
+                let mut order = Order::try_from(row)?;
+                order.items = items
+                    .iter()
+                    .filter(|item| item.order_id == order.id)
+                    .map(|item| item.to_item(&order.total.currency))
+                    .collect();
+                Ok(order)
+            })
+            .collect()
+    }
+}
+
+pub fn status_to_str(status: OrderStatus) -> &'static str {
+    match status {
+        OrderStatus::Pending => "pending",
+        OrderStatus::Confirmed => "confirmed",
+        OrderStatus::Preparing => "preparing",
+        OrderStatus::Ready => "ready",
+        OrderStatus::InDelivery => "in_delivery",
+        OrderStatus::Completed => "completed",
+        OrderStatus::Cancelled => "cancelled",
+    }
+}
+
+pub fn status_from_str(status: &str) -> Result<OrderStatus> {
+    Ok(match status {
+        "pending" => OrderStatus::Pending,
+        "confirmed" => OrderStatus::Confirmed,
+        "preparing" => OrderStatus::Preparing,
+        "ready" => OrderStatus::Ready,
+        "in_delivery" => OrderStatus::InDelivery,
+        "completed" => OrderStatus::Completed,
+        "cancelled" => OrderStatus::Cancelled,
+        other => anyhow::bail!("Unknown order status: {}", other),
+    })
+}
+
+fn source_to_str(source: OrderSource) -> &'static str {
+    match source {
+        OrderSource::InStore => "in_store",
+        OrderSource::IFood => "ifood",
+        OrderSource::WhatsApp => "whatsapp",
+        OrderSource::Web => "web",
+        OrderSource::Phone => "phone",
+    }
+}
+
+fn source_from_str(source: &str) -> Result<OrderSource> {
+    Ok(match source {
+        "in_store" => OrderSource::InStore,
+        "ifood" => OrderSource::IFood,
+        "whatsapp" => OrderSource::WhatsApp,
+        "web" => OrderSource::Web,
+        "phone" => OrderSource::Phone,
+        other => anyhow::bail!("Unknown order source: {}", other),
+    })
+}
+
+fn payment_method_to_str(method: PaymentMethod) -> &'static str {
+    match method {
+        PaymentMethod::Cash => "cash",
+        PaymentMethod::DebitCard => "debit_card",
+        PaymentMethod::CreditCard => "credit_card",
+        PaymentMethod::Pix => "pix",
+        PaymentMethod::VoucherMeal => "voucher_meal",
+        PaymentMethod::VoucherFood => "voucher_food",
+        PaymentMethod::Multiple => "multiple",
+    }
+}
+
+fn payment_method_from_str(method: &str) -> Result<PaymentMethod> {
+    Ok(match method {
+        "cash" => PaymentMethod::Cash,
+        "debit_card" => PaymentMethod::DebitCard,
+        "credit_card" => PaymentMethod::CreditCard,
+        "pix" => PaymentMethod::Pix,
+        "voucher_meal" => PaymentMethod::VoucherMeal,
+        "voucher_food" => PaymentMethod::VoucherFood,
+        "multiple" => PaymentMethod::Multiple,
+        other => anyhow::bail!("Unknown payment method: {}", other),
+    })
+}
+
+// Helper structs for sqlx mapping
+#[derive(sqlx::FromRow)]
+struct OrderRow {
+    id: Uuid,
+    order_number: String,
+    customer_id: Option<Uuid>,
+    customer_name: Option<String>,
+    customer_cpf: Option<String>,
+    currency: String,
+    subtotal: f64,
+    discount: f64,
+    delivery_fee: f64,
+    total: f64,
+    status: String,
+    source: String,
+    payment_method: Option<String>,
+    payment_id: Option<Uuid>,
+    is_paid: bool,
+    delivery_address: Option<String>,
+    delivery_time: Option<DateTime<Utc>>,
+    ifood_order_id: Option<String>,
+    ifood_reference: Option<String>,
+    table_number: Option<String>,
+    turnstile_entry_id: Option<Uuid>,
+    notes: Option<String>,
+    estimated_preparation_time: Option<i32>,
+    preparation_started_at: Option<DateTime<Utc>>,
+    ready_at: Option<DateTime<Utc>>,
+    delivered_at: Option<DateTime<Utc>>,
+    cancelled_at: Option<DateTime<Utc>>,
+    cancellation_reason: Option<String>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl TryFrom<OrderRow> for Order {
+    type Error = anyhow::Error;
+    
+    fn try_from(row: OrderRow) -> Result<Self> {
+        let money = |amount| Money::new(amount, row.currency.clone());
+        
+        Ok(Order {
+            id: row.id,
+            order_number: row.order_number,
+            customer_id: row.customer_id,
+            customer_name: row.customer_name,
+            customer_cpf: row.customer_cpf,
+            items: vec![],
+            subtotal: money(row.subtotal),
+            discount: money(row.discount),
+            delivery_fee: money(row.delivery_fee),
+            total: money(row.total),
+            status: status_from_str(&row.status)?,
+            source: source_from_str(&row.source)?,
+            payment_method: row.payment_method.as_deref().map(payment_method_from_str).transpose()?,
+            payment_id: row.payment_id,
+            is_paid: row.is_paid,
+            delivery_address: row.delivery_address,
+            delivery_time: row.delivery_time,
+            ifood_order_id: row.ifood_order_id,
+            ifood_reference: row.ifood_reference,
+            table_number: row.table_number,
+            turnstile_entry_id: row.turnstile_entry_id,
+            notes: row.notes,
+            estimated_preparation_time: row.estimated_preparation_time,
+            preparation_started_at: row.preparation_started_at,
+            ready_at: row.ready_at,
+            delivered_at: row.delivered_at,
+            cancelled_at: row.cancelled_at,
+            cancellation_reason: row.cancellation_reason,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        })
+    }
+}
+
+struct OrderItemRow {
+    id: Uuid,
+    order_id: Uuid,
+    product_id: Uuid,
+    product_name: String,
+    quantity: f64,
+    unit_price: f64,
+    total_price: f64,
+    notes: Option<String>,
+}
+
+impl OrderItemRow {
+    fn to_item(&self, currency: &str) -> OrderItem {
+        OrderItem {
+            id: self.id,
+            product_id: self.product_id,
+            product_name: self.product_name.clone(),
+            quantity: self.quantity,
+            unit_price: Money::new(self.unit_price, currency.to_string()),
+            total_price: Money::new(self.total_price, currency.to_string()),
+            notes: self.notes.clone(),
+        }
     }
 }
@@ -1,10 +1,97 @@
 use async_trait::async_trait;
-use sqlx::PgPool;
+use sqlx::{PgPool, Postgres, QueryBuilder};
 use uuid::Uuid;
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use delpopolo_domain::{Product, ProductCategory, UnitOfMeasure, Money};
 use delpopolo_core::traits::Repository;
 
+const PRODUCT_COLUMNS: &str = r#"
+    id, name, description, sku, barcode, category, unit_of_measure,
+    price_amount, price_currency, cost_amount, cost_currency,
+    stock_quantity, min_stock_level, max_stock_level,
+    is_active, is_available_online,
+    image_url, weight, preparation_time_minutes,
+    supplier_id, nfe_ncm, nfe_cest, nfe_cfop,
+    created_at, updated_at
+"#;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ProductSort {
+    #[default]
+    Name,
+    Price,
+    CreatedAt,
+}
+
+/// Posição após o último item da página anterior (valor da ordenação + id como desempate)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProductCursor {
+    pub value: String,
+    pub id: Uuid,
+}
+
+/// Filtros, ordenação e paginação por cursor (keyset) da listagem de produtos
+#[derive(Debug, Clone)]
+pub struct ProductQuery {
+    pub search: Option<String>, // nome, SKU ou código de barras
+    pub category: Option<ProductCategory>,
+    pub active: Option<bool>,
+    pub low_stock: bool,
+    pub sort: ProductSort,
+    pub descending: bool,
+    pub after: Option<ProductCursor>,
+    pub limit: i64,
+}
+
+impl Default for ProductQuery {
+    fn default() -> Self {
+        Self {
+            search: None,
+            category: None,
+            active: None,
+            low_stock: false,
+            sort: ProductSort::Name,
+            descending: false,
+            after: None,
+            limit: 50,
+        }
+    }
+}
+
+impl ProductQuery {
+    /// Mesmo critério do SQL, para armazenamentos em memória
+    pub fn matches(&self, product: &Product) -> bool {
+        let search = self.search.as_deref().map(str::to_lowercase);
+        let text_ok = search.is_none_or(|s| {
+            product.name.to_lowercase().contains(&s)
+                || product.sku.to_lowercase().contains(&s)
+                || product.barcode.as_deref() == Some(s.as_str())
+        });
+        
+        text_ok
+            && self.category.is_none_or(|c| product.category == c)
+            && self.active.is_none_or(|a| product.is_active == a)
+            && (!self.low_stock || product.is_low_stock())
+    }
+    
+    /// Valor de ordenação do produto, no formato usado pelo cursor
+    pub fn cursor_value(&self, product: &Product) -> String {
+        match self.sort {
+            ProductSort::Name => product.name.clone(),
+            ProductSort::Price => product.price.amount.to_string(),
+            ProductSort::CreatedAt => product.created_at.to_rfc3339(),
+        }
+    }
+    
+    pub fn cursor_for(&self, product: &Product) -> ProductCursor {
+        ProductCursor {
+            value: self.cursor_value(product),
+            id: product.id,
+        }
+    }
+}
+
 pub struct ProductRepository {
     pool: PgPool,
 }
@@ -18,10 +105,8 @@ impl ProductRepository {
         let products = sqlx::query_as!(
             ProductRow,
             r#"
-            SELECT 
-                id, name, description, sku, barcode,
-                category as "category: ProductCategory",
-                unit_of_measure as "unit_of_measure: UnitOfMeasure",
+            SELECT
+                id, name, description, sku, barcode, category, unit_of_measure,
                 price_amount, price_currency,
                 cost_amount, cost_currency,
                 stock_quantity, min_stock_level, max_stock_level,
@@ -31,22 +116,21 @@ impl ProductRepository {
                 created_at, updated_at
             FROM products
             WHERE stock_quantity <= min_stock_level AND is_active = true
+            ORDER BY name
             "#
         )
         .fetch_all(&self.pool)
         .await?;
         
-        Ok(products.into_iter().map(|row| row.into()).collect())
+        products.into_iter().map(Product::try_from).collect()
     }
     
     pub async fn find_by_barcode(&self, barcode: &str) -> Result<Option<Product>> {
         let product = sqlx::query_as!(
             ProductRow,
             r#"
-            SELECT 
-                id, name, description, sku, barcode,
-                category as "category: ProductCategory",
-                unit_of_measure as "unit_of_measure: UnitOfMeasure",
+            SELECT
+                id, name, description, sku, barcode, category, unit_of_measure,
                 price_amount, price_currency,
                 cost_amount, cost_currency,
                 stock_quantity, min_stock_level, max_stock_level,
@@ -62,63 +146,77 @@ impl ProductRepository {
         .fetch_optional(&self.pool)
         .await?;
         
-        Ok(product.map(|row| row.into()))
+        product.map(Product::try_from).transpose()
     }
     
     pub async fn search(&self, query: &str, category: Option<ProductCategory>) -> Result<Vec<Product>> {
-        let products = if let Some(cat) = category {
-            sqlx::query_as!(
-                ProductRow,
-                r#"
-                SELECT 
-                    id, name, description, sku, barcode,
-                    category as "category: ProductCategory",
-                    unit_of_measure as "unit_of_measure: UnitOfMeasure",
-                    price_amount, price_currency,
-                    cost_amount, cost_currency,
-                    stock_quantity, min_stock_level, max_stock_level,
-                    is_active, is_available_online,
-                    image_url, weight, preparation_time_minutes,
-                    supplier_id, nfe_ncm, nfe_cest, nfe_cfop,
-                    created_at, updated_at
-                FROM products
-                WHERE (name ILIKE $1 OR sku ILIKE $1) 
-                  AND category = $2
-                  AND is_active = true
-                ORDER BY name
-                "#,
-                format!("%{}%", query),
-                cat as ProductCategory
-            )
-            .fetch_all(&self.pool)
-            .await?
-        } else {
-            sqlx::query_as!(
-                ProductRow,
-                r#"
-                SELECT 
-                    id, name, description, sku, barcode,
-                    category as "category: ProductCategory",
-                    unit_of_measure as "unit_of_measure: UnitOfMeasure",
-                    price_amount, price_currency,
-                    cost_amount, cost_currency,
-                    stock_quantity, min_stock_level, max_stock_level,
-                    is_active, is_available_online,
-                    image_url, weight, preparation_time_minutes,
-                    supplier_id, nfe_ncm, nfe_cest, nfe_cfop,
-                    created_at, updated_at
-                FROM products
-                WHERE (name ILIKE $1 OR sku ILIKE $1)
-                  AND is_active = true
-                ORDER BY name
-                "#,
-                format!("%{}%", query)
-            )
-            .fetch_all(&self.pool)
-            .await?
+        self.list(&ProductQuery {
+            search: Some(query.to_string()),
+            category,
+            active: Some(true),
+            limit: 1000,
+            ..Default::default()
+        })
+        .await
+    }
+    
+    /// Listagem paginada; retorna até `limit` itens após o cursor
+    pub async fn list(&self, query: &ProductQuery) -> Result<Vec<Product>> {
+        let mut builder: QueryBuilder<Postgres> =
+            QueryBuilder::new(format!("SELECT {} FROM products WHERE true", PRODUCT_COLUMNS));
+        
+        if let Some(search) = &query.search {
+            let pattern = format!("%{}%", search);
+            builder
+                .push(" AND (name ILIKE ")
+                .push_bind(pattern.clone())
+                .push(" OR sku ILIKE ")
+                .push_bind(pattern)
+                .push(" OR barcode = ")
+                .push_bind(search.clone())
+                .push(")");
+        }
+        if let Some(category) = query.category {
+            builder.push(" AND category = ").push_bind(category_to_str(category));
+        }
+        if let Some(active) = query.active {
+            builder.push(" AND is_active = ").push_bind(active);
+        }
+        if query.low_stock {
+            builder.push(" AND stock_quantity <= min_stock_level");
+        }
+        
+        let column = match query.sort {
+            ProductSort::Name => "name",
+            ProductSort::Price => "price_amount",
+            ProductSort::CreatedAt => "created_at",
         };
+        let (cmp, direction) = if query.descending { ("<", "DESC") } else { (">", "ASC") };
+        
+        if let Some(cursor) = &query.after {
+            builder.push(format!(" AND ({}, id) {} (", column, cmp));
+            match query.sort {
+                ProductSort::Name => {
+                    builder.push_bind(cursor.value.clone());
+                }
+                ProductSort::Price => {
+                    let price: f64 = cursor.value.parse()?;
+                    builder.push_bind(price);
+                }
+                ProductSort::CreatedAt => {
+                    let created_at = DateTime::parse_from_rfc3339(&cursor.value)?.with_timezone(&Utc);
+                    builder.push_bind(created_at);
+                }
+            }
+            builder.push(", ").push_bind(cursor.id).push(")");
+        }
+        
+        builder
+            .push(format!(" ORDER BY {} {}, id {} LIMIT ", column, direction, direction))
+            .push_bind(query.limit);
         
-        Ok(products.into_iter().map(|row| row.into()).collect())
+        let rows: Vec<ProductRow> = builder.build_query_as().fetch_all(&self.pool).await?;
+        rows.into_iter().map(Product::try_from).collect()
     }
 }
 
@@ -128,10 +226,8 @@ impl Repository<Product> for ProductRepository {
         sqlx::query_as!(
             ProductRow,
             r#"
-            SELECT 
-                id, name, description, sku, barcode,
-                category as "category: ProductCategory",
-                unit_of_measure as "unit_of_measure: UnitOfMeasure",
+            SELECT
+                id, name, description, sku, barcode, category, unit_of_measure,
                 price_amount, price_currency,
                 cost_amount, cost_currency,
                 stock_quantity, min_stock_level, max_stock_level,
@@ -147,17 +243,15 @@ impl Repository<Product> for ProductRepository {
         .fetch_optional(&self.pool)
         .await
         .ok()?
-        .map(|row| row.into())
+        .and_then(|row| row.try_into().ok())
     }
     
     async fn find_all(&self) -> Vec<Product> {
         sqlx::query_as!(
             ProductRow,
             r#"
-            SELECT 
-                id, name, description, sku, barcode,
-                category as "category: ProductCategory",
-                unit_of_measure as "unit_of_measure: UnitOfMeasure",
+            SELECT
+                id, name, description, sku, barcode, category, unit_of_measure,
                 price_amount, price_currency,
                 cost_amount, cost_currency,
                 stock_quantity, min_stock_level, max_stock_level,
@@ -173,7 +267,7 @@ impl Repository<Product> for ProductRepository {
         .await
         .unwrap_or_default()
         .into_iter()
-        .map(|row| row.into())
+        .filter_map(|row| row.try_into().ok())
         .collect()
     }
     
@@ -198,8 +292,8 @@ impl Repository<Product> for ProductRepository {
             entity.description,
             entity.sku,
             entity.barcode,
-            entity.category as ProductCategory,
-            entity.unit_of_measure as UnitOfMeasure,
+            category_to_str(entity.category),
+            unit_to_str(entity.unit_of_measure),
             entity.price.amount,
             entity.price.currency,
             entity.cost.amount,
@@ -245,8 +339,8 @@ impl Repository<Product> for ProductRepository {
             entity.description,
             entity.sku,
             entity.barcode,
-            entity.category as ProductCategory,
-            entity.unit_of_measure as UnitOfMeasure,
+            category_to_str(entity.category),
+            unit_to_str(entity.unit_of_measure),
             entity.price.amount,
             entity.price.currency,
             entity.cost.amount,
@@ -271,24 +365,79 @@ impl Repository<Product> for ProductRepository {
         Ok(entity.clone())
     }
     
+    /// Só desativa: estoque, movimentações, fornecedores e vendas continuam apontando para ele
     async fn delete(&self, id: Uuid) -> Result<(), Box<dyn std::error::Error>> {
-        sqlx::query!("DELETE FROM products WHERE id = $1", id)
+        sqlx::query!("UPDATE products SET is_active = false, updated_at = NOW() WHERE id = $1", id)
             .execute(&self.pool)
             .await?;
         Ok(())
     }
 }
 
+pub fn category_to_str(category: ProductCategory) -> &'static str {
+    match category {
+        ProductCategory::Bread => "bread",
+        ProductCategory::Cake => "cake",
+        ProductCategory::Cookie => "cookie",
+        ProductCategory::Pastry => "pastry",
+        ProductCategory::Beverage => "beverage",
+        ProductCategory::Sandwich => "sandwich",
+        ProductCategory::Snack => "snack",
+        ProductCategory::RawMaterial => "raw_material",
+        ProductCategory::Other => "other",
+    }
+}
+
+pub fn category_from_str(category: &str) -> Result<ProductCategory> {
+    Ok(match category {
+        "bread" => ProductCategory::Bread,
+        "cake" => ProductCategory::Cake,
+        "cookie" => ProductCategory::Cookie,
+        "pastry" => ProductCategory::Pastry,
+        "beverage" => ProductCategory::Beverage,
+        "sandwich" => ProductCategory::Sandwich,
+        "snack" => ProductCategory::Snack,
+        "raw_material" => ProductCategory::RawMaterial,
+        "other" => ProductCategory::Other,
+        other => anyhow::bail!("Unknown product category: {}", other),
+    })
+}
+
+fn unit_to_str(unit: UnitOfMeasure) -> &'static str {
+    match unit {
+        UnitOfMeasure::Unit => "unit",
+        UnitOfMeasure::Kilogram => "kilogram",
+        UnitOfMeasure::Gram => "gram",
+        UnitOfMeasure::Liter => "liter",
+        UnitOfMeasure::Milliliter => "milliliter",
+        UnitOfMeasure::Dozen => "dozen",
+        UnitOfMeasure::Package => "package",
+    }
+}
+
+fn unit_from_str(unit: &str) -> Result<UnitOfMeasure> {
+    Ok(match unit {
+        "unit" => UnitOfMeasure::Unit,
+        "kilogram" => UnitOfMeasure::Kilogram,
+        "gram" => UnitOfMeasure::Gram,
+        "liter" => UnitOfMeasure::Liter,
+        "milliliter" => UnitOfMeasure::Milliliter,
+        "dozen" => UnitOfMeasure::Dozen,
+        "package" => UnitOfMeasure::Package,
+        other => anyhow::bail!("Unknown unit of measure: {}", other),
+    })
+}
+
 // Helper struct for sqlx mapping
-#[allow(dead_code)]
+#[derive(sqlx::FromRow)]
 struct ProductRow {
     id: Uuid,
     name: String,
     description: Option<String>,
     sku: String,
     barcode: Option<String>,
-    category: ProductCategory,
-    unit_of_measure: UnitOfMeasure,
+    category: String,
+    unit_of_measure: String,
     price_amount: f64,
     price_currency: String,
     cost_amount: f64,
@@ -305,20 +454,22 @@ struct ProductRow {
     nfe_ncm: Option<String>,
     nfe_cest: Option<String>,
     nfe_cfop: Option<String>,
-    created_at: chrono::DateTime<chrono::Utc>,
-    updated_at: chrono::DateTime<chrono::Utc>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
 }
 
-impl From<ProductRow> for Product {
-    fn from(row: ProductRow) -> Self {
-        Product {
+impl TryFrom<ProductRow> for Product {
+    type Error = anyhow::Error;
+    
+    fn try_from(row: ProductRow) -> Result<Self> {
+        Ok(Product {
             id: row.id,
             name: row.name,
             description: row.description,
             sku: row.sku,
             barcode: row.barcode,
-            category: row.category,
-            unit_of_measure: row.unit_of_measure,
+            category: category_from_str(&row.category)?,
+            unit_of_measure: unit_from_str(&row.unit_of_measure)?,
             price: Money::new(row.price_amount, row.price_currency),
             cost: Money::new(row.cost_amount, row.cost_currency),
             stock_quantity: row.stock_quantity,
@@ -335,6 +486,6 @@ impl From<ProductRow> for Product {
             nfe_cfop: row.nfe_cfop,
             created_at: row.created_at,
             updated_at: row.updated_at,
-        }
+        })
     }
 }
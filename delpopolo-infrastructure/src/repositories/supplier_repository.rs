@@ -1,17 +1,387 @@
-use sqlx::PgPool;
-use delpopolo_domain::Supplier;
+use sqlx::{PgPool, Postgres, QueryBuilder, Transaction};
+use uuid::Uuid;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use delpopolo_domain::{Address, Cnpj, Email, Phone, Supplier, SupplierContact, SupplierProduct};
+
+const SUPPLIER_COLUMNS: &str = r#"
+    id, name, trade_name, cnpj, email, phone, whatsapp, contact_person,
+    contacts, address, rating, is_active, is_preferred, created_at, updated_at
+"#;
+
+/// Posição após o último fornecedor da página anterior (ordem alfabética)
+#[derive(Debug, Clone, PartialEq)]
+pub struct SupplierCursor {
+    pub name: String,
+    pub id: Uuid,
+}
+
+/// Busca de fornecedores; os filtros informados são combinados
+#[derive(Debug, Clone)]
+pub struct SupplierQuery {
+    pub cnpj: Option<String>, // só dígitos, comparação exata
+    pub name: Option<String>, // razão social ou nome fantasia
+    pub active: Option<bool>,
+    pub preferred: Option<bool>,
+    pub after: Option<SupplierCursor>,
+    pub limit: i64,
+}
+
+impl Default for SupplierQuery {
+    fn default() -> Self {
+        Self {
+            cnpj: None,
+            name: None,
+            active: None,
+            preferred: None,
+            after: None,
+            limit: 50,
+        }
+    }
+}
+
+impl SupplierQuery {
+    /// Mesmo critério do SQL, para armazenamentos em memória
+    pub fn matches(&self, supplier: &Supplier) -> bool {
+        let name_ok = self.name.as_deref().is_none_or(|name| {
+            let name = name.to_lowercase();
+            supplier.name.to_lowercase().contains(&name)
+                || supplier.trade_name.as_deref().is_some_and(|t| t.to_lowercase().contains(&name))
+        });
+        
+        name_ok
+            && self.cnpj.as_deref().is_none_or(|cnpj| supplier.cnpj.as_ref().is_some_and(|c| c.value() == cnpj))
+            && self.active.is_none_or(|a| supplier.is_active == a)
+            && self.preferred.is_none_or(|p| supplier.is_preferred == p)
+    }
+}
 
 pub struct SupplierRepository {
-    _pool: PgPool,
+    pool: PgPool,
 }
 
 impl SupplierRepository {
     pub fn new(pool: PgPool) -> Self {
-        Self { _pool: pool }
+        Self { pool }
+    }
+    
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Option<Supplier>> {
+        let row = sqlx::query_as!(
+            SupplierRow,
+            r#"
+            SELECT
+                id, name, trade_name, cnpj, email, phone, whatsapp, contact_person,
+                contacts, address, rating, is_active, is_preferred, created_at, updated_at
+            FROM suppliers
+            WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        
+        self.with_products(row.into_iter().collect()).await.map(|mut suppliers| suppliers.pop())
+    }
+    
+    pub async fn find_by_cnpj(&self, cnpj: &str) -> Result<Option<Supplier>> {
+        let row = sqlx::query_as!(
+            SupplierRow,
+            r#"
+            SELECT
+                id, name, trade_name, cnpj, email, phone, whatsapp, contact_person,
+                contacts, address, rating, is_active, is_preferred, created_at, updated_at
+            FROM suppliers
+            WHERE cnpj = $1
+            "#,
+            cnpj
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        
+        self.with_products(row.into_iter().collect()).await.map(|mut suppliers| suppliers.pop())
+    }
+    
+    pub async fn find_active_suppliers(&self) -> Result<Vec<Supplier>> {
+        let rows = sqlx::query_as!(
+            SupplierRow,
+            r#"
+            SELECT
+                id, name, trade_name, cnpj, email, phone, whatsapp, contact_person,
+                contacts, address, rating, is_active, is_preferred, created_at, updated_at
+            FROM suppliers
+            WHERE is_active
+            ORDER BY name, id
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        
+        self.with_products(rows).await
+    }
+    
+    /// Fornecedores ativos com o produto disponível no catálogo
+    pub async fn find_offering(&self, product_id: Uuid) -> Result<Vec<Supplier>> {
+        let rows = sqlx::query_as!(
+            SupplierRow,
+            r#"
+            SELECT
+                s.id, s.name, s.trade_name, s.cnpj, s.email, s.phone, s.whatsapp, s.contact_person,
+                s.contacts, s.address, s.rating, s.is_active, s.is_preferred, s.created_at, s.updated_at
+            FROM suppliers s
+            JOIN supplier_products sp ON sp.supplier_id = s.id
+            WHERE sp.product_id = $1 AND sp.is_available AND s.is_active
+            ORDER BY s.name, s.id
+            "#,
+            product_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        
+        self.with_products(rows).await
     }
     
-    pub async fn find_active_suppliers(&self) -> Vec<Supplier> {
-        // TODO: Implement
-        vec![]
+    /// Listagem paginada por nome; retorna até `limit` fornecedores após o cursor
+    pub async fn list(&self, query: &SupplierQuery) -> Result<Vec<Supplier>> {
+        let mut builder: QueryBuilder<Postgres> =
+            QueryBuilder::new(format!("SELECT {} FROM suppliers WHERE true", SUPPLIER_COLUMNS));
+        
+        if let Some(cnpj) = &query.cnpj {
+            builder.push(" AND cnpj = ").push_bind(cnpj.clone());
+        }
+        if let Some(name) = &query.name {
+            let pattern = format!("%{}%", name);
+            builder
+                .push(" AND (name ILIKE ")
+                .push_bind(pattern.clone())
+                .push(" OR trade_name ILIKE ")
+                .push_bind(pattern)
+                .push(")");
+        }
+        if let Some(active) = query.active {
+            builder.push(" AND is_active = ").push_bind(active);
+        }
+        if let Some(preferred) = query.preferred {
+            builder.push(" AND is_preferred = ").push_bind(preferred);
+        }
+        if let Some(cursor) = &query.after {
+            builder
+                .push(" AND (name, id) > (")
+                .push_bind(cursor.name.clone())
+                .push(", ")
+                .push_bind(cursor.id)
+                .push(")");
+        }
+        
+        builder.push(" ORDER BY name, id LIMIT ").push_bind(query.limit);
+        
+        let rows: Vec<SupplierRow> = builder.build_query_as().fetch_all(&self.pool).await?;
+        self.with_products(rows).await
+    }
+    
+    /// Grava o fornecedor e o catálogo numa única transação
+    pub async fn insert(&self, supplier: &Supplier) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        
+        sqlx::query!(
+            r#"
+            INSERT INTO suppliers (
+                id, name, trade_name, cnpj, email, phone, whatsapp, contact_person,
+                contacts, address, rating, is_active, is_preferred, created_at, updated_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
+            "#,
+            supplier.id,
+            supplier.name,
+            supplier.trade_name,
+            supplier.cnpj.as_ref().map(|c| c.value()),
+            supplier.email.as_ref().map(|e| e.value()),
+            supplier.phone.as_ref().map(|p| p.value()),
+            supplier.whatsapp,
+            supplier.contact_person,
+            serde_json::to_value(&supplier.contacts)?,
+            supplier.address.as_ref().map(serde_json::to_value).transpose()?,
+            supplier.rating,
+            supplier.is_active,
+            supplier.is_preferred,
+            supplier.created_at,
+            supplier.updated_at
+        )
+        .execute(&mut *tx)
+        .await?;
+        
+        Self::insert_products(&mut tx, supplier).await?;
+        tx.commit().await?;
+        
+        Ok(())
+    }
+    
+    /// Atualiza o fornecedor e substitui o catálogo numa única transação.
+    /// Retorna `false` sem gravar nada se o fornecedor mudou desde `expected_updated_at`
+    pub async fn update(&self, supplier: &Supplier, expected_updated_at: DateTime<Utc>) -> Result<bool> {
+        let mut tx = self.pool.begin().await?;
+        
+        let updated = sqlx::query!(
+            r#"
+            UPDATE suppliers SET
+                name = $2, trade_name = $3, cnpj = $4, email = $5, phone = $6,
+                whatsapp = $7, contact_person = $8, contacts = $9, address = $10,
+                rating = $11, is_active = $12, is_preferred = $13, updated_at = $14
+            WHERE id = $1 AND updated_at = $15
+            "#,
+            supplier.id,
+            supplier.name,
+            supplier.trade_name,
+            supplier.cnpj.as_ref().map(|c| c.value()),
+            supplier.email.as_ref().map(|e| e.value()),
+            supplier.phone.as_ref().map(|p| p.value()),
+            supplier.whatsapp,
+            supplier.contact_person,
+            serde_json::to_value(&supplier.contacts)?,
+            supplier.address.as_ref().map(serde_json::to_value).transpose()?,
+            supplier.rating,
+            supplier.is_active,
+            supplier.is_preferred,
+            supplier.updated_at,
+            expected_updated_at
+        )
+        .execute(&mut *tx)
+        .await?;
+        
+        if updated.rows_affected() == 0 {
+            return Ok(false);
+        }
+        
+        sqlx::query!("DELETE FROM supplier_products WHERE supplier_id = $1", supplier.id)
+            .execute(&mut *tx)
+            .await?;
+        Self::insert_products(&mut tx, supplier).await?;
+        tx.commit().await?;
+        
+        Ok(true)
+    }
+    
+    async fn insert_products(tx: &mut Transaction<'_, Postgres>, supplier: &Supplier) -> Result<()> {
+        for product in &supplier.products {
+            sqlx::query!(
+                r#"
+                INSERT INTO supplier_products (
+                    supplier_id, product_id, supplier_sku, unit_price,
+                    min_order_quantity, lead_time_days, is_available, updated_at
+                ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                "#,
+                supplier.id,
+                product.product_id,
+                product.supplier_sku,
+                product.unit_price,
+                product.min_order_quantity,
+                product.lead_time_days,
+                product.is_available,
+                product.updated_at
+            )
+            .execute(&mut **tx)
+            .await?;
+        }
+        Ok(())
+    }
+    
+    async fn with_products(&self, rows: Vec<SupplierRow>) -> Result<Vec<Supplier>> {
+        if rows.is_empty() {
+            return Ok(vec![]);
+        }
+        
+        let ids: Vec<Uuid> = rows.iter().map(|row| row.id).collect();
+        let products = sqlx::query_as!(
+            SupplierProductRow,
+            r#"
+            SELECT supplier_id, product_id, supplier_sku, unit_price,
+                   min_order_quantity, lead_time_days, is_available, updated_at
+            FROM supplier_products
+            WHERE supplier_id = ANY($1)
+            ORDER BY supplier_id, product_id
+            "#,
+            &ids
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        
+        rows.into_iter()
+            .map(|row| {
+                let mut supplier = Supplier::try_from(row)?;
+                supplier.products = products
+                    .iter()
+                    .filter(|p| p.supplier_id == supplier.id)
+                    .map(SupplierProductRow::to_product)
+                    .collect();
+                Ok(supplier)
+            })
+            .collect()
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct SupplierRow {
+    id: Uuid,
+    name: String,
+    trade_name: Option<String>,
+    cnpj: Option<String>,
+    email: Option<String>,
+    phone: Option<String>,
+    whatsapp: Option<String>,
+    contact_person: Option<String>,
+    contacts: serde_json::Value,
+    address: Option<serde_json::Value>,
+    rating: Option<f32>,
+    is_active: bool,
+    is_preferred: bool,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl TryFrom<SupplierRow> for Supplier {
+    type Error = anyhow::Error;
+    
+    fn try_from(row: SupplierRow) -> Result<Self> {
+        Ok(Supplier {
+            id: row.id,
+            name: row.name,
+            trade_name: row.trade_name,
+            cnpj: row.cnpj.map(Cnpj::new).transpose().map_err(anyhow::Error::msg)?,
+            email: row.email.map(Email::new).transpose().map_err(anyhow::Error::msg)?,
+            phone: row.phone.map(Phone::new).transpose().map_err(anyhow::Error::msg)?,
+            whatsapp: row.whatsapp,
+            contact_person: row.contact_person,
+            contacts: serde_json::from_value::<Vec<SupplierContact>>(row.contacts)?,
+            address: row.address.map(serde_json::from_value::<Address>).transpose()?,
+            products: vec![],
+            rating: row.rating,
+            is_active: row.is_active,
+            is_preferred: row.is_preferred,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        })
+    }
+}
+
+struct SupplierProductRow {
+    supplier_id: Uuid,
+    product_id: Uuid,
+    supplier_sku: Option<String>,
+    unit_price: f64,
+    min_order_quantity: Option<f64>,
+    lead_time_days: Option<i32>,
+    is_available: bool,
+    updated_at: DateTime<Utc>,
+}
+
+impl SupplierProductRow {
+    fn to_product(&self) -> SupplierProduct {
+        SupplierProduct {
+            product_id: self.product_id,
+            supplier_sku: self.supplier_sku.clone(),
+            unit_price: self.unit_price,
+            min_order_quantity: self.min_order_quantity,
+            lead_time_days: self.lead_time_days,
+            is_available: self.is_available,
+            updated_at: self.updated_at,
+        }
     }
 }
@@ -0,0 +1,244 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use delpopolo_domain::{RefreshToken, User, UserRole};
+
+pub struct UserRepository {
+    pool: PgPool,
+}
+
+impl UserRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+    
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Option<User>> {
+        let user = sqlx::query_as!(
+            UserRow,
+            r#"
+            SELECT id, name, email, password_hash, role, is_active, last_login_at,
+                   failed_login_attempts, locked_until, created_at, updated_at
+            FROM users
+            WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        
+        user.map(User::try_from).transpose()
+    }
+    
+    pub async fn find_by_email(&self, email: &str) -> Result<Option<User>> {
+        let user = sqlx::query_as!(
+            UserRow,
+            r#"
+            SELECT id, name, email, password_hash, role, is_active, last_login_at,
+                   failed_login_attempts, locked_until, created_at, updated_at
+            FROM users
+            WHERE lower(email) = lower($1)
+            "#,
+            email
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        
+        user.map(User::try_from).transpose()
+    }
+    
+    pub async fn save(&self, user: &User) -> Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO users (
+                id, name, email, password_hash, role, is_active, last_login_at,
+                failed_login_attempts, locked_until, created_at, updated_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            ON CONFLICT (id) DO UPDATE SET
+                name = $2,
+                email = $3,
+                password_hash = $4,
+                role = $5,
+                is_active = $6,
+                last_login_at = $7,
+                failed_login_attempts = $8,
+                locked_until = $9,
+                updated_at = $11
+            "#,
+            user.id,
+            user.name,
+            user.email,
+            user.password_hash,
+            role_to_str(user.role),
+            user.is_active,
+            user.last_login_at,
+            user.failed_login_attempts,
+            user.locked_until,
+            user.created_at,
+            user.updated_at
+        )
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(())
+    }
+    
+    pub async fn save_refresh_token(&self, token: &RefreshToken) -> Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO refresh_tokens (
+                id, user_id, family_id, expires_at, revoked_at, replaced_by, created_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (id) DO UPDATE SET
+                revoked_at = $5,
+                replaced_by = $6
+            "#,
+            token.id,
+            token.user_id,
+            token.family_id,
+            token.expires_at,
+            token.revoked_at,
+            token.replaced_by,
+            token.created_at
+        )
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(())
+    }
+    
+    pub async fn find_refresh_token(&self, id: Uuid) -> Result<Option<RefreshToken>> {
+        let token = sqlx::query_as!(
+            RefreshTokenRow,
+            r#"
+            SELECT id, user_id, family_id, expires_at, revoked_at, replaced_by, created_at
+            FROM refresh_tokens
+            WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        
+        Ok(token.map(|row| row.into()))
+    }
+    
+    /// Revoga o token apontando o sucessor, só se ainda estiver ativo; `false` quando
+    /// outra requisição já o revogou (uso concorrente ou reuso)
+    pub async fn rotate_refresh_token(&self, id: Uuid, replaced_by: Uuid) -> Result<bool> {
+        let result = sqlx::query!(
+            "UPDATE refresh_tokens SET revoked_at = now(), replaced_by = $2 WHERE id = $1 AND revoked_at IS NULL",
+            id,
+            replaced_by
+        )
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(result.rows_affected() == 1)
+    }
+    
+    /// Revoga todos os tokens ainda ativos de uma família (logout ou reuso detectado)
+    pub async fn revoke_token_family(&self, family_id: Uuid) -> Result<u64> {
+        let result = sqlx::query!(
+            "UPDATE refresh_tokens SET revoked_at = now() WHERE family_id = $1 AND revoked_at IS NULL",
+            family_id
+        )
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(result.rows_affected())
+    }
+    
+    /// Revoga todas as sessões do usuário (ex.: troca de senha)
+    pub async fn revoke_user_tokens(&self, user_id: Uuid) -> Result<u64> {
+        let result = sqlx::query!(
+            "UPDATE refresh_tokens SET revoked_at = now() WHERE user_id = $1 AND revoked_at IS NULL",
+            user_id
+        )
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(result.rows_affected())
+    }
+}
+
+fn role_to_str(role: UserRole) -> &'static str {
+    match role {
+        UserRole::Admin => "admin",
+        UserRole::Manager => "manager",
+        UserRole::Cashier => "cashier",
+        UserRole::InventoryManager => "inventory_manager",
+        UserRole::Kitchen => "kitchen",
+        UserRole::Delivery => "delivery",
+    }
+}
+
+fn role_from_str(role: &str) -> Result<UserRole> {
+    Ok(match role {
+        "admin" => UserRole::Admin,
+        "manager" => UserRole::Manager,
+        "cashier" => UserRole::Cashier,
+        "inventory_manager" => UserRole::InventoryManager,
+        "kitchen" => UserRole::Kitchen,
+        "delivery" => UserRole::Delivery,
+        other => anyhow::bail!("Unknown user role: {}", other),
+    })
+}
+
+struct UserRow {
+    id: Uuid,
+    name: String,
+    email: String,
+    password_hash: String,
+    role: String,
+    is_active: bool,
+    last_login_at: Option<DateTime<Utc>>,
+    failed_login_attempts: i32,
+    locked_until: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl TryFrom<UserRow> for User {
+    type Error = anyhow::Error;
+    
+    fn try_from(row: UserRow) -> Result<Self> {
+        Ok(User {
+            id: row.id,
+            name: row.name,
+            email: row.email,
+            password_hash: row.password_hash,
+            role: role_from_str(&row.role)?,
+            is_active: row.is_active,
+            last_login_at: row.last_login_at,
+            failed_login_attempts: row.failed_login_attempts,
+            locked_until: row.locked_until,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        })
+    }
+}
+
+struct RefreshTokenRow {
+    id: Uuid,
+    user_id: Uuid,
+    family_id: Uuid,
+    expires_at: DateTime<Utc>,
+    revoked_at: Option<DateTime<Utc>>,
+    replaced_by: Option<Uuid>,
+    created_at: DateTime<Utc>,
+}
+
+impl From<RefreshTokenRow> for RefreshToken {
+    fn from(row: RefreshTokenRow) -> Self {
+        RefreshToken {
+            id: row.id,
+            user_id: row.user_id,
+            family_id: row.family_id,
+            expires_at: row.expires_at,
+            revoked_at: row.revoked_at,
+            replaced_by: row.replaced_by,
+            created_at: row.created_at,
+        }
+    }
+}
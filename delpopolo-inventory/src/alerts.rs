@@ -3,14 +3,16 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub enum AlertLevel {
     Critical,  // Estoque zerado
-    High,      // Abaixo do mnimo
-    Medium,    // Prximo do mnimo
+    High,      // Abaixo do mínimo
+    Medium,    // Próximo do mínimo
     Low,       // Informativo
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct StockAlert {
     pub id: Uuid,
     pub product_id: Uuid,
@@ -75,23 +77,23 @@ impl StockAlert {
     ) -> String {
         match level {
             AlertLevel::Critical => {
-                format!("? CRTICO: {} est ZERADO no estoque!", product_name)
+                format!("? CRÍTICO: {} está ZERADO no estoque!", product_name)
             }
             AlertLevel::High => {
                 format!(
-                    "?? URGENTE: {} est com {} unidades (mnimo: {})",
+                    "?? URGENTE: {} está com {} unidades (mínimo: {})",
                     product_name, current, min
                 )
             }
             AlertLevel::Medium => {
                 format!(
-                    "? ATENO: {} est prximo do mnimo ({}/{})",
+                    "? ATENÇÃO: {} está próximo do mínimo ({}/{})",
                     product_name, current, min
                 )
             }
             AlertLevel::Low => {
                 format!(
-                    "?? INFO: {} est em nvel adequado ({})",
+                    "?? INFO: {} está em nível adequado ({})",
                     product_name, current
                 )
             }
@@ -99,7 +101,7 @@ impl StockAlert {
     }
     
     fn calculate_order_quantity(current: f64, min: f64) -> f64 {
-        let target = min * 3.0; // Pedir para 3x o mnimo
+        let target = min * 3.0; // Pedir para 3x o mínimo
         (target - current).max(0.0)
     }
     
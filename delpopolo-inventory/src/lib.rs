@@ -1,7 +1,9 @@
 pub mod service;
 pub mod alerts;
 pub mod replenishment;
+pub mod nfe_events;
 
 pub use service::InventoryService;
 pub use alerts::{StockAlert, AlertLevel, AlertManager};
 pub use replenishment::{ReplenishmentSuggestion, ReplenishmentEngine};
+pub use nfe_events::NFeEventLog;
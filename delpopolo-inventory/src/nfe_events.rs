@@ -0,0 +1,45 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use delpopolo_infrastructure::repositories::nfe_event_repository::NFeEventRecord;
+use delpopolo_infrastructure::repositories::NFeEventRepository;
+use delpopolo_nfe::{EventoTipo, NFeEvento, NFeEventStore};
+
+/// Eventos de NF-e aplicados ao estoque, gravados em `nfe_events`
+pub struct NFeEventLog {
+    repo: NFeEventRepository,
+}
+
+impl NFeEventLog {
+    pub fn new(repo: NFeEventRepository) -> Self {
+        Self { repo }
+    }
+}
+
+#[async_trait]
+impl NFeEventStore for NFeEventLog {
+    async fn is_processed(&self, chave: &str, tipo: EventoTipo, sequencia: u32) -> Result<bool> {
+        self.repo.exists(chave, tipo.code(), sequencia as i32).await
+    }
+    
+    async fn record(&self, evento: &NFeEvento) -> Result<()> {
+        self.repo.record(&NFeEventRecord {
+            nfe_key: evento.chave.clone(),
+            event_type: evento.tipo.code().to_string(),
+            sequence: evento.sequencia as i32,
+            event_at: evento.data_evento,
+            author_cnpj: evento.cnpj_autor.clone(),
+            justification: evento.justificativa.clone(),
+            correction: evento.correcao.clone(),
+            protocol: evento.protocolo.clone(),
+        })
+        .await
+    }
+    
+    async fn is_cancelled(&self, chave: &str) -> Result<bool> {
+        self.repo.is_cancelled(chave).await
+    }
+    
+    async fn correcao(&self, chave: &str) -> Result<Option<String>> {
+        self.repo.latest_correction(chave).await
+    }
+}
@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 use delpopolo_domain::{Product, Supplier};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct SupplierQuote {
     pub supplier_id: Uuid,
     pub supplier_name: String,
@@ -15,6 +16,7 @@ pub struct SupplierQuote {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct ReplenishmentSuggestion {
     pub product_id: Uuid,
     pub product_name: String,
@@ -53,15 +55,15 @@ impl ReplenishmentSuggestion {
         self.update_best_quote();
     }
     
+    /// Mantém `quotes` ranqueadas; a melhor é sempre a primeira
     fn update_best_quote(&mut self) {
         if self.quotes.is_empty() {
             self.best_quote = None;
             return;
         }
         
-        // Ordenar por: preferido > menor preo > melhor rating
-        let mut sorted_quotes = self.quotes.clone();
-        sorted_quotes.sort_by(|a, b| {
+        // Ordenar por: preferido > menor preço > melhor rating
+        self.quotes.sort_by(|a, b| {
             // Primeiro: fornecedor preferido
             match (a.is_preferred, b.is_preferred) {
                 (true, false) => return std::cmp::Ordering::Less,
@@ -69,7 +71,7 @@ impl ReplenishmentSuggestion {
                 _ => {}
             }
             
-            // Segundo: menor preo
+            // Segundo: menor preço
             match a.total_cost.partial_cmp(&b.total_cost) {
                 Some(std::cmp::Ordering::Equal) => {}
                 Some(ordering) => return ordering,
@@ -85,7 +87,7 @@ impl ReplenishmentSuggestion {
             }
         });
         
-        self.best_quote = sorted_quotes.first().cloned();
+        self.best_quote = self.quotes.first().cloned();
     }
     
     fn calculate_order_quantity(current: f64, min: f64, max: Option<f64>) -> f64 {
@@ -95,7 +97,7 @@ impl ReplenishmentSuggestion {
     
     fn calculate_urgency(current: f64, min: f64) -> f32 {
         if current <= 0.0 {
-            1.0 // Urgncia mxima
+            1.0 // Urgência máxima
         } else if current < min * 0.5 {
             0.9
         } else if current < min {
@@ -1,8 +1,19 @@
+use std::collections::HashMap;
 use anyhow::Result;
+use async_trait::async_trait;
 use uuid::Uuid;
 use tracing::{info, warn};
-use delpopolo_domain::{Product, Inventory, InventoryMovement, MovementType};
+use delpopolo_core::CoreError;
+use delpopolo_domain::{Inventory, InventoryMovement, MovementType};
+use delpopolo_infrastructure::repositories::inventory_repository::{MovementQuery, PendingConsumption, ProcessedRequest};
+use delpopolo_infrastructure::repositories::inventory_repository::InventoryTransaction;
 use delpopolo_infrastructure::repositories::InventoryRepository;
+use delpopolo_nfe::PurchaseReversal;
+use crate::alerts::{AlertManager, StockAlert};
+
+fn not_found(product_id: Uuid) -> anyhow::Error {
+    CoreError::not_found(format!("Produto {} sem registro de estoque", product_id)).into()
+}
 
 pub struct InventoryService {
     inventory_repo: InventoryRepository,
@@ -13,20 +24,33 @@ impl InventoryService {
         Self { inventory_repo }
     }
     
-    /// Adiciona quantidade ao estoque
+    /// Saldo atual; `CoreError::NotFound` se o produto nunca teve estoque
+    async fn load(&self, product_id: Uuid) -> Result<Inventory> {
+        self.inventory_repo
+            .find_by_product_id(product_id)
+            .await?
+            .ok_or_else(|| not_found(product_id))
+    }
+    
+    /// Como `load`, mas travando o saldo até o fim da transação
+    async fn lock(tx: &mut InventoryTransaction, product_id: Uuid) -> Result<Inventory> {
+        tx.lock(product_id).await?.ok_or_else(|| not_found(product_id))
+    }
+    
+    /// Adiciona quantidade ao estoque e devolve o saldo atualizado
     pub async fn add_stock(
         &self,
         product_id: Uuid,
         quantity: f64,
         unit_cost: Option<f64>,
         nfe_key: Option<String>,
-    ) -> Result<()> {
+        supplier_id: Option<Uuid>,
+        performed_by: Option<Uuid>,
+    ) -> Result<Inventory> {
         info!("Adding {} units to product {}", quantity, product_id);
         
-        let mut inventory = self.inventory_repo
-            .find_by_product_id(product_id)
-            .await?
-            .unwrap_or_else(|| Inventory::new(product_id));
+        let mut tx = self.inventory_repo.begin().await?;
+        let mut inventory = tx.lock_or_create(product_id).await?;
         
         inventory.add_quantity(quantity);
         
@@ -43,11 +67,82 @@ impl InventoryService {
         if let Some(key) = nfe_key {
             movement = movement.with_nfe(key);
         }
+        movement.supplier_id = supplier_id;
+        movement.performed_by = performed_by;
         
-        self.inventory_repo.save(&inventory).await?;
-        self.inventory_repo.save_movement(&movement).await?;
+        tx.save(&inventory).await?;
+        tx.save_movement(&movement).await?;
+        tx.commit().await?;
         
-        Ok(())
+        Ok(inventory)
+    }
+    
+    /// Estorna as entradas (Purchase) de uma NF-e cancelada pelo fornecedor, numa transação só.
+    /// Não faz nada se a nota já foi estornada.
+    pub async fn reverse_nfe_purchase(&self, nfe_key: &str, reason: &str) -> Result<usize> {
+        let mut tx = self.inventory_repo.begin().await?;
+        
+        let mut product_ids: Vec<Uuid> = tx.find_movements_by_nfe_key(nfe_key).await?
+            .iter()
+            .filter(|m| m.movement_type == MovementType::Purchase)
+            .map(|m| m.product_id)
+            .collect();
+        product_ids.sort();
+        product_ids.dedup();
+        
+        // Trava os saldos em ordem fixa: um estorno concorrente da mesma nota espera este
+        // terminar e, ao reler as movimentações, encontra o estorno já gravado
+        let mut inventories = HashMap::new();
+        for product_id in product_ids {
+            inventories.insert(product_id, tx.lock_or_create(product_id).await?);
+        }
+        
+        let movements = tx.find_movements_by_nfe_key(nfe_key).await?;
+        if movements.iter().any(|m| m.movement_type == MovementType::Return) {
+            info!("NFe {} purchase already reversed", nfe_key);
+            return Ok(0);
+        }
+        
+        let purchases: Vec<&InventoryMovement> = movements
+            .iter()
+            .filter(|m| m.movement_type == MovementType::Purchase)
+            .collect();
+        
+        for purchase in &purchases {
+            let inventory = inventories
+                .get_mut(&purchase.product_id)
+                .expect("saldo travado para todo produto da nota");
+            
+            if inventory.remove_quantity(purchase.quantity).is_err() {
+                // A mercadoria já foi consumida; o saldo fica negativo até a devolução/ajuste
+                warn!("Reversing NFe {} leaves product {} with negative stock", nfe_key, purchase.product_id);
+                inventory.add_quantity(-purchase.quantity);
+            }
+            
+            let mut movement = InventoryMovement::new(
+                purchase.product_id,
+                MovementType::Return,
+                purchase.quantity,
+            )
+            .with_nfe(nfe_key.to_string());
+            
+            if let Some(cost) = purchase.unit_cost {
+                movement = movement.with_cost(cost);
+            }
+            movement.supplier_id = purchase.supplier_id;
+            movement.notes = Some(reason.to_string());
+            
+            tx.save_movement(&movement).await?;
+        }
+        
+        for inventory in inventories.values() {
+            tx.save(inventory).await?;
+        }
+        tx.commit().await?;
+        
+        info!("Reversed {} purchase movements of NFe {}", purchases.len(), nfe_key);
+        
+        Ok(purchases.len())
     }
     
     /// Remove quantidade do estoque (venda)
@@ -56,15 +151,13 @@ impl InventoryService {
         product_id: Uuid,
         quantity: f64,
         order_id: Option<Uuid>,
-    ) -> Result<()> {
+        performed_by: Option<Uuid>,
+    ) -> Result<Inventory> {
         info!("Removing {} units from product {}", quantity, product_id);
         
-        let mut inventory = self.inventory_repo
-            .find_by_product_id(product_id)
-            .await?
-            .ok_or_else(|| anyhow::anyhow!("Inventory not found"))?;
-        
-        inventory.remove_quantity(quantity)?;
+        let mut tx = self.inventory_repo.begin().await?;
+        let mut inventory = Self::lock(&mut tx, product_id).await?;
+        inventory.remove_quantity(quantity).map_err(CoreError::conflict)?;
         
         let mut movement = InventoryMovement::new(
             product_id,
@@ -72,11 +165,82 @@ impl InventoryService {
             quantity,
         );
         movement.order_id = order_id;
+        movement.performed_by = performed_by;
+        
+        tx.save(&inventory).await?;
+        tx.save_movement(&movement).await?;
+        tx.commit().await?;
+        
+        Ok(inventory)
+    }
+    
+    /// Aplica as baixas de venda pendentes de um pedido concluído; devolve quantas aplicou
+    pub async fn consume_order(&self, order_id: Uuid) -> Result<usize> {
+        let pending = self.inventory_repo.pending_consumptions(Some(order_id), i64::MAX).await?;
+        self.apply_consumptions(pending).await
+    }
+    
+    /// Reaplica as baixas que ficaram pendentes, mais antigas primeiro
+    pub async fn consume_pending(&self, limit: i64) -> Result<usize> {
+        let pending = self.inventory_repo.pending_consumptions(None, limit).await?;
+        self.apply_consumptions(pending).await
+    }
+    
+    /// Tenta todas; as que falham ficam pendentes com o erro registrado
+    async fn apply_consumptions(&self, pending: Vec<PendingConsumption>) -> Result<usize> {
+        let mut applied = 0;
+        let mut first_error = None;
+        for consumption in pending {
+            match self.apply_consumption(&consumption).await {
+                Ok(true) => applied += 1,
+                Ok(false) => {}
+                Err(e) => {
+                    warn!("Sale of product {} for order {} kept pending: {}", consumption.product_id, consumption.order_id, e);
+                    if let Err(e) = self.inventory_repo
+                        .record_consumption_failure(consumption.order_id, consumption.product_id, &e.to_string())
+                        .await
+                    {
+                        warn!("Failed to record pending sale failure: {}", e);
+                    }
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+        
+        match first_error {
+            Some(e) => Err(e),
+            None => Ok(applied),
+        }
+    }
+    
+    /// Libera a reserva, dá a baixa e marca a pendência como feita numa transação só
+    async fn apply_consumption(&self, consumption: &PendingConsumption) -> Result<bool> {
+        let mut tx = self.inventory_repo.begin().await?;
+        let Some(consumption) = tx.claim_consumption(consumption.order_id, consumption.product_id).await? else {
+            return Ok(false);
+        };
+        
+        let mut inventory = tx.lock_or_create(consumption.product_id).await?;
+        inventory.release_reservation(consumption.quantity);
+        if inventory.remove_quantity(consumption.quantity).is_err() {
+            // A venda já aconteceu; o saldo fica negativo até a próxima entrada/ajuste
+            warn!("Sale for order {} leaves product {} with negative stock", consumption.order_id, consumption.product_id);
+            inventory.add_quantity(-consumption.quantity);
+        }
+        
+        let mut movement = InventoryMovement::new(
+            consumption.product_id,
+            MovementType::Sale,
+            consumption.quantity,
+        );
+        movement.order_id = Some(consumption.order_id);
         
-        self.inventory_repo.save(&inventory).await?;
-        self.inventory_repo.save_movement(&movement).await?;
+        tx.save(&inventory).await?;
+        tx.save_movement(&movement).await?;
+        tx.mark_consumed(consumption.order_id, consumption.product_id).await?;
+        tx.commit().await?;
         
-        Ok(())
+        Ok(true)
     }
     
     /// Reserva quantidade para um pedido
@@ -85,20 +249,18 @@ impl InventoryService {
         product_id: Uuid,
         quantity: f64,
         order_id: Uuid,
-    ) -> Result<()> {
+    ) -> Result<Inventory> {
         info!("Reserving {} units of product {} for order {}", 
             quantity, product_id, order_id);
         
-        let mut inventory = self.inventory_repo
-            .find_by_product_id(product_id)
-            .await?
-            .ok_or_else(|| anyhow::anyhow!("Inventory not found"))?;
-        
-        inventory.reserve(quantity)?;
+        let mut tx = self.inventory_repo.begin().await?;
+        let mut inventory = Self::lock(&mut tx, product_id).await?;
+        inventory.reserve(quantity).map_err(CoreError::conflict)?;
         
-        self.inventory_repo.save(&inventory).await?;
+        tx.save(&inventory).await?;
+        tx.commit().await?;
         
-        Ok(())
+        Ok(inventory)
     }
     
     /// Libera reserva de estoque
@@ -106,19 +268,17 @@ impl InventoryService {
         &self,
         product_id: Uuid,
         quantity: f64,
-    ) -> Result<()> {
+    ) -> Result<Inventory> {
         info!("Releasing {} units reservation for product {}", quantity, product_id);
         
-        let mut inventory = self.inventory_repo
-            .find_by_product_id(product_id)
-            .await?
-            .ok_or_else(|| anyhow::anyhow!("Inventory not found"))?;
-        
+        let mut tx = self.inventory_repo.begin().await?;
+        let mut inventory = Self::lock(&mut tx, product_id).await?;
         inventory.release_reservation(quantity);
         
-        self.inventory_repo.save(&inventory).await?;
+        tx.save(&inventory).await?;
+        tx.commit().await?;
         
-        Ok(())
+        Ok(inventory)
     }
     
     /// Ajuste manual de estoque
@@ -128,13 +288,11 @@ impl InventoryService {
         new_quantity: f64,
         reason: String,
         performed_by: Uuid,
-    ) -> Result<()> {
+    ) -> Result<Inventory> {
         warn!("Manual stock adjustment for product {}: reason={}", product_id, reason);
         
-        let mut inventory = self.inventory_repo
-            .find_by_product_id(product_id)
-            .await?
-            .unwrap_or_else(|| Inventory::new(product_id));
+        let mut tx = self.inventory_repo.begin().await?;
+        let mut inventory = tx.lock_or_create(product_id).await?;
         
         let old_quantity = inventory.quantity;
         let difference = new_quantity - old_quantity;
@@ -144,18 +302,20 @@ impl InventoryService {
         inventory.last_movement_at = Some(chrono::Utc::now());
         inventory.updated_at = chrono::Utc::now();
         
+        // Diferença com sinal: o razão precisa saber se o ajuste entrou ou saiu
         let mut movement = InventoryMovement::new(
             product_id,
             MovementType::Adjustment,
-            difference.abs(),
+            difference,
         );
         movement.notes = Some(reason);
         movement.performed_by = Some(performed_by);
         
-        self.inventory_repo.save(&inventory).await?;
-        self.inventory_repo.save_movement(&movement).await?;
+        tx.save(&inventory).await?;
+        tx.save_movement(&movement).await?;
+        tx.commit().await?;
         
-        Ok(())
+        Ok(inventory)
     }
     
     /// Registra perda/quebra de estoque
@@ -165,16 +325,13 @@ impl InventoryService {
         quantity: f64,
         reason: String,
         performed_by: Uuid,
-    ) -> Result<()> {
+    ) -> Result<Inventory> {
         warn!("Stock loss registered for product {}: {} units - {}", 
             product_id, quantity, reason);
         
-        let mut inventory = self.inventory_repo
-            .find_by_product_id(product_id)
-            .await?
-            .ok_or_else(|| anyhow::anyhow!("Inventory not found"))?;
-        
-        inventory.remove_quantity(quantity)?;
+        let mut tx = self.inventory_repo.begin().await?;
+        let mut inventory = Self::lock(&mut tx, product_id).await?;
+        inventory.remove_quantity(quantity).map_err(CoreError::conflict)?;
         
         let mut movement = InventoryMovement::new(
             product_id,
@@ -184,33 +341,84 @@ impl InventoryService {
         movement.notes = Some(reason);
         movement.performed_by = Some(performed_by);
         
-        self.inventory_repo.save(&inventory).await?;
-        self.inventory_repo.save_movement(&movement).await?;
+        tx.save(&inventory).await?;
+        tx.save_movement(&movement).await?;
+        tx.commit().await?;
         
-        Ok(())
+        Ok(inventory)
     }
     
-    /// Verifica se h estoque disponvel
+    /// Verifica se há estoque disponível
     pub async fn check_availability(
         &self,
         product_id: Uuid,
         required_quantity: f64,
     ) -> Result<bool> {
-        let inventory = self.inventory_repo
-            .find_by_product_id(product_id)
-            .await?
-            .ok_or_else(|| anyhow::anyhow!("Inventory not found"))?;
+        let inventory = self.load(product_id).await?;
         
         Ok(inventory.available_quantity >= required_quantity)
     }
     
-    /// Obtm quantidade disponvel
+    /// Obtém quantidade disponível
     pub async fn get_available_quantity(&self, product_id: Uuid) -> Result<f64> {
-        let inventory = self.inventory_repo
-            .find_by_product_id(product_id)
-            .await?
-            .ok_or_else(|| anyhow::anyhow!("Inventory not found"))?;
+        let inventory = self.load(product_id).await?;
         
         Ok(inventory.available_quantity)
     }
+    
+    /// Saldos na ordem pedida; produto sem registro aparece zerado
+    pub async fn get_levels(&self, product_ids: &[Uuid]) -> Result<Vec<Inventory>> {
+        let found = self.inventory_repo.find_by_product_ids(product_ids).await?;
+        
+        Ok(product_ids
+            .iter()
+            .map(|id| {
+                found
+                    .iter()
+                    .find(|i| i.product_id == *id)
+                    .cloned()
+                    .unwrap_or_else(|| Inventory::new(*id))
+            })
+            .collect())
+    }
+    
+    /// Razão de movimentações de um produto
+    pub async fn get_movements(&self, query: &MovementQuery) -> Result<Vec<InventoryMovement>> {
+        self.inventory_repo.list_movements(query).await
+    }
+    
+    /// Alertas pendentes de produtos perto ou abaixo do mínimo, mais graves primeiro
+    pub async fn stock_alerts(&self) -> Result<Vec<StockAlert>> {
+        let mut manager = AlertManager::new();
+        for low in self.inventory_repo.find_low_stock().await? {
+            manager.add_alert(StockAlert::new(
+                low.product_id,
+                low.product_name,
+                low.available_quantity,
+                low.min_stock_level,
+            ));
+        }
+        
+        Ok(manager.get_all_unacknowledged().into_iter().cloned().collect())
+    }
+    
+    /// Idempotência por id enviado pelo cliente; ver `InventoryRepository::begin_request`
+    pub async fn begin_request(&self, request_id: Uuid, payload: &serde_json::Value) -> Result<Option<ProcessedRequest>> {
+        self.inventory_repo.begin_request(request_id, payload).await
+    }
+    
+    pub async fn complete_request(&self, request_id: Uuid, response: &serde_json::Value) -> Result<()> {
+        self.inventory_repo.complete_request(request_id, response).await
+    }
+    
+    pub async fn abandon_request(&self, request_id: Uuid) -> Result<()> {
+        self.inventory_repo.abandon_request(request_id).await
+    }
+}
+
+#[async_trait]
+impl PurchaseReversal for InventoryService {
+    async fn reverse_purchase(&self, nfe_key: &str, reason: &str) -> Result<usize> {
+        self.reverse_nfe_purchase(nfe_key, reason).await
+    }
 }
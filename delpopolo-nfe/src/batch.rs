@@ -0,0 +1,566 @@
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{Read, Seek};
+use std::path::{Path, PathBuf};
+use anyhow::{Context, Result};
+use serde::Serialize;
+use tracing::{info, warn};
+use super::importer::{ImportResult, NFeImporter};
+use super::parser::NFeParser;
+use super::validator::NFeValidator;
+
+/// Importação em lote de NF-e a partir de diretórios ou arquivos ZIP
+/// (ex.: pacote mensal de XMLs enviado pela contabilidade)
+pub struct NFeBatchImporter {
+    known_keys: HashSet<String>,
+    limits: ZipLimits,
+}
+
+/// Limites de leitura de arquivos ZIP, contra pacotes maliciosos (zip bomb)
+#[derive(Debug, Clone, Copy)]
+pub struct ZipLimits {
+    pub max_entries: usize,
+    /// Tamanho descompactado máximo de cada XML
+    pub max_entry_bytes: u64,
+    /// Tamanho descompactado máximo somando todos os XMLs do pacote
+    pub max_total_bytes: u64,
+}
+
+impl Default for ZipLimits {
+    fn default() -> Self {
+        Self {
+            max_entries: 10_000,
+            max_entry_bytes: 5 * 1024 * 1024,
+            max_total_bytes: 512 * 1024 * 1024,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchEntryStatus {
+    Imported,
+    Duplicate,
+    Invalid,
+    NotEntrada,
+}
+
+impl BatchEntryStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BatchEntryStatus::Imported => "imported",
+            BatchEntryStatus::Duplicate => "duplicate",
+            BatchEntryStatus::Invalid => "invalid",
+            BatchEntryStatus::NotEntrada => "not_entrada",
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct BatchEntry {
+    pub file_name: String,
+    pub chave: Option<String>,
+    pub status: BatchEntryStatus,
+    pub reason: Option<String>,
+    pub emitente: Option<String>,
+    pub valor_total: Option<f64>,
+    pub import: Option<ImportResult>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct BatchSummary {
+    pub total_files: usize,
+    pub imported: usize,
+    pub duplicates: usize,
+    pub invalid: usize,
+    pub not_entrada: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BatchImportReport {
+    pub source: String,
+    pub summary: BatchSummary,
+    pub entries: Vec<BatchEntry>,
+}
+
+impl NFeBatchImporter {
+    pub fn new() -> Self {
+        Self {
+            known_keys: HashSet::new(),
+            limits: ZipLimits::default(),
+        }
+    }
+    
+    pub fn with_limits(mut self, limits: ZipLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+    
+    /// Chaves já importadas anteriormente, que serão tratadas como duplicadas
+    pub fn with_known_keys<I: IntoIterator<Item = String>>(mut self, keys: I) -> Self {
+        self.known_keys.extend(keys);
+        self
+    }
+    
+    /// Importa a partir de um diretório ou de um arquivo `.zip`
+    pub fn import_path(&mut self, path: &Path) -> Result<BatchImportReport> {
+        if path.is_dir() {
+            self.import_directory(path)
+        } else if Self::has_extension(path, "zip") {
+            self.import_zip(path)
+        } else {
+            anyhow::bail!("Caminho deve ser um diretório ou arquivo ZIP: {}", path.display());
+        }
+    }
+    
+    pub fn import_directory(&mut self, dir: &Path) -> Result<BatchImportReport> {
+        info!("Batch importing NFe XMLs from directory {}", dir.display());
+        
+        let mut files = Vec::new();
+        Self::collect_xml_files(dir, &mut files)?;
+        files.sort();
+        
+        let mut report = BatchImportReport::new(dir.display().to_string());
+        
+        for file in files {
+            let file_name = file
+                .strip_prefix(dir)
+                .unwrap_or(&file)
+                .display()
+                .to_string();
+            
+            match std::fs::read_to_string(&file) {
+                Ok(xml) => self.process(&file_name, &xml, &mut report),
+                Err(e) => report.push_invalid(file_name, format!("Erro de leitura: {}", e)),
+            }
+        }
+        
+        report.log_summary();
+        Ok(report)
+    }
+    
+    pub fn import_zip(&mut self, zip_path: &Path) -> Result<BatchImportReport> {
+        let file = File::open(zip_path)
+            .with_context(|| format!("Não foi possível abrir {}", zip_path.display()))?;
+        
+        self.import_zip_reader(file, zip_path.display().to_string())
+    }
+    
+    pub fn import_zip_reader<R: Read + Seek>(&mut self, reader: R, source: String) -> Result<BatchImportReport> {
+        info!("Batch importing NFe XMLs from ZIP {}", source);
+        
+        let mut archive = zip::ZipArchive::new(reader).context("Arquivo ZIP inválido")?;
+        if archive.len() > self.limits.max_entries {
+            anyhow::bail!(
+                "ZIP com {} arquivos excede o limite de {}",
+                archive.len(),
+                self.limits.max_entries
+            );
+        }
+        
+        let mut names: Vec<String> = archive
+            .file_names()
+            .filter(|name| !name.ends_with('/') && Self::has_extension(Path::new(name), "xml"))
+            .map(String::from)
+            .collect();
+        names.sort();
+        
+        // Tamanhos declarados nos cabeçalhos: recusa o pacote antes de descompactar qualquer coisa
+        let declared: u64 = names
+            .iter()
+            .map(|name| archive.by_name(name).map(|entry| entry.size()).unwrap_or(0))
+            .sum();
+        if declared > self.limits.max_total_bytes {
+            anyhow::bail!(
+                "ZIP descompactado teria {} bytes, acima do limite de {}",
+                declared,
+                self.limits.max_total_bytes
+            );
+        }
+        
+        let mut report = BatchImportReport::new(source);
+        let mut total: u64 = 0;
+        
+        for name in names {
+            match self.read_zip_entry(&mut archive, &name) {
+                Ok(xml) => {
+                    // Os cabeçalhos podem mentir; confere também o que foi de fato lido
+                    total += xml.len() as u64;
+                    if total > self.limits.max_total_bytes {
+                        anyhow::bail!(
+                            "ZIP descompactado excede o limite de {} bytes",
+                            self.limits.max_total_bytes
+                        );
+                    }
+                    self.process(&name, &xml, &mut report)
+                }
+                Err(e) => report.push_invalid(name, format!("Erro de leitura: {}", e)),
+            }
+        }
+        
+        report.log_summary();
+        Ok(report)
+    }
+    
+    fn read_zip_entry<R: Read + Seek>(&self, archive: &mut zip::ZipArchive<R>, name: &str) -> Result<String> {
+        let max = self.limits.max_entry_bytes;
+        let entry = archive.by_name(name)?;
+        if entry.size() > max {
+            anyhow::bail!("arquivo descompactado excede o limite de {} bytes", max);
+        }
+        
+        // Lê no máximo um byte além do limite, caso o tamanho declarado seja falso
+        let mut xml = String::new();
+        entry.take(max + 1).read_to_string(&mut xml)?;
+        if xml.len() as u64 > max {
+            anyhow::bail!("arquivo descompactado excede o limite de {} bytes", max);
+        }
+        Ok(xml)
+    }
+    
+    fn process(&mut self, file_name: &str, xml: &str, report: &mut BatchImportReport) {
+        let nfe = match NFeParser::parse_xml(xml) {
+            Ok(nfe) => nfe,
+            Err(e) => {
+                report.push_invalid(file_name.to_string(), e.to_string());
+                return;
+            }
+        };
+        
+        let mut entry = BatchEntry {
+            file_name: file_name.to_string(),
+            chave: Some(nfe.chave.clone()),
+            status: BatchEntryStatus::Invalid,
+            reason: None,
+            emitente: Some(nfe.emitente.razao_social.clone()),
+            valor_total: Some(nfe.valor_total()),
+            import: None,
+        };
+        
+        if self.known_keys.contains(&nfe.chave) {
+            entry.status = BatchEntryStatus::Duplicate;
+            entry.reason = Some("Chave de acesso já importada".to_string());
+        } else if let Err(e) = NFeValidator::validate(&nfe) {
+            entry.reason = Some(e.to_string());
+        } else if !NFeValidator::is_valid_for_import(&nfe) {
+            entry.status = BatchEntryStatus::NotEntrada;
+            entry.reason = Some("Nota não é de entrada (CFOP de saída)".to_string());
+        } else {
+            match NFeImporter::import_products_from_nfe(&nfe) {
+                Ok(result) => {
+                    self.known_keys.insert(nfe.chave.clone());
+                    entry.status = BatchEntryStatus::Imported;
+                    entry.import = Some(result);
+                }
+                Err(e) => {
+                    warn!("Failed to import NFe {} from {}: {}", nfe.chave, file_name, e);
+                    entry.reason = Some(e.to_string());
+                }
+            }
+        }
+        
+        report.push(entry);
+    }
+    
+    fn collect_xml_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                Self::collect_xml_files(&path, files)?;
+            } else if Self::has_extension(&path, "xml") {
+                files.push(path);
+            }
+        }
+        Ok(())
+    }
+    
+    fn has_extension(path: &Path, extension: &str) -> bool {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.eq_ignore_ascii_case(extension))
+            .unwrap_or(false)
+    }
+}
+
+impl Default for NFeBatchImporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BatchImportReport {
+    fn new(source: String) -> Self {
+        Self {
+            source,
+            summary: BatchSummary::default(),
+            entries: Vec::new(),
+        }
+    }
+    
+    fn push(&mut self, entry: BatchEntry) {
+        self.summary.total_files += 1;
+        match entry.status {
+            BatchEntryStatus::Imported => self.summary.imported += 1,
+            BatchEntryStatus::Duplicate => self.summary.duplicates += 1,
+            BatchEntryStatus::Invalid => self.summary.invalid += 1,
+            BatchEntryStatus::NotEntrada => self.summary.not_entrada += 1,
+        }
+        self.entries.push(entry);
+    }
+    
+    fn push_invalid(&mut self, file_name: String, reason: String) {
+        self.push(BatchEntry {
+            file_name,
+            chave: None,
+            status: BatchEntryStatus::Invalid,
+            reason: Some(reason),
+            emitente: None,
+            valor_total: None,
+            import: None,
+        });
+    }
+    
+    fn log_summary(&self) {
+        info!(
+            "Batch import of {} completed: {} imported, {} duplicate, {} invalid, {} not entrada",
+            self.source,
+            self.summary.imported,
+            self.summary.duplicates,
+            self.summary.invalid,
+            self.summary.not_entrada
+        );
+    }
+    
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+    
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("arquivo,chave,status,motivo,emitente,valor_total,produtos_criados,produtos_ignorados\n");
+        
+        for entry in &self.entries {
+            let (created, skipped) = entry.import
+                .as_ref()
+                .map(|r| (r.products_created.len().to_string(), r.products_skipped.len().to_string()))
+                .unwrap_or_default();
+            
+            let fields = [
+                entry.file_name.clone(),
+                entry.chave.clone().unwrap_or_default(),
+                entry.status.as_str().to_string(),
+                entry.reason.clone().unwrap_or_default(),
+                entry.emitente.clone().unwrap_or_default(),
+                entry.valor_total.map(|v| format!("{:.2}", v)).unwrap_or_default(),
+                created,
+                skipped,
+            ];
+            
+            let line: Vec<String> = fields.iter().map(|f| Self::csv_field(f)).collect();
+            csv.push_str(&line.join(","));
+            csv.push('\n');
+        }
+        
+        csv
+    }
+    
+    fn csv_field(value: &str) -> String {
+        if value.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", value.replace('"', "\"\""))
+        } else {
+            value.to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, Write};
+    use crate::models::*;
+    use crate::chave::ChaveAcesso;
+    
+    fn sample_nfe(chave: &str, cfop: &str) -> NFe {
+        let endereco = Endereco {
+            logradouro: "Rua do Trigo".to_string(),
+            numero: "100".to_string(),
+            complemento: None,
+            bairro: "Centro".to_string(),
+            municipio: "Sao Paulo".to_string(),
+            uf: "SP".to_string(),
+            cep: "01000000".to_string(),
+        };
+        
+        NFe {
+            chave: chave.to_string(),
+            numero: ChaveAcesso::new(chave.to_string()).unwrap().numero().to_string(),
+            serie: "1".to_string(),
+            data_emissao: chrono::Utc::now(),
+            emitente: Emitente {
+                cnpj: "11222333000181".to_string(),
+                razao_social: "Moinho Paulista LTDA".to_string(),
+                nome_fantasia: None,
+                endereco: endereco.clone(),
+                telefone: None,
+                email: None,
+            },
+            destinatario: Destinatario {
+                cnpj_cpf: "98765432000110".to_string(),
+                razao_social: "DelPopolo Panificadora".to_string(),
+                endereco,
+                telefone: None,
+                email: None,
+            },
+            itens: vec![ItemNFe {
+                numero_item: 1,
+                codigo_produto: "FT01".to_string(),
+                descricao: "Farinha de trigo 25kg".to_string(),
+                ncm: "11010010".to_string(),
+                cest: None,
+                cfop: cfop.to_string(),
+                unidade_comercial: "UN".to_string(),
+                quantidade_comercial: 10.0,
+                valor_unitario_comercial: 95.0,
+                valor_total_bruto: 950.0,
+                ean: None,
+                ean_tributavel: None,
+                origem: None,
+                icms: None,
+                ipi: None,
+                pis: None,
+                cofins: None,
+            }],
+            totais: Totais {
+                base_calculo_icms: 0.0,
+                valor_icms: 0.0,
+                valor_icms_desonerado: 0.0,
+                base_calculo_icms_st: 0.0,
+                valor_icms_st: 0.0,
+                valor_total_produtos: 950.0,
+                valor_frete: 0.0,
+                valor_seguro: 0.0,
+                valor_desconto: 0.0,
+                valor_total_ii: 0.0,
+                valor_ipi: 0.0,
+                valor_pis: 0.0,
+                valor_cofins: 0.0,
+                valor_outras_despesas: 0.0,
+                valor_total_nota: 950.0,
+            },
+            transporte: None,
+            informacoes_adicionais: None,
+        }
+    }
+    
+    fn to_xml(nfe: &NFe) -> String {
+        // Campos `None` são serializados como elementos vazios; removê-los
+        // para que voltem como `None` na leitura
+        let mut xml = quick_xml::se::to_string_with_root("NFe", nfe).unwrap();
+        while let Some(end) = xml.find("/>") {
+            let start = xml[..end].rfind('<').unwrap();
+            xml.replace_range(start..end + 2, "");
+        }
+        xml
+    }
+    
+    #[test]
+    fn test_import_zip_report() {
+        let chave = "35250111222333000181550010000000011123456780";
+        let outra_chave = "35250111222333000181550010000000021123456787";
+        
+        let mut buffer = Cursor::new(Vec::new());
+        {
+            let mut zip = zip::ZipWriter::new(&mut buffer);
+            let options = zip::write::SimpleFileOptions::default();
+            
+            for (name, content) in [
+                ("janeiro/nota1.xml", to_xml(&sample_nfe(chave, "1102"))),
+                ("janeiro/nota1-copia.xml", to_xml(&sample_nfe(chave, "1102"))),
+                ("janeiro/nota2.xml", to_xml(&sample_nfe(outra_chave, "5102"))),
+                ("janeiro/quebrado.xml", "<NFe><chave>".to_string()),
+                ("janeiro/leiame.txt", "ignorado".to_string()),
+            ] {
+                zip.start_file(name, options).unwrap();
+                zip.write_all(content.as_bytes()).unwrap();
+            }
+            zip.finish().unwrap();
+        }
+        buffer.set_position(0);
+        
+        let report = NFeBatchImporter::new()
+            .import_zip_reader(buffer, "janeiro.zip".to_string())
+            .unwrap();
+        
+        assert_eq!(report.summary.total_files, 4);
+        assert_eq!(report.summary.imported, 1);
+        assert_eq!(report.summary.duplicates, 1);
+        assert_eq!(report.summary.not_entrada, 1);
+        assert_eq!(report.summary.invalid, 1);
+        
+        let csv = report.to_csv();
+        assert_eq!(csv.lines().count(), 5);
+        assert!(csv.contains("janeiro/nota2.xml,35250111222333000181550010000000021123456787,not_entrada"));
+        
+        let json: serde_json::Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+        assert_eq!(json["summary"]["imported"], 1);
+    }
+    
+    fn zip_with(files: &[(&str, String)]) -> Cursor<Vec<u8>> {
+        let mut buffer = Cursor::new(Vec::new());
+        {
+            let mut zip = zip::ZipWriter::new(&mut buffer);
+            for (name, content) in files {
+                zip.start_file(*name, zip::write::SimpleFileOptions::default()).unwrap();
+                zip.write_all(content.as_bytes()).unwrap();
+            }
+            zip.finish().unwrap();
+        }
+        buffer.set_position(0);
+        buffer
+    }
+    
+    #[test]
+    fn test_zip_limits() {
+        let chave = "35250111222333000181550010000000011123456780";
+        let limits = ZipLimits { max_entries: 3, max_entry_bytes: 64 * 1024, max_total_bytes: 256 * 1024 };
+        
+        // XML grande demais é recusado sem derrubar o restante do pacote
+        let report = NFeBatchImporter::new()
+            .with_limits(limits)
+            .import_zip_reader(
+                zip_with(&[
+                    ("nota.xml", to_xml(&sample_nfe(chave, "1102"))),
+                    ("bomba.xml", " ".repeat(100 * 1024)),
+                ]),
+                "pacote.zip".to_string(),
+            )
+            .unwrap();
+        assert_eq!(report.summary.imported, 1);
+        assert_eq!(report.summary.invalid, 1);
+        assert!(report.entries.iter().any(|e| e.file_name == "bomba.xml" && e.reason.as_deref().unwrap().contains("limite")));
+        
+        let muitos: Vec<(&str, String)> = ["a.xml", "b.xml", "c.xml", "d.xml"].iter().map(|n| (*n, String::new())).collect();
+        assert!(NFeBatchImporter::new().with_limits(limits).import_zip_reader(zip_with(&muitos), "muitos.zip".to_string()).is_err());
+        
+        let grandes: Vec<(&str, String)> = ["a.xml", "b.xml", "c.xml"].iter().map(|n| (*n, " ".repeat(60 * 1024))).collect();
+        let total = ZipLimits { max_total_bytes: 100 * 1024, ..limits };
+        assert!(NFeBatchImporter::new().with_limits(total).import_zip_reader(zip_with(&grandes), "grandes.zip".to_string()).is_err());
+    }
+    
+    #[test]
+    fn test_known_keys_are_duplicates() {
+        let chave = "35250111222333000181550010000000011123456780";
+        let dir = std::env::temp_dir().join(format!("nfe-batch-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("nota.XML"), to_xml(&sample_nfe(chave, "1102"))).unwrap();
+        
+        let report = NFeBatchImporter::new()
+            .with_known_keys(vec![chave.to_string()])
+            .import_path(&dir)
+            .unwrap();
+        
+        std::fs::remove_dir_all(&dir).unwrap();
+        
+        assert_eq!(report.summary.duplicates, 1);
+        assert_eq!(report.entries[0].status, BatchEntryStatus::Duplicate);
+    }
+}
@@ -0,0 +1,271 @@
+use serde::{Deserialize, Serialize};
+use delpopolo_core::traits::ValueObject;
+use delpopolo_domain::Cnpj;
+use crate::parser::NFeParser;
+
+/// Campos que compõem a chave de acesso, na ordem do leiaute
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChaveAcessoPartes {
+    pub codigo_uf: u8,
+    pub ano: u8, // AA (dois dígitos)
+    pub mes: u8,
+    pub documento: String, // CNPJ do emitente, ou CPF completado com zeros à esquerda
+    pub modelo: u8,
+    pub serie: u16,
+    pub numero: u32,
+    pub tipo_emissao: u8,
+    pub codigo_numerico: u32,
+}
+
+/// Chave de acesso de 44 dígitos da NF-e/NFC-e:
+/// cUF(2) + AAMM(4) + CNPJ/CPF(14) + mod(2) + serie(3) + nNF(9) + tpEmis(1) + cNF(8) + cDV(1)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChaveAcesso {
+    value: String,
+    partes: ChaveAcessoPartes,
+}
+
+impl ChaveAcesso {
+    pub fn new(chave: String) -> Result<Self, String> {
+        let cleaned = chave.chars().filter(|c| !c.is_whitespace()).collect::<String>();
+        
+        if cleaned.len() != 44 || !cleaned.chars().all(|c| c.is_ascii_digit()) {
+            return Err("Chave de acesso deve ter 44 dígitos".to_string());
+        }
+        
+        if !NFeParser::validate_chave(&cleaned) {
+            return Err("Dígito verificador da chave de acesso inválido".to_string());
+        }
+        
+        let number = |range: std::ops::Range<usize>| -> u32 {
+            cleaned[range].parse().expect("apenas dígitos")
+        };
+        
+        let partes = ChaveAcessoPartes {
+            codigo_uf: number(0..2) as u8,
+            ano: number(2..4) as u8,
+            mes: number(4..6) as u8,
+            // Só o DV da chave é conferido: emitente pessoa física traz o CPF no lugar do CNPJ
+            documento: cleaned[6..20].to_string(),
+            modelo: number(20..22) as u8,
+            serie: number(22..25) as u16,
+            numero: number(25..34),
+            tipo_emissao: number(34..35) as u8,
+            codigo_numerico: number(35..43),
+        };
+        
+        Self::validate_partes(&partes)?;
+        
+        Ok(Self { value: cleaned, partes })
+    }
+    
+    /// Monta a chave a partir dos campos, calculando o dígito verificador
+    pub fn from_partes(partes: ChaveAcessoPartes) -> Result<Self, String> {
+        Self::validate_partes(&partes)?;
+        
+        if partes.serie > 999 || partes.numero > 999_999_999 || partes.codigo_numerico > 99_999_999 {
+            return Err("Série, número ou código numérico excede o tamanho do campo".to_string());
+        }
+        
+        let sem_dv = format!(
+            "{:02}{:02}{:02}{}{:02}{:03}{:09}{}{:08}",
+            partes.codigo_uf,
+            partes.ano,
+            partes.mes,
+            partes.documento,
+            partes.modelo,
+            partes.serie,
+            partes.numero,
+            partes.tipo_emissao,
+            partes.codigo_numerico
+        );
+        
+        let digits: Vec<u32> = sem_dv.chars().filter_map(|c| c.to_digit(10)).collect();
+        let dv = NFeParser::calculate_check_digit(&digits);
+        
+        Ok(Self {
+            value: format!("{}{}", sem_dv, dv),
+            partes,
+        })
+    }
+    
+    fn validate_partes(partes: &ChaveAcessoPartes) -> Result<(), String> {
+        if uf_sigla(partes.codigo_uf).is_none() {
+            return Err(format!("Código de UF inválido: {}", partes.codigo_uf));
+        }
+        
+        if !(1..=12).contains(&partes.mes) || partes.ano > 99 {
+            return Err(format!("Ano/mês de emissão inválido: {:02}{:02}", partes.ano, partes.mes));
+        }
+        
+        if partes.documento.len() != 14 || !partes.documento.chars().all(|c| c.is_ascii_digit()) {
+            return Err("Documento do emitente deve ter 14 dígitos".to_string());
+        }
+        
+        if partes.modelo != 55 && partes.modelo != 65 {
+            return Err(format!("Modelo {} não é NF-e (55) nem NFC-e (65)", partes.modelo));
+        }
+        
+        if !(1..=9).contains(&partes.tipo_emissao) {
+            return Err(format!("Tipo de emissão inválido: {}", partes.tipo_emissao));
+        }
+        
+        Ok(())
+    }
+    
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+    
+    pub fn partes(&self) -> &ChaveAcessoPartes {
+        &self.partes
+    }
+    
+    pub fn codigo_uf(&self) -> u8 {
+        self.partes.codigo_uf
+    }
+    
+    pub fn uf(&self) -> &'static str {
+        uf_sigla(self.partes.codigo_uf).expect("UF validada na construção")
+    }
+    
+    /// Ano com quatro dígitos e mês de emissão
+    pub fn ano_mes(&self) -> (u16, u8) {
+        (2000 + self.partes.ano as u16, self.partes.mes)
+    }
+    
+    /// CNPJ ou CPF do emitente com 14 dígitos, como está na chave
+    pub fn documento(&self) -> &str {
+        &self.partes.documento
+    }
+    
+    /// `None` quando o emitente é pessoa física
+    pub fn cnpj(&self) -> Option<Cnpj> {
+        Cnpj::new(self.partes.documento.clone()).ok()
+    }
+    
+    pub fn modelo(&self) -> u8 {
+        self.partes.modelo
+    }
+    
+    pub fn is_nfce(&self) -> bool {
+        self.partes.modelo == 65
+    }
+    
+    pub fn serie(&self) -> u16 {
+        self.partes.serie
+    }
+    
+    pub fn numero(&self) -> u32 {
+        self.partes.numero
+    }
+    
+    pub fn tipo_emissao(&self) -> u8 {
+        self.partes.tipo_emissao
+    }
+    
+    pub fn codigo_numerico(&self) -> u32 {
+        self.partes.codigo_numerico
+    }
+    
+    pub fn digito_verificador(&self) -> u8 {
+        self.value.as_bytes()[43] - b'0'
+    }
+    
+    /// Blocos de quatro dígitos, como impresso no DANFE
+    pub fn formatted(&self) -> String {
+        self.value
+            .as_bytes()
+            .chunks(4)
+            .map(|chunk| std::str::from_utf8(chunk).expect("apenas dígitos"))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+impl ValueObject for ChaveAcesso {}
+
+impl std::fmt::Display for ChaveAcesso {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+/// Sigla da UF a partir do código do IBGE
+pub fn uf_sigla(codigo_uf: u8) -> Option<&'static str> {
+    let sigla = match codigo_uf {
+        11 => "RO", 12 => "AC", 13 => "AM", 14 => "RR", 15 => "PA", 16 => "AP", 17 => "TO",
+        21 => "MA", 22 => "PI", 23 => "CE", 24 => "RN", 25 => "PB", 26 => "PE", 27 => "AL",
+        28 => "SE", 29 => "BA",
+        31 => "MG", 32 => "ES", 33 => "RJ", 35 => "SP",
+        41 => "PR", 42 => "SC", 43 => "RS",
+        50 => "MS", 51 => "MT", 52 => "GO", 53 => "DF",
+        _ => return None,
+    };
+    Some(sigla)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn partes() -> ChaveAcessoPartes {
+        ChaveAcessoPartes {
+            codigo_uf: 35,
+            ano: 25,
+            mes: 1,
+            documento: "11222333000181".to_string(),
+            modelo: 65,
+            serie: 1,
+            numero: 42,
+            tipo_emissao: 1,
+            codigo_numerico: 12345678,
+        }
+    }
+    
+    #[test]
+    fn test_build_and_decode() {
+        let chave = ChaveAcesso::from_partes(partes()).unwrap();
+        
+        assert_eq!(chave.value(), "35250111222333000181650010000000421123456787");
+        assert_eq!(chave.digito_verificador(), 7);
+        
+        let decoded = ChaveAcesso::new(chave.formatted()).unwrap();
+        assert_eq!(decoded, chave);
+        assert_eq!(decoded.uf(), "SP");
+        assert_eq!(decoded.ano_mes(), (2025, 1));
+        assert_eq!(decoded.documento(), "11222333000181");
+        assert_eq!(decoded.cnpj().unwrap().value(), "11222333000181");
+        assert!(decoded.is_nfce());
+        assert_eq!(decoded.serie(), 1);
+        assert_eq!(decoded.numero(), 42);
+        assert_eq!(decoded.codigo_numerico(), 12345678);
+    }
+    
+    #[test]
+    fn test_cpf_emitter() {
+        // Produtor rural pessoa física: CPF com zeros à esquerda no lugar do CNPJ
+        let mut cpf = partes();
+        cpf.documento = "00012345678909".to_string();
+        cpf.modelo = 55;
+        let chave = ChaveAcesso::from_partes(cpf).unwrap();
+        
+        let decoded = ChaveAcesso::new(chave.value().to_string()).unwrap();
+        assert_eq!(decoded.documento(), "00012345678909");
+        assert!(decoded.cnpj().is_none());
+    }
+    
+    #[test]
+    fn test_rejects_invalid_keys() {
+        // DV errado
+        assert!(ChaveAcesso::new("35250111222333000181650010000000421123456780".to_string()).is_err());
+        
+        let mut modelo_invalido = partes();
+        modelo_invalido.modelo = 57;
+        assert!(ChaveAcesso::from_partes(modelo_invalido).is_err());
+        
+        let mut uf_invalida = partes();
+        uf_invalida.codigo_uf = 99;
+        assert!(ChaveAcesso::from_partes(uf_invalida).is_err());
+    }
+}
@@ -0,0 +1,427 @@
+use anyhow::Result;
+use crate::models::{ItemNFe, NFe};
+use super::pdf::{fit_text, text_width, Font, PdfDocument, PdfPage, A4_HEIGHT, A4_WIDTH};
+use super::{format_chave, format_data_hora, format_documento, format_numero, format_quantidade, format_valor};
+
+const MARGIN: f64 = 20.0;
+const WIDTH: f64 = A4_WIDTH - 2.0 * MARGIN;
+const BOTTOM: f64 = A4_HEIGHT - MARGIN;
+const FIELD_HEIGHT: f64 = 20.0;
+const SECTION_GAP: f64 = 4.0;
+const ROW_HEIGHT: f64 = 10.0;
+const TABLE_HEADER_HEIGHT: f64 = 14.0;
+const ADDITIONAL_HEIGHT: f64 = 60.0;
+const LABEL_SIZE: f64 = 5.5;
+const VALUE_SIZE: f64 = 8.0;
+const ITEM_SIZE: f64 = 6.5;
+
+/// Colunas do quadro de produtos (rótulo, largura, alinhado à direita)
+const ITEM_COLUMNS: [(&str, f64, bool); 12] = [
+    ("CÓDIGO", 50.0, false),
+    ("DESCRIÇÃO DO PRODUTO / SERVIÇO", 165.28, false),
+    ("NCM/SH", 40.0, false),
+    ("CST", 22.0, false),
+    ("CFOP", 24.0, false),
+    ("UN", 22.0, false),
+    ("QUANT.", 40.0, true),
+    ("V. UNIT.", 45.0, true),
+    ("V. TOTAL", 45.0, true),
+    ("BC ICMS", 40.0, true),
+    ("V. ICMS", 35.0, true),
+    ("ALÍQ. ICMS", 27.0, true),
+];
+
+/// DANFE em formato retrato A4
+pub struct DanfePdf;
+
+impl DanfePdf {
+    pub fn render(nfe: &NFe, protocolo: Option<&str>) -> Result<Vec<u8>> {
+        if nfe.chave.len() != 44 || !nfe.chave.chars().all(|c| c.is_ascii_digit()) {
+            anyhow::bail!("Chave de acesso inválida para o DANFE: {}", nfe.chave);
+        }
+        
+        // Primeira página leva canhoto, destinatário, impostos e dados adicionais;
+        // as demais continuam apenas o quadro de produtos
+        let first_capacity = Self::capacity(Self::first_page_top(&mut PdfPage::new(), nfe, protocolo, 1, 1), BOTTOM - ADDITIONAL_HEIGHT - 8.0 - SECTION_GAP);
+        let other_capacity = Self::capacity(Self::header_top(&mut PdfPage::new(), nfe, protocolo, MARGIN, 1, 1), BOTTOM);
+        
+        let mut chunks: Vec<&[ItemNFe]> = Vec::new();
+        let (first, mut rest) = nfe.itens.split_at(nfe.itens.len().min(first_capacity));
+        chunks.push(first);
+        while !rest.is_empty() {
+            let (chunk, remaining) = rest.split_at(rest.len().min(other_capacity));
+            chunks.push(chunk);
+            rest = remaining;
+        }
+        
+        let total = chunks.len();
+        let mut document = PdfDocument::new();
+        
+        for (index, itens) in chunks.into_iter().enumerate() {
+            let folha = index + 1;
+            let mut page = PdfPage::new();
+            page.line_width(0.5);
+            
+            if folha == 1 {
+                let top = Self::first_page_top(&mut page, nfe, protocolo, folha, total);
+                let bottom = BOTTOM - ADDITIONAL_HEIGHT - 8.0 - SECTION_GAP;
+                Self::items(&mut page, itens, top, bottom);
+                Self::additional_data(&mut page, nfe, bottom + SECTION_GAP);
+            } else {
+                let top = Self::header_top(&mut page, nfe, protocolo, MARGIN, folha, total);
+                Self::items(&mut page, itens, top, BOTTOM);
+            }
+            
+            document.add_page(page);
+        }
+        
+        Ok(document.to_bytes())
+    }
+    
+    /// Quantidade de linhas de produto entre o topo do quadro e `bottom`
+    fn capacity(top: f64, bottom: f64) -> usize {
+        let available = bottom - top - 8.0 - TABLE_HEADER_HEIGHT;
+        (available / ROW_HEIGHT).floor().max(1.0) as usize
+    }
+    
+    fn first_page_top(page: &mut PdfPage, nfe: &NFe, protocolo: Option<&str>, folha: usize, total: usize) -> f64 {
+        let y = Self::receipt(page, nfe);
+        let y = Self::header_top(page, nfe, protocolo, y, folha, total);
+        let y = Self::recipient(page, nfe, y);
+        let y = Self::taxes(page, nfe, y);
+        Self::transport(page, nfe, y)
+    }
+    
+    /// Canhoto de recebimento, destacado por linha tracejada
+    fn receipt(page: &mut PdfPage, nfe: &NFe) -> f64 {
+        let height = 40.0;
+        let stub_width = 110.0;
+        let left_width = WIDTH - stub_width;
+        
+        page.rect(MARGIN, MARGIN, left_width, height);
+        let recebemos = format!(
+            "RECEBEMOS DE {} OS PRODUTOS E/OU SERVIÇOS CONSTANTES DA NOTA FISCAL ELETRÔNICA INDICADA AO LADO",
+            nfe.emitente.razao_social.to_uppercase()
+        );
+        page.text(MARGIN + 2.0, MARGIN + 10.0, 6.0, Font::Regular, &fit_text(&recebemos, 6.0, left_width - 4.0));
+        Self::field(page, MARGIN, MARGIN + height - FIELD_HEIGHT, 100.0, "DATA DE RECEBIMENTO", "", false);
+        Self::field(page, MARGIN + 100.0, MARGIN + height - FIELD_HEIGHT, left_width - 100.0, "IDENTIFICAÇÃO E ASSINATURA DO RECEBEDOR", "", false);
+        
+        let stub_x = MARGIN + left_width;
+        let center = stub_x + stub_width / 2.0;
+        page.rect(stub_x, MARGIN, stub_width, height);
+        page.text_center(center, MARGIN + 12.0, 10.0, Font::Bold, "NF-e");
+        page.text_center(center, MARGIN + 24.0, 8.0, Font::Bold, &format!("Nº {}", format_numero(&nfe.numero)));
+        page.text_center(center, MARGIN + 34.0, 8.0, Font::Bold, &format!("SÉRIE {:0>3}", nfe.serie));
+        
+        let cut = MARGIN + height + 6.0;
+        page.dashed_line(MARGIN, cut, MARGIN + WIDTH, cut);
+        
+        cut + 6.0
+    }
+    
+    /// Quadro do emitente, identificação do DANFE, chave de acesso e protocolo
+    fn header_top(page: &mut PdfPage, nfe: &NFe, protocolo: Option<&str>, y: f64, folha: usize, total: usize) -> f64 {
+        let height = 110.0;
+        let emit_width = 230.0;
+        let danfe_width = 105.0;
+        let key_width = WIDTH - emit_width - danfe_width;
+        let emit = &nfe.emitente;
+        
+        // Emitente
+        page.rect(MARGIN, y, emit_width, height);
+        page.text_center(MARGIN + emit_width / 2.0, y + 10.0, LABEL_SIZE, Font::Regular, "IDENTIFICAÇÃO DO EMITENTE");
+        page.text_center(MARGIN + emit_width / 2.0, y + 30.0, 10.0, Font::Bold, &fit_text(&emit.razao_social, 10.0, emit_width - 8.0));
+        
+        let mut endereco = format!("{}, {}", emit.endereco.logradouro, emit.endereco.numero);
+        if let Some(complemento) = &emit.endereco.complemento {
+            endereco.push_str(&format!(" - {}", complemento));
+        }
+        let mut lines = vec![
+            endereco,
+            format!("{} - {}/{}", emit.endereco.bairro, emit.endereco.municipio, emit.endereco.uf),
+            format!("CEP: {}", emit.endereco.cep),
+        ];
+        if let Some(telefone) = &emit.telefone {
+            lines.push(format!("Fone: {}", telefone));
+        }
+        for (i, line) in lines.iter().enumerate() {
+            page.text_center(MARGIN + emit_width / 2.0, y + 48.0 + i as f64 * 11.0, 7.5, Font::Regular, &fit_text(line, 7.5, emit_width - 8.0));
+        }
+        
+        // Identificação do documento
+        let danfe_x = MARGIN + emit_width;
+        let center = danfe_x + danfe_width / 2.0;
+        page.rect(danfe_x, y, danfe_width, height);
+        page.text_center(center, y + 16.0, 14.0, Font::Bold, "DANFE");
+        page.text_center(center, y + 26.0, 6.0, Font::Regular, "DOCUMENTO AUXILIAR DA");
+        page.text_center(center, y + 33.0, 6.0, Font::Regular, "NOTA FISCAL ELETRÔNICA");
+        page.text(danfe_x + 10.0, y + 46.0, 7.0, Font::Regular, "0 - ENTRADA");
+        page.text(danfe_x + 10.0, y + 55.0, 7.0, Font::Regular, "1 - SAÍDA");
+        page.rect(danfe_x + danfe_width - 30.0, y + 40.0, 16.0, 16.0);
+        page.text_center(danfe_x + danfe_width - 22.0, y + 52.0, 10.0, Font::Bold, if nfe.is_entrada() { "0" } else { "1" });
+        page.text_center(center, y + 74.0, 9.0, Font::Bold, &format!("Nº {}", format_numero(&nfe.numero)));
+        page.text_center(center, y + 86.0, 9.0, Font::Bold, &format!("SÉRIE {:0>3}", nfe.serie));
+        page.text_center(center, y + 100.0, 8.0, Font::Regular, &format!("FOLHA {}/{}", folha, total));
+        
+        // Código de barras e chave de acesso
+        let key_x = danfe_x + danfe_width;
+        let key_center = key_x + key_width / 2.0;
+        page.rect(key_x, y, key_width, height);
+        page.barcode_128c(key_x + 8.0, y + 6.0, key_width - 16.0, 36.0, &nfe.chave);
+        page.line(key_x, y + 48.0, key_x + key_width, y + 48.0);
+        page.text(key_x + 2.0, y + 54.0, LABEL_SIZE, Font::Regular, "CHAVE DE ACESSO");
+        page.text_center(key_center, y + 65.0, 7.5, Font::Bold, &format_chave(&nfe.chave));
+        page.line(key_x, y + 72.0, key_x + key_width, y + 72.0);
+        page.text_center(key_center, y + 86.0, 7.0, Font::Regular, "Consulta de autenticidade no portal nacional da NF-e");
+        page.text_center(key_center, y + 95.0, 7.0, Font::Regular, "www.nfe.fazenda.gov.br/portal ou no site da Sefaz");
+        page.text_center(key_center, y + 104.0, 7.0, Font::Regular, "Autorizadora");
+        
+        let y = y + height;
+        let protocolo_texto = protocolo.unwrap_or("").to_string();
+        Self::field(page, MARGIN, y, emit_width + danfe_width, "CNPJ DO EMITENTE", &format_documento(&emit.cnpj), false);
+        Self::field(page, key_x, y, key_width, "PROTOCOLO DE AUTORIZAÇÃO DE USO", &protocolo_texto, false);
+        
+        y + FIELD_HEIGHT + SECTION_GAP
+    }
+    
+    fn recipient(page: &mut PdfPage, nfe: &NFe, y: f64) -> f64 {
+        let dest = &nfe.destinatario;
+        let end = &dest.endereco;
+        let y = Self::section_title(page, y, "DESTINATÁRIO / REMETENTE");
+        
+        Self::row(page, y, &[
+            ("NOME / RAZÃO SOCIAL", dest.razao_social.clone(), 330.0, false),
+            ("CNPJ / CPF", format_documento(&dest.cnpj_cpf), 125.0, false),
+            ("DATA DA EMISSÃO", format_data_hora(nfe.data_emissao)[..10].to_string(), 0.0, false),
+        ]);
+        
+        let mut logradouro = format!("{}, {}", end.logradouro, end.numero);
+        if let Some(complemento) = &end.complemento {
+            logradouro.push_str(&format!(" - {}", complemento));
+        }
+        let y = y + FIELD_HEIGHT;
+        Self::row(page, y, &[
+            ("ENDEREÇO", if end.logradouro.is_empty() { String::new() } else { logradouro }, 250.0, false),
+            ("BAIRRO / DISTRITO", end.bairro.clone(), 150.0, false),
+            ("CEP", end.cep.clone(), 0.0, false),
+        ]);
+        
+        let y = y + FIELD_HEIGHT;
+        Self::row(page, y, &[
+            ("MUNICÍPIO", end.municipio.clone(), 250.0, false),
+            ("UF", end.uf.clone(), 40.0, false),
+            ("FONE / FAX", dest.telefone.clone().unwrap_or_default(), 0.0, false),
+        ]);
+        
+        y + FIELD_HEIGHT + SECTION_GAP
+    }
+    
+    fn taxes(page: &mut PdfPage, nfe: &NFe, y: f64) -> f64 {
+        let t = &nfe.totais;
+        let y = Self::section_title(page, y, "CÁLCULO DO IMPOSTO");
+        
+        Self::equal_row(page, y, &[
+            ("BASE DE CÁLC. DO ICMS", t.base_calculo_icms),
+            ("VALOR DO ICMS", t.valor_icms),
+            ("BASE DE CÁLC. ICMS S.T.", t.base_calculo_icms_st),
+            ("VALOR DO ICMS SUBST.", t.valor_icms_st),
+            ("VALOR DO PIS", t.valor_pis),
+            ("V. TOTAL PRODUTOS", t.valor_total_produtos),
+        ]);
+        
+        let y = y + FIELD_HEIGHT;
+        Self::equal_row(page, y, &[
+            ("VALOR DO FRETE", t.valor_frete),
+            ("VALOR DO SEGURO", t.valor_seguro),
+            ("DESCONTO", t.valor_desconto),
+            ("OUTRAS DESPESAS", t.valor_outras_despesas),
+            ("VALOR TOTAL IPI", t.valor_ipi),
+            ("VALOR DA COFINS", t.valor_cofins),
+            ("V. TOTAL DA NOTA", t.valor_total_nota),
+        ]);
+        
+        y + FIELD_HEIGHT + SECTION_GAP
+    }
+    
+    fn transport(page: &mut PdfPage, nfe: &NFe, y: f64) -> f64 {
+        let y = Self::section_title(page, y, "TRANSPORTADOR / VOLUMES TRANSPORTADOS");
+        
+        let (modalidade, razao_social, placa, uf, quantidade, peso_bruto, peso_liquido) = match &nfe.transporte {
+            Some(transporte) => {
+                let transportadora = transporte.transportadora.as_ref();
+                let veiculo = transporte.veiculo.as_ref();
+                let quantidade: i32 = transporte.volumes.iter().map(|v| v.quantidade).sum();
+                let peso_bruto: f64 = transporte.volumes.iter().filter_map(|v| v.peso_bruto).sum();
+                let peso_liquido: f64 = transporte.volumes.iter().filter_map(|v| v.peso_liquido).sum();
+                
+                (
+                    modalidade_frete(&transporte.modalidade),
+                    transportadora.and_then(|t| t.razao_social.clone()).unwrap_or_default(),
+                    veiculo.map(|v| v.placa.clone()).unwrap_or_default(),
+                    veiculo.map(|v| v.uf.clone()).unwrap_or_default(),
+                    if transporte.volumes.is_empty() { String::new() } else { quantidade.to_string() },
+                    if peso_bruto > 0.0 { format_quantidade(peso_bruto) } else { String::new() },
+                    if peso_liquido > 0.0 { format_quantidade(peso_liquido) } else { String::new() },
+                )
+            }
+            None => (
+                modalidade_frete("9"),
+                String::new(),
+                String::new(),
+                String::new(),
+                String::new(),
+                String::new(),
+                String::new(),
+            ),
+        };
+        
+        Self::row(page, y, &[
+            ("RAZÃO SOCIAL", razao_social, 190.0, false),
+            ("FRETE POR CONTA", modalidade, 110.0, false),
+            ("PLACA DO VEÍCULO", placa, 60.0, false),
+            ("UF", uf, 25.0, false),
+            ("QUANTIDADE", quantidade, 50.0, true),
+            ("PESO BRUTO", peso_bruto, 60.0, true),
+            ("PESO LÍQUIDO", peso_liquido, 0.0, true),
+        ]);
+        
+        y + FIELD_HEIGHT + SECTION_GAP
+    }
+    
+    /// Quadro de produtos ocupando de `top` até `bottom`
+    fn items(page: &mut PdfPage, itens: &[ItemNFe], top: f64, bottom: f64) {
+        let y = Self::section_title(page, top, "DADOS DO PRODUTO / SERVIÇO");
+        
+        page.rect(MARGIN, y, WIDTH, bottom - y);
+        page.line(MARGIN, y + TABLE_HEADER_HEIGHT, MARGIN + WIDTH, y + TABLE_HEADER_HEIGHT);
+        
+        let mut x = MARGIN;
+        for (i, (label, width, _)) in ITEM_COLUMNS.iter().enumerate() {
+            if i > 0 {
+                page.line(x, y, x, bottom);
+            }
+            page.text_center(x + width / 2.0, y + 9.0, 5.0, Font::Bold, &fit_text(label, 5.0, width - 2.0));
+            x += width;
+        }
+        
+        for (row, item) in itens.iter().enumerate() {
+            let baseline = y + TABLE_HEADER_HEIGHT + row as f64 * ROW_HEIGHT + 7.5;
+            let icms = item.icms.as_ref();
+            let cst = icms
+                .map(|icms| format!("{}{}", item.origem.clone().unwrap_or_default(), icms.situacao_tributaria))
+                .unwrap_or_default();
+            let optional = |value: Option<f64>| value.map(format_valor).unwrap_or_default();
+            
+            let values = [
+                item.codigo_produto.clone(),
+                item.descricao.clone(),
+                item.ncm.clone(),
+                cst,
+                item.cfop.clone(),
+                item.unidade_comercial.clone(),
+                format_quantidade(item.quantidade_comercial),
+                format_valor(item.valor_unitario_comercial),
+                format_valor(item.valor_total_bruto),
+                optional(icms.and_then(|i| i.base_calculo)),
+                optional(icms.and_then(|i| i.valor)),
+                optional(icms.and_then(|i| i.aliquota)),
+            ];
+            
+            let mut x = MARGIN;
+            for ((_, width, right), value) in ITEM_COLUMNS.iter().zip(values.iter()) {
+                let value = fit_text(value, ITEM_SIZE, width - 4.0);
+                if *right {
+                    page.text_right(x + width - 2.0, baseline, ITEM_SIZE, Font::Regular, &value);
+                } else {
+                    page.text(x + 2.0, baseline, ITEM_SIZE, Font::Regular, &value);
+                }
+                x += width;
+            }
+        }
+    }
+    
+    fn additional_data(page: &mut PdfPage, nfe: &NFe, y: f64) {
+        let y = Self::section_title(page, y, "DADOS ADICIONAIS");
+        let info_width = 370.0;
+        
+        page.rect(MARGIN, y, info_width, ADDITIONAL_HEIGHT);
+        page.text(MARGIN + 2.0, y + 6.0, LABEL_SIZE, Font::Regular, "INFORMAÇÕES COMPLEMENTARES");
+        
+        let texto = nfe.informacoes_adicionais.clone().unwrap_or_default();
+        let max_lines = ((ADDITIONAL_HEIGHT - 10.0) / 7.0) as usize;
+        for (i, line) in wrap_width(&texto, 6.0, info_width - 4.0).iter().take(max_lines).enumerate() {
+            page.text(MARGIN + 2.0, y + 14.0 + i as f64 * 7.0, 6.0, Font::Regular, line);
+        }
+        
+        page.rect(MARGIN + info_width, y, WIDTH - info_width, ADDITIONAL_HEIGHT);
+        page.text(MARGIN + info_width + 2.0, y + 6.0, LABEL_SIZE, Font::Regular, "RESERVADO AO FISCO");
+    }
+    
+    fn section_title(page: &mut PdfPage, y: f64, title: &str) -> f64 {
+        page.text(MARGIN, y + 6.0, 6.5, Font::Bold, title);
+        y + 8.0
+    }
+    
+    fn field(page: &mut PdfPage, x: f64, y: f64, width: f64, label: &str, value: &str, right: bool) {
+        page.rect(x, y, width, FIELD_HEIGHT);
+        page.text(x + 2.0, y + 6.0, LABEL_SIZE, Font::Regular, &fit_text(label, LABEL_SIZE, width - 4.0));
+        
+        let value = fit_text(value, VALUE_SIZE, width - 4.0);
+        if right {
+            page.text_right(x + width - 2.0, y + 16.0, VALUE_SIZE, Font::Regular, &value);
+        } else {
+            page.text(x + 2.0, y + 16.0, VALUE_SIZE, Font::Regular, &value);
+        }
+    }
+    
+    /// Linha de campos; largura 0 ocupa o restante da página
+    fn row(page: &mut PdfPage, y: f64, fields: &[(&str, String, f64, bool)]) {
+        let mut x = MARGIN;
+        for (label, value, width, right) in fields {
+            let width = if *width > 0.0 { *width } else { MARGIN + WIDTH - x };
+            Self::field(page, x, y, width, label, value, *right);
+            x += width;
+        }
+    }
+    
+    fn equal_row(page: &mut PdfPage, y: f64, fields: &[(&str, f64)]) {
+        let width = WIDTH / fields.len() as f64;
+        for (i, (label, value)) in fields.iter().enumerate() {
+            Self::field(page, MARGIN + i as f64 * width, y, width, label, &format_valor(*value), true);
+        }
+    }
+}
+
+/// Modalidade do frete (modFrete)
+fn modalidade_frete(codigo: &str) -> String {
+    let descricao = match codigo {
+        "0" => "Emitente",
+        "1" => "Destinatário",
+        "2" => "Terceiros",
+        "3" => "Próprio Remetente",
+        "4" => "Próprio Destinatário",
+        _ => "Sem Transporte",
+    };
+    format!("{} - {}", codigo, descricao)
+}
+
+fn wrap_width(text: &str, size: f64, width: f64) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    
+    for word in text.split_whitespace() {
+        let candidate = if current.is_empty() { word.to_string() } else { format!("{} {}", current, word) };
+        if text_width(&candidate, size) <= width || current.is_empty() {
+            current = fit_text(&candidate, size, width);
+        } else {
+            lines.push(std::mem::replace(&mut current, fit_text(word, size, width)));
+        }
+    }
+    
+    if !current.is_empty() {
+        lines.push(current);
+    }
+    
+    lines
+}
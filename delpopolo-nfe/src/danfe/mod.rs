@@ -0,0 +1,262 @@
+pub mod pdf;
+pub mod a4;
+pub mod thermal;
+
+pub use a4::DanfePdf;
+pub use thermal::{DanfeNfce, DanfeNfceInfo};
+
+use chrono::{DateTime, FixedOffset, Utc};
+
+/// Chave de acesso em blocos de 4 dígitos
+pub fn format_chave(chave: &str) -> String {
+    chave
+        .as_bytes()
+        .chunks(4)
+        .map(|chunk| String::from_utf8_lossy(chunk).to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// CNPJ (14 dígitos) ou CPF (11 dígitos) com máscara
+pub fn format_documento(documento: &str) -> String {
+    let d: String = documento.chars().filter(|c| c.is_ascii_digit()).collect();
+    
+    match d.len() {
+        14 => format!("{}.{}.{}/{}-{}", &d[..2], &d[2..5], &d[5..8], &d[8..12], &d[12..]),
+        11 => format!("{}.{}.{}-{}", &d[..3], &d[3..6], &d[6..9], &d[9..]),
+        _ => documento.to_string(),
+    }
+}
+
+/// Valor monetário no formato brasileiro (1.234,56)
+pub fn format_valor(valor: f64) -> String {
+    format_decimal(valor, 2)
+}
+
+pub fn format_quantidade(quantidade: f64) -> String {
+    format_decimal(quantidade, 3)
+}
+
+fn format_decimal(value: f64, casas: usize) -> String {
+    let formatted = format!("{:.*}", casas, value.abs());
+    let (inteiro, decimal) = formatted.split_once('.').unwrap_or((&formatted, ""));
+    
+    let mut agrupado = String::new();
+    for (i, c) in inteiro.chars().enumerate() {
+        if i > 0 && (inteiro.len() - i) % 3 == 0 {
+            agrupado.push('.');
+        }
+        agrupado.push(c);
+    }
+    
+    let sinal = if value < 0.0 && formatted.chars().any(|c| c.is_ascii_digit() && c != '0') { "-" } else { "" };
+    if decimal.is_empty() {
+        format!("{}{}", sinal, agrupado)
+    } else {
+        format!("{}{},{}", sinal, agrupado, decimal)
+    }
+}
+
+/// Número da nota com 9 dígitos (000.000.042)
+pub fn format_numero(numero: &str) -> String {
+    let padded = format!("{:0>9}", numero.trim());
+    if padded.len() == 9 && padded.chars().all(|c| c.is_ascii_digit()) {
+        format!("{}.{}.{}", &padded[..3], &padded[3..6], &padded[6..])
+    } else {
+        numero.to_string()
+    }
+}
+
+/// Data/hora no fuso de Brasília, como impressa nos documentos auxiliares
+pub fn format_data_hora(data: DateTime<Utc>) -> String {
+    let brasilia = FixedOffset::west_opt(3 * 3600).expect("valid offset");
+    data.with_timezone(&brasilia).format("%d/%m/%Y %H:%M:%S").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use crate::models::*;
+    use crate::nfce::Ambiente;
+    
+    fn endereco() -> Endereco {
+        Endereco {
+            logradouro: "Rua das Padarias".to_string(),
+            numero: "10".to_string(),
+            complemento: None,
+            bairro: "Centro".to_string(),
+            municipio: "São Paulo".to_string(),
+            uf: "SP".to_string(),
+            cep: "01001000".to_string(),
+        }
+    }
+    
+    fn item(numero_item: i32, descricao: &str, quantidade: f64, valor_unitario: f64) -> ItemNFe {
+        let total = (quantidade * valor_unitario * 100.0).round() / 100.0;
+        ItemNFe {
+            numero_item,
+            codigo_produto: format!("P{:03}", numero_item),
+            descricao: descricao.to_string(),
+            ncm: "19059010".to_string(),
+            cest: None,
+            cfop: "5102".to_string(),
+            unidade_comercial: "UN".to_string(),
+            quantidade_comercial: quantidade,
+            valor_unitario_comercial: valor_unitario,
+            valor_total_bruto: total,
+            ean: None,
+            ean_tributavel: None,
+            origem: Some("0".to_string()),
+            icms: Some(ICMS {
+                situacao_tributaria: "00".to_string(),
+                base_calculo: Some(total),
+                aliquota: Some(18.0),
+                valor: Some((total * 18.0).round() / 100.0),
+            }),
+            ipi: None,
+            pis: None,
+            cofins: None,
+        }
+    }
+    
+    fn sample_nfe(itens: usize) -> NFe {
+        let itens: Vec<ItemNFe> = (1..=itens as i32)
+            .map(|i| item(i, &format!("Pão de fermentação natural {}", i), 0.5, 20.0))
+            .collect();
+        let total = itens.iter().map(|i| i.valor_total_bruto).sum::<f64>();
+        let icms = itens.iter().filter_map(|i| i.icms.as_ref()?.valor).sum::<f64>();
+        
+        NFe {
+            chave: "35250111222333000181650010000000421123456787".to_string(),
+            numero: "42".to_string(),
+            serie: "1".to_string(),
+            data_emissao: Utc.with_ymd_and_hms(2025, 1, 15, 15, 30, 0).unwrap(),
+            emitente: Emitente {
+                cnpj: "11222333000181".to_string(),
+                razao_social: "DelPopolo Panificadora LTDA".to_string(),
+                nome_fantasia: Some("DelPopolo".to_string()),
+                endereco: endereco(),
+                telefone: Some("1133334444".to_string()),
+                email: None,
+            },
+            destinatario: Destinatario {
+                cnpj_cpf: String::new(),
+                razao_social: "CONSUMIDOR NÃO IDENTIFICADO".to_string(),
+                endereco: endereco(),
+                telefone: None,
+                email: None,
+            },
+            itens,
+            totais: Totais {
+                base_calculo_icms: total,
+                valor_icms: icms,
+                valor_icms_desonerado: 0.0,
+                base_calculo_icms_st: 0.0,
+                valor_icms_st: 0.0,
+                valor_total_produtos: total,
+                valor_frete: 0.0,
+                valor_seguro: 0.0,
+                valor_desconto: 0.0,
+                valor_total_ii: 0.0,
+                valor_ipi: 0.0,
+                valor_pis: 0.0,
+                valor_cofins: 0.0,
+                valor_outras_despesas: 0.0,
+                valor_total_nota: total,
+            },
+            transporte: None,
+            informacoes_adicionais: Some("Pedido 1234".to_string()),
+        }
+    }
+    
+    fn info() -> DanfeNfceInfo {
+        DanfeNfceInfo {
+            url_consulta: "https://www.homologacao.nfce.fazenda.sp.gov.br/consulta".to_string(),
+            qr_code_url: "https://www.homologacao.nfce.fazenda.sp.gov.br/qrcode?p=35250111222333000181650010000000421123456787|2|2|1|5532DEE5041442BB9940540B582F74957A3BD136".to_string(),
+            ambiente: Ambiente::Homologacao,
+            protocolo: Some("135250000000001".to_string()),
+            forma_pagamento: Some("17".to_string()),
+            valor_pago: Some(10.0),
+        }
+    }
+    
+    #[test]
+    fn test_format_helpers() {
+        assert_eq!(format_documento("11222333000181"), "11.222.333/0001-81");
+        assert_eq!(format_documento("12345678909"), "123.456.789-09");
+        assert_eq!(format_valor(1234567.5), "1.234.567,50");
+        assert_eq!(format_valor(-2.0), "-2,00");
+        assert_eq!(format_numero("42"), "000.000.042");
+        assert_eq!(
+            format_chave("35250111222333000181650010000000421123456787"),
+            "3525 0111 2223 3300 0181 6500 1000 0000 4211 2345 6787"
+        );
+    }
+    
+    #[test]
+    fn test_danfe_nfce_text_layout() {
+        let text = DanfeNfce::render_text(&sample_nfe(1), &info()).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        
+        assert!(lines.iter().all(|line| line.chars().count() <= DanfeNfce::COLUNAS));
+        assert_eq!(lines[0].trim(), "DelPopolo Panificadora LTDA");
+        assert!(lines.contains(&"001 P001 Pão de fermentação natural 1"));
+        assert!(lines.contains(&"    0,500 UN x 20,00                       10,00"));
+        assert!(lines.contains(&"Valor a pagar R$                           10,00"));
+        assert!(lines.contains(&"PIX                                        10,00"));
+        assert!(lines.iter().any(|line| line.trim() == "3525 0111 2223 3300 0181 6500"));
+        assert!(lines.iter().any(|line| line.trim() == "EMITIDA EM AMBIENTE DE HOMOLOGAÇÃO"));
+        assert!(lines.iter().any(|line| line.trim() == "NFC-e nº 000000042 Série 001 15/01/2025 12:30:00"));
+        assert!(text.contains('▀') || text.contains('▄'));
+        
+        assert_eq!(text, DanfeNfce::render_text(&sample_nfe(1), &info()).unwrap());
+    }
+    
+    #[test]
+    fn test_danfe_nfce_escpos() {
+        let bytes = DanfeNfce::render_escpos(&sample_nfe(1), &info()).unwrap();
+        
+        assert!(bytes.starts_with(&[0x1B, 0x40]));
+        assert!(bytes.ends_with(&[0x1D, 0x56, 0x42, 0x00]));
+        
+        // Armazenamento do QR Code: GS ( k pL pH 49 80 48 <dados>
+        let url = info().qr_code_url;
+        let mut store = vec![0x1D, 0x28, 0x6B];
+        let length = url.len() + 3;
+        store.extend_from_slice(&[(length % 256) as u8, (length / 256) as u8, 49, 80, 48]);
+        store.extend_from_slice(url.as_bytes());
+        assert!(bytes.windows(store.len()).any(|window| window == store.as_slice()));
+        
+        let text = String::from_utf8_lossy(&bytes);
+        assert!(text.contains("EMITIDA EM AMBIENTE DE HOMOLOGACAO"));
+        assert!(text.contains("NFC-e no 000000042 Serie 001"));
+    }
+    
+    #[test]
+    fn test_danfe_pdf_is_deterministic_and_paginates() {
+        let nfe = sample_nfe(3);
+        let pdf = DanfePdf::render(&nfe, Some("135250000000001")).unwrap();
+        
+        assert!(pdf.starts_with(b"%PDF-1.4"));
+        assert_eq!(pdf, DanfePdf::render(&nfe, Some("135250000000001")).unwrap());
+        
+        let content = String::from_utf8_lossy(&pdf);
+        assert!(content.contains("/Count 1 "));
+        assert!(content.contains("(3525 0111 2223 3300 0181 6500 1000 0000 4211 2345 6787)"));
+        assert!(content.contains("(FOLHA 1/1)"));
+        
+        let long = DanfePdf::render(&sample_nfe(120), None).unwrap();
+        let content = String::from_utf8_lossy(&long);
+        assert!(content.contains("/Count 3 "));
+        assert!(content.contains("(FOLHA 3/3)"));
+    }
+    
+    #[test]
+    fn test_danfe_pdf_rejects_invalid_chave() {
+        let mut nfe = sample_nfe(1);
+        nfe.chave = "123".to_string();
+        
+        assert!(DanfePdf::render(&nfe, None).is_err());
+    }
+}
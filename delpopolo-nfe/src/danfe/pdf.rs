@@ -0,0 +1,361 @@
+use std::fmt::Write as _;
+
+/// Largura e altura de uma página A4 em pontos
+pub const A4_WIDTH: f64 = 595.28;
+pub const A4_HEIGHT: f64 = 841.89;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Font {
+    Regular,
+    Bold,
+}
+
+impl Font {
+    fn resource(&self) -> &'static str {
+        match self {
+            Font::Regular => "F1",
+            Font::Bold => "F2",
+        }
+    }
+}
+
+/// Página com coordenadas a partir do canto superior esquerdo (em pontos)
+#[derive(Debug, Default)]
+pub struct PdfPage {
+    content: String,
+}
+
+impl PdfPage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    pub fn text(&mut self, x: f64, y: f64, size: f64, font: Font, text: &str) {
+        let _ = writeln!(
+            self.content,
+            "BT /{} {} Tf {} {} Td ({}) Tj ET",
+            font.resource(),
+            num(size),
+            num(x),
+            num(A4_HEIGHT - y),
+            escape(text)
+        );
+    }
+    
+    pub fn text_right(&mut self, right: f64, y: f64, size: f64, font: Font, text: &str) {
+        self.text(right - text_width(text, size), y, size, font, text);
+    }
+    
+    pub fn text_center(&mut self, center: f64, y: f64, size: f64, font: Font, text: &str) {
+        self.text(center - text_width(text, size) / 2.0, y, size, font, text);
+    }
+    
+    pub fn rect(&mut self, x: f64, y: f64, width: f64, height: f64) {
+        let _ = writeln!(
+            self.content,
+            "{} {} {} {} re S",
+            num(x),
+            num(A4_HEIGHT - y - height),
+            num(width),
+            num(height)
+        );
+    }
+    
+    pub fn fill_rect(&mut self, x: f64, y: f64, width: f64, height: f64) {
+        let _ = writeln!(
+            self.content,
+            "{} {} {} {} re f",
+            num(x),
+            num(A4_HEIGHT - y - height),
+            num(width),
+            num(height)
+        );
+    }
+    
+    pub fn line(&mut self, x1: f64, y1: f64, x2: f64, y2: f64) {
+        let _ = writeln!(
+            self.content,
+            "{} {} m {} {} l S",
+            num(x1),
+            num(A4_HEIGHT - y1),
+            num(x2),
+            num(A4_HEIGHT - y2)
+        );
+    }
+    
+    pub fn dashed_line(&mut self, x1: f64, y1: f64, x2: f64, y2: f64) {
+        self.content.push_str("[3 2] 0 d\n");
+        self.line(x1, y1, x2, y2);
+        self.content.push_str("[] 0 d\n");
+    }
+    
+    pub fn line_width(&mut self, width: f64) {
+        let _ = writeln!(self.content, "{} w", num(width));
+    }
+    
+    /// Código de barras CODE-128C (apenas dígitos, quantidade par)
+    pub fn barcode_128c(&mut self, x: f64, y: f64, width: f64, height: f64, digits: &str) {
+        let modules = code128c_modules(digits);
+        let module_width = width / modules.len() as f64;
+        
+        let mut start = 0;
+        while start < modules.len() {
+            if !modules[start] {
+                start += 1;
+                continue;
+            }
+            
+            let mut end = start;
+            while end < modules.len() && modules[end] {
+                end += 1;
+            }
+            
+            self.fill_rect(x + start as f64 * module_width, y, (end - start) as f64 * module_width, height);
+            start = end;
+        }
+    }
+}
+
+/// Gerador mínimo de PDF 1.4 com as fontes padrão Helvetica.
+///
+/// Não grava data de criação nem identificadores aleatórios: o mesmo conteúdo
+/// sempre produz os mesmos bytes.
+#[derive(Debug, Default)]
+pub struct PdfDocument {
+    pages: Vec<PdfPage>,
+}
+
+impl PdfDocument {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    pub fn add_page(&mut self, page: PdfPage) {
+        self.pages.push(page);
+    }
+    
+    pub fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+    
+    pub fn to_bytes(&self) -> Vec<u8> {
+        // 1: Catalog, 2: Pages, 3/4: fontes, depois pares (página, conteúdo)
+        let page_ids: Vec<usize> = (0..self.pages.len()).map(|i| 5 + i * 2).collect();
+        
+        let mut objects: Vec<Vec<u8>> = vec![
+            b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {} >>",
+                page_ids.iter().map(|id| format!("{} 0 R", id)).collect::<Vec<_>>().join(" "),
+                self.pages.len()
+            )
+            .into_bytes(),
+            b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>".to_vec(),
+            b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>".to_vec(),
+        ];
+        
+        for (page, id) in self.pages.iter().zip(&page_ids) {
+            objects.push(
+                format!(
+                    "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+                    num(A4_WIDTH),
+                    num(A4_HEIGHT),
+                    id + 1
+                )
+                .into_bytes(),
+            );
+            
+            let content = latin1(&page.content);
+            let mut stream = format!("<< /Length {} >>\nstream\n", content.len()).into_bytes();
+            stream.extend_from_slice(&content);
+            stream.extend_from_slice(b"\nendstream");
+            objects.push(stream);
+        }
+        
+        let mut out: Vec<u8> = b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec();
+        let mut offsets = Vec::with_capacity(objects.len());
+        
+        for (i, object) in objects.iter().enumerate() {
+            offsets.push(out.len());
+            out.extend_from_slice(format!("{} 0 obj\n", i + 1).as_bytes());
+            out.extend_from_slice(object);
+            out.extend_from_slice(b"\nendobj\n");
+        }
+        
+        let xref_offset = out.len();
+        let mut xref = format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+        for offset in offsets {
+            let _ = writeln!(xref, "{:010} 00000 n ", offset);
+        }
+        let _ = write!(
+            xref,
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            xref_offset
+        );
+        out.extend_from_slice(xref.as_bytes());
+        
+        out
+    }
+}
+
+/// Largura aproximada do texto em Helvetica (métricas AFM em milésimos de em)
+pub fn text_width(text: &str, size: f64) -> f64 {
+    let units: u32 = text
+        .chars()
+        .map(|c| match c {
+            ' ' | ',' | '.' | '/' | ':' | ';' | 'i' | 'j' | 'l' | 'I' | '!' | '|' => 278,
+            '-' | '(' | ')' | 'f' | 'r' | 't' => 333,
+            '0'..='9' | '$' => 556,
+            'm' | 'M' => 833,
+            'W' => 944,
+            'w' => 722,
+            c if c.is_uppercase() => 667,
+            _ => 556,
+        })
+        .sum();
+    
+    units as f64 * size / 1000.0
+}
+
+/// Corta o texto para caber em `width` pontos
+pub fn fit_text(text: &str, size: f64, width: f64) -> String {
+    let mut fitted = String::new();
+    for c in text.chars() {
+        fitted.push(c);
+        if text_width(&fitted, size) > width {
+            fitted.pop();
+            break;
+        }
+    }
+    fitted
+}
+
+fn num(value: f64) -> String {
+    let formatted = format!("{:.2}", value);
+    formatted
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_string()
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '(' | ')' | '\\' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' | '\r' => escaped.push(' '),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// WinAnsiEncoding coincide com Latin-1 para os caracteres acentuados do português
+fn latin1(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| if (c as u32) < 256 { c as u8 } else { b'?' })
+        .collect()
+}
+
+const CODE128_PATTERNS: [&str; 106] = [
+    "212222", "222122", "222221", "121223", "121322", "131222", "122213", "122312", "132212", "221213",
+    "221312", "231212", "112232", "122132", "122231", "113222", "123122", "123221", "223211", "221132",
+    "221231", "213212", "223112", "312131", "311222", "321122", "321221", "312212", "322112", "322211",
+    "212123", "212321", "232121", "111323", "131123", "131321", "112313", "132113", "132311", "211313",
+    "231113", "231311", "112133", "112331", "132131", "113123", "113321", "133121", "313121", "211331",
+    "231131", "213113", "213311", "213131", "311123", "311321", "331121", "312113", "312311", "332111",
+    "314111", "221411", "431111", "111224", "111422", "121124", "121421", "141122", "141221", "112214",
+    "112412", "122114", "122411", "142112", "142211", "241211", "221114", "413111", "241112", "134111",
+    "111242", "121142", "121241", "114212", "124112", "124211", "411212", "421112", "421211", "212141",
+    "214121", "412121", "111143", "111341", "131141", "114113", "114311", "411113", "411311", "113141",
+    "114131", "311141", "411131", "211412", "211214", "211232",
+];
+const CODE128_START_C: usize = 105;
+const CODE128_STOP: &str = "2331112";
+
+/// Módulos (true = barra) do CODE-128 conjunto C, incluindo dígito verificador
+pub fn code128c_modules(digits: &str) -> Vec<bool> {
+    let values: Vec<usize> = digits
+        .as_bytes()
+        .chunks(2)
+        .map(|pair| pair.iter().fold(0, |acc, d| acc * 10 + (d - b'0') as usize))
+        .collect();
+    
+    let checksum = values
+        .iter()
+        .enumerate()
+        .fold(CODE128_START_C, |acc, (i, value)| acc + value * (i + 1))
+        % 103;
+    
+    let mut symbols = vec![CODE128_PATTERNS[CODE128_START_C]];
+    symbols.extend(values.iter().map(|value| CODE128_PATTERNS[*value]));
+    symbols.push(CODE128_PATTERNS[checksum]);
+    symbols.push(CODE128_STOP);
+    
+    let mut modules = Vec::new();
+    for pattern in symbols {
+        for (i, width) in pattern.bytes().enumerate() {
+            let bar = i % 2 == 0;
+            modules.extend(std::iter::repeat_n(bar, (width - b'0') as usize));
+        }
+    }
+    modules
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_code128c_checksum_and_length() {
+        let modules = code128c_modules("12345678");
+        
+        // start + 4 pares + verificador (47) + stop
+        assert_eq!(modules.len(), 11 * 6 + 13);
+        
+        let expected: String = [105, 12, 34, 56, 78, 47]
+            .iter()
+            .map(|value| CODE128_PATTERNS[*value])
+            .chain(std::iter::once(CODE128_STOP))
+            .collect::<Vec<_>>()
+            .iter()
+            .flat_map(|pattern| {
+                pattern.bytes().enumerate().flat_map(|(i, w)| {
+                    std::iter::repeat_n(if i % 2 == 0 { '1' } else { '0' }, (w - b'0') as usize)
+                })
+            })
+            .collect();
+        let actual: String = modules.iter().map(|bar| if *bar { '1' } else { '0' }).collect();
+        
+        assert_eq!(actual, expected);
+    }
+    
+    #[test]
+    fn test_xref_offsets() {
+        let mut page = PdfPage::new();
+        page.text(10.0, 20.0, 8.0, Font::Bold, "Nota (teste) é válida");
+        
+        let mut document = PdfDocument::new();
+        document.add_page(page);
+        let bytes = document.to_bytes();
+        let text = String::from_utf8_lossy(&bytes);
+        
+        assert!(bytes.starts_with(b"%PDF-1.4"));
+        assert!(bytes.ends_with(b"%%EOF\n"));
+        assert!(text.contains("(Nota \\(teste\\) "));
+        
+        // Offsets do xref apontam para o início de cada objeto
+        let xref_at: usize = text.lines().rev().nth(1).unwrap().parse().unwrap();
+        assert!(bytes[xref_at..].starts_with(b"xref"));
+        
+        let xref = String::from_utf8_lossy(&bytes[xref_at..]).to_string();
+        for (i, entry) in xref.lines().skip(3).take(6).enumerate() {
+            let offset: usize = entry[..10].parse().unwrap();
+            assert!(bytes[offset..].starts_with(format!("{} 0 obj", i + 1).as_bytes()));
+        }
+    }
+}
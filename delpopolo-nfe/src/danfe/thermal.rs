@@ -0,0 +1,374 @@
+use anyhow::Result;
+use qrcode::{Color, EcLevel, QrCode};
+use serde::{Deserialize, Serialize};
+use crate::models::NFe;
+use crate::nfce::{Ambiente, NFCe};
+use crate::sefaz::extract_last_tag;
+use super::{format_chave, format_data_hora, format_documento, format_quantidade, format_valor};
+
+/// Dados da NFC-e que não fazem parte do modelo `NFe`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DanfeNfceInfo {
+    pub url_consulta: String,
+    pub qr_code_url: String,
+    pub ambiente: Ambiente,
+    pub protocolo: Option<String>,
+    pub forma_pagamento: Option<String>, // tPag
+    pub valor_pago: Option<f64>,
+}
+
+impl DanfeNfceInfo {
+    pub fn from_nfce(nfce: &NFCe) -> Self {
+        Self {
+            url_consulta: nfce.url_consulta.clone(),
+            qr_code_url: nfce.qr_code_url.clone(),
+            ambiente: nfce.ambiente,
+            protocolo: nfce.protocolo.clone(),
+            forma_pagamento: extract_last_tag(&nfce.xml, "tPag"),
+            valor_pago: extract_last_tag(&nfce.xml, "vPag").and_then(|v| v.parse().ok()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Align {
+    Left,
+    Center,
+}
+
+#[derive(Debug, Clone)]
+enum Line {
+    Text { text: String, align: Align, bold: bool },
+    Separator,
+    QrCode(String),
+}
+
+/// DANFE-NFC-e para impressoras térmicas de 80mm (48 colunas)
+pub struct DanfeNfce;
+
+impl DanfeNfce {
+    pub const COLUNAS: usize = 48;
+    
+    /// Layout em texto puro, com o QR Code desenhado com caracteres de meio bloco
+    pub fn render_text(nfe: &NFe, info: &DanfeNfceInfo) -> Result<String> {
+        let mut out = String::new();
+        
+        for line in Self::layout(nfe, info) {
+            match line {
+                Line::Text { text, align, .. } => {
+                    out.push_str(&Self::align(&text, align));
+                    out.push('\n');
+                }
+                Line::Separator => {
+                    out.push_str(&"-".repeat(Self::COLUNAS));
+                    out.push('\n');
+                }
+                Line::QrCode(data) => {
+                    for row in Self::qr_text(&data)? {
+                        out.push_str(&Self::align(&row, Align::Center));
+                        out.push('\n');
+                    }
+                }
+            }
+        }
+        
+        Ok(out)
+    }
+    
+    /// Comandos ESC/POS; o QR Code é gerado pela própria impressora (GS ( k)
+    pub fn render_escpos(nfe: &NFe, info: &DanfeNfceInfo) -> Result<Vec<u8>> {
+        const ESC: u8 = 0x1B;
+        const GS: u8 = 0x1D;
+        
+        let mut out = vec![ESC, b'@'];
+        
+        for line in Self::layout(nfe, info) {
+            match line {
+                Line::Text { text, align, bold } => {
+                    out.extend_from_slice(&[ESC, b'a', if align == Align::Center { 1 } else { 0 }]);
+                    out.extend_from_slice(&[ESC, b'E', bold as u8]);
+                    out.extend_from_slice(remove_acentos(&text).as_bytes());
+                    out.push(b'\n');
+                }
+                Line::Separator => {
+                    out.extend_from_slice(&[ESC, b'a', 0, ESC, b'E', 0]);
+                    out.extend_from_slice("-".repeat(Self::COLUNAS).as_bytes());
+                    out.push(b'\n');
+                }
+                Line::QrCode(data) => {
+                    if data.len() + 3 > u16::MAX as usize {
+                        anyhow::bail!("Conteúdo do QR Code muito longo");
+                    }
+                    
+                    let length = data.len() + 3;
+                    out.extend_from_slice(&[ESC, b'a', 1]);
+                    // Modelo 2, módulo de 4 pontos, correção de erro nível M
+                    out.extend_from_slice(&[GS, b'(', b'k', 4, 0, 49, 65, 50, 0]);
+                    out.extend_from_slice(&[GS, b'(', b'k', 3, 0, 49, 67, 4]);
+                    out.extend_from_slice(&[GS, b'(', b'k', 3, 0, 49, 69, 49]);
+                    out.extend_from_slice(&[GS, b'(', b'k', (length % 256) as u8, (length / 256) as u8, 49, 80, 48]);
+                    out.extend_from_slice(data.as_bytes());
+                    out.extend_from_slice(&[GS, b'(', b'k', 3, 0, 49, 81, 48]);
+                    out.push(b'\n');
+                }
+            }
+        }
+        
+        // Avança o papel e faz o corte parcial
+        out.extend_from_slice(&[ESC, b'd', 4, GS, b'V', 66, 0]);
+        
+        Ok(out)
+    }
+    
+    /// Divisões do DANFE-NFC-e conforme o Manual de Especificações Técnicas
+    fn layout(nfe: &NFe, info: &DanfeNfceInfo) -> Vec<Line> {
+        let mut lines = Vec::new();
+        let emit = &nfe.emitente;
+        
+        // Divisão I - emitente
+        Self::push_wrapped(&mut lines, &emit.razao_social, Align::Center, true);
+        Self::push_wrapped(&mut lines, &format!("CNPJ: {}", format_documento(&emit.cnpj)), Align::Center, false);
+        let mut endereco = format!("{}, {}", emit.endereco.logradouro, emit.endereco.numero);
+        if let Some(complemento) = &emit.endereco.complemento {
+            endereco.push_str(&format!(" {}", complemento));
+        }
+        endereco.push_str(&format!(" - {} - {}/{}", emit.endereco.bairro, emit.endereco.municipio, emit.endereco.uf));
+        Self::push_wrapped(&mut lines, &endereco, Align::Center, false);
+        
+        // Divisão II - identificação do documento
+        lines.push(Line::Separator);
+        Self::push_wrapped(&mut lines, "DANFE NFC-e - Documento Auxiliar da Nota Fiscal de Consumidor Eletrônica", Align::Center, true);
+        
+        if info.ambiente == Ambiente::Homologacao {
+            lines.push(Line::Separator);
+            Self::push_wrapped(&mut lines, "EMITIDA EM AMBIENTE DE HOMOLOGAÇÃO", Align::Center, true);
+            Self::push_wrapped(&mut lines, "SEM VALOR FISCAL", Align::Center, true);
+        }
+        
+        // Divisão III - itens
+        lines.push(Line::Separator);
+        Self::push_text(&mut lines, "#   CÓDIGO DESCRIÇÃO", Align::Left, true);
+        Self::push_text(&mut lines, &Self::columns("    QTD UN x VL UNIT", "VL TOTAL"), Align::Left, true);
+        
+        for item in &nfe.itens {
+            let descricao = format!("{:03} {} {}", item.numero_item, item.codigo_produto, item.descricao);
+            Self::push_wrapped(&mut lines, &descricao, Align::Left, false);
+            
+            let quantidade = format!(
+                "    {} {} x {}",
+                format_quantidade(item.quantidade_comercial),
+                item.unidade_comercial,
+                format_valor(item.valor_unitario_comercial)
+            );
+            Self::push_text(&mut lines, &Self::columns(&quantidade, &format_valor(item.valor_total_bruto)), Align::Left, false);
+        }
+        
+        // Divisão IV - totais e pagamento
+        let totais = &nfe.totais;
+        lines.push(Line::Separator);
+        Self::push_text(&mut lines, &Self::columns("Qtd. total de itens", &nfe.total_itens().to_string()), Align::Left, false);
+        Self::push_text(&mut lines, &Self::columns("Valor total R$", &format_valor(totais.valor_total_produtos)), Align::Left, false);
+        
+        if totais.valor_desconto > 0.0 {
+            Self::push_text(&mut lines, &Self::columns("Desconto R$", &format_valor(totais.valor_desconto)), Align::Left, false);
+        }
+        let acrescimos = totais.valor_frete + totais.valor_seguro + totais.valor_outras_despesas;
+        if acrescimos > 0.0 {
+            Self::push_text(&mut lines, &Self::columns("Acréscimos R$", &format_valor(acrescimos)), Align::Left, false);
+        }
+        Self::push_text(&mut lines, &Self::columns("Valor a pagar R$", &format_valor(totais.valor_total_nota)), Align::Left, true);
+        
+        if let Some(forma) = &info.forma_pagamento {
+            Self::push_text(&mut lines, &Self::columns("FORMA DE PAGAMENTO", "VALOR PAGO R$"), Align::Left, true);
+            let valor = info.valor_pago.unwrap_or(totais.valor_total_nota);
+            Self::push_text(&mut lines, &Self::columns(forma_pagamento(forma), &format_valor(valor)), Align::Left, false);
+        }
+        
+        // Divisão V - consulta pela chave de acesso
+        lines.push(Line::Separator);
+        Self::push_wrapped(&mut lines, "Consulte pela Chave de Acesso em", Align::Center, true);
+        Self::push_wrapped(&mut lines, &info.url_consulta, Align::Center, false);
+        let chave = format_chave(&nfe.chave);
+        let (primeira, segunda) = chave.split_at(chave.len().min(29));
+        Self::push_text(&mut lines, primeira.trim(), Align::Center, false);
+        Self::push_text(&mut lines, segunda.trim(), Align::Center, false);
+        
+        // Divisão VI - consumidor
+        lines.push(Line::Separator);
+        let dest = &nfe.destinatario;
+        if dest.cnpj_cpf.is_empty() {
+            Self::push_wrapped(&mut lines, "CONSUMIDOR NÃO IDENTIFICADO", Align::Center, true);
+        } else {
+            let tipo = if dest.cnpj_cpf.len() == 14 { "CNPJ" } else { "CPF" };
+            Self::push_wrapped(&mut lines, &format!("CONSUMIDOR {}: {}", tipo, format_documento(&dest.cnpj_cpf)), Align::Center, true);
+            Self::push_wrapped(&mut lines, &dest.razao_social, Align::Center, false);
+        }
+        
+        // Divisão VII - identificação e protocolo
+        lines.push(Line::Separator);
+        Self::push_wrapped(
+            &mut lines,
+            &format!(
+                "NFC-e nº {:0>9} Série {:0>3} {}",
+                nfe.numero,
+                nfe.serie,
+                format_data_hora(nfe.data_emissao)
+            ),
+            Align::Center,
+            true,
+        );
+        
+        match &info.protocolo {
+            Some(protocolo) => Self::push_wrapped(&mut lines, &format!("Protocolo de autorização: {}", protocolo), Align::Center, false),
+            None => Self::push_wrapped(&mut lines, "EMITIDA EM CONTINGÊNCIA - PENDENTE DE AUTORIZAÇÃO", Align::Center, true),
+        }
+        
+        // Divisão VIII - QR Code
+        lines.push(Line::QrCode(info.qr_code_url.clone()));
+        
+        // Divisão IX - tributos (Lei da Transparência) e informações adicionais
+        let tributos = totais.valor_icms + totais.valor_icms_st + totais.valor_ipi + totais.valor_pis + totais.valor_cofins;
+        Self::push_wrapped(
+            &mut lines,
+            &format!("Tributos Totais Incidentes (Lei Federal 12.741/2012): R$ {}", format_valor(tributos)),
+            Align::Center,
+            false,
+        );
+        
+        if let Some(informacoes) = &nfe.informacoes_adicionais {
+            Self::push_wrapped(&mut lines, informacoes, Align::Center, false);
+        }
+        
+        lines
+    }
+    
+    fn push_text(lines: &mut Vec<Line>, text: &str, align: Align, bold: bool) {
+        lines.push(Line::Text { text: text.to_string(), align, bold });
+    }
+    
+    fn push_wrapped(lines: &mut Vec<Line>, text: &str, align: Align, bold: bool) {
+        for wrapped in wrap(text, Self::COLUNAS) {
+            Self::push_text(lines, &wrapped, align, bold);
+        }
+    }
+    
+    /// Texto à esquerda e valor alinhado à direita na mesma linha
+    fn columns(left: &str, right: &str) -> String {
+        let used = left.chars().count() + right.chars().count();
+        let padding = Self::COLUNAS.saturating_sub(used).max(1);
+        format!("{}{}{}", left, " ".repeat(padding), right)
+    }
+    
+    fn align(text: &str, align: Align) -> String {
+        match align {
+            Align::Left => text.to_string(),
+            Align::Center => {
+                let padding = Self::COLUNAS.saturating_sub(text.chars().count()) / 2;
+                format!("{}{}", " ".repeat(padding), text)
+            }
+        }
+    }
+    
+    /// QR Code com zona de silêncio de 1 módulo; duas linhas de módulos por linha de texto
+    fn qr_text(data: &str) -> Result<Vec<String>> {
+        let code = QrCode::with_error_correction_level(data.as_bytes(), EcLevel::M)?;
+        let width = code.width();
+        let colors = code.to_colors();
+        let dark = |x: isize, y: isize| {
+            x >= 0 && y >= 0 && (x as usize) < width && (y as usize) < width
+                && colors[y as usize * width + x as usize] == Color::Dark
+        };
+        
+        let size = width as isize;
+        let mut rows = Vec::new();
+        let mut y = -1;
+        while y <= size {
+            let row: String = (-1..=size)
+                .map(|x| match (dark(x, y), dark(x, y + 1)) {
+                    (true, true) => '█',
+                    (true, false) => '▀',
+                    (false, true) => '▄',
+                    (false, false) => ' ',
+                })
+                .collect();
+            rows.push(row.trim_end().to_string());
+            y += 2;
+        }
+        
+        Ok(rows)
+    }
+}
+
+/// Descrição do meio de pagamento (tPag)
+fn forma_pagamento(codigo: &str) -> &'static str {
+    match codigo {
+        "01" => "Dinheiro",
+        "02" => "Cheque",
+        "03" => "Cartão de Crédito",
+        "04" => "Cartão de Débito",
+        "05" => "Crédito Loja",
+        "10" => "Vale Alimentação",
+        "11" => "Vale Refeição",
+        "15" => "Boleto Bancário",
+        "17" => "PIX",
+        _ => "Outros",
+    }
+}
+
+/// Quebra por palavras; palavras maiores que a largura são cortadas
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    
+    for word in text.split_whitespace() {
+        let mut word = word.to_string();
+        
+        while word.chars().count() > width {
+            if !current.is_empty() {
+                lines.push(std::mem::take(&mut current));
+            }
+            let head: String = word.chars().take(width).collect();
+            word = word.chars().skip(width).collect();
+            lines.push(head);
+        }
+        
+        if current.is_empty() {
+            current = word;
+        } else if current.chars().count() + 1 + word.chars().count() <= width {
+            current.push(' ');
+            current.push_str(&word);
+        } else {
+            lines.push(std::mem::replace(&mut current, word));
+        }
+    }
+    
+    if !current.is_empty() {
+        lines.push(current);
+    }
+    
+    lines
+}
+
+/// Impressoras térmicas nem sempre têm a página de código com acentos
+fn remove_acentos(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            'á' | 'à' | 'â' | 'ã' | 'ä' => 'a',
+            'Á' | 'À' | 'Â' | 'Ã' | 'Ä' => 'A',
+            'é' | 'è' | 'ê' | 'ë' => 'e',
+            'É' | 'È' | 'Ê' | 'Ë' => 'E',
+            'í' | 'ì' | 'î' | 'ï' => 'i',
+            'Í' | 'Ì' | 'Î' | 'Ï' => 'I',
+            'ó' | 'ò' | 'ô' | 'õ' | 'ö' => 'o',
+            'Ó' | 'Ò' | 'Ô' | 'Õ' | 'Ö' => 'O',
+            'ú' | 'ù' | 'û' | 'ü' => 'u',
+            'Ú' | 'Ù' | 'Û' | 'Ü' => 'U',
+            'ç' => 'c',
+            'Ç' => 'C',
+            'º' | '°' => 'o',
+            'ª' => 'a',
+            c if c.is_ascii() => c,
+            _ => '?',
+        })
+        .collect()
+}
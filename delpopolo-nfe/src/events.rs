@@ -0,0 +1,567 @@
+use std::collections::HashSet;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, FixedOffset, Utc};
+use quick_xml::de::from_str;
+use quick_xml::escape::partial_escape;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+use crate::nfce::Ambiente;
+
+/// Tipos de evento da NF-e (tpEvento)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EventoTipo {
+    Cancelamento,             // 110111
+    CartaCorrecao,            // 110110
+    ConfirmacaoOperacao,      // 210200
+    CienciaOperacao,          // 210210
+    DesconhecimentoOperacao,  // 210220
+    OperacaoNaoRealizada,     // 210240
+}
+
+impl EventoTipo {
+    pub fn code(&self) -> &'static str {
+        match self {
+            EventoTipo::Cancelamento => "110111",
+            EventoTipo::CartaCorrecao => "110110",
+            EventoTipo::ConfirmacaoOperacao => "210200",
+            EventoTipo::CienciaOperacao => "210210",
+            EventoTipo::DesconhecimentoOperacao => "210220",
+            EventoTipo::OperacaoNaoRealizada => "210240",
+        }
+    }
+    
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "110111" => Some(EventoTipo::Cancelamento),
+            "110110" => Some(EventoTipo::CartaCorrecao),
+            "210200" => Some(EventoTipo::ConfirmacaoOperacao),
+            "210210" => Some(EventoTipo::CienciaOperacao),
+            "210220" => Some(EventoTipo::DesconhecimentoOperacao),
+            "210240" => Some(EventoTipo::OperacaoNaoRealizada),
+            _ => None,
+        }
+    }
+    
+    /// descEvento, como exigido pelo schema (sem acentos)
+    pub fn descricao(&self) -> &'static str {
+        match self {
+            EventoTipo::Cancelamento => "Cancelamento",
+            EventoTipo::CartaCorrecao => "Carta de Correcao",
+            EventoTipo::ConfirmacaoOperacao => "Confirmacao da Operacao",
+            EventoTipo::CienciaOperacao => "Ciencia da Operacao",
+            EventoTipo::DesconhecimentoOperacao => "Desconhecimento da Operacao",
+            EventoTipo::OperacaoNaoRealizada => "Operacao nao Realizada",
+        }
+    }
+    
+    /// Eventos de manifestação do destinatário (série 210200)
+    pub fn is_manifestacao(&self) -> bool {
+        self.code().starts_with("2102")
+    }
+}
+
+/// Evento vinculado a uma NF-e, extraído de um `procEventoNFe`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NFeEvento {
+    pub chave: String,
+    pub tipo: EventoTipo,
+    pub sequencia: u32,
+    pub data_evento: DateTime<Utc>,
+    pub cnpj_autor: Option<String>,
+    pub justificativa: Option<String>, // xJust (cancelamento / operação não realizada)
+    pub correcao: Option<String>,      // xCorrecao (CC-e)
+    pub protocolo_nfe: Option<String>, // nProt da NF-e cancelada
+    pub status_code: Option<u16>,      // cStat do retEvento
+    pub protocolo: Option<String>,     // nProt do registro do evento
+}
+
+impl NFeEvento {
+    pub fn parse_xml(xml_content: &str) -> Result<Self> {
+        let proc: ProcEventoXml = from_str(xml_content)
+            .map_err(|e| anyhow::anyhow!("procEventoNFe parsing error: {}", e))?;
+        
+        let inf = proc.evento.inf_evento;
+        let tipo = EventoTipo::from_code(&inf.tp_evento)
+            .with_context(|| format!("Tipo de evento não suportado: {}", inf.tp_evento))?;
+        let data_evento = DateTime::parse_from_rfc3339(&inf.dh_evento)
+            .with_context(|| format!("dhEvento inválido: {}", inf.dh_evento))?
+            .with_timezone(&Utc);
+        let ret = proc.ret_evento.map(|r| r.inf_evento);
+        
+        Ok(Self {
+            chave: inf.ch_nfe,
+            tipo,
+            sequencia: inf.n_seq_evento,
+            data_evento,
+            cnpj_autor: inf.cnpj,
+            justificativa: inf.det_evento.x_just,
+            correcao: inf.det_evento.x_correcao,
+            protocolo_nfe: inf.det_evento.n_prot,
+            status_code: ret.as_ref().map(|r| r.c_stat),
+            protocolo: ret.and_then(|r| r.n_prot),
+        })
+    }
+    
+    pub fn parse_from_file(file_path: &str) -> Result<Self> {
+        let xml_content = std::fs::read_to_string(file_path)?;
+        Self::parse_xml(&xml_content)
+    }
+    
+    /// 135 = registrado e vinculado; 136 = registrado sem vínculo; 155 = cancelamento fora de prazo.
+    /// Sem `retEvento` o evento não tem comprovação de registro.
+    pub fn is_registrado(&self) -> bool {
+        matches!(self.status_code, Some(135 | 136 | 155))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ProcEventoXml {
+    evento: EventoXml,
+    #[serde(rename = "retEvento")]
+    ret_evento: Option<RetEventoXml>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EventoXml {
+    #[serde(rename = "infEvento")]
+    inf_evento: InfEventoXml,
+}
+
+#[derive(Debug, Deserialize)]
+struct InfEventoXml {
+    #[serde(rename = "CNPJ")]
+    cnpj: Option<String>,
+    #[serde(rename = "chNFe")]
+    ch_nfe: String,
+    #[serde(rename = "dhEvento")]
+    dh_evento: String,
+    #[serde(rename = "tpEvento")]
+    tp_evento: String,
+    #[serde(rename = "nSeqEvento")]
+    n_seq_evento: u32,
+    #[serde(rename = "detEvento")]
+    det_evento: DetEventoXml,
+}
+
+#[derive(Debug, Deserialize)]
+struct DetEventoXml {
+    #[serde(rename = "nProt")]
+    n_prot: Option<String>,
+    #[serde(rename = "xJust")]
+    x_just: Option<String>,
+    #[serde(rename = "xCorrecao")]
+    x_correcao: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RetEventoXml {
+    #[serde(rename = "infEvento")]
+    inf_evento: RetInfEventoXml,
+}
+
+#[derive(Debug, Deserialize)]
+struct RetInfEventoXml {
+    #[serde(rename = "cStat")]
+    c_stat: u16,
+    #[serde(rename = "nProt")]
+    n_prot: Option<String>,
+}
+
+/// Estorno das entradas de estoque de uma nota cancelada.
+///
+/// Implementado pelo `InventoryService`; devolve quantos movimentos foram estornados.
+#[async_trait]
+pub trait PurchaseReversal: Send + Sync {
+    async fn reverse_purchase(&self, nfe_key: &str, reason: &str) -> Result<usize>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventoStatus {
+    Aplicado,
+    Duplicado,
+    NaoVinculado, // chave não encontrada entre as notas importadas
+    NaoRegistrado, // sem retEvento autorizado
+}
+
+#[derive(Debug, Serialize)]
+pub struct EventoResult {
+    pub chave: String,
+    pub tipo: EventoTipo,
+    pub sequencia: u32,
+    pub status: EventoStatus,
+    pub movimentos_estornados: usize,
+}
+
+/// Eventos já aplicados, persistidos por (chave, tpEvento, nSeqEvento).
+///
+/// Implementado sobre a tabela `nfe_events`; cancelamento e CC-e vigente saem dela.
+#[async_trait]
+pub trait NFeEventStore: Send + Sync {
+    async fn is_processed(&self, chave: &str, tipo: EventoTipo, sequencia: u32) -> Result<bool>;
+    /// Gravar de novo o mesmo evento não tem efeito
+    async fn record(&self, evento: &NFeEvento) -> Result<()>;
+    async fn is_cancelled(&self, chave: &str) -> Result<bool>;
+    /// Texto da CC-e de maior sequência
+    async fn correcao(&self, chave: &str) -> Result<Option<String>>;
+}
+
+/// Aplica eventos às notas já importadas
+pub struct NFeEventProcessor {
+    known_keys: HashSet<String>,
+    store: Box<dyn NFeEventStore>,
+}
+
+impl NFeEventProcessor {
+    pub fn new<I: IntoIterator<Item = String>>(known_keys: I, store: Box<dyn NFeEventStore>) -> Self {
+        Self {
+            known_keys: known_keys.into_iter().collect(),
+            store,
+        }
+    }
+    
+    pub async fn process(&self, evento: &NFeEvento, reversal: &dyn PurchaseReversal) -> Result<EventoResult> {
+        let mut result = EventoResult {
+            chave: evento.chave.clone(),
+            tipo: evento.tipo,
+            sequencia: evento.sequencia,
+            status: EventoStatus::Aplicado,
+            movimentos_estornados: 0,
+        };
+        
+        if !self.known_keys.contains(&evento.chave) {
+            warn!("Event {} for unknown NFe {}", evento.tipo.code(), evento.chave);
+            result.status = EventoStatus::NaoVinculado;
+            return Ok(result);
+        }
+        
+        if !evento.is_registrado() {
+            warn!("Event {} for NFe {} has no registration receipt", evento.tipo.code(), evento.chave);
+            result.status = EventoStatus::NaoRegistrado;
+            return Ok(result);
+        }
+        
+        if self.store.is_processed(&evento.chave, evento.tipo, evento.sequencia).await? {
+            result.status = EventoStatus::Duplicado;
+            return Ok(result);
+        }
+        
+        // Aplica antes de gravar: se a gravação falhar, reprocessar é seguro porque o estorno
+        // não se repete para uma nota já estornada
+        match evento.tipo {
+            EventoTipo::Cancelamento => {
+                let motivo = format!(
+                    "Cancelamento da NF-e {}: {}",
+                    evento.chave,
+                    evento.justificativa.as_deref().unwrap_or("sem justificativa")
+                );
+                result.movimentos_estornados = reversal.reverse_purchase(&evento.chave, &motivo).await?;
+                info!("NFe {} cancelled, {} movements reversed", evento.chave, result.movimentos_estornados);
+            }
+            EventoTipo::CartaCorrecao => {
+                info!("CC-e {} registered for NFe {}", evento.sequencia, evento.chave);
+            }
+            tipo => {
+                info!("Manifestation {} registered for NFe {}", tipo.descricao(), evento.chave);
+            }
+        }
+        self.store.record(evento).await?;
+        
+        Ok(result)
+    }
+    
+    pub async fn is_cancelled(&self, chave: &str) -> Result<bool> {
+        self.store.is_cancelled(chave).await
+    }
+    
+    /// Texto da carta de correção vigente; cada CC-e substitui a anterior
+    pub async fn correcao(&self, chave: &str) -> Result<Option<String>> {
+        self.store.correcao(chave).await
+    }
+}
+
+/// Manifestação do destinatário a ser enviada ao Ambiente Nacional
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Manifestacao {
+    pub tipo: EventoTipo,
+    pub cnpj_destinatario: String,
+    pub justificativa: Option<String>,
+    pub ambiente: Ambiente,
+    pub sequencia: u32,
+}
+
+impl Manifestacao {
+    pub fn new(tipo: EventoTipo, cnpj_destinatario: String, ambiente: Ambiente) -> Self {
+        Self {
+            tipo,
+            cnpj_destinatario,
+            justificativa: None,
+            ambiente,
+            sequencia: 1,
+        }
+    }
+    
+    pub fn with_justificativa(mut self, justificativa: String) -> Self {
+        self.justificativa = Some(justificativa);
+        self
+    }
+    
+    /// Id do infEvento: "ID" + tpEvento + chave + nSeqEvento
+    pub fn event_id(&self, chave: &str) -> String {
+        format!("ID{}{}{:02}", self.tipo.code(), chave, self.sequencia)
+    }
+    
+    /// XML do `<evento>` ainda não assinado
+    pub fn to_xml(&self, chave: &str, data_evento: DateTime<Utc>) -> Result<String> {
+        if !self.tipo.is_manifestacao() {
+            anyhow::bail!("{} não é um evento de manifestação do destinatário", self.tipo.descricao());
+        }
+        
+        let justificativa = match (self.tipo, self.justificativa.as_deref()) {
+            (EventoTipo::OperacaoNaoRealizada, Some(j)) if (15..=255).contains(&j.chars().count()) => {
+                format!("<xJust>{}</xJust>", partial_escape(j))
+            }
+            (EventoTipo::OperacaoNaoRealizada, _) => {
+                anyhow::bail!("Operação não realizada exige justificativa de 15 a 255 caracteres");
+            }
+            _ => String::new(),
+        };
+        
+        let brasilia = FixedOffset::west_opt(3 * 3600).expect("valid offset");
+        
+        Ok(format!(
+            concat!(
+                "<evento xmlns=\"http://www.portalfiscal.inf.br/nfe\" versao=\"1.00\">",
+                "<infEvento Id=\"{id}\"><cOrgao>91</cOrgao><tpAmb>{amb}</tpAmb><CNPJ>{cnpj}</CNPJ>",
+                "<chNFe>{chave}</chNFe><dhEvento>{dh}</dhEvento><tpEvento>{tp}</tpEvento>",
+                "<nSeqEvento>{seq}</nSeqEvento><verEvento>1.00</verEvento>",
+                "<detEvento versao=\"1.00\"><descEvento>{desc}</descEvento>{just}</detEvento>",
+                "</infEvento></evento>"
+            ),
+            id = self.event_id(chave),
+            amb = self.ambiente.code(),
+            cnpj = self.cnpj_destinatario,
+            chave = chave,
+            dh = data_evento.with_timezone(&brasilia).format("%Y-%m-%dT%H:%M:%S%:z"),
+            tp = self.tipo.code(),
+            seq = self.sequencia,
+            desc = self.tipo.descricao(),
+            just = justificativa,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use chrono::TimeZone;
+    use crate::importer::NFeImporter;
+    use crate::nfce::XmlSigner;
+    use crate::sefaz::{SefazClient, SefazResponse};
+    
+    const CHAVE: &str = "35250111222333000181550010000001231000001234";
+    
+    fn proc_evento(tp_evento: &str, seq: u32, det: &str, c_stat: u16) -> String {
+        format!(
+            concat!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>",
+                "<procEventoNFe xmlns=\"http://www.portalfiscal.inf.br/nfe\" versao=\"1.00\">",
+                "<evento versao=\"1.00\"><infEvento Id=\"ID{tp}{chave}{seq:02}\"><cOrgao>35</cOrgao><tpAmb>1</tpAmb>",
+                "<CNPJ>11222333000181</CNPJ><chNFe>{chave}</chNFe><dhEvento>2025-01-20T10:00:00-03:00</dhEvento>",
+                "<tpEvento>{tp}</tpEvento><nSeqEvento>{seq}</nSeqEvento><verEvento>1.00</verEvento>",
+                "<detEvento versao=\"1.00\">{det}</detEvento></infEvento></evento>",
+                "<retEvento versao=\"1.00\"><infEvento><tpAmb>1</tpAmb><cStat>{stat}</cStat>",
+                "<xMotivo>Evento registrado e vinculado a NF-e</xMotivo><chNFe>{chave}</chNFe>",
+                "<nProt>135250000000099</nProt></infEvento></retEvento></procEventoNFe>"
+            ),
+            tp = tp_evento,
+            chave = CHAVE,
+            seq = seq,
+            det = det,
+            stat = c_stat,
+        )
+    }
+    
+    /// Eventos gravados em memória; os clones compartilham os eventos, como o banco
+    #[derive(Default, Clone)]
+    struct MemoryEventStore {
+        eventos: Arc<Mutex<Vec<NFeEvento>>>,
+    }
+    
+    #[async_trait]
+    impl NFeEventStore for MemoryEventStore {
+        async fn is_processed(&self, chave: &str, tipo: EventoTipo, sequencia: u32) -> Result<bool> {
+            Ok(self.eventos.lock().unwrap().iter().any(|e| e.chave == chave && e.tipo == tipo && e.sequencia == sequencia))
+        }
+        
+        async fn record(&self, evento: &NFeEvento) -> Result<()> {
+            if !self.is_processed(&evento.chave, evento.tipo, evento.sequencia).await? {
+                self.eventos.lock().unwrap().push(evento.clone());
+            }
+            Ok(())
+        }
+        
+        async fn is_cancelled(&self, chave: &str) -> Result<bool> {
+            Ok(self.eventos.lock().unwrap().iter().any(|e| e.chave == chave && e.tipo == EventoTipo::Cancelamento))
+        }
+        
+        async fn correcao(&self, chave: &str) -> Result<Option<String>> {
+            Ok(self.eventos.lock().unwrap()
+                .iter()
+                .filter(|e| e.chave == chave && e.tipo == EventoTipo::CartaCorrecao)
+                .max_by_key(|e| e.sequencia)
+                .and_then(|e| e.correcao.clone()))
+        }
+    }
+    
+    #[derive(Default)]
+    struct RecordingReversal {
+        calls: Mutex<Vec<String>>,
+    }
+    
+    #[async_trait]
+    impl PurchaseReversal for RecordingReversal {
+        async fn reverse_purchase(&self, nfe_key: &str, _reason: &str) -> Result<usize> {
+            self.calls.lock().unwrap().push(nfe_key.to_string());
+            Ok(2)
+        }
+    }
+    
+    #[test]
+    fn test_parse_cancelamento() {
+        let xml = proc_evento(
+            "110111",
+            1,
+            "<descEvento>Cancelamento</descEvento><nProt>135250000000001</nProt><xJust>Nota emitida em duplicidade</xJust>",
+            135,
+        );
+        
+        let evento = NFeEvento::parse_xml(&xml).unwrap();
+        
+        assert_eq!(evento.chave, CHAVE);
+        assert_eq!(evento.tipo, EventoTipo::Cancelamento);
+        assert_eq!(evento.protocolo_nfe.as_deref(), Some("135250000000001"));
+        assert_eq!(evento.justificativa.as_deref(), Some("Nota emitida em duplicidade"));
+        assert_eq!(evento.data_evento, Utc.with_ymd_and_hms(2025, 1, 20, 13, 0, 0).unwrap());
+        assert!(evento.is_registrado());
+    }
+    
+    #[tokio::test]
+    async fn test_process_events() {
+        let reversal = RecordingReversal::default();
+        let store = MemoryEventStore::default();
+        let processor = NFeEventProcessor::new(vec![CHAVE.to_string()], Box::new(store.clone()));
+        
+        let cce = |seq: u32, texto: &str| {
+            NFeEvento::parse_xml(&proc_evento(
+                "110110",
+                seq,
+                &format!("<descEvento>Carta de Correcao</descEvento><xCorrecao>{}</xCorrecao>", texto),
+                135,
+            ))
+            .unwrap()
+        };
+        processor.process(&cce(2, "Peso liquido 25kg"), &reversal).await.unwrap();
+        processor.process(&cce(1, "Peso liquido 20kg"), &reversal).await.unwrap();
+        assert_eq!(processor.correcao(CHAVE).await.unwrap().as_deref(), Some("Peso liquido 25kg"));
+        
+        let cancelamento = NFeEvento::parse_xml(&proc_evento(
+            "110111",
+            1,
+            "<descEvento>Cancelamento</descEvento><nProt>1</nProt><xJust>Erro de digitacao no pedido</xJust>",
+            135,
+        ))
+        .unwrap();
+        
+        let result = processor.process(&cancelamento, &reversal).await.unwrap();
+        assert_eq!(result.status, EventoStatus::Aplicado);
+        assert_eq!(result.movimentos_estornados, 2);
+        assert!(processor.is_cancelled(CHAVE).await.unwrap());
+        
+        // Reprocessar o mesmo evento não estorna de novo
+        let again = processor.process(&cancelamento, &reversal).await.unwrap();
+        assert_eq!(again.status, EventoStatus::Duplicado);
+        assert_eq!(reversal.calls.lock().unwrap().len(), 1);
+        
+        // O estado vem do armazenamento, não da instância: sobrevive a um reinício
+        let restarted = NFeEventProcessor::new(vec![CHAVE.to_string()], Box::new(store));
+        assert_eq!(restarted.process(&cancelamento, &reversal).await.unwrap().status, EventoStatus::Duplicado);
+        assert!(restarted.is_cancelled(CHAVE).await.unwrap());
+        assert_eq!(restarted.correcao(CHAVE).await.unwrap().as_deref(), Some("Peso liquido 25kg"));
+        
+        let mut desconhecida = cancelamento.clone();
+        desconhecida.chave = "35250199888777000166550010000000011000000015".to_string();
+        let result = processor.process(&desconhecida, &reversal).await.unwrap();
+        assert_eq!(result.status, EventoStatus::NaoVinculado);
+    }
+    
+    #[test]
+    fn test_manifestacao_xml() {
+        let data = Utc.with_ymd_and_hms(2025, 1, 20, 13, 0, 0).unwrap();
+        let ciencia = Manifestacao::new(EventoTipo::CienciaOperacao, "99888777000166".to_string(), Ambiente::Homologacao);
+        
+        let xml = ciencia.to_xml(CHAVE, data).unwrap();
+        
+        assert!(xml.contains(&format!("<infEvento Id=\"ID210210{}01\">", CHAVE)));
+        assert!(xml.contains("<cOrgao>91</cOrgao><tpAmb>2</tpAmb>"));
+        assert!(xml.contains("<dhEvento>2025-01-20T10:00:00-03:00</dhEvento>"));
+        assert!(xml.contains("<descEvento>Ciencia da Operacao</descEvento></detEvento>"));
+        
+        let nao_realizada = Manifestacao::new(EventoTipo::OperacaoNaoRealizada, "99888777000166".to_string(), Ambiente::Homologacao);
+        assert!(nao_realizada.to_xml(CHAVE, data).is_err());
+        assert!(nao_realizada
+            .with_justificativa("Mercadoria devolvida ao fornecedor".to_string())
+            .to_xml(CHAVE, data)
+            .is_ok());
+        
+        let cancelamento = Manifestacao::new(EventoTipo::Cancelamento, "99888777000166".to_string(), Ambiente::Homologacao);
+        assert!(cancelamento.to_xml(CHAVE, data).is_err());
+    }
+    
+    struct TestSigner;
+    
+    impl XmlSigner for TestSigner {
+        fn sign(&self, xml: &str, _reference_id: &str) -> Result<String> {
+            Ok(xml.replace("</evento>", "<Signature></Signature></evento>"))
+        }
+    }
+    
+    struct StubSefaz {
+        enviados: Mutex<Vec<String>>,
+    }
+    
+    #[async_trait]
+    impl SefazClient for StubSefaz {
+        async fn autorizar(&self, _xml_assinado: &str) -> Result<SefazResponse> {
+            anyhow::bail!("não utilizado")
+        }
+        
+        async fn status_servico(&self) -> Result<SefazResponse> {
+            anyhow::bail!("não utilizado")
+        }
+        
+        async fn registrar_evento(&self, evento_assinado: &str) -> Result<SefazResponse> {
+            self.enviados.lock().unwrap().push(evento_assinado.to_string());
+            SefazResponse::from_xml(concat!(
+                "<retEnvEvento><cStat>128</cStat><xMotivo>Lote de evento processado</xMotivo>",
+                "<retEvento><infEvento><cStat>135</cStat><xMotivo>Evento registrado e vinculado a NF-e</xMotivo>",
+                "<nProt>891250000000001</nProt></infEvento></retEvento></retEnvEvento>"
+            ))
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_importer_sends_manifestacao() {
+        let sefaz = StubSefaz { enviados: Mutex::new(Vec::new()) };
+        let ciencia = Manifestacao::new(EventoTipo::CienciaOperacao, "99888777000166".to_string(), Ambiente::Producao);
+        
+        let retorno = NFeImporter::manifestar(CHAVE, &ciencia, &TestSigner, &sefaz).await.unwrap();
+        
+        assert_eq!(retorno.protocolo.as_deref(), Some("891250000000001"));
+        let enviados = sefaz.enviados.lock().unwrap();
+        assert!(enviados[0].contains("<tpEvento>210210</tpEvento>"));
+        assert!(enviados[0].ends_with("<Signature></Signature></evento>"));
+    }
+}
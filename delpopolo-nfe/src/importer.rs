@@ -1,12 +1,16 @@
 use anyhow::Result;
 use uuid::Uuid;
+use serde::Serialize;
 use tracing::{info, warn};
 use delpopolo_domain::{Product, ProductCategory, UnitOfMeasure, Money, Supplier};
 use super::models::{NFe, ItemNFe};
+use super::events::Manifestacao;
+use super::nfce::XmlSigner;
+use super::sefaz::{SefazClient, SefazResponse};
 
 pub struct NFeImporter;
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct ImportResult {
     pub nfe_key: String,
     pub supplier_created: bool,
@@ -19,7 +23,7 @@ pub struct ImportResult {
 
 impl NFeImporter {
     /// Importa produtos da NFe para o sistema
-    /// Retorna resultado da importao com detalhes
+    /// Retorna resultado da importação com detalhes
     pub fn import_products_from_nfe(nfe: &NFe) -> Result<ImportResult> {
         info!("Importing products from NFe {}", nfe.chave);
         
@@ -34,10 +38,10 @@ impl NFeImporter {
         };
         
         for item in &nfe.itens {
-            match Self::create_product_from_item(item, nfe.chave.as_str()) {
+            match Self::create_product_from_item(item) {
                 Ok(_product) => {
-                    // TODO: Verificar se produto j existe por EAN/cdigo
-                    // Se existir, atualizar; se no, criar
+                    // TODO: Verificar se produto já existe por EAN/código
+                    // Se existir, atualizar; se não, criar
                     result.products_created.push(Uuid::new_v4());
                 }
                 Err(e) => {
@@ -55,7 +59,7 @@ impl NFeImporter {
         Ok(result)
     }
     
-    fn create_product_from_item(item: &ItemNFe, nfe_key: &str) -> Result<Product> {
+    fn create_product_from_item(item: &ItemNFe) -> Result<Product> {
         let category = Self::map_ncm_to_category(&item.ncm);
         let unit = Self::map_unit(&item.unidade_comercial);
         
@@ -63,7 +67,7 @@ impl NFeImporter {
             item.descricao.clone(),
             category,
             unit,
-            Money::brl(item.valor_unitario_comercial * 1.3), // Preo de venda com margem
+            Money::brl(item.valor_unitario_comercial * 1.3), // Preço de venda com margem
             Money::brl(item.valor_unitario_comercial), // Custo
         );
         
@@ -77,7 +81,7 @@ impl NFeImporter {
     }
     
     fn map_ncm_to_category(ncm: &str) -> ProductCategory {
-        // Mapeamento bsico de NCM para categorias
+        // Mapeamento básico de NCM para categorias
         // NCM 1905 = Produtos de padaria
         if ncm.starts_with("1905") {
             ProductCategory::Bread
@@ -108,6 +112,29 @@ impl NFeImporter {
         }
     }
     
+    /// Envia a manifestação do destinatário (ciência, confirmação, desconhecimento
+    /// ou operação não realizada) para a nota com a chave informada
+    pub async fn manifestar(
+        chave: &str,
+        manifestacao: &Manifestacao,
+        signer: &dyn XmlSigner,
+        sefaz: &dyn SefazClient,
+    ) -> Result<SefazResponse> {
+        info!("Sending {} for NFe {}", manifestacao.tipo.descricao(), chave);
+        
+        let xml = manifestacao.to_xml(chave, chrono::Utc::now())?;
+        let xml = signer.sign(&xml, &manifestacao.event_id(chave))?;
+        
+        let retorno = sefaz.registrar_evento(&xml).await?;
+        
+        if !retorno.is_event_registered() {
+            warn!("Manifestation for NFe {} rejected: {} - {}", chave, retorno.status_code, retorno.motivo);
+            anyhow::bail!("Manifestação rejeitada pela SEFAZ ({}): {}", retorno.status_code, retorno.motivo);
+        }
+        
+        Ok(retorno)
+    }
+    
     pub fn create_supplier_from_nfe(nfe: &NFe) -> Supplier {
         let mut supplier = Supplier::new(nfe.emitente.razao_social.clone());
         
@@ -0,0 +1,456 @@
+use std::collections::{BTreeMap, HashSet};
+use chrono::{DateTime, Datelike, FixedOffset, TimeZone, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+use delpopolo_domain::{FiscalEntry, FiscalEntryItem};
+use super::models::{ItemNFe, NFe};
+
+/// Livro fiscal: extrai os tributos das NF-e importadas, consolida por
+/// CFOP/CST e exporta para a contabilidade
+pub struct FiscalLedger;
+
+/// Linha do resumo mensal (equivalente ao registro analítico C190)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ResumoFiscal {
+    pub cfop: String,
+    pub cst_icms: String,
+    pub aliquota_icms: f64,
+    pub notas: usize,
+    pub valor_operacao: f64,
+    pub base_icms: f64,
+    pub valor_icms: f64,
+    pub valor_ipi: f64,
+    pub valor_pis: f64,
+    pub valor_cofins: f64,
+    pub credito: f64, // ICMS + PIS + COFINS aproveitáveis nas entradas
+}
+
+impl FiscalLedger {
+    /// Converte a NF-e em lançamento fiscal, item a item
+    pub fn extract(nfe: &NFe) -> FiscalEntry {
+        let now = Utc::now();
+        
+        FiscalEntry {
+            id: Uuid::new_v4(),
+            nfe_key: nfe.chave.clone(),
+            number: nfe.numero.clone(),
+            series: nfe.serie.clone(),
+            model: nfe.chave.get(20..22).unwrap_or("55").to_string(),
+            issued_at: nfe.data_emissao,
+            issuer_cnpj: nfe.emitente.cnpj.clone(),
+            issuer_name: nfe.emitente.razao_social.clone(),
+            recipient_document: nfe.destinatario.cnpj_cpf.clone(),
+            total_value: nfe.totais.valor_total_nota,
+            products_value: nfe.totais.valor_total_produtos,
+            discount_value: nfe.totais.valor_desconto,
+            freight_value: nfe.totais.valor_frete,
+            icms_base: nfe.totais.base_calculo_icms,
+            icms_value: nfe.totais.valor_icms,
+            icms_st_base: nfe.totais.base_calculo_icms_st,
+            icms_st_value: nfe.totais.valor_icms_st,
+            ipi_value: nfe.totais.valor_ipi,
+            pis_value: nfe.totais.valor_pis,
+            cofins_value: nfe.totais.valor_cofins,
+            items: nfe.itens.iter().map(Self::extract_item).collect(),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+    
+    fn extract_item(item: &ItemNFe) -> FiscalEntryItem {
+        let icms = item.icms.as_ref();
+        
+        FiscalEntryItem {
+            item_number: item.numero_item,
+            product_code: item.codigo_produto.clone(),
+            description: item.descricao.clone(),
+            ncm: item.ncm.clone(),
+            cfop: item.cfop.clone(),
+            unit: item.unidade_comercial.clone(),
+            quantity: item.quantidade_comercial,
+            total_value: item.valor_total_bruto,
+            // No SPED o CST do ICMS tem 3 dígitos: origem + tributação
+            icms_cst: icms.map(|i| match (&item.origem, i.situacao_tributaria.len()) {
+                (Some(origem), 2) => format!("{}{}", origem, i.situacao_tributaria),
+                _ => i.situacao_tributaria.clone(),
+            }),
+            icms_base: icms.and_then(|i| i.base_calculo).unwrap_or(0.0),
+            icms_rate: icms.and_then(|i| i.aliquota).unwrap_or(0.0),
+            icms_value: icms.and_then(|i| i.valor).unwrap_or(0.0),
+            ipi_cst: item.ipi.as_ref().map(|i| i.situacao_tributaria.clone()),
+            ipi_value: item.ipi.as_ref().and_then(|i| i.valor).unwrap_or(0.0),
+            pis_cst: item.pis.as_ref().map(|i| i.situacao_tributaria.clone()),
+            pis_value: item.pis.as_ref().and_then(|i| i.valor).unwrap_or(0.0),
+            cofins_cst: item.cofins.as_ref().map(|i| i.situacao_tributaria.clone()),
+            cofins_value: item.cofins.as_ref().and_then(|i| i.valor).unwrap_or(0.0),
+        }
+    }
+    
+    /// Lançamentos emitidos no mês (horário de Brasília)
+    pub fn entries_in_month(entries: &[FiscalEntry], ano: i32, mes: u32) -> Vec<&FiscalEntry> {
+        entries
+            .iter()
+            .filter(|entry| {
+                let data = Self::brasilia(entry.issued_at);
+                data.year() == ano && data.month() == mes
+            })
+            .collect()
+    }
+    
+    /// Intervalo `[início, fim)` do mês no horário de Brasília, em UTC, para buscar os lançamentos
+    pub fn month_range(ano: i32, mes: u32) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+        let fuso = FixedOffset::west_opt(3 * 3600)?;
+        let (ano_fim, mes_fim) = if mes == 12 { (ano + 1, 1) } else { (ano, mes + 1) };
+        let inicio = fuso.with_ymd_and_hms(ano, mes, 1, 0, 0, 0).single()?;
+        let fim = fuso.with_ymd_and_hms(ano_fim, mes_fim, 1, 0, 0, 0).single()?;
+        Some((inicio.with_timezone(&Utc), fim.with_timezone(&Utc)))
+    }
+    
+    /// Resumo mensal agrupado por CFOP, CST do ICMS e alíquota
+    pub fn monthly_summary(entries: &[FiscalEntry], ano: i32, mes: u32) -> Vec<ResumoFiscal> {
+        let mut grupos: BTreeMap<(String, String, i64), (ResumoFiscal, HashSet<&str>)> = BTreeMap::new();
+        
+        for entry in Self::entries_in_month(entries, ano, mes) {
+            for item in &entry.items {
+                let cst = item.icms_cst.clone().unwrap_or_default();
+                let chave = (item.cfop.clone(), cst.clone(), (item.icms_rate * 100.0).round() as i64);
+                
+                let (resumo, notas) = grupos.entry(chave).or_insert_with(|| {
+                    (
+                        ResumoFiscal {
+                            cfop: item.cfop.clone(),
+                            cst_icms: cst,
+                            aliquota_icms: item.icms_rate,
+                            notas: 0,
+                            valor_operacao: 0.0,
+                            base_icms: 0.0,
+                            valor_icms: 0.0,
+                            valor_ipi: 0.0,
+                            valor_pis: 0.0,
+                            valor_cofins: 0.0,
+                            credito: 0.0,
+                        },
+                        HashSet::new(),
+                    )
+                });
+                
+                notas.insert(entry.nfe_key.as_str());
+                resumo.notas = notas.len();
+                resumo.valor_operacao += item.total_value + item.ipi_value;
+                resumo.base_icms += item.icms_base;
+                resumo.valor_icms += item.icms_value;
+                resumo.valor_ipi += item.ipi_value;
+                resumo.valor_pis += item.pis_value;
+                resumo.valor_cofins += item.cofins_value;
+                if item.is_inbound() {
+                    resumo.credito += item.icms_value + item.pis_value + item.cofins_value;
+                }
+            }
+        }
+        
+        grupos.into_values().map(|(resumo, _)| Self::round_resumo(resumo)).collect()
+    }
+    
+    fn round_resumo(mut resumo: ResumoFiscal) -> ResumoFiscal {
+        for valor in [
+            &mut resumo.valor_operacao,
+            &mut resumo.base_icms,
+            &mut resumo.valor_icms,
+            &mut resumo.valor_ipi,
+            &mut resumo.valor_pis,
+            &mut resumo.valor_cofins,
+            &mut resumo.credito,
+        ] {
+            *valor = (*valor * 100.0).round() / 100.0;
+        }
+        resumo
+    }
+    
+    pub fn summary_to_csv(resumo: &[ResumoFiscal]) -> String {
+        let mut csv = String::from(
+            "cfop,cst_icms,aliquota_icms,notas,valor_operacao,base_icms,valor_icms,valor_ipi,valor_pis,valor_cofins,credito\n",
+        );
+        
+        for linha in resumo {
+            csv.push_str(&format!(
+                "{},{},{:.2},{},{:.2},{:.2},{:.2},{:.2},{:.2},{:.2},{:.2}\n",
+                linha.cfop,
+                linha.cst_icms,
+                linha.aliquota_icms,
+                linha.notas,
+                linha.valor_operacao,
+                linha.base_icms,
+                linha.valor_icms,
+                linha.valor_ipi,
+                linha.valor_pis,
+                linha.valor_cofins,
+                linha.credito
+            ));
+        }
+        
+        csv
+    }
+    
+    /// Registros no leiaute do SPED Fiscal (C100 por nota, C170 por item e
+    /// C190 analítico), com campos separados por `|` e valores com vírgula.
+    /// Só os campos disponíveis na NF-e são exportados.
+    pub fn to_sped(entries: &[&FiscalEntry]) -> String {
+        let mut sped = String::new();
+        
+        for entry in entries {
+            let entrada = entry.is_inbound();
+            let data = Self::brasilia(entry.issued_at).format("%d%m%Y").to_string();
+            
+            Self::push_record(&mut sped, &[
+                "C100".to_string(),
+                if entrada { "0" } else { "1" }.to_string(), // IND_OPER
+                if entrada { "1" } else { "0" }.to_string(), // IND_EMIT: terceiros na entrada
+                entry.issuer_cnpj.clone(),
+                entry.model.clone(),
+                "00".to_string(), // COD_SIT: documento regular
+                entry.series.clone(),
+                entry.number.clone(),
+                entry.nfe_key.clone(),
+                data.clone(),
+                data,
+                Self::valor(entry.total_value),
+                Self::valor(entry.discount_value),
+                Self::valor(entry.products_value),
+                Self::valor(entry.freight_value),
+                Self::valor(entry.icms_base),
+                Self::valor(entry.icms_value),
+                Self::valor(entry.icms_st_base),
+                Self::valor(entry.icms_st_value),
+                Self::valor(entry.ipi_value),
+                Self::valor(entry.pis_value),
+                Self::valor(entry.cofins_value),
+            ]);
+            
+            for item in &entry.items {
+                Self::push_record(&mut sped, &[
+                    "C170".to_string(),
+                    item.item_number.to_string(),
+                    item.product_code.clone(),
+                    item.description.clone(),
+                    Self::quantidade(item.quantity),
+                    item.unit.clone(),
+                    Self::valor(item.total_value),
+                    item.icms_cst.clone().unwrap_or_default(),
+                    item.cfop.clone(),
+                    Self::valor(item.icms_base),
+                    Self::valor(item.icms_rate),
+                    Self::valor(item.icms_value),
+                    item.ipi_cst.clone().unwrap_or_default(),
+                    Self::valor(item.ipi_value),
+                    item.pis_cst.clone().unwrap_or_default(),
+                    Self::valor(item.pis_value),
+                    item.cofins_cst.clone().unwrap_or_default(),
+                    Self::valor(item.cofins_value),
+                ]);
+            }
+            
+            let data = Self::brasilia(entry.issued_at);
+            for linha in Self::monthly_summary(std::slice::from_ref(*entry), data.year(), data.month()) {
+                Self::push_record(&mut sped, &[
+                    "C190".to_string(),
+                    linha.cst_icms,
+                    linha.cfop,
+                    Self::valor(linha.aliquota_icms),
+                    Self::valor(linha.valor_operacao),
+                    Self::valor(linha.base_icms),
+                    Self::valor(linha.valor_icms),
+                    Self::valor(linha.valor_ipi),
+                ]);
+            }
+        }
+        
+        sped
+    }
+    
+    fn push_record(sped: &mut String, campos: &[String]) {
+        sped.push('|');
+        for campo in campos {
+            // O separador não pode aparecer dentro do campo
+            sped.push_str(&campo.replace('|', " "));
+            sped.push('|');
+        }
+        sped.push('\n');
+    }
+    
+    fn valor(valor: f64) -> String {
+        format!("{:.2}", valor).replace('.', ",")
+    }
+    
+    fn quantidade(valor: f64) -> String {
+        format!("{:.5}", valor).replace('.', ",")
+    }
+    
+    fn brasilia(data: DateTime<Utc>) -> DateTime<FixedOffset> {
+        data.with_timezone(&FixedOffset::west_opt(3 * 3600).expect("valid offset"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use crate::models::*;
+    
+    fn endereco() -> Endereco {
+        Endereco {
+            logradouro: "Rua do Trigo".to_string(),
+            numero: "100".to_string(),
+            complemento: None,
+            bairro: "Centro".to_string(),
+            municipio: "São Paulo".to_string(),
+            uf: "SP".to_string(),
+            cep: "01001000".to_string(),
+        }
+    }
+    
+    fn item(numero_item: i32, cfop: &str, valor: f64, aliquota: f64) -> ItemNFe {
+        ItemNFe {
+            numero_item,
+            codigo_produto: format!("F{:03}", numero_item),
+            descricao: "Farinha de trigo 25kg".to_string(),
+            ncm: "11010010".to_string(),
+            cest: None,
+            cfop: cfop.to_string(),
+            unidade_comercial: "SC".to_string(),
+            quantidade_comercial: 1.0,
+            valor_unitario_comercial: valor,
+            valor_total_bruto: valor,
+            ean: None,
+            ean_tributavel: None,
+            origem: Some("0".to_string()),
+            icms: Some(ICMS {
+                situacao_tributaria: "00".to_string(),
+                base_calculo: Some(valor),
+                aliquota: Some(aliquota),
+                valor: Some(valor * aliquota / 100.0),
+            }),
+            ipi: None,
+            pis: Some(PIS {
+                situacao_tributaria: "50".to_string(),
+                base_calculo: Some(valor),
+                aliquota: Some(1.65),
+                valor: Some(valor * 0.0165),
+            }),
+            cofins: Some(COFINS {
+                situacao_tributaria: "50".to_string(),
+                base_calculo: Some(valor),
+                aliquota: Some(7.6),
+                valor: Some(valor * 0.076),
+            }),
+        }
+    }
+    
+    fn sample_nfe(chave: &str, data: DateTime<Utc>, itens: Vec<ItemNFe>) -> NFe {
+        let total = itens.iter().map(|i| i.valor_total_bruto).sum::<f64>();
+        let icms = itens.iter().filter_map(|i| i.icms.as_ref()?.valor).sum::<f64>();
+        
+        NFe {
+            chave: chave.to_string(),
+            numero: "1".to_string(),
+            serie: "1".to_string(),
+            data_emissao: data,
+            emitente: Emitente {
+                cnpj: "11222333000181".to_string(),
+                razao_social: "Moinho Trigo Bom LTDA".to_string(),
+                nome_fantasia: None,
+                endereco: endereco(),
+                telefone: None,
+                email: None,
+            },
+            destinatario: Destinatario {
+                cnpj_cpf: "98765432000110".to_string(),
+                razao_social: "DelPopolo Panificadora LTDA".to_string(),
+                endereco: endereco(),
+                telefone: None,
+                email: None,
+            },
+            itens,
+            totais: Totais {
+                base_calculo_icms: total,
+                valor_icms: icms,
+                valor_icms_desonerado: 0.0,
+                base_calculo_icms_st: 0.0,
+                valor_icms_st: 0.0,
+                valor_total_produtos: total,
+                valor_frete: 0.0,
+                valor_seguro: 0.0,
+                valor_desconto: 0.0,
+                valor_total_ii: 0.0,
+                valor_ipi: 0.0,
+                valor_pis: 0.0,
+                valor_cofins: 0.0,
+                valor_outras_despesas: 0.0,
+                valor_total_nota: total,
+            },
+            transporte: None,
+            informacoes_adicionais: None,
+        }
+    }
+    
+    #[test]
+    fn test_monthly_summary_groups_by_cfop_and_cst() {
+        let janeiro = Utc.with_ymd_and_hms(2025, 1, 10, 12, 0, 0).unwrap();
+        // 01/02 00:30 UTC ainda é 31/01 em Brasília
+        let virada = Utc.with_ymd_and_hms(2025, 2, 1, 0, 30, 0).unwrap();
+        let fevereiro = Utc.with_ymd_and_hms(2025, 2, 10, 12, 0, 0).unwrap();
+        
+        let entries: Vec<FiscalEntry> = [
+            sample_nfe("A", janeiro, vec![item(1, "1102", 100.0, 18.0), item(2, "1102", 200.0, 12.0)]),
+            sample_nfe("B", virada, vec![item(1, "1102", 60.0, 18.0)]),
+            sample_nfe("C", fevereiro, vec![item(1, "1102", 999.0, 18.0)]),
+            sample_nfe("D", janeiro, vec![item(1, "5102", 80.0, 18.0)]),
+        ]
+        .iter()
+        .map(FiscalLedger::extract)
+        .collect();
+        
+        let resumo = FiscalLedger::monthly_summary(&entries, 2025, 1);
+        assert_eq!(resumo.len(), 3);
+        
+        // A busca por período usa o mesmo mês de Brasília
+        let (inicio, fim) = FiscalLedger::month_range(2025, 1).unwrap();
+        assert!(inicio <= janeiro && virada < fim && fevereiro >= fim);
+        
+        let credito_18 = &resumo[1];
+        assert_eq!((credito_18.cfop.as_str(), credito_18.cst_icms.as_str()), ("1102", "000"));
+        assert_eq!(credito_18.aliquota_icms, 18.0);
+        assert_eq!(credito_18.notas, 2);
+        assert_eq!(credito_18.valor_operacao, 160.0);
+        assert_eq!(credito_18.valor_icms, 28.8);
+        assert_eq!(credito_18.credito, 43.6); // 28,80 + 2,64 + 12,16 (PIS/COFINS)
+        
+        // Saída não gera crédito
+        assert_eq!(resumo[2].cfop, "5102");
+        assert_eq!(resumo[2].credito, 0.0);
+        
+        let csv = FiscalLedger::summary_to_csv(&resumo);
+        assert!(csv.contains("\n1102,000,18.00,2,160.00,160.00,28.80,0.00,2.64,12.16,43.60\n"));
+    }
+    
+    #[test]
+    fn test_sped_records() {
+        let data = Utc.with_ymd_and_hms(2025, 1, 10, 12, 0, 0).unwrap();
+        let entry = FiscalLedger::extract(&sample_nfe(
+            "35250111222333000181550010000000011123456780",
+            data,
+            vec![item(1, "1102", 100.0, 18.0), item(2, "1102", 200.0, 18.0)],
+        ));
+        
+        let sped = FiscalLedger::to_sped(&[&entry]);
+        let linhas: Vec<&str> = sped.lines().collect();
+        
+        assert_eq!(linhas.len(), 4);
+        assert!(linhas[0].starts_with(
+            "|C100|0|1|11222333000181|55|00|1|1|35250111222333000181550010000000011123456780|10012025|10012025|300,00|"
+        ));
+        assert!(linhas[1].starts_with("|C170|1|F001|Farinha de trigo 25kg|1,00000|SC|100,00|000|1102|"));
+        assert_eq!(linhas[3], "|C190|000|1102|18,00|300,00|300,00|54,00|0,00|");
+        assert!(linhas.iter().all(|l| l.starts_with('|') && l.ends_with('|')));
+    }
+}
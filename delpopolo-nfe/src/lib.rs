@@ -1,9 +1,23 @@
 pub mod parser;
+pub mod chave;
 pub mod models;
 pub mod validator;
 pub mod importer;
+pub mod batch;
+pub mod nfce;
+pub mod sefaz;
+pub mod danfe;
+pub mod events;
+pub mod ledger;
 
 pub use parser::NFeParser;
+pub use chave::ChaveAcesso;
 pub use models::*;
 pub use validator::NFeValidator;
 pub use importer::NFeImporter;
+pub use batch::{NFeBatchImporter, BatchImportReport, BatchEntryStatus, ZipLimits};
+pub use nfce::{NFCe, NFCeBuilder, NFCeEmitter};
+pub use sefaz::{SefazClient, SefazHttpClient, SefazResponse};
+pub use danfe::{DanfeNfce, DanfeNfceInfo, DanfePdf};
+pub use events::{EventoTipo, NFeEvento, NFeEventProcessor, NFeEventStore, PurchaseReversal};
+pub use ledger::{FiscalLedger, ResumoFiscal};
@@ -3,7 +3,7 @@ use chrono::{DateTime, Utc};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NFe {
-    pub chave: String, // Chave de acesso de 44 dgitos
+    pub chave: String, // Chave de acesso de 44 dígitos
     pub numero: String,
     pub serie: String,
     pub data_emissao: DateTime<Utc>,
@@ -162,7 +162,7 @@ impl NFe {
     }
     
     pub fn is_entrada(&self) -> bool {
-        // Determina se  nota de entrada (compra) baseado no CFOP
+        // Determina se é nota de entrada (compra) baseado no CFOP
         self.itens.iter().any(|item| {
             item.cfop.starts_with('1') || item.cfop.starts_with('2') || item.cfop.starts_with('3')
         })
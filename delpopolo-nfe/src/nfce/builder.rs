@@ -0,0 +1,504 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Datelike, FixedOffset, Utc};
+use quick_xml::escape::partial_escape;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+use delpopolo_domain::{Cnpj, Order, OrderStatus, PaymentMethod, Product};
+use crate::models::{Destinatario, Emitente, Endereco, ItemNFe, NFe, Totais};
+use crate::chave::{ChaveAcesso, ChaveAcessoPartes};
+use super::qrcode::NFCeQrCode;
+use super::signer::XmlSigner;
+use super::tax::{round2, TaxCalculator};
+
+pub const MODELO_NFCE: u8 = 65;
+const VERSAO_LAYOUT: &str = "4.00";
+const VERSAO_PROCESSO: &str = "delpopolo-1.0";
+/// xProd obrigatório no primeiro item das notas emitidas em homologação
+const DESCRICAO_HOMOLOGACAO: &str = "NOTA FISCAL EMITIDA EM AMBIENTE DE HOMOLOGACAO - SEM VALOR FISCAL";
+
+/// Ambiente de emissão (tpAmb)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Ambiente {
+    Producao,
+    Homologacao,
+}
+
+impl Ambiente {
+    pub fn code(&self) -> u8 {
+        match self {
+            Ambiente::Producao => 1,
+            Ambiente::Homologacao => 2,
+        }
+    }
+}
+
+/// Dados fixos do emitente e da SEFAZ usados em toda NFC-e
+#[derive(Debug, Clone)]
+pub struct NFCeConfig {
+    pub emitente: Emitente,
+    pub inscricao_estadual: String,
+    pub codigo_uf: u8,          // cUF (IBGE), ex.: 35 = SP
+    pub codigo_municipio: String, // cMunFG (IBGE)
+    pub serie: u32,
+    pub ambiente: Ambiente,
+    pub csc_id: u32,
+    pub csc: String,
+    pub url_qrcode: String,
+    pub url_consulta: String,
+}
+
+/// NFC-e montada e assinada, pronta para transmissão
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NFCe {
+    pub nfe: NFe,
+    pub xml: String,
+    pub qr_code_url: String,
+    pub url_consulta: String,
+    pub ambiente: Ambiente,
+    pub protocolo: Option<String>,
+}
+
+pub struct NFCeBuilder {
+    config: NFCeConfig,
+    tax_calculator: TaxCalculator,
+    signer: Box<dyn XmlSigner>,
+}
+
+impl NFCeBuilder {
+    pub fn new(config: NFCeConfig, tax_calculator: TaxCalculator, signer: Box<dyn XmlSigner>) -> Self {
+        Self {
+            config,
+            tax_calculator,
+            signer,
+        }
+    }
+    
+    pub fn config(&self) -> &NFCeConfig {
+        &self.config
+    }
+    
+    /// Monta a NFC-e de um pedido concluído com número `numero` da série configurada
+    pub fn build(&self, order: &Order, products: &[Product], numero: u32) -> Result<NFCe> {
+        let codigo_numerico = u32::from_be_bytes(
+            uuid::Uuid::new_v4().as_bytes()[..4].try_into()?,
+        ) % 100_000_000;
+        
+        self.build_at(order, products, numero, codigo_numerico, Utc::now())
+    }
+    
+    pub fn build_at(
+        &self,
+        order: &Order,
+        products: &[Product],
+        numero: u32,
+        codigo_numerico: u32,
+        emitted_at: DateTime<Utc>,
+    ) -> Result<NFCe> {
+        if order.status != OrderStatus::Completed {
+            anyhow::bail!("Pedido {} não está concluído", order.order_number);
+        }
+        
+        if order.items.is_empty() {
+            anyhow::bail!("Pedido {} não possui itens", order.order_number);
+        }
+        
+        info!("Building NFC-e {} for order {}", numero, order.order_number);
+        
+        let chave = self.generate_chave(numero, codigo_numerico, emitted_at)?;
+        let itens = self.build_items(order, products)?;
+        let totais = Self::build_totais(&itens, order);
+        
+        let nfe = NFe {
+            chave: chave.clone(),
+            numero: numero.to_string(),
+            serie: self.config.serie.to_string(),
+            data_emissao: emitted_at,
+            emitente: self.config.emitente.clone(),
+            destinatario: Self::build_destinatario(order),
+            itens,
+            totais,
+            transporte: None,
+            informacoes_adicionais: Some(format!("Pedido {}", order.order_number)),
+        };
+        
+        let qr_code_url = NFCeQrCode::build_url(
+            &self.config.url_qrcode,
+            &chave,
+            self.config.ambiente,
+            self.config.csc_id,
+            &self.config.csc,
+        );
+        
+        let xml = self.render_xml(&nfe, order, codigo_numerico, &qr_code_url)?;
+        let xml = self.signer.sign(&xml, &format!("NFe{}", chave))?;
+        
+        Ok(NFCe {
+            nfe,
+            xml,
+            qr_code_url,
+            url_consulta: self.config.url_consulta.clone(),
+            ambiente: self.config.ambiente,
+            protocolo: None,
+        })
+    }
+    
+    /// cUF + AAMM + CNPJ + modelo + série + número + tpEmis + cNF + DV
+    fn generate_chave(&self, numero: u32, codigo_numerico: u32, emitted_at: DateTime<Utc>) -> Result<String> {
+        let emitted_at = emitted_at.with_timezone(&Self::brasilia());
+        let cnpj = Cnpj::new(self.config.emitente.cnpj.clone())
+            .map_err(|e| anyhow::anyhow!("CNPJ do emitente: {}", e))?;
+        
+        let chave = ChaveAcesso::from_partes(ChaveAcessoPartes {
+            codigo_uf: self.config.codigo_uf,
+            ano: (emitted_at.year() % 100) as u8,
+            mes: emitted_at.month() as u8,
+            documento: cnpj.value().to_string(),
+            modelo: MODELO_NFCE,
+            serie: u16::try_from(self.config.serie).context("Série excede 3 dígitos")?,
+            numero,
+            tipo_emissao: 1, // emissão normal
+            codigo_numerico,
+        })
+        .map_err(|e| anyhow::anyhow!("Chave de acesso: {}", e))?;
+        
+        Ok(chave.value().to_string())
+    }
+    
+    fn build_items(&self, order: &Order, products: &[Product]) -> Result<Vec<ItemNFe>> {
+        let descontos = Self::apportion(order.discount.amount, order);
+        let outros = Self::apportion(order.delivery_fee.amount, order);
+        
+        order.items
+            .iter()
+            .zip(descontos.into_iter().zip(outros))
+            .enumerate()
+            .map(|(index, (item, (desconto, outro)))| {
+                let product = products
+                    .iter()
+                    .find(|p| p.id == item.product_id)
+                    .with_context(|| format!("Produto {} não encontrado", item.product_name))?;
+                
+                let ncm = product.nfe_ncm
+                    .as_deref()
+                    .with_context(|| format!("Produto {} sem NCM cadastrado", product.name))?;
+                let cfop = TaxCalculator::sale_cfop(product.nfe_cfop.as_deref().unwrap_or("5102"))
+                    .with_context(|| format!("Produto {}", product.name))?;
+                
+                let valor_total = round2(item.total_price.amount);
+                let taxes = self.tax_calculator
+                    .calculate(ncm, cfop, valor_total - desconto + outro)
+                    .with_context(|| format!("Tributação do produto {}", product.name))?;
+                
+                let descricao = if index == 0 && self.config.ambiente == Ambiente::Homologacao {
+                    DESCRICAO_HOMOLOGACAO.to_string()
+                } else {
+                    product.name.clone()
+                };
+                
+                Ok(ItemNFe {
+                    numero_item: index as i32 + 1,
+                    codigo_produto: if product.sku.is_empty() { product.id.to_string() } else { product.sku.clone() },
+                    descricao,
+                    ncm: ncm.to_string(),
+                    cest: product.nfe_cest.clone(),
+                    cfop: cfop.to_string(),
+                    unidade_comercial: Self::unit_code(product),
+                    quantidade_comercial: item.quantity,
+                    valor_unitario_comercial: item.unit_price.amount,
+                    valor_total_bruto: valor_total,
+                    ean: product.barcode.clone(),
+                    ean_tributavel: product.barcode.clone(),
+                    origem: Some("0".to_string()),
+                    icms: Some(taxes.icms),
+                    ipi: None,
+                    pis: Some(taxes.pis),
+                    cofins: Some(taxes.cofins),
+                })
+            })
+            .collect()
+    }
+    
+    /// Rateia um valor do pedido (desconto, taxa de entrega) proporcionalmente ao valor de cada item
+    fn apportion(total: f64, order: &Order) -> Vec<f64> {
+        let subtotal: f64 = order.items.iter().map(|i| i.total_price.amount).sum();
+        let total = round2(total);
+        
+        if total <= 0.0 || subtotal <= 0.0 {
+            return vec![0.0; order.items.len()];
+        }
+        
+        let mut shares: Vec<f64> = order.items
+            .iter()
+            .map(|i| round2(total * i.total_price.amount / subtotal))
+            .collect();
+        
+        // Diferença de arredondamento vai para o último item
+        let diferenca = round2(total - shares.iter().sum::<f64>());
+        if let Some(last) = shares.last_mut() {
+            *last = round2(*last + diferenca);
+        }
+        
+        shares
+    }
+    
+    fn build_totais(itens: &[ItemNFe], order: &Order) -> Totais {
+        let sum = |f: &dyn Fn(&ItemNFe) -> Option<f64>| round2(itens.iter().filter_map(f).sum());
+        
+        let valor_total_produtos = sum(&|i| Some(i.valor_total_bruto));
+        let valor_desconto = round2(order.discount.amount);
+        // Taxa de entrega vai em vOutro: NFC-e só admite modFrete 9, sem vFrete
+        let valor_outras_despesas = round2(order.delivery_fee.amount);
+        
+        Totais {
+            base_calculo_icms: sum(&|i| i.icms.as_ref().and_then(|t| t.base_calculo)),
+            valor_icms: sum(&|i| i.icms.as_ref().and_then(|t| t.valor)),
+            valor_icms_desonerado: 0.0,
+            base_calculo_icms_st: 0.0,
+            valor_icms_st: 0.0,
+            valor_total_produtos,
+            valor_frete: 0.0,
+            valor_seguro: 0.0,
+            valor_desconto,
+            valor_total_ii: 0.0,
+            valor_ipi: 0.0,
+            valor_pis: sum(&|i| i.pis.as_ref().and_then(|t| t.valor)),
+            valor_cofins: sum(&|i| i.cofins.as_ref().and_then(|t| t.valor)),
+            valor_outras_despesas,
+            valor_total_nota: round2(valor_total_produtos - valor_desconto + valor_outras_despesas),
+        }
+    }
+    
+    fn build_destinatario(order: &Order) -> Destinatario {
+        Destinatario {
+            cnpj_cpf: order.customer_cpf.clone().unwrap_or_default(),
+            razao_social: order.customer_name
+                .clone()
+                .unwrap_or_else(|| "CONSUMIDOR NÃO IDENTIFICADO".to_string()),
+            endereco: Endereco {
+                logradouro: String::new(),
+                numero: String::new(),
+                complemento: None,
+                bairro: String::new(),
+                municipio: String::new(),
+                uf: String::new(),
+                cep: String::new(),
+            },
+            telefone: None,
+            email: None,
+        }
+    }
+    
+    fn unit_code(product: &Product) -> String {
+        use delpopolo_domain::UnitOfMeasure;
+        
+        match product.unit_of_measure {
+            UnitOfMeasure::Unit => "UN",
+            UnitOfMeasure::Kilogram => "KG",
+            UnitOfMeasure::Gram => "G",
+            UnitOfMeasure::Liter => "L",
+            UnitOfMeasure::Milliliter => "ML",
+            UnitOfMeasure::Dozen => "DZ",
+            UnitOfMeasure::Package => "PCT",
+        }
+        .to_string()
+    }
+    
+    /// Meio de pagamento (tPag) conforme tabela da SEFAZ
+    fn payment_code(method: Option<PaymentMethod>) -> &'static str {
+        match method {
+            Some(PaymentMethod::Cash) => "01",
+            Some(PaymentMethod::CreditCard) => "03",
+            Some(PaymentMethod::DebitCard) => "04",
+            Some(PaymentMethod::VoucherFood) => "10",
+            Some(PaymentMethod::VoucherMeal) => "11",
+            Some(PaymentMethod::Pix) => "17",
+            Some(PaymentMethod::Multiple) | None => "99",
+        }
+    }
+    
+    fn brasilia() -> FixedOffset {
+        FixedOffset::west_opt(3 * 3600).expect("valid offset")
+    }
+    
+    fn render_xml(&self, nfe: &NFe, order: &Order, codigo_numerico: u32, qr_code_url: &str) -> Result<String> {
+        let emit = &nfe.emitente;
+        let ender = &emit.endereco;
+        let mut xml = String::new();
+        
+        xml.push_str(&format!(
+            "<NFe xmlns=\"http://www.portalfiscal.inf.br/nfe\"><infNFe Id=\"NFe{}\" versao=\"{}\">",
+            nfe.chave, VERSAO_LAYOUT
+        ));
+        
+        xml.push_str(&format!(
+            concat!(
+                "<ide><cUF>{}</cUF><cNF>{:08}</cNF><natOp>VENDA</natOp><mod>{}</mod><serie>{}</serie><nNF>{}</nNF>",
+                "<dhEmi>{}</dhEmi><tpNF>1</tpNF><idDest>1</idDest><cMunFG>{}</cMunFG><tpImp>4</tpImp><tpEmis>1</tpEmis>",
+                "<cDV>{}</cDV><tpAmb>{}</tpAmb><finNFe>1</finNFe><indFinal>1</indFinal><indPres>1</indPres>",
+                "<procEmi>0</procEmi><verProc>{}</verProc></ide>"
+            ),
+            self.config.codigo_uf,
+            codigo_numerico,
+            MODELO_NFCE,
+            nfe.serie,
+            nfe.numero,
+            nfe.data_emissao.with_timezone(&Self::brasilia()).format("%Y-%m-%dT%H:%M:%S%:z"),
+            self.config.codigo_municipio,
+            &nfe.chave[43..],
+            self.config.ambiente.code(),
+            VERSAO_PROCESSO,
+        ));
+        
+        // Só &, < e > viram entidades, como na canonicalização (C14N) usada no digest da assinatura
+        xml.push_str(&format!("<emit><CNPJ>{}</CNPJ><xNome>{}</xNome>", emit.cnpj, partial_escape(&emit.razao_social)));
+        if let Some(fantasia) = &emit.nome_fantasia {
+            xml.push_str(&format!("<xFant>{}</xFant>", partial_escape(fantasia)));
+        }
+        xml.push_str(&format!(
+            "<enderEmit><xLgr>{}</xLgr><nro>{}</nro><xBairro>{}</xBairro><cMun>{}</cMun><xMun>{}</xMun><UF>{}</UF><CEP>{}</CEP></enderEmit>",
+            partial_escape(&ender.logradouro),
+            partial_escape(&ender.numero),
+            partial_escape(&ender.bairro),
+            self.config.codigo_municipio,
+            partial_escape(&ender.municipio),
+            ender.uf,
+            ender.cep,
+        ));
+        xml.push_str(&format!(
+            "<IE>{}</IE><CRT>{}</CRT></emit>",
+            self.config.inscricao_estadual,
+            self.tax_calculator.regime.crt()
+        ));
+        
+        let documento: String = nfe.destinatario.cnpj_cpf.chars().filter(|c| c.is_ascii_digit()).collect();
+        if documento.len() == 11 {
+            xml.push_str(&format!("<dest><CPF>{}</CPF><indIEDest>9</indIEDest></dest>", documento));
+        }
+        
+        let descontos = Self::apportion(order.discount.amount, order);
+        let outros = Self::apportion(order.delivery_fee.amount, order);
+        for ((item, desconto), outro) in nfe.itens.iter().zip(descontos).zip(outros) {
+            Self::render_item(&mut xml, item, desconto, outro);
+        }
+        
+        let t = &nfe.totais;
+        xml.push_str(&format!(
+            concat!(
+                "<total><ICMSTot><vBC>{:.2}</vBC><vICMS>{:.2}</vICMS><vICMSDeson>0.00</vICMSDeson><vFCP>0.00</vFCP>",
+                "<vBCST>0.00</vBCST><vST>0.00</vST><vFCPST>0.00</vFCPST><vFCPSTRet>0.00</vFCPSTRet><vProd>{:.2}</vProd>",
+                "<vFrete>0.00</vFrete><vSeg>0.00</vSeg><vDesc>{:.2}</vDesc><vII>0.00</vII><vIPI>0.00</vIPI><vIPIDevol>0.00</vIPIDevol>",
+                "<vPIS>{:.2}</vPIS><vCOFINS>{:.2}</vCOFINS><vOutro>{:.2}</vOutro><vNF>{:.2}</vNF></ICMSTot></total>"
+            ),
+            t.base_calculo_icms,
+            t.valor_icms,
+            t.valor_total_produtos,
+            t.valor_desconto,
+            t.valor_pis,
+            t.valor_cofins,
+            t.valor_outras_despesas,
+            t.valor_total_nota,
+        ));
+        
+        xml.push_str("<transp><modFrete>9</modFrete></transp>");
+        xml.push_str(&format!(
+            "<pag><detPag><tPag>{}</tPag><vPag>{:.2}</vPag></detPag></pag>",
+            Self::payment_code(order.payment_method),
+            t.valor_total_nota
+        ));
+        
+        if let Some(info) = &nfe.informacoes_adicionais {
+            xml.push_str(&format!("<infAdic><infCpl>{}</infCpl></infAdic>", partial_escape(info)));
+        }
+        
+        xml.push_str("</infNFe>");
+        xml.push_str(&format!(
+            "<infNFeSupl><qrCode><![CDATA[{}]]></qrCode><urlChave>{}</urlChave></infNFeSupl>",
+            qr_code_url,
+            partial_escape(&self.config.url_consulta)
+        ));
+        xml.push_str("</NFe>");
+        
+        Ok(xml)
+    }
+    
+    fn render_item(xml: &mut String, item: &ItemNFe, desconto: f64, outro: f64) {
+        let gtin = item.ean.clone().unwrap_or_else(|| "SEM GTIN".to_string());
+        
+        xml.push_str(&format!(
+            concat!(
+                "<det nItem=\"{n}\"><prod><cProd>{cprod}</cProd><cEAN>{gtin}</cEAN><xProd>{desc}</xProd><NCM>{ncm}</NCM>",
+                "{cest}<CFOP>{cfop}</CFOP><uCom>{un}</uCom><qCom>{qtd:.4}</qCom><vUnCom>{vun:.4}</vUnCom><vProd>{vprod:.2}</vProd>",
+                "<cEANTrib>{gtin}</cEANTrib><uTrib>{un}</uTrib><qTrib>{qtd:.4}</qTrib><vUnTrib>{vun:.4}</vUnTrib>",
+                "{vdesc}{voutro}<indTot>1</indTot></prod><imposto>"
+            ),
+            n = item.numero_item,
+            cprod = partial_escape(&item.codigo_produto),
+            gtin = gtin,
+            desc = partial_escape(&item.descricao),
+            ncm = item.ncm,
+            cest = item.cest.as_ref().map(|c| format!("<CEST>{}</CEST>", c)).unwrap_or_default(),
+            cfop = item.cfop,
+            un = item.unidade_comercial,
+            qtd = item.quantidade_comercial,
+            vun = item.valor_unitario_comercial,
+            vprod = item.valor_total_bruto,
+            vdesc = if desconto > 0.0 { format!("<vDesc>{:.2}</vDesc>", desconto) } else { String::new() },
+            voutro = if outro > 0.0 { format!("<vOutro>{:.2}</vOutro>", outro) } else { String::new() },
+        ));
+        
+        if let Some(icms) = &item.icms {
+            let origem = item.origem.as_deref().unwrap_or("0");
+            let cst = icms.situacao_tributaria.as_str();
+            let grupo = match cst {
+                "00" => format!(
+                    "<ICMS00><orig>{}</orig><CST>00</CST><modBC>3</modBC><vBC>{:.2}</vBC><pICMS>{:.2}</pICMS><vICMS>{:.2}</vICMS></ICMS00>",
+                    origem,
+                    icms.base_calculo.unwrap_or_default(),
+                    icms.aliquota.unwrap_or_default(),
+                    icms.valor.unwrap_or_default(),
+                ),
+                "60" => format!("<ICMS60><orig>{}</orig><CST>60</CST></ICMS60>", origem),
+                "500" => format!("<ICMSSN500><orig>{}</orig><CSOSN>500</CSOSN></ICMSSN500>", origem),
+                csosn => format!("<ICMSSN102><orig>{}</orig><CSOSN>{}</CSOSN></ICMSSN102>", origem, csosn),
+            };
+            xml.push_str(&format!("<ICMS>{}</ICMS>", grupo));
+        }
+        
+        if let Some(pis) = &item.pis {
+            xml.push_str(&Self::render_pis_cofins("PIS", &pis.situacao_tributaria, pis.base_calculo, pis.aliquota, pis.valor));
+        }
+        
+        if let Some(cofins) = &item.cofins {
+            xml.push_str(&Self::render_pis_cofins("COFINS", &cofins.situacao_tributaria, cofins.base_calculo, cofins.aliquota, cofins.valor));
+        }
+        
+        xml.push_str("</imposto></det>");
+    }
+    
+    fn render_pis_cofins(
+        tributo: &str,
+        cst: &str,
+        base_calculo: Option<f64>,
+        aliquota: Option<f64>,
+        valor: Option<f64>,
+    ) -> String {
+        match cst {
+            "01" | "02" => format!(
+                "<{t}><{t}Aliq><CST>{cst}</CST><vBC>{:.2}</vBC><p{t}>{:.4}</p{t}><v{t}>{:.2}</v{t}></{t}Aliq></{t}>",
+                base_calculo.unwrap_or_default(),
+                aliquota.unwrap_or_default(),
+                valor.unwrap_or_default(),
+                t = tributo,
+                cst = cst,
+            ),
+            "04" | "05" | "06" | "07" | "08" | "09" => {
+                format!("<{t}><{t}NT><CST>{cst}</CST></{t}NT></{t}>", t = tributo, cst = cst)
+            }
+            _ => format!(
+                "<{t}><{t}Outr><CST>{cst}</CST><vBC>0.00</vBC><p{t}>0.0000</p{t}><v{t}>0.00</v{t}></{t}Outr></{t}>",
+                t = tributo,
+                cst = cst,
+            ),
+        }
+    }
+}
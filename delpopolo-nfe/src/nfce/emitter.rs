@@ -0,0 +1,281 @@
+use anyhow::Result;
+use tracing::{info, warn};
+use delpopolo_domain::{Order, Product};
+use crate::sefaz::SefazClient;
+use super::builder::{NFCe, NFCeBuilder};
+
+/// Emissão de NFC-e: monta, assina e transmite para a SEFAZ
+pub struct NFCeEmitter {
+    builder: NFCeBuilder,
+    sefaz: Box<dyn SefazClient>,
+}
+
+impl NFCeEmitter {
+    pub fn new(builder: NFCeBuilder, sefaz: Box<dyn SefazClient>) -> Self {
+        Self { builder, sefaz }
+    }
+    
+    pub async fn emit(&self, order: &Order, products: &[Product], numero: u32) -> Result<NFCe> {
+        let nfce = self.builder.build(order, products, numero)?;
+        self.transmit(nfce).await
+    }
+    
+    pub async fn transmit(&self, mut nfce: NFCe) -> Result<NFCe> {
+        info!("Transmitting NFC-e {} to SEFAZ", nfce.nfe.chave);
+        
+        let retorno = self.sefaz.autorizar(&nfce.xml).await?;
+        
+        if !retorno.is_authorized() {
+            warn!("NFC-e {} rejected: {} - {}", nfce.nfe.chave, retorno.status_code, retorno.motivo);
+            anyhow::bail!("NFC-e rejeitada pela SEFAZ ({}): {}", retorno.status_code, retorno.motivo);
+        }
+        
+        info!("NFC-e {} authorized, protocol {:?}", nfce.nfe.chave, retorno.protocolo);
+        nfce.protocolo = retorno.protocolo;
+        
+        Ok(nfce)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use delpopolo_domain::{Money, OrderSource, PaymentMethod, ProductCategory, UnitOfMeasure};
+    use delpopolo_domain::entities::order::OrderItem;
+    use crate::models::{Emitente, Endereco};
+    use crate::nfce::{Ambiente, NFCeConfig, TaxCalculator, TaxRegime, XmlSigner};
+    use crate::sefaz::{SefazEndpoints, SefazHttpClient};
+    use crate::NFeParser;
+    
+    struct TestSigner;
+    
+    impl XmlSigner for TestSigner {
+        fn sign(&self, xml: &str, _reference_id: &str) -> Result<String> {
+            Ok(xml.replace("</NFe>", "<Signature></Signature></NFe>"))
+        }
+    }
+    
+    /// Servidor SEFAZ local que responde uma única requisição com `response`
+    async fn stub_sefaz(response: &'static str) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0u8; 4096];
+            
+            loop {
+                let n = socket.read(&mut buffer).await.unwrap();
+                request.extend_from_slice(&buffer[..n]);
+                
+                let text = String::from_utf8_lossy(&request);
+                if let Some(header_end) = text.find("\r\n\r\n") {
+                    let content_length = text[..header_end]
+                        .lines()
+                        .find_map(|l| l.to_lowercase().strip_prefix("content-length:").map(|v| v.trim().parse::<usize>().unwrap()))
+                        .unwrap_or(0);
+                    if request.len() >= header_end + 4 + content_length || n == 0 {
+                        break;
+                    }
+                }
+            }
+            
+            let reply = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/soap+xml\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                response.len(),
+                response
+            );
+            socket.write_all(reply.as_bytes()).await.unwrap();
+        });
+        
+        format!("http://{}", addr)
+    }
+    
+    fn config() -> NFCeConfig {
+        NFCeConfig {
+            emitente: Emitente {
+                cnpj: "11222333000181".to_string(),
+                razao_social: "DelPopolo Panificadora LTDA".to_string(),
+                nome_fantasia: Some("DelPopolo".to_string()),
+                endereco: Endereco {
+                    logradouro: "Rua das Padarias".to_string(),
+                    numero: "10".to_string(),
+                    complemento: None,
+                    bairro: "Centro".to_string(),
+                    municipio: "Sao Paulo".to_string(),
+                    uf: "SP".to_string(),
+                    cep: "01001000".to_string(),
+                },
+                telefone: None,
+                email: None,
+            },
+            inscricao_estadual: "111111111111".to_string(),
+            codigo_uf: 35,
+            codigo_municipio: "3550308".to_string(),
+            serie: 1,
+            ambiente: Ambiente::Homologacao,
+            csc_id: 1,
+            csc: "CSC-TESTE".to_string(),
+            url_qrcode: "https://www.homologacao.nfce.fazenda.sp.gov.br/qrcode".to_string(),
+            url_consulta: "https://www.homologacao.nfce.fazenda.sp.gov.br/consulta".to_string(),
+        }
+    }
+    
+    fn builder() -> NFCeBuilder {
+        NFCeBuilder::new(config(), TaxCalculator::new(TaxRegime::Normal), Box::new(TestSigner))
+    }
+    
+    fn completed_order() -> (Order, Vec<Product>) {
+        let mut pao = Product::new(
+            "Pao Frances".to_string(),
+            ProductCategory::Bread,
+            UnitOfMeasure::Kilogram,
+            Money::brl(20.0),
+            Money::brl(8.0),
+        );
+        pao.nfe_ncm = Some("19059010".to_string());
+        pao.nfe_cfop = Some("5102".to_string());
+        
+        let mut refri = Product::new(
+            "Refrigerante Lata".to_string(),
+            ProductCategory::Beverage,
+            UnitOfMeasure::Unit,
+            Money::brl(6.0),
+            Money::brl(3.0),
+        );
+        refri.nfe_ncm = Some("22021000".to_string());
+        refri.nfe_cfop = Some("5405".to_string());
+        
+        let mut order = Order::new(OrderSource::InStore);
+        for (product, quantity) in [(&pao, 0.5), (&refri, 2.0)] {
+            order.add_item(OrderItem {
+                id: uuid::Uuid::new_v4(),
+                product_id: product.id,
+                product_name: product.name.clone(),
+                quantity,
+                unit_price: product.price.clone(),
+                total_price: product.price.multiply(quantity),
+                notes: None,
+            });
+        }
+        order.discount = Money::brl(2.0);
+        order.recalculate_totals();
+        order.payment_method = Some(PaymentMethod::Pix);
+        order.complete();
+        
+        (order, vec![pao, refri])
+    }
+    
+    #[test]
+    fn test_build_nfce() {
+        let (order, products) = completed_order();
+        let emitted_at = chrono::Utc.with_ymd_and_hms(2025, 1, 15, 15, 30, 0).unwrap();
+        
+        let nfce = builder().build_at(&order, &products, 42, 12345678, emitted_at).unwrap();
+        
+        assert_eq!(&nfce.nfe.chave[..43], "3525011122233300018165001000000042112345678");
+        assert!(NFeParser::validate_chave(&nfce.nfe.chave));
+        
+        // Pão francês: ICMS 18% e PIS/COFINS alíquota zero; refrigerante com ICMS-ST
+        let pao = &nfce.nfe.itens[0];
+        assert_eq!(pao.icms.as_ref().unwrap().situacao_tributaria, "00");
+        assert_eq!(pao.pis.as_ref().unwrap().situacao_tributaria, "06");
+        assert_eq!(nfce.nfe.itens[1].icms.as_ref().unwrap().situacao_tributaria, "60");
+        
+        assert_eq!(nfce.nfe.totais.valor_total_produtos, 22.0);
+        assert_eq!(nfce.nfe.totais.valor_total_nota, 20.0);
+        assert_eq!(nfce.nfe.totais.valor_icms, 1.64); // (10.00 - 0.91) * 18%
+        
+        assert!(nfce.xml.contains("<mod>65</mod>"));
+        assert!(nfce.xml.contains("<dhEmi>2025-01-15T12:30:00-03:00</dhEmi>"));
+        assert!(nfce.xml.contains("<tPag>17</tPag><vPag>20.00</vPag>"));
+        assert!(nfce.xml.contains(&format!("<qrCode><![CDATA[{}]]></qrCode>", nfce.qr_code_url)));
+        assert!(nfce.xml.ends_with("<Signature></Signature></NFe>"));
+    }
+    
+    #[test]
+    fn test_text_escaping_matches_canonical_form() {
+        let mut config = config();
+        config.emitente.razao_social = "Padaria D'Ávila & Filhos".to_string();
+        let builder = NFCeBuilder::new(config, TaxCalculator::new(TaxRegime::Normal), Box::new(TestSigner));
+        let (order, products) = completed_order();
+        
+        let nfce = builder.build(&order, &products, 1).unwrap();
+        
+        // Aspas e apóstrofos ficam literais: C14N não os escapa e o digest não bateria
+        assert!(nfce.xml.contains("<xNome>Padaria D'Ávila &amp; Filhos</xNome>"));
+    }
+    
+    #[test]
+    fn test_delivery_fee_and_homologacao_description() {
+        let (mut order, products) = completed_order();
+        order.delivery_fee = Money::brl(5.0);
+        order.recalculate_totals();
+        
+        let nfce = builder().build(&order, &products, 1).unwrap();
+        
+        assert_eq!(nfce.nfe.totais.valor_outras_despesas, 5.0);
+        assert_eq!(nfce.nfe.totais.valor_total_nota, order.total.amount);
+        assert!(nfce.xml.contains("<vOutro>5.00</vOutro><vNF>25.00</vNF>"));
+        assert!(nfce.xml.contains("<vPag>25.00</vPag>"));
+        
+        assert!(nfce.xml.contains("<xProd>NOTA FISCAL EMITIDA EM AMBIENTE DE HOMOLOGACAO - SEM VALOR FISCAL</xProd>"));
+        assert!(nfce.xml.contains("<xProd>Refrigerante Lata</xProd>"));
+    }
+    
+    #[test]
+    fn test_imported_products_use_sale_cfop() {
+        let (order, mut products) = completed_order();
+        // CFOPs de compra gravados pela importação da NF-e do fornecedor
+        products[0].nfe_cfop = Some("1102".to_string());
+        products[1].nfe_cfop = Some("2403".to_string());
+        
+        let nfce = builder().build(&order, &products, 1).unwrap();
+        
+        assert_eq!(nfce.nfe.itens[0].cfop, "5102");
+        assert_eq!(nfce.nfe.itens[1].cfop, "5405");
+        assert_eq!(nfce.nfe.itens[1].icms.as_ref().unwrap().situacao_tributaria, "60");
+        
+        products[0].nfe_cfop = Some("1556".to_string());
+        assert!(builder().build(&order, &products, 1).is_err());
+    }
+    
+    #[test]
+    fn test_build_requires_completed_order() {
+        let (mut order, products) = completed_order();
+        order.start_preparation();
+        
+        assert!(builder().build(&order, &products, 1).is_err());
+    }
+    
+    #[tokio::test]
+    async fn test_emit_through_sefaz_stub() {
+        let url = stub_sefaz(concat!(
+            "<soap:Envelope xmlns:soap=\"http://www.w3.org/2003/05/soap-envelope\"><soap:Body>",
+            "<nfeResultMsg><retEnviNFe><cStat>104</cStat><xMotivo>Lote processado</xMotivo>",
+            "<protNFe><infProt><chNFe>35250111222333000181650010000000421123456780</chNFe>",
+            "<dhRecbto>2025-01-15T12:30:01-03:00</dhRecbto><nProt>135250000000001</nProt>",
+            "<cStat>100</cStat><xMotivo>Autorizado o uso da NF-e</xMotivo></infProt></protNFe>",
+            "</retEnviNFe></nfeResultMsg></soap:Body></soap:Envelope>"
+        ))
+        .await;
+        
+        let sefaz = SefazHttpClient::new(SefazEndpoints {
+            autorizacao_url: format!("{}/NFeAutorizacao4", url),
+            status_servico_url: format!("{}/NFeStatusServico4", url),
+            evento_url: format!("{}/NFeRecepcaoEvento4", url),
+            codigo_uf: 35,
+            ambiente: 2,
+        });
+        
+        let (order, products) = completed_order();
+        let emitter = NFCeEmitter::new(builder(), Box::new(sefaz));
+        
+        let nfce = emitter.emit(&order, &products, 42).await.unwrap();
+        
+        assert_eq!(nfce.protocolo.as_deref(), Some("135250000000001"));
+    }
+}
@@ -0,0 +1,11 @@
+pub mod builder;
+pub mod tax;
+pub mod qrcode;
+pub mod signer;
+pub mod emitter;
+
+pub use builder::{Ambiente, NFCe, NFCeBuilder, NFCeConfig, MODELO_NFCE};
+pub use tax::{ItemTaxes, TaxCalculator, TaxRegime};
+pub use qrcode::NFCeQrCode;
+pub use signer::{CertificateSigner, XmlSigner};
+pub use emitter::NFCeEmitter;
@@ -0,0 +1,59 @@
+use sha1::{Digest, Sha1};
+use super::builder::Ambiente;
+
+/// Versão 2 do QR Code da NFC-e (emissão online)
+const QRCODE_VERSION: u8 = 2;
+
+/// Monta a URL do QR Code da NFC-e com o hash do CSC (Código de Segurança do Contribuinte)
+pub struct NFCeQrCode;
+
+impl NFCeQrCode {
+    /// `url_qrcode` é o endereço de consulta por QR Code da SEFAZ da UF;
+    /// `csc_id` é o identificador do CSC (cIdToken) e `csc` o próprio código.
+    pub fn build_url(
+        url_qrcode: &str,
+        chave: &str,
+        ambiente: Ambiente,
+        csc_id: u32,
+        csc: &str,
+    ) -> String {
+        let parametros = format!(
+            "{}|{}|{}|{}",
+            chave,
+            QRCODE_VERSION,
+            ambiente.code(),
+            csc_id
+        );
+        
+        let hash = Self::hash(&parametros, csc);
+        
+        format!("{}?p={}|{}", url_qrcode, parametros, hash)
+    }
+    
+    /// SHA-1 em hexadecimal maiúsculo de `parametros` concatenados ao CSC
+    pub fn hash(parametros: &str, csc: &str) -> String {
+        let digest = Sha1::digest(format!("{}{}", parametros, csc).as_bytes());
+        digest.iter().map(|b| format!("{:02X}", b)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_build_url() {
+        let url = NFCeQrCode::build_url(
+            "https://www.homologacao.nfce.fazenda.sp.gov.br/qrcode",
+            "35250112345678000190650010000000011000000019",
+            Ambiente::Homologacao,
+            1,
+            "CSC-TESTE",
+        );
+        
+        assert_eq!(
+            url,
+            "https://www.homologacao.nfce.fazenda.sp.gov.br/qrcode?p=35250112345678000190650010000000011000000019|2|2|1|5532DEE5041442BB9940540B582F74957A3BD136"
+        );
+    }
+}
@@ -0,0 +1,226 @@
+use anyhow::{Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use rsa::pkcs1v15::SigningKey;
+use rsa::pkcs8::DecodePrivateKey;
+use rsa::signature::{SignatureEncoding, Signer};
+use rsa::RsaPrivateKey;
+use sha1::{Digest, Sha1};
+
+const NFE_NAMESPACE: &str = "http://www.portalfiscal.inf.br/nfe";
+const XMLDSIG_NAMESPACE: &str = "http://www.w3.org/2000/09/xmldsig#";
+
+/// Assinatura digital XMLDSig exigida pela SEFAZ (enveloped, RSA-SHA1, C14N)
+pub trait XmlSigner: Send + Sync {
+    /// Assina o elemento com atributo `Id` igual a `reference_id` e devolve o XML com
+    /// o elemento `<Signature>` inserido ao final do elemento pai (`<NFe>`, `<evento>`)
+    fn sign(&self, xml: &str, reference_id: &str) -> Result<String>;
+}
+
+/// Assinador baseado no certificado A1 do contribuinte.
+///
+/// Espera a chave privada em PKCS#8 PEM e o certificado em PEM; um `.pfx` pode ser
+/// convertido com `openssl pkcs12 -in certificado.pfx -nodes`.
+pub struct CertificateSigner {
+    signing_key: SigningKey<Sha1>,
+    certificate_base64: String,
+}
+
+impl CertificateSigner {
+    pub fn new(private_key: RsaPrivateKey, certificate_der: &[u8]) -> Self {
+        Self {
+            signing_key: SigningKey::<Sha1>::new(private_key),
+            certificate_base64: STANDARD.encode(certificate_der),
+        }
+    }
+    
+    pub fn from_pem(private_key_pem: &str, certificate_pem: &str) -> Result<Self> {
+        let private_key = RsaPrivateKey::from_pkcs8_pem(private_key_pem)
+            .context("Chave privada do certificado inválida")?;
+        
+        let certificate_base64: String = certificate_pem
+            .lines()
+            .filter(|line| !line.starts_with("-----"))
+            .map(str::trim)
+            .collect();
+        
+        let certificate_der = STANDARD
+            .decode(&certificate_base64)
+            .context("Certificado PEM inválido")?;
+        
+        Ok(Self::new(private_key, &certificate_der))
+    }
+    
+    pub fn from_files(private_key_path: &str, certificate_path: &str) -> Result<Self> {
+        let private_key_pem = std::fs::read_to_string(private_key_path)?;
+        let certificate_pem = std::fs::read_to_string(certificate_path)?;
+        Self::from_pem(&private_key_pem, &certificate_pem)
+    }
+    
+    /// Posição inicial e final do elemento com o `Id` informado
+    fn element_bounds(xml: &str, reference_id: &str) -> Result<(usize, usize, String)> {
+        let id_attribute = format!("Id=\"{}\"", reference_id);
+        let id_position = xml
+            .find(&id_attribute)
+            .with_context(|| format!("Elemento com Id {} não encontrado", reference_id))?;
+        
+        let start = xml[..id_position]
+            .rfind('<')
+            .context("XML malformado")?;
+        let tag_name: String = xml[start + 1..]
+            .chars()
+            .take_while(|c| !c.is_whitespace())
+            .collect();
+        
+        let closing = format!("</{}>", tag_name);
+        let end = xml[start..]
+            .find(&closing)
+            .map(|pos| start + pos + closing.len())
+            .with_context(|| format!("Elemento {} sem fechamento", tag_name))?;
+        
+        Ok((start, end, tag_name))
+    }
+    
+    /// Forma canônica do elemento referenciado: o XML é gerado sem espaços e com
+    /// atributos já ordenados, então basta herdar o namespace do documento.
+    fn canonical_element(xml: &str, reference_id: &str) -> Result<String> {
+        let (start, end, tag_name) = Self::element_bounds(xml, reference_id)?;
+        let element = &xml[start..end];
+        let open_tag = format!("<{}", tag_name);
+        
+        Ok(element.replacen(
+            &open_tag,
+            &format!("<{} xmlns=\"{}\"", tag_name, NFE_NAMESPACE),
+            1,
+        ))
+    }
+    
+    /// Posição da tag de fechamento do elemento que contém o trecho iniciado em `from`
+    fn parent_close(xml: &str, from: usize) -> Option<usize> {
+        let mut depth = 0usize;
+        let mut pos = from;
+        
+        while let Some(offset) = xml[pos..].find('<') {
+            let tag_start = pos + offset;
+            let rest = &xml[tag_start..];
+            
+            if rest.starts_with("<![CDATA[") {
+                pos = tag_start + rest.find("]]>")? + 3;
+                continue;
+            }
+            
+            let tag_end = tag_start + rest.find('>')?;
+            if rest.starts_with("</") {
+                if depth == 0 {
+                    return Some(tag_start);
+                }
+                depth -= 1;
+            } else if !rest.starts_with("<?") && !rest.starts_with("<!") && !xml[..tag_end].ends_with('/') {
+                depth += 1;
+            }
+            pos = tag_end + 1;
+        }
+        
+        None
+    }
+    
+    fn signed_info(reference_id: &str, digest_base64: &str) -> String {
+        format!(
+            concat!(
+                "<SignedInfo xmlns=\"{ns}\">",
+                "<CanonicalizationMethod Algorithm=\"http://www.w3.org/TR/2001/REC-xml-c14n-20010315\"></CanonicalizationMethod>",
+                "<SignatureMethod Algorithm=\"http://www.w3.org/2000/09/xmldsig#rsa-sha1\"></SignatureMethod>",
+                "<Reference URI=\"#{id}\">",
+                "<Transforms>",
+                "<Transform Algorithm=\"http://www.w3.org/2000/09/xmldsig#enveloped-signature\"></Transform>",
+                "<Transform Algorithm=\"http://www.w3.org/TR/2001/REC-xml-c14n-20010315\"></Transform>",
+                "</Transforms>",
+                "<DigestMethod Algorithm=\"http://www.w3.org/2000/09/xmldsig#sha1\"></DigestMethod>",
+                "<DigestValue>{digest}</DigestValue>",
+                "</Reference>",
+                "</SignedInfo>"
+            ),
+            ns = XMLDSIG_NAMESPACE,
+            id = reference_id,
+            digest = digest_base64,
+        )
+    }
+}
+
+impl XmlSigner for CertificateSigner {
+    fn sign(&self, xml: &str, reference_id: &str) -> Result<String> {
+        let element = Self::canonical_element(xml, reference_id)?;
+        let digest = STANDARD.encode(Sha1::digest(element.as_bytes()));
+        
+        let signed_info = Self::signed_info(reference_id, &digest);
+        let signature = self.signing_key.sign(signed_info.as_bytes());
+        let signature_value = STANDARD.encode(signature.to_bytes());
+        
+        // Dentro de <Signature> o namespace é herdado, então é removido do SignedInfo
+        let signature_element = format!(
+            "<Signature xmlns=\"{ns}\">{signed_info}<SignatureValue>{value}</SignatureValue><KeyInfo><X509Data><X509Certificate>{cert}</X509Certificate></X509Data></KeyInfo></Signature>",
+            ns = XMLDSIG_NAMESPACE,
+            signed_info = signed_info.replacen(&format!(" xmlns=\"{}\"", XMLDSIG_NAMESPACE), "", 1),
+            value = signature_value,
+            cert = self.certificate_base64,
+        );
+        
+        let (_, end, _) = Self::element_bounds(xml, reference_id)?;
+        let insert_at = Self::parent_close(xml, end)
+            .context("Elemento assinado precisa estar dentro de outro elemento")?;
+        
+        let mut signed = String::with_capacity(xml.len() + signature_element.len());
+        signed.push_str(&xml[..insert_at]);
+        signed.push_str(&signature_element);
+        signed.push_str(&xml[insert_at..]);
+        
+        Ok(signed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rsa::pkcs1v15::{Signature, VerifyingKey};
+    use rsa::signature::Verifier;
+    
+    #[test]
+    fn test_sign_enveloped() {
+        let private_key = RsaPrivateKey::new(&mut rand::thread_rng(), 1024).unwrap();
+        let verifying_key = VerifyingKey::<Sha1>::new(private_key.to_public_key());
+        let signer = CertificateSigner::new(private_key, b"certificado");
+        
+        let xml = "<NFe xmlns=\"http://www.portalfiscal.inf.br/nfe\"><infNFe Id=\"NFe123\" versao=\"4.00\"><ide><mod>65</mod></ide></infNFe></NFe>";
+        let signed = signer.sign(xml, "NFe123").unwrap();
+        
+        assert!(signed.ends_with("</Signature></NFe>"));
+        assert!(signed.contains("<Reference URI=\"#NFe123\">"));
+        
+        let expected_digest = STANDARD.encode(Sha1::digest(
+            "<infNFe xmlns=\"http://www.portalfiscal.inf.br/nfe\" Id=\"NFe123\" versao=\"4.00\"><ide><mod>65</mod></ide></infNFe>".as_bytes(),
+        ));
+        assert!(signed.contains(&format!("<DigestValue>{}</DigestValue>", expected_digest)));
+        
+        let start = signed.find("<SignatureValue>").unwrap() + "<SignatureValue>".len();
+        let end = signed.find("</SignatureValue>").unwrap();
+        let signature = Signature::try_from(STANDARD.decode(&signed[start..end]).unwrap().as_slice()).unwrap();
+        let signed_info = CertificateSigner::signed_info("NFe123", &expected_digest);
+        
+        assert!(verifying_key.verify(signed_info.as_bytes(), &signature).is_ok());
+    }
+    
+    #[test]
+    fn test_signature_goes_to_parent_element() {
+        let private_key = RsaPrivateKey::new(&mut rand::thread_rng(), 1024).unwrap();
+        let signer = CertificateSigner::new(private_key, b"certificado");
+        
+        // NFC-e: infNFeSupl (com CDATA) fica entre infNFe e a assinatura
+        let nfce = "<NFe xmlns=\"http://www.portalfiscal.inf.br/nfe\"><infNFe Id=\"NFe1\"><a/></infNFe><infNFeSupl><qrCode><![CDATA[https://x?p=1</y>]]></qrCode></infNFeSupl></NFe>";
+        let signed = signer.sign(nfce, "NFe1").unwrap();
+        assert!(signed.contains("</infNFeSupl><Signature xmlns="));
+        assert!(signed.ends_with("</Signature></NFe>"));
+        
+        let evento = "<envEvento><evento versao=\"1.00\"><infEvento Id=\"ID1\"><x>1</x></infEvento></evento></envEvento>";
+        let signed = signer.sign(evento, "ID1").unwrap();
+        assert!(signed.ends_with("</Signature></evento></envEvento>"));
+    }
+}
@@ -0,0 +1,150 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use crate::models::{COFINS, ICMS, PIS};
+
+/// Regime tributário do emitente (CRT)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TaxRegime {
+    SimplesNacional, // CRT 1
+    Normal,          // CRT 3
+}
+
+impl TaxRegime {
+    pub fn crt(&self) -> u8 {
+        match self {
+            TaxRegime::SimplesNacional => 1,
+            TaxRegime::Normal => 3,
+        }
+    }
+}
+
+/// Tributos calculados para um item da nota
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ItemTaxes {
+    pub icms: ICMS,
+    pub pis: PIS,
+    pub cofins: COFINS,
+}
+
+/// Calcula ICMS/PIS/COFINS por item a partir do NCM e CFOP do produto
+#[derive(Debug, Clone)]
+pub struct TaxCalculator {
+    pub regime: TaxRegime,
+    pub icms_aliquota: f64,
+    pub pis_aliquota: f64,
+    pub cofins_aliquota: f64,
+    /// Prefixos de NCM com alíquota zero de PIS/COFINS (ex.: pão francês, farinha de trigo)
+    pub ncm_aliquota_zero: Vec<String>,
+}
+
+impl TaxCalculator {
+    pub fn new(regime: TaxRegime) -> Self {
+        Self {
+            regime,
+            icms_aliquota: 18.0,
+            pis_aliquota: 1.65,
+            cofins_aliquota: 7.6,
+            ncm_aliquota_zero: vec![
+                "19059010".to_string(), // Pão francês
+                "1101".to_string(),     // Farinha de trigo
+                "1902".to_string(),     // Massas alimentícias
+                "0401".to_string(),     // Leite
+            ],
+        }
+    }
+    
+    pub fn with_icms_aliquota(mut self, aliquota: f64) -> Self {
+        self.icms_aliquota = aliquota;
+        self
+    }
+    
+    pub fn calculate(&self, ncm: &str, cfop: &str, base_calculo: f64) -> Result<ItemTaxes> {
+        if ncm.len() != 8 || !ncm.chars().all(|c| c.is_ascii_digit()) {
+            anyhow::bail!("NCM inválido: '{}'", ncm);
+        }
+        
+        if cfop.len() != 4 || !cfop.starts_with('5') {
+            anyhow::bail!("CFOP {} não é de saída dentro do estado", cfop);
+        }
+        
+        Ok(ItemTaxes {
+            icms: self.calculate_icms(cfop, base_calculo),
+            pis: self.calculate_pis(ncm, base_calculo),
+            cofins: self.calculate_cofins(ncm, base_calculo),
+        })
+    }
+    
+    /// CFOP de venda do produto; o cadastrado na importação é o da compra
+    pub fn sale_cfop(cfop: &str) -> Result<&str> {
+        let saida = match cfop {
+            "1101" | "2101" => "5101",
+            "1102" | "2102" | "6102" => "5102",
+            // Mercadoria recebida com ICMS retido por substituição tributária
+            "1401" | "2401" | "1403" | "2403" | "6401" | "6403" | "6404" => "5405",
+            c if c.starts_with('5') => c,
+            c => anyhow::bail!("CFOP {} sem correspondente de venda; cadastre o CFOP de saída do produto", c),
+        };
+        Ok(saida)
+    }
+    
+    fn calculate_icms(&self, cfop: &str, base_calculo: f64) -> ICMS {
+        // CFOP 5405: mercadoria com ICMS retido anteriormente por substituição tributária
+        let substituicao = cfop == "5405";
+        
+        match (self.regime, substituicao) {
+            (TaxRegime::SimplesNacional, true) => Self::icms_sem_destaque("500"),
+            (TaxRegime::SimplesNacional, false) => Self::icms_sem_destaque("102"),
+            (TaxRegime::Normal, true) => Self::icms_sem_destaque("60"),
+            (TaxRegime::Normal, false) => ICMS {
+                situacao_tributaria: "00".to_string(),
+                base_calculo: Some(round2(base_calculo)),
+                aliquota: Some(self.icms_aliquota),
+                valor: Some(round2(base_calculo * self.icms_aliquota / 100.0)),
+            },
+        }
+    }
+    
+    fn icms_sem_destaque(situacao_tributaria: &str) -> ICMS {
+        ICMS {
+            situacao_tributaria: situacao_tributaria.to_string(),
+            base_calculo: None,
+            aliquota: None,
+            valor: None,
+        }
+    }
+    
+    fn calculate_pis(&self, ncm: &str, base_calculo: f64) -> PIS {
+        let (cst, aliquota) = self.pis_cofins_cst(ncm, self.pis_aliquota);
+        PIS {
+            situacao_tributaria: cst.to_string(),
+            base_calculo: aliquota.map(|_| round2(base_calculo)),
+            aliquota,
+            valor: aliquota.map(|a| round2(base_calculo * a / 100.0)),
+        }
+    }
+    
+    fn calculate_cofins(&self, ncm: &str, base_calculo: f64) -> COFINS {
+        let (cst, aliquota) = self.pis_cofins_cst(ncm, self.cofins_aliquota);
+        COFINS {
+            situacao_tributaria: cst.to_string(),
+            base_calculo: aliquota.map(|_| round2(base_calculo)),
+            aliquota,
+            valor: aliquota.map(|a| round2(base_calculo * a / 100.0)),
+        }
+    }
+    
+    fn pis_cofins_cst(&self, ncm: &str, aliquota: f64) -> (&'static str, Option<f64>) {
+        if self.regime == TaxRegime::SimplesNacional {
+            // Simples Nacional recolhe PIS/COFINS no DAS
+            ("49", None)
+        } else if self.ncm_aliquota_zero.iter().any(|prefix| ncm.starts_with(prefix.as_str())) {
+            ("06", None)
+        } else {
+            ("01", Some(aliquota))
+        }
+    }
+}
+
+pub(crate) fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
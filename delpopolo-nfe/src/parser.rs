@@ -2,6 +2,7 @@ use quick_xml::de::from_str;
 use anyhow::Result;
 use tracing::{info, error};
 use super::models::NFe;
+use super::chave::ChaveAcesso;
 
 pub struct NFeParser;
 
@@ -9,8 +10,8 @@ impl NFeParser {
     pub fn parse_xml(xml_content: &str) -> Result<NFe> {
         info!("Parsing NFe XML");
         
-        // Simplificao: na prtica, o XML da NFe  complexo e aninhado
-        // Aqui faremos um parsing bsico que pode ser expandido
+        // Simplificação: na prática, o XML da NFe é complexo e aninhado
+        // Aqui faremos um parsing básico que pode ser expandido
         
         match from_str::<NFe>(xml_content) {
             Ok(nfe) => {
@@ -32,13 +33,11 @@ impl NFeParser {
     }
     
     pub fn extract_chave_from_barcode(barcode: &str) -> Option<String> {
-        // Cdigo de barras da NFe tem 44 dgitos
-        // Formato: chave de acesso
-        if barcode.len() == 44 && barcode.chars().all(|c| c.is_numeric()) {
-            Some(barcode.to_string())
-        } else {
-            None
-        }
+        // Código de barras da NFe (Code 128C) carrega a chave de acesso;
+        // leitores podem incluir espaços ou caracteres de controle
+        let digits: String = barcode.chars().filter(|c| c.is_ascii_digit()).collect();
+        
+        ChaveAcesso::new(digits).ok().map(|chave| chave.value().to_string())
     }
     
     pub fn validate_chave(chave: &str) -> bool {
@@ -50,7 +49,7 @@ impl NFeParser {
             return false;
         }
         
-        // Validao do dgito verificador (posio 44)
+        // Validação do dígito verificador (posição 44)
         let digits: Vec<u32> = chave.chars()
             .map(|c| c.to_digit(10).unwrap())
             .collect();
@@ -60,7 +59,7 @@ impl NFeParser {
         digits[43] == check_digit
     }
     
-    fn calculate_check_digit(digits: &[u32]) -> u32 {
+    pub fn calculate_check_digit(digits: &[u32]) -> u32 {
         let weights = [4, 3, 2, 9, 8, 7, 6, 5, 4, 3, 2];
         let mut sum = 0u32;
         
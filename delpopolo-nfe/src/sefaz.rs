@@ -0,0 +1,187 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use reqwest::{header, Client};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info};
+
+/// Resposta da SEFAZ (retorno de autorização ou status do serviço)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SefazResponse {
+    pub status_code: u16, // cStat
+    pub motivo: String,   // xMotivo
+    pub chave: Option<String>,
+    pub protocolo: Option<String>,
+    pub recebido_em: Option<String>,
+    pub xml: String,
+}
+
+impl SefazResponse {
+    /// 100 = autorizado o uso; 150 = autorizado fora de prazo
+    pub fn is_authorized(&self) -> bool {
+        matches!(self.status_code, 100 | 150)
+    }
+    
+    /// 135 = evento registrado e vinculado; 136 = registrado sem vínculo; 155 = cancelamento fora de prazo
+    pub fn is_event_registered(&self) -> bool {
+        matches!(self.status_code, 135 | 136 | 155)
+    }
+    
+    pub fn from_xml(xml: &str) -> Result<Self> {
+        // Em lote síncrono o retorno traz o cStat do lote e depois o do protNFe;
+        // o último cStat é o que se refere ao documento
+        let status_code = extract_last_tag(xml, "cStat")
+            .ok_or_else(|| anyhow::anyhow!("Resposta da SEFAZ sem cStat"))?
+            .parse()?;
+        
+        Ok(Self {
+            status_code,
+            motivo: extract_last_tag(xml, "xMotivo").unwrap_or_default(),
+            chave: extract_last_tag(xml, "chNFe"),
+            protocolo: extract_last_tag(xml, "nProt"),
+            recebido_em: extract_last_tag(xml, "dhRecbto"),
+            xml: xml.to_string(),
+        })
+    }
+}
+
+/// Comunicação com os web services da SEFAZ.
+///
+/// Implementado por `SefazHttpClient`; em testes pode ser atendido por um servidor local.
+#[async_trait]
+pub trait SefazClient: Send + Sync {
+    /// Envia uma NF-e/NFC-e assinada para autorização (lote síncrono)
+    async fn autorizar(&self, xml_assinado: &str) -> Result<SefazResponse>;
+    
+    async fn status_servico(&self) -> Result<SefazResponse>;
+    
+    /// Envia um `<evento>` assinado (cancelamento, CC-e, manifestação do destinatário)
+    async fn registrar_evento(&self, evento_assinado: &str) -> Result<SefazResponse>;
+}
+
+/// Endereços dos web services da UF/ambiente
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SefazEndpoints {
+    pub autorizacao_url: String,
+    pub status_servico_url: String,
+    /// RecepcaoEvento4; manifestações do destinatário vão para o Ambiente Nacional
+    pub evento_url: String,
+    pub codigo_uf: u8,
+    pub ambiente: u8,
+}
+
+pub struct SefazHttpClient {
+    client: Client,
+    endpoints: SefazEndpoints,
+}
+
+impl SefazHttpClient {
+    pub fn new(endpoints: SefazEndpoints) -> Self {
+        Self::with_client(Client::new(), endpoints)
+    }
+    
+    /// Permite informar um `Client` configurado com o certificado A1 (TLS mútuo)
+    pub fn with_client(client: Client, endpoints: SefazEndpoints) -> Self {
+        Self { client, endpoints }
+    }
+    
+    async fn send(&self, url: &str, service: &str, body: String) -> Result<SefazResponse> {
+        let envelope = format!(
+            concat!(
+                "<?xml version=\"1.0\" encoding=\"utf-8\"?>",
+                "<soap12:Envelope xmlns:soap12=\"http://www.w3.org/2003/05/soap-envelope\">",
+                "<soap12:Body><nfeDadosMsg xmlns=\"http://www.portalfiscal.inf.br/nfe/wsdl/{}\">{}</nfeDadosMsg></soap12:Body>",
+                "</soap12:Envelope>"
+            ),
+            service, body
+        );
+        
+        let response = self.client
+            .post(url)
+            .header(header::CONTENT_TYPE, "application/soap+xml; charset=utf-8")
+            .body(envelope)
+            .send()
+            .await?;
+        
+        if !response.status().is_success() {
+            error!("SEFAZ {} returned HTTP {}", service, response.status());
+            anyhow::bail!("SEFAZ indisponível: HTTP {}", response.status());
+        }
+        
+        let xml = response.text().await?;
+        let retorno = SefazResponse::from_xml(&xml)?;
+        
+        info!("SEFAZ {} responded {} - {}", service, retorno.status_code, retorno.motivo);
+        Ok(retorno)
+    }
+}
+
+#[async_trait]
+impl SefazClient for SefazHttpClient {
+    async fn autorizar(&self, xml_assinado: &str) -> Result<SefazResponse> {
+        let id_lote = chrono::Utc::now().timestamp_millis() % 1_000_000_000_000_000;
+        let body = format!(
+            "<enviNFe xmlns=\"http://www.portalfiscal.inf.br/nfe\" versao=\"4.00\"><idLote>{}</idLote><indSinc>1</indSinc>{}</enviNFe>",
+            id_lote,
+            strip_xml_declaration(xml_assinado)
+        );
+        
+        self.send(&self.endpoints.autorizacao_url, "NFeAutorizacao4", body).await
+    }
+    
+    async fn status_servico(&self) -> Result<SefazResponse> {
+        let body = format!(
+            "<consStatServ xmlns=\"http://www.portalfiscal.inf.br/nfe\" versao=\"4.00\"><tpAmb>{}</tpAmb><cUF>{}</cUF><xServ>STATUS</xServ></consStatServ>",
+            self.endpoints.ambiente, self.endpoints.codigo_uf
+        );
+        
+        self.send(&self.endpoints.status_servico_url, "NFeStatusServico4", body).await
+    }
+    
+    async fn registrar_evento(&self, evento_assinado: &str) -> Result<SefazResponse> {
+        let id_lote = chrono::Utc::now().timestamp_millis() % 1_000_000_000_000_000;
+        let body = format!(
+            "<envEvento xmlns=\"http://www.portalfiscal.inf.br/nfe\" versao=\"1.00\"><idLote>{}</idLote>{}</envEvento>",
+            id_lote,
+            strip_xml_declaration(evento_assinado)
+        );
+        
+        self.send(&self.endpoints.evento_url, "NFeRecepcaoEvento4", body).await
+    }
+}
+
+fn strip_xml_declaration(xml: &str) -> &str {
+    if xml.starts_with("<?xml") {
+        xml.find("?>").map(|pos| xml[pos + 2..].trim_start()).unwrap_or(xml)
+    } else {
+        xml
+    }
+}
+
+/// Conteúdo do último elemento `tag` (ignorando prefixos de namespace)
+pub(crate) fn extract_last_tag(xml: &str, tag: &str) -> Option<String> {
+    let open = format!("{}>", tag);
+    let mut search_end = xml.len();
+    
+    while let Some(pos) = xml[..search_end].rfind(&open) {
+        let before = &xml[..pos];
+        search_end = pos;
+        
+        // Deve ser uma tag de abertura: "<tag>" ou "<prefixo:tag>"
+        let lt = match before.rfind('<') {
+            Some(lt) => lt,
+            None => continue,
+        };
+        let name = &before[lt + 1..];
+        if name.starts_with('/') || !(name.is_empty() || name.ends_with(':')) {
+            continue;
+        }
+        
+        let content_start = pos + open.len();
+        let close = format!("</{}", &xml[lt + 1..content_start - 1]);
+        return xml[content_start..]
+            .find(&close)
+            .map(|end| xml[content_start..content_start + end].trim().to_string());
+    }
+    
+    None
+}
@@ -1,25 +1,27 @@
 use anyhow::Result;
 use super::models::NFe;
+use super::chave::ChaveAcesso;
 
 pub struct NFeValidator;
 
 impl NFeValidator {
     pub fn validate(nfe: &NFe) -> Result<()> {
         Self::validate_chave(&nfe.chave)?;
-        Self::validate_emitente(&nfe)?;
-        Self::validate_itens(&nfe)?;
-        Self::validate_totais(&nfe)?;
+        Self::validate_emitente(nfe)?;
+        Self::validate_chave_confere(nfe)?;
+        Self::validate_itens(nfe)?;
+        Self::validate_totais(nfe)?;
         
         Ok(())
     }
     
     fn validate_chave(chave: &str) -> Result<()> {
         if chave.len() != 44 {
-            anyhow::bail!("Chave de acesso deve ter 44 dgitos");
+            anyhow::bail!("Chave de acesso deve ter 44 dígitos");
         }
         
         if !chave.chars().all(|c| c.is_numeric()) {
-            anyhow::bail!("Chave de acesso deve conter apenas nmeros");
+            anyhow::bail!("Chave de acesso deve conter apenas números");
         }
         
         Ok(())
@@ -27,11 +29,36 @@ impl NFeValidator {
     
     fn validate_emitente(nfe: &NFe) -> Result<()> {
         if nfe.emitente.cnpj.is_empty() {
-            anyhow::bail!("CNPJ do emitente  obrigatrio");
+            anyhow::bail!("CNPJ do emitente é obrigatório");
         }
         
         if nfe.emitente.razao_social.is_empty() {
-            anyhow::bail!("Razo social do emitente  obrigatria");
+            anyhow::bail!("Razão social do emitente é obrigatória");
+        }
+        
+        Ok(())
+    }
+    
+    /// Confere CNPJ/CPF, número e série decodificados da chave com os dados da nota
+    fn validate_chave_confere(nfe: &NFe) -> Result<()> {
+        let chave = ChaveAcesso::new(nfe.chave.clone())
+            .map_err(|e| anyhow::anyhow!("Chave de acesso inválida: {}", e))?;
+        
+        let documento_emitente: String = nfe.emitente.cnpj.chars().filter(|c| c.is_ascii_digit()).collect();
+        if chave.documento() != format!("{:0>14}", documento_emitente) {
+            anyhow::bail!(
+                "CNPJ/CPF da chave ({}) não confere com o emitente ({})",
+                chave.documento(),
+                nfe.emitente.cnpj
+            );
+        }
+        
+        if nfe.numero.trim().parse::<u32>().ok() != Some(chave.numero()) {
+            anyhow::bail!("Número da chave ({}) não confere com a nota ({})", chave.numero(), nfe.numero);
+        }
+        
+        if nfe.serie.trim().parse::<u16>().ok() != Some(chave.serie()) {
+            anyhow::bail!("Série da chave ({}) não confere com a nota ({})", chave.serie(), nfe.serie);
         }
         
         Ok(())
@@ -44,7 +71,7 @@ impl NFeValidator {
         
         for item in &nfe.itens {
             if item.descricao.is_empty() {
-                anyhow::bail!("Descrio do item {} est vazia", item.numero_item);
+                anyhow::bail!("Descrição do item {} está vazia", item.numero_item);
             }
             
             if item.quantidade_comercial <= 0.0 {
@@ -52,7 +79,7 @@ impl NFeValidator {
             }
             
             if item.valor_unitario_comercial <= 0.0 {
-                anyhow::bail!("Valor unitrio do item {} deve ser maior que zero", item.numero_item);
+                anyhow::bail!("Valor unitário do item {} deve ser maior que zero", item.numero_item);
             }
         }
         
@@ -68,7 +95,7 @@ impl NFeValidator {
         
         if diferenca > 0.01 {
             anyhow::bail!(
-                "Soma dos itens ({}) no confere com total de produtos ({})",
+                "Soma dos itens ({}) não confere com total de produtos ({})",
                 soma_itens,
                 nfe.totais.valor_total_produtos
             );
@@ -78,7 +105,7 @@ impl NFeValidator {
     }
     
     pub fn is_valid_for_import(nfe: &NFe) -> bool {
-        // Verifica se  nota de entrada (CFOP iniciando com 1, 2 ou 3)
+        // Verifica se é nota de entrada (CFOP iniciando com 1, 2 ou 3)
         nfe.is_entrada() && nfe.itens.iter().all(|item| {
             !item.descricao.is_empty() && 
             item.quantidade_comercial > 0.0 &&